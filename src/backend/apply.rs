@@ -0,0 +1,186 @@
+// 配置应用模块 - TUI编辑表单和命令行共用的静态IP设置流程
+use crate::backend::netplan::{Ipv6Settings, NameserverConfig, NetplanManager};
+use crate::backend::runtime;
+use anyhow::{Context, Result};
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::path::PathBuf;
+
+/// 允许设置的最小MTU（IPv4要求的最小值）
+pub const MIN_MTU: u32 = 68;
+/// 允许设置的最大MTU
+pub const MAX_MTU: u32 = 65535;
+
+/// 已验证的静态IPv4配置
+#[derive(Debug, Clone)]
+pub struct StaticIpv4 {
+    pub address: Ipv4Addr,
+    pub prefix: u8,
+    pub gateway: Option<Ipv4Addr>,
+    pub nameservers: Option<NameserverConfig>,
+}
+
+impl StaticIpv4 {
+    /// 验证地址和网关，在修改系统之前调用；DNS和搜索域都为空时不写入nameservers
+    pub fn new(address: &str, prefix: u8, gateway: Option<&str>, nameservers: Option<NameserverConfig>) -> Result<Self> {
+        let address: Ipv4Addr = address.trim().parse()
+            .with_context(|| format!("无效的IP地址: {}", address))?;
+        if prefix > 32 {
+            anyhow::bail!("前缀长度超出范围: {}", prefix);
+        }
+        let gateway = gateway
+            .map(str::trim)
+            .filter(|gw| !gw.is_empty())
+            .map(|gw| gw.parse::<Ipv4Addr>().with_context(|| format!("无效的网关地址: {}", gw)))
+            .transpose()?;
+
+        Ok(Self {
+            address,
+            prefix,
+            gateway,
+            nameservers: nameservers.filter(|ns| !ns.addresses.is_empty() || !ns.search.is_empty()),
+        })
+    }
+
+    /// CIDR格式的地址，如 192.168.1.10/24
+    pub fn cidr(&self) -> String {
+        format!("{}/{}", self.address, self.prefix)
+    }
+}
+
+/// 解析CIDR格式的IPv4地址（如 192.168.1.10/24）
+pub fn parse_ipv4_cidr(cidr: &str) -> Result<(Ipv4Addr, u8)> {
+    let (ip, prefix) = cidr
+        .split_once('/')
+        .with_context(|| format!("地址缺少前缀长度: {}", cidr))?;
+
+    let ip: Ipv4Addr = ip.parse()
+        .with_context(|| format!("无效的IP地址: {}", ip))?;
+    let prefix: u8 = prefix.parse()
+        .with_context(|| format!("无效的前缀长度: {}", prefix))?;
+
+    if prefix > 32 {
+        anyhow::bail!("前缀长度超出范围: {}", prefix);
+    }
+
+    Ok((ip, prefix))
+}
+
+/// 解析并验证MTU，必须在 MIN_MTU..=MAX_MTU 范围内
+pub fn parse_mtu(value: &str) -> Result<u32> {
+    let mtu: u32 = value.trim().parse()
+        .with_context(|| format!("无效的MTU: {}", value))?;
+    if !(MIN_MTU..=MAX_MTU).contains(&mtu) {
+        anyhow::bail!("MTU超出范围 ({}-{}): {}", MIN_MTU, MAX_MTU, mtu);
+    }
+    Ok(mtu)
+}
+
+/// 验证IPv6地址列表和IPv6网关并规范为 地址/前缀 格式，都为空时返回空设置
+pub fn parse_ipv6_settings(addresses: &[String], gateway: Option<&str>) -> Result<Ipv6Settings> {
+    let addresses = addresses
+        .iter()
+        .map(|address| runtime::parse_ipv6_cidr(address).map(|(ip, prefix)| format!("{}/{}", ip, prefix)))
+        .collect::<Result<Vec<_>>>()?;
+    // 链路本地网关（如 fe80::1）很常见，不做限制
+    let gateway = gateway
+        .map(str::trim)
+        .filter(|gw| !gw.is_empty())
+        .map(|gw| {
+            gw.parse::<Ipv6Addr>()
+                .map(|gw| gw.to_string())
+                .with_context(|| format!("无效的IPv6网关地址: {}", gw))
+        })
+        .transpose()?;
+    Ok(Ipv6Settings { addresses, gateway })
+}
+
+/// 在运行时替换接口的全局IPv6地址和IPv6默认路由，链路本地地址不会被清除
+pub fn apply_runtime_ipv6(iface_name: &str, ipv6: &Ipv6Settings) -> Result<()> {
+    runtime::flush_ipv6_addresses(iface_name)?;
+    for address in &ipv6.addresses {
+        runtime::set_ipv6_address(iface_name, address)?;
+    }
+    match &ipv6.gateway {
+        Some(gateway) => runtime::set_ipv6_gateway(gateway, iface_name)?,
+        None => runtime::remove_ipv6_gateway(iface_name),
+    }
+    Ok(())
+}
+
+/// 为接口设置静态IPv4：清除原有IPv4地址后立即生效，`persist` 为true时写入Netplan
+///
+/// ipv6不为None时同时替换IPv6地址和IPv6网关，否则保持不变；mtu不为None时一起修改。
+/// 持久化时返回写入的Netplan配置文件和备份路径（文件是新建的时备份为None），不持久化时返回None
+pub fn apply_static_ipv4(
+    netplan: &NetplanManager,
+    iface_name: &str,
+    config: &StaticIpv4,
+    ipv6: Option<&Ipv6Settings>,
+    mtu: Option<u32>,
+    persist: bool,
+) -> Result<Option<(PathBuf, Option<PathBuf>)>> {
+    // 1. 运行时修改（立即生效）
+    runtime::flush_ipv4_addresses(iface_name)?;
+    runtime::set_ipv4_address(iface_name, &config.address.to_string(), config.prefix)?;
+    let gateway = config.gateway.map(|gw| gw.to_string());
+    if let Some(gateway) = &gateway {
+        runtime::set_default_gateway(gateway, iface_name)?;
+    }
+    if let Some(ipv6) = ipv6 {
+        apply_runtime_ipv6(iface_name, ipv6)?;
+    }
+    if let Some(mtu) = mtu {
+        runtime::set_mtu(iface_name, mtu)?;
+    }
+    if !persist {
+        return Ok(None);
+    }
+
+    // 2. 持久化到Netplan
+    netplan.set_static_ip(iface_name, &config.cidr(), gateway.as_deref(), ipv6, config.nameservers.clone(), mtu).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_static_ipv4_validation() {
+        let empty = || Some(NameserverConfig { addresses: Vec::new(), search: Vec::new() });
+
+        let config = StaticIpv4::new("192.168.1.50", 24, Some("192.168.1.1"), empty()).unwrap();
+        assert_eq!(config.cidr(), "192.168.1.50/24");
+        assert_eq!(config.gateway, Some(Ipv4Addr::new(192, 168, 1, 1)));
+        assert!(config.nameservers.is_none());
+
+        // 空网关视为没有网关
+        assert!(StaticIpv4::new("192.168.1.50", 24, Some(" "), empty()).unwrap().gateway.is_none());
+
+        assert!(StaticIpv4::new("192.168.1.500", 24, None, empty()).is_err());
+        assert!(StaticIpv4::new("192.168.1.50", 33, None, empty()).is_err());
+        assert!(StaticIpv4::new("192.168.1.50", 24, Some("gw.local"), empty()).is_err());
+    }
+
+    #[test]
+    fn test_parse_mtu() {
+        assert_eq!(parse_mtu(" 9000 ").unwrap(), 9000);
+        assert_eq!(parse_mtu("68").unwrap(), MIN_MTU);
+        assert_eq!(parse_mtu("65535").unwrap(), MAX_MTU);
+        assert!(parse_mtu("67").is_err());
+        assert!(parse_mtu("65536").is_err());
+        assert!(parse_mtu("jumbo").is_err());
+    }
+
+    #[test]
+    fn test_parse_ipv6_settings() {
+        // 空的IPv6部分表示只配置IPv4
+        assert_eq!(parse_ipv6_settings(&[], Some("")).unwrap(), Ipv6Settings::default());
+
+        let ipv6 = parse_ipv6_settings(&["2001:DB8::10".to_string()], Some("fe80::1")).unwrap();
+        assert_eq!(ipv6.addresses, ["2001:db8::10/64"]);
+        assert_eq!(ipv6.gateway.as_deref(), Some("fe80::1"));
+
+        assert!(parse_ipv6_settings(&["2001:db8::zz/64".to_string()], None).is_err());
+        assert!(parse_ipv6_settings(&[], Some("192.168.1.1")).is_err());
+    }
+}
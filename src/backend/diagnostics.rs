@@ -0,0 +1,171 @@
+// 诊断信息模块 - 收集网络状态和配置，生成用于提交bug报告的文本
+use crate::backend::netplan::NetplanManager;
+use crate::model::NetInterface;
+use crate::utils::command::{execute_command_stdout, read_privileged};
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::fmt::Write;
+use std::fs;
+use std::path::PathBuf;
+
+/// 收集诊断信息，redact为true时隐藏IP和MAC地址
+pub fn collect(interfaces: &[NetInterface], netplan: &NetplanManager, redact: bool) -> String {
+    let mut out = String::new();
+
+    writeln!(out, "nicman {} 诊断信息", env!("CARGO_PKG_VERSION")).ok();
+    writeln!(out, "生成时间: {}", chrono::Local::now().format("%Y-%m-%d %H:%M:%S")).ok();
+    writeln!(out, "地址已隐藏: {}", if redact { "是" } else { "否" }).ok();
+
+    for args in [
+        &["link", "show"][..],
+        &["addr", "show"][..],
+        &["route", "show"][..],
+        &["-6", "route", "show"][..],
+    ] {
+        section(&mut out, &format!("ip {}", args.join(" ")));
+        match execute_command_stdout("ip", args) {
+            Ok(output) => out.push_str(&output),
+            Err(e) => {
+                writeln!(out, "执行失败: {:#}", e).ok();
+            }
+        }
+    }
+
+    match netplan.list_config_files() {
+        Ok(files) => {
+            for file in files {
+                section(&mut out, &file.display().to_string());
+                match read_privileged(&file) {
+                    Ok(content) => out.push_str(&mask_secrets(&content)),
+                    Err(e) => {
+                        writeln!(out, "读取失败: {:#}", e).ok();
+                    }
+                }
+            }
+        }
+        Err(e) => {
+            section(&mut out, "netplan");
+            writeln!(out, "列出配置文件失败: {:#}", e).ok();
+        }
+    }
+
+    section(&mut out, "接口与创建者");
+    for iface in interfaces {
+        let owner = iface
+            .owner
+            .as_ref()
+            .map(|owner| owner.display_name())
+            .unwrap_or_else(|| "-".to_string());
+        writeln!(out, "{} ({}) {:?} 创建者: {}", iface.name, iface.kind.display_name(), iface.state, owner).ok();
+    }
+
+    if redact { redact_addresses(&out) } else { out }
+}
+
+/// 将诊断信息写入 /tmp 下新建的私有目录，返回文件路径
+pub fn write_bundle(content: &str) -> Result<PathBuf> {
+    use std::io::Write as _;
+    use std::os::unix::fs::{DirBuilderExt, OpenOptionsExt};
+
+    // 目录和文件都要求新建，避免跟随他人预先放置的符号链接；
+    // 诊断信息可能包含网络拓扑，只允许所有者访问
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    let dir = std::env::temp_dir().join(format!("nicman-diag-{}-{}", timestamp, std::process::id()));
+    fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&dir)
+        .with_context(|| format!("创建诊断目录失败: {:?}", dir))?;
+
+    let path = dir.join("diagnostics.txt");
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(&path)
+        .with_context(|| format!("创建诊断文件失败: {:?}", path))?;
+    file.write_all(content.as_bytes())
+        .with_context(|| format!("写入诊断文件失败: {:?}", path))?;
+    Ok(path)
+}
+
+fn section(out: &mut String, title: &str) {
+    writeln!(out, "\n===== {} =====", title).ok();
+}
+
+/// 隐藏Netplan配置中的密钥和密码（WireGuard私钥/预共享密钥、WiFi密码、802.1x凭据），
+/// 无论是否选择隐藏地址都会执行
+fn mask_secrets(text: &str) -> String {
+    let secret = Regex::new(
+        r#"(\b(?:key|password|private-key-password|private-key|private|shared|psk|preshared-key)\s*:[ \t]*)("[^"]*"|'[^']*'|[^\s,}#]+)"#,
+    )
+    .unwrap();
+    secret.replace_all(text, "${1}******").to_string()
+}
+
+/// 隐藏文本中的MAC、IPv4和IPv6地址（保留前缀长度，便于分析子网配置）
+fn redact_addresses(text: &str) -> String {
+    let mac = Regex::new(r"\b(?:[0-9a-fA-F]{2}:){5}[0-9a-fA-F]{2}\b").unwrap();
+    let ipv4 = Regex::new(r"\b(?:\d{1,3}\.){3}\d{1,3}\b").unwrap();
+    // 先粗略匹配含冒号的十六进制串，再用标准库校验是否为IPv6地址
+    let ipv6 = Regex::new(r"[0-9a-fA-F]*:[0-9a-fA-F:]+").unwrap();
+
+    let text = mac.replace_all(text, "xx:xx:xx:xx:xx:xx");
+    let text = ipv4.replace_all(&text, "x.x.x.x");
+    ipv6.replace_all(&text, |caps: &regex::Captures| {
+        if caps[0].parse::<std::net::Ipv6Addr>().is_ok() {
+            "x:x::x".to_string()
+        } else {
+            caps[0].to_string()
+        }
+    })
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mask_secrets() {
+        let input = "    wg0:\n\
+                     \x20     key: /etc/wireguard/wg0.key\n\
+                     \x20     keys: {private: \"aGVsbG8=\", shared: c2VjcmV0}\n\
+                     \x20     public: cHVibGlj\n\
+                     \x20     \"home\":\n\
+                     \x20       password: \"hunter2\"\n\
+                     \x20       auth:\n\
+                     \x20         private-key-password: 'p@ss'";
+        let output = mask_secrets(input);
+
+        assert!(!output.contains("wg0.key"));
+        assert!(!output.contains("aGVsbG8="));
+        assert!(!output.contains("c2VjcmV0"));
+        assert!(!output.contains("hunter2"));
+        assert!(!output.contains("p@ss"));
+        assert!(output.contains("password: ******"));
+        assert!(output.contains("{private: ******, shared: ******}"));
+        // 公钥不是机密，保留原样
+        assert!(output.contains("public: cHVibGlj"));
+    }
+
+    #[test]
+    fn test_redact_addresses() {
+        let input = "link/ether 52:54:00:12:34:56 brd ff:ff:ff:ff:ff:ff\n\
+                     inet 192.168.1.10/24 brd 192.168.1.255 scope global eth0\n\
+                     inet6 fe80::5054:ff:fe12:3456/64 scope link\n\
+                     default via 10.0.0.1 dev eth0 metric 100\n\
+                     生成时间: 2026-10-15 12:00:00";
+        let output = redact_addresses(input);
+
+        assert!(!output.contains("52:54:00"));
+        assert!(!output.contains("192.168"));
+        assert!(!output.contains("fe80::5054"));
+        assert!(!output.contains("10.0.0.1"));
+        // 前缀长度和其他字段保留
+        assert!(output.contains("x.x.x.x/24"));
+        assert!(output.contains("scope global eth0"));
+        assert!(output.contains("metric 100"));
+        assert!(output.contains("x:x::x/64"));
+        assert!(output.contains("12:00:00"));
+    }
+}
@@ -0,0 +1,168 @@
+// DNS解析器管理模块 - 检测解析器后端并在DNS修改后刷新
+use crate::model::{DnsConfig, NetInterface};
+use crate::utils::command::{command_success, execute_command_stdout, execute_privileged_stdout};
+use anyhow::{Context, Result};
+use std::fs;
+use std::net::IpAddr;
+
+/// 系统DNS解析器后端
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolverBackend {
+    /// systemd-resolved（resolv.conf 指向 /run/systemd/resolve）
+    SystemdResolved,
+    /// 静态 /etc/resolv.conf 文件
+    StaticFile,
+}
+
+impl ResolverBackend {
+    /// 检测当前使用的解析器后端
+    pub fn detect() -> Self {
+        let points_to_resolved = fs::read_link("/etc/resolv.conf")
+            .map(|target| target.to_string_lossy().contains("systemd/resolve"))
+            .unwrap_or(false);
+
+        if points_to_resolved && command_success("systemctl", &["is-active", "--quiet", "systemd-resolved"]) {
+            ResolverBackend::SystemdResolved
+        } else {
+            ResolverBackend::StaticFile
+        }
+    }
+}
+
+/// DNS修改后刷新解析器，返回执行结果描述
+pub fn refresh_resolver(iface_name: &str, nameservers: &[String], search: &[String]) -> Result<String> {
+    match ResolverBackend::detect() {
+        ResolverBackend::SystemdResolved => {
+            // 立即为接口下发DNS服务器和搜索域，无需等待netplan apply
+            if !nameservers.is_empty() {
+                let mut args = vec!["dns", iface_name];
+                args.extend(nameservers.iter().map(|s| s.as_str()));
+                execute_privileged_stdout("resolvectl", &args)
+                    .with_context(|| format!("为接口 {} 设置DNS失败", iface_name))?;
+            }
+            if !search.is_empty() {
+                let mut args = vec!["domain", iface_name];
+                args.extend(search.iter().map(|s| s.as_str()));
+                execute_privileged_stdout("resolvectl", &args)
+                    .with_context(|| format!("为接口 {} 设置DNS搜索域失败", iface_name))?;
+            }
+
+            execute_privileged_stdout("resolvectl", &["flush-caches"])
+                .context("刷新DNS缓存失败")?;
+
+            Ok("已更新systemd-resolved并刷新DNS缓存".to_string())
+        }
+        ResolverBackend::StaticFile => {
+            Ok("resolv.conf为静态文件，未使用systemd-resolved，跳过刷新".to_string())
+        }
+    }
+}
+
+/// 读取接口要写入Netplan的DNS配置，并去掉环回地址上的本地解析器
+///
+/// 使用systemd-resolved时resolv.conf中只有存根地址127.0.0.53，改为查询该链路自己的DNS服务器和搜索域
+pub fn link_dns_config(iface: &NetInterface) -> DnsConfig {
+    let mut config = match ResolverBackend::detect() {
+        ResolverBackend::SystemdResolved => {
+            let query = |kind: &str| {
+                execute_command_stdout("resolvectl", &[kind, &iface.name])
+                    .map(|output| parse_resolvectl_link(&output))
+                    .unwrap_or_default()
+            };
+            DnsConfig {
+                nameservers: query("dns"),
+                // ~开头的是仅用于路由查询的域，不是搜索域
+                search: query("domain").into_iter().filter(|domain| !domain.starts_with('~')).collect(),
+            }
+        }
+        ResolverBackend::StaticFile => iface
+            .dns_config
+            .clone()
+            .unwrap_or(DnsConfig { nameservers: Vec::new(), search: Vec::new() }),
+    };
+    config.nameservers.retain(|ns| !ns.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback()));
+    config
+}
+
+/// 解析 `resolvectl dns/domain <接口>` 的输出，如 "Link 2 (eth0): 192.168.1.1 1.1.1.1#cloudflare-dns.com"
+fn parse_resolvectl_link(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| line.split_once("):").map(|(_, values)| values))
+        .flat_map(str::split_whitespace)
+        .map(|value| value.split('#').next().unwrap_or(value).to_string())
+        .collect()
+}
+
+/// 解析逗号分隔的DNS服务器列表，每一项都必须是IPv4或IPv6地址
+pub fn parse_nameservers(input: &str) -> Result<Vec<String>> {
+    split_list(input)
+        .map(|token| {
+            token
+                .parse::<IpAddr>()
+                .map(|ip| ip.to_string())
+                .map_err(|_| anyhow::anyhow!("无效的DNS服务器地址: \"{}\"（需要IPv4或IPv6地址）", token))
+        })
+        .collect()
+}
+
+/// 解析逗号分隔的DNS搜索域列表，如 example.com,corp.local
+pub fn parse_search_domains(input: &str) -> Result<Vec<String>> {
+    split_list(input)
+        .map(|token| {
+            let domain = token.trim_end_matches('.');
+            let valid = !domain.is_empty()
+                && domain.len() <= 253
+                && domain.split('.').all(|label| {
+                    !label.is_empty()
+                        && label.len() <= 63
+                        && !label.starts_with('-')
+                        && !label.ends_with('-')
+                        && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+                });
+            if valid {
+                Ok(domain.to_string())
+            } else {
+                Err(anyhow::anyhow!("无效的搜索域: \"{}\"", token))
+            }
+        })
+        .collect()
+}
+
+fn split_list(input: &str) -> impl Iterator<Item = &str> {
+    input.split(',').map(str::trim).filter(|s| !s.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_resolvectl_link() {
+        let output = "Link 2 (eth0): 192.168.1.1 1.1.1.1#cloudflare-dns.com 2606:4700::1111\n";
+        assert_eq!(parse_resolvectl_link(output), ["192.168.1.1", "1.1.1.1", "2606:4700::1111"]);
+        assert!(parse_resolvectl_link("Link 3 (eth1):\n").is_empty());
+        assert_eq!(parse_resolvectl_link("Link 2 (eth0): corp.local ~.\n"), ["corp.local", "~."]);
+    }
+
+    #[test]
+    fn test_parse_nameservers() {
+        assert_eq!(parse_nameservers(" 1.1.1.1, 2606:4700::1111 ,").unwrap(), vec!["1.1.1.1", "2606:4700::1111"]);
+        assert!(parse_nameservers("").unwrap().is_empty());
+
+        let err = parse_nameservers("8.8.8.8,8.8.4.256").unwrap_err().to_string();
+        assert!(err.contains("\"8.8.4.256\""));
+        assert!(parse_nameservers("dns.google").is_err());
+    }
+
+    #[test]
+    fn test_parse_search_domains() {
+        assert_eq!(parse_search_domains("example.com, corp.local.").unwrap(), vec!["example.com", "corp.local"]);
+        assert!(parse_search_domains("lab").is_ok());
+
+        let err = parse_search_domains("good.com,bad_domain.com").unwrap_err().to_string();
+        assert!(err.contains("\"bad_domain.com\""));
+        assert!(parse_search_domains("-bad.com").is_err());
+        assert!(parse_search_domains("a..b").is_err());
+    }
+}
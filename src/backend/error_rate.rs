@@ -0,0 +1,171 @@
+// 错误/丢包速率告警模块 - 检测错误和丢包计数的异常增长
+use crate::model::TrafficStats;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// 每次采样（约1秒）新增错误+丢包数的默认告警阈值
+pub const DEFAULT_ERROR_RATE_THRESHOLD: u64 = 10;
+/// 相对近期平均值的默认突增倍数
+pub const DEFAULT_ERROR_SPIKE_FACTOR: f64 = 5.0;
+
+/// 计算近期平均值使用的采样数
+const HISTORY_LEN: usize = 30;
+/// 至少积累这么多采样后才判断突增
+const MIN_HISTORY: usize = 5;
+/// 低于此速率不算突增（避免从0跳到1就告警）
+const MIN_SPIKE_RATE: u64 = 3;
+
+/// 触发告警的原因
+#[derive(Debug, Clone, PartialEq)]
+pub enum ErrorAlert {
+    /// 速率超过固定阈值
+    Threshold { rate: u64, threshold: u64 },
+    /// 速率相对近期平均值突增
+    Spike { rate: u64, average: f64 },
+}
+
+impl ErrorAlert {
+    /// 状态栏中显示的说明
+    pub fn message(&self, iface_name: &str) -> String {
+        match self {
+            ErrorAlert::Threshold { rate, threshold } => {
+                format!("{} 错误/丢包速率 {}/s，超过阈值 {}/s", iface_name, rate, threshold)
+            }
+            ErrorAlert::Spike { rate, average } => {
+                format!("{} 错误/丢包速率突增至 {}/s（近期平均 {:.1}/s）", iface_name, rate, average)
+            }
+        }
+    }
+}
+
+/// 错误/丢包速率监控器
+#[derive(Debug)]
+pub struct ErrorRateMonitor {
+    threshold: u64,
+    spike_factor: f64,
+    last_counts: HashMap<String, u64>,
+    history: HashMap<String, VecDeque<u64>>,
+    alerting: HashSet<String>,
+}
+
+impl Default for ErrorRateMonitor {
+    fn default() -> Self {
+        Self {
+            threshold: DEFAULT_ERROR_RATE_THRESHOLD,
+            spike_factor: DEFAULT_ERROR_SPIKE_FACTOR,
+            last_counts: HashMap::new(),
+            history: HashMap::new(),
+            alerting: HashSet::new(),
+        }
+    }
+}
+
+impl ErrorRateMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置告警阈值和突增倍数（0表示不启用对应的判断）
+    pub fn with_limits(mut self, threshold: u64, spike_factor: f64) -> Self {
+        self.threshold = threshold;
+        self.spike_factor = spike_factor;
+        self
+    }
+
+    /// 记录一次采样，接口新进入告警状态时返回告警原因
+    pub fn observe(&mut self, iface_name: &str, stats: &TrafficStats) -> Option<ErrorAlert> {
+        let total = stats.rx_errors + stats.tx_errors + stats.rx_dropped + stats.tx_dropped;
+        // 首次采样只记录基准；计数器被重置时按0处理
+        let last = self.last_counts.insert(iface_name.to_string(), total)?;
+        let rate = total.saturating_sub(last);
+
+        let history = self.history.entry(iface_name.to_string()).or_default();
+        let alert = evaluate(rate, history, self.threshold, self.spike_factor);
+        if history.len() == HISTORY_LEN {
+            history.pop_front();
+        }
+        history.push_back(rate);
+
+        match alert {
+            Some(alert) => self.alerting.insert(iface_name.to_string()).then_some(alert),
+            None => {
+                self.alerting.remove(iface_name);
+                None
+            }
+        }
+    }
+
+    /// 接口当前是否处于告警状态
+    pub fn is_alerting(&self, iface_name: &str) -> bool {
+        self.alerting.contains(iface_name)
+    }
+
+    /// 最近一次采样的错误/丢包速率
+    pub fn rate(&self, iface_name: &str) -> Option<u64> {
+        self.history.get(iface_name).and_then(|history| history.back().copied())
+    }
+}
+
+/// 判断本次速率是否超过阈值或相对历史平均值突增
+fn evaluate(rate: u64, history: &VecDeque<u64>, threshold: u64, spike_factor: f64) -> Option<ErrorAlert> {
+    if threshold > 0 && rate >= threshold {
+        return Some(ErrorAlert::Threshold { rate, threshold });
+    }
+    if spike_factor > 0.0 && rate >= MIN_SPIKE_RATE && history.len() >= MIN_HISTORY {
+        let average = history.iter().sum::<u64>() as f64 / history.len() as f64;
+        // 平均值为0时任何达到最小值的速率都算突增
+        if rate as f64 > average * spike_factor {
+            return Some(ErrorAlert::Spike { rate, average });
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(rx_errors: u64, tx_dropped: u64) -> TrafficStats {
+        TrafficStats { rx_errors, tx_dropped, ..Default::default() }
+    }
+
+    #[test]
+    fn test_threshold_alert() {
+        let mut monitor = ErrorRateMonitor::new().with_limits(10, 0.0);
+
+        // 首次采样不计算速率
+        assert_eq!(monitor.observe("eth0", &stats(1000, 0)), None);
+        assert_eq!(monitor.observe("eth0", &stats(1005, 0)), None);
+        assert_eq!(monitor.rate("eth0"), Some(5));
+
+        assert_eq!(
+            monitor.observe("eth0", &stats(1010, 6)),
+            Some(ErrorAlert::Threshold { rate: 11, threshold: 10 })
+        );
+        assert!(monitor.is_alerting("eth0"));
+
+        // 持续超过阈值时只在进入告警时返回一次
+        assert_eq!(monitor.observe("eth0", &stats(1030, 6)), None);
+        assert!(monitor.is_alerting("eth0"));
+
+        monitor.observe("eth0", &stats(1031, 6));
+        assert!(!monitor.is_alerting("eth0"));
+    }
+
+    #[test]
+    fn test_spike_alert() {
+        let mut monitor = ErrorRateMonitor::new().with_limits(0, 5.0);
+        monitor.observe("eth0", &stats(0, 0));
+        for i in 1..=MIN_HISTORY as u64 {
+            assert_eq!(monitor.observe("eth0", &stats(i, 0)), None);
+        }
+
+        // 平均1/s，突增到6/s
+        let alert = monitor.observe("eth0", &stats(MIN_HISTORY as u64 + 6, 0));
+        assert_eq!(alert, Some(ErrorAlert::Spike { rate: 6, average: 1.0 }));
+
+        // 计数器重置不会告警
+        monitor.observe("eth1", &stats(500, 0));
+        assert_eq!(monitor.observe("eth1", &stats(0, 0)), None);
+        assert_eq!(monitor.rate("eth1"), Some(0));
+    }
+}
@@ -0,0 +1,126 @@
+// ethtool模块 - 读取物理网卡的协商速率、双工模式、链路检测状态和出厂MAC地址
+use crate::model::{Duplex, LinkInfo};
+use crate::utils::command::execute_command_stdout;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex};
+
+/// 接口名和其sysfs设备路径，网卡更换或改名后缓存失效
+type DeviceKey = (String, Option<PathBuf>);
+
+/// 出厂MAC地址缓存
+static PERMANENT_ADDRESSES: LazyLock<Mutex<HashMap<DeviceKey, Option<String>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 读取 `ethtool <iface>` 的完整输出，链路信息和网络唤醒设置都从这一份输出解析
+pub fn get_settings(iface_name: &str) -> Option<String> {
+    execute_command_stdout("ethtool", &[iface_name]).ok()
+}
+
+/// 解析 ethtool 输出中的 Speed、Duplex、Auto-negotiation 和 Link detected 行
+///
+/// 链路断开时速率和双工通常为 "Unknown!"，解析为None
+pub fn parse_link_info(output: &str) -> LinkInfo {
+    let mut info = LinkInfo::default();
+
+    for line in output.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            // 示例: Speed: 1000Mb/s
+            "Speed" => info.speed_mbps = value.strip_suffix("Mb/s").and_then(|speed| speed.parse().ok()),
+            "Duplex" => {
+                info.duplex = match value {
+                    "Full" => Some(Duplex::Full),
+                    "Half" => Some(Duplex::Half),
+                    _ => None,
+                }
+            }
+            "Auto-negotiation" => info.autoneg = parse_on_off(value),
+            "Link detected" => info.link_detected = parse_on_off(value),
+            _ => {}
+        }
+    }
+
+    info
+}
+
+/// 读取网卡的出厂MAC地址（ethtool -P），不支持或全零时为None
+///
+/// 出厂地址不会变化，同一设备只执行一次ethtool -P
+pub fn get_permanent_address(iface_name: &str) -> Option<String> {
+    let device = fs::read_link(format!("/sys/class/net/{}/device", iface_name)).ok();
+    let key = (iface_name.to_string(), device);
+    if let Some(cached) = PERMANENT_ADDRESSES.lock().ok().and_then(|cache| cache.get(&key).cloned()) {
+        return cached;
+    }
+
+    let mac = execute_command_stdout("ethtool", &["-P", iface_name])
+        .ok()
+        .and_then(|output| parse_permanent_address(&output));
+    if let Ok(mut cache) = PERMANENT_ADDRESSES.lock() {
+        cache.insert(key, mac.clone());
+    }
+    mac
+}
+
+/// 解析 "Permanent address: 52:54:00:12:34:56"，虚拟网卡通常输出全零地址
+pub fn parse_permanent_address(output: &str) -> Option<String> {
+    let (_, mac) = output.lines().find_map(|line| line.split_once("Permanent address:"))?;
+    let mac = mac.trim().to_lowercase();
+    (!mac.is_empty() && mac != "00:00:00:00:00:00").then_some(mac)
+}
+
+fn parse_on_off(value: &str) -> Option<bool> {
+    match value {
+        "on" | "yes" => Some(true),
+        "off" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_link_info() {
+        let output = "Settings for enp3s0:
+\tSupported ports: [ TP ]
+\tSpeed: 1000Mb/s
+\tDuplex: Full
+\tAuto-negotiation: on
+\tSupports Wake-on: pumbg
+\tWake-on: d
+\tLink detected: yes
+";
+        let info = parse_link_info(output);
+        assert_eq!(info.speed_mbps, Some(1000));
+        assert_eq!(info.duplex, Some(Duplex::Full));
+        assert_eq!(info.autoneg, Some(true));
+        assert_eq!(info.link_detected, Some(true));
+
+        // 链路断开时速率和双工未知
+        let down = "Settings for eth1:\n\tSpeed: Unknown!\n\tDuplex: Unknown! (255)\n\tAuto-negotiation: off\n\tLink detected: no\n";
+        let info = parse_link_info(down);
+        assert_eq!(info.speed_mbps, None);
+        assert_eq!(info.duplex, None);
+        assert_eq!(info.autoneg, Some(false));
+        assert_eq!(info.link_detected, Some(false));
+
+        assert_eq!(parse_link_info(""), LinkInfo::default());
+    }
+
+    #[test]
+    fn test_parse_permanent_address() {
+        assert_eq!(
+            parse_permanent_address("Permanent address: 52:54:00:AB:CD:EF\n").as_deref(),
+            Some("52:54:00:ab:cd:ef")
+        );
+        assert_eq!(parse_permanent_address("Permanent address: 00:00:00:00:00:00\n"), None);
+        assert_eq!(parse_permanent_address("Cannot read permanent address\n"), None);
+    }
+}
@@ -0,0 +1,59 @@
+// 链路抖动检测模块 - 统计会话期间接口状态的变化次数
+use crate::model::LinkHealth;
+use std::collections::HashMap;
+
+/// 链路抖动检测器
+#[derive(Debug, Default)]
+pub struct FlapDetector {
+    last_health: HashMap<String, LinkHealth>,
+    counts: HashMap<String, u32>,
+}
+
+impl FlapDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录一次观测结果，状态发生变化时计数加一
+    pub fn observe(&mut self, iface_name: &str, health: LinkHealth) {
+        if let Some(last) = self.last_health.insert(iface_name.to_string(), health)
+            && last != health {
+            *self.counts.entry(iface_name.to_string()).or_insert(0) += 1;
+        }
+    }
+
+    /// 获取接口的抖动次数
+    pub fn count(&self, iface_name: &str) -> u32 {
+        self.counts.get(iface_name).copied().unwrap_or(0)
+    }
+
+    /// 清零接口的抖动计数
+    pub fn reset(&mut self, iface_name: &str) {
+        self.counts.remove(iface_name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flap_counting() {
+        let mut detector = FlapDetector::new();
+
+        // 首次观测不计数
+        detector.observe("eth0", LinkHealth::Up);
+        assert_eq!(detector.count("eth0"), 0);
+
+        detector.observe("eth0", LinkHealth::Up);
+        detector.observe("eth0", LinkHealth::NoCarrier);
+        detector.observe("eth0", LinkHealth::Up);
+        assert_eq!(detector.count("eth0"), 2);
+        assert_eq!(detector.count("eth1"), 0);
+
+        detector.reset("eth0");
+        assert_eq!(detector.count("eth0"), 0);
+        detector.observe("eth0", LinkHealth::Down);
+        assert_eq!(detector.count("eth0"), 1);
+    }
+}
@@ -0,0 +1,132 @@
+// 连通性检查模块 - 依次检查载波、地址、网关和DNS，判断接口是否端到端可用
+use crate::backend::runtime;
+use crate::utils::command::command_success;
+use std::net::ToSocketAddrs;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// DNS解析测试使用的域名
+const DNS_TEST_HOST: &str = "www.baidu.com";
+
+/// 单个检查项的状态
+#[derive(Debug, Clone, PartialEq)]
+pub enum CheckStatus {
+    Pending,          // 等待执行
+    Running,          // 正在执行
+    Passed(String),   // 通过（附说明）
+    Failed(String),   // 失败（附原因）
+    Skipped(String),  // 跳过（附原因）
+}
+
+/// 检查项
+#[derive(Debug, Clone)]
+pub struct HealthStep {
+    pub name: &'static str,
+    pub status: CheckStatus,
+}
+
+/// 检查报告（供界面轮询显示）
+#[derive(Debug, Clone, Default)]
+pub struct HealthReport {
+    pub iface_name: String,
+    pub steps: Vec<HealthStep>,
+    pub finished: bool,
+}
+
+impl HealthReport {
+    /// 所有检查项都通过
+    pub fn all_passed(&self) -> bool {
+        self.steps.iter().all(|step| matches!(step.status, CheckStatus::Passed(_)))
+    }
+}
+
+/// 在后台线程中启动连通性检查
+pub fn spawn_check(iface_name: &str, gateway: Option<String>) -> Arc<Mutex<HealthReport>> {
+    let report = Arc::new(Mutex::new(HealthReport {
+        iface_name: iface_name.to_string(),
+        steps: ["载波", "IP地址", "网关可达", "DNS解析"]
+            .into_iter()
+            .map(|name| HealthStep { name, status: CheckStatus::Pending })
+            .collect(),
+        finished: false,
+    }));
+
+    let shared = Arc::clone(&report);
+    let iface_name = iface_name.to_string();
+    thread::spawn(move || {
+        let update = |index: usize, status: CheckStatus| {
+            if let Ok(mut r) = shared.lock() {
+                r.steps[index].status = status;
+            }
+        };
+
+        update(0, CheckStatus::Running);
+        update(0, check_carrier(&iface_name));
+
+        update(1, CheckStatus::Running);
+        update(1, check_address(&iface_name));
+
+        update(2, CheckStatus::Running);
+        update(2, check_gateway(&iface_name, gateway.as_deref()));
+
+        update(3, CheckStatus::Running);
+        update(3, check_dns(DNS_TEST_HOST));
+
+        if let Ok(mut r) = shared.lock() {
+            r.finished = true;
+        }
+    });
+
+    report
+}
+
+fn check_carrier(iface_name: &str) -> CheckStatus {
+    match runtime::read_link_state(iface_name).1 {
+        Some(true) => CheckStatus::Passed("已检测到载波".to_string()),
+        Some(false) => CheckStatus::Failed("无载波（网线未连接？）".to_string()),
+        None => CheckStatus::Skipped("无法读取载波状态".to_string()),
+    }
+}
+
+fn check_address(iface_name: &str) -> CheckStatus {
+    // 重新读取地址，避免使用过时的快照
+    let interfaces = match runtime::list_interfaces(false) {
+        Ok(interfaces) => interfaces,
+        Err(e) => return CheckStatus::Failed(format!("读取接口失败: {}", e)),
+    };
+
+    let Some(iface) = interfaces.into_iter().find(|i| i.name == iface_name) else {
+        return CheckStatus::Failed("接口不存在".to_string());
+    };
+
+    let global_v6 = iface.ipv6_details.iter().find(|a| a.scope == "global");
+    if let Some(addr) = iface.ipv4_addresses.first() {
+        CheckStatus::Passed(addr.clone())
+    } else if let Some(addr) = global_v6 {
+        CheckStatus::Passed(addr.address.clone())
+    } else {
+        CheckStatus::Failed("没有可用的IP地址".to_string())
+    }
+}
+
+fn check_gateway(iface_name: &str, gateway: Option<&str>) -> CheckStatus {
+    let Some(gateway) = gateway else {
+        return CheckStatus::Skipped("未配置默认网关".to_string());
+    };
+
+    if command_success("ping", &["-c", "2", "-W", "1", "-I", iface_name, gateway]) {
+        CheckStatus::Passed(format!("{} 可达", gateway))
+    } else {
+        CheckStatus::Failed(format!("{} 不可达", gateway))
+    }
+}
+
+fn check_dns(host: &str) -> CheckStatus {
+    match (host, 80).to_socket_addrs() {
+        Ok(mut addrs) => match addrs.next() {
+            Some(addr) => CheckStatus::Passed(format!("{} -> {}", host, addr.ip())),
+            None => CheckStatus::Failed(format!("{} 没有解析结果", host)),
+        },
+        Err(e) => CheckStatus::Failed(format!("解析 {} 失败: {}", host, e)),
+    }
+}
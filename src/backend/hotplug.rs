@@ -0,0 +1,101 @@
+// 热插拔检测模块 - 轮询 /sys/class/net 发现接口的增加和删除
+use std::collections::BTreeSet;
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
+
+/// 接口集合变化后等待稳定的时间，避免容器批量启停时反复刷新
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// 热插拔检测器
+#[derive(Debug, Default)]
+pub struct HotplugWatcher {
+    /// 排序后接口名列表的哈希，每次轮询只比较哈希值
+    known: u64,
+    changed_at: Option<Instant>,
+}
+
+impl HotplugWatcher {
+    /// 以当前接口集合为起点创建检测器
+    pub fn new() -> Self {
+        Self {
+            known: hash_names(&read_interface_names()),
+            changed_at: None,
+        }
+    }
+
+    /// 检查接口集合，变化稳定后返回true表示需要刷新
+    pub fn poll(&mut self) -> bool {
+        self.observe(read_interface_names(), Instant::now())
+    }
+
+    fn observe(&mut self, names: BTreeSet<String>, now: Instant) -> bool {
+        let hash = hash_names(&names);
+        if hash != self.known {
+            // 集合仍在变化，重新开始计时
+            self.known = hash;
+            self.changed_at = Some(now);
+            return false;
+        }
+
+        match self.changed_at {
+            Some(changed_at) if now.duration_since(changed_at) >= DEBOUNCE => {
+                self.changed_at = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// 计算接口名集合的哈希（BTreeSet已按名称排序）
+fn hash_names(names: &BTreeSet<String>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    names.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// 读取 /sys/class/net 下的接口名称
+fn read_interface_names() -> BTreeSet<String> {
+    fs::read_dir("/sys/class/net")
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(list: &[&str]) -> BTreeSet<String> {
+        list.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_hotplug_debounce() {
+        let mut watcher = HotplugWatcher {
+            known: hash_names(&names(&["lo", "eth0"])),
+            changed_at: None,
+        };
+        let start = Instant::now();
+        assert!(!watcher.observe(names(&["lo", "eth0"]), start));
+
+        // 新增veth后不立即刷新
+        let t1 = start + Duration::from_secs(1);
+        assert!(!watcher.observe(names(&["lo", "eth0", "veth1"]), t1));
+
+        // 稳定期内再次变化，重新计时
+        let t2 = t1 + Duration::from_millis(300);
+        assert!(!watcher.observe(names(&["lo", "eth0", "veth1", "veth2"]), t2));
+        assert!(!watcher.observe(names(&["lo", "eth0", "veth1", "veth2"]), t2 + Duration::from_millis(300)));
+
+        // 稳定后刷新一次
+        assert!(watcher.observe(names(&["lo", "eth0", "veth1", "veth2"]), t2 + DEBOUNCE));
+        assert!(!watcher.observe(names(&["lo", "eth0", "veth1", "veth2"]), t2 + DEBOUNCE * 2));
+    }
+}
@@ -1,7 +1,24 @@
 // 后端模块
 pub mod runtime;
+pub mod ethtool;
+#[cfg(feature = "netlink")]
+pub mod netlink;
+pub mod apply;
 pub mod traffic;
 pub mod owner_detection;
+pub mod owner_inspect;
 pub mod removal;
 pub mod netplan;
+pub mod dns;
+pub mod pmtu;
+pub mod tc;
+pub mod diagnostics;
+pub mod flap;
+pub mod error_rate;
+pub mod health;
+pub mod hotplug;
+pub mod policy;
+pub mod routes;
+pub mod neighbors;
+pub mod wol;
 
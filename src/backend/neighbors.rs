@@ -0,0 +1,79 @@
+// 邻居表模块 - 读取接口的ARP/NDP邻居缓存（ip neigh）
+use crate::utils::command::execute_command_stdout;
+use anyhow::{Context, Result};
+
+/// 邻居缓存中的一个条目
+#[derive(Debug, Clone, PartialEq)]
+pub struct Neighbor {
+    pub ip: String,
+    pub mac: Option<String>,  // 解析失败（FAILED/INCOMPLETE）的条目没有MAC地址
+    pub state: String,        // NUD状态，如 REACHABLE、STALE、FAILED
+    pub router: bool,         // IPv6邻居声明自己是路由器
+}
+
+/// 读取接口的邻居表，邻居表为空时返回空列表
+pub fn list_neighbors(iface_name: &str) -> Result<Vec<Neighbor>> {
+    let output = execute_command_stdout("ip", &["neigh", "show", "dev", iface_name])
+        .with_context(|| format!("查询 {} 的邻居表失败", iface_name))?;
+    Ok(parse_neighbors(&output))
+}
+
+/// 解析 ip neigh show dev X 输出，如 "192.168.1.1 lladdr 52:54:00:12:34:56 REACHABLE"
+///
+/// 状态是行尾的大写单词，一个条目可能同时有多个状态（如 "STALE PROBE"），取最后一个
+pub fn parse_neighbors(output: &str) -> Vec<Neighbor> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            let ip = tokens.first()?.to_string();
+            let mac = tokens
+                .iter()
+                .position(|token| *token == "lladdr")
+                .and_then(|i| tokens.get(i + 1))
+                .map(|mac| mac.to_string());
+            let state = tokens
+                .iter()
+                .skip(1)
+                .rev()
+                .find(|token| token.chars().all(|c| c.is_ascii_uppercase()))
+                .map(|state| state.to_string())
+                .unwrap_or_else(|| "NONE".to_string());
+            Some(Neighbor {
+                ip,
+                mac,
+                state,
+                router: tokens.contains(&"router"),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_neighbors() {
+        let output = "192.168.1.1 lladdr 52:54:00:12:34:56 REACHABLE
+192.168.1.20 lladdr 52:54:00:aa:bb:cc STALE
+192.168.1.99  FAILED
+fe80::1 lladdr 52:54:00:12:34:56 router DELAY
+";
+        let neighbors = parse_neighbors(output);
+        assert_eq!(neighbors.len(), 4);
+        assert_eq!(neighbors[0], Neighbor {
+            ip: "192.168.1.1".to_string(),
+            mac: Some("52:54:00:12:34:56".to_string()),
+            state: "REACHABLE".to_string(),
+            router: false,
+        });
+        assert_eq!(neighbors[1].state, "STALE");
+        assert_eq!(neighbors[2].mac, None);
+        assert_eq!(neighbors[2].state, "FAILED");
+        assert!(neighbors[3].router);
+        assert_eq!(neighbors[3].state, "DELAY");
+
+        assert!(parse_neighbors("").is_empty());
+    }
+}
@@ -0,0 +1,299 @@
+// netlink模块 - 通过rtnetlink一次性读取所有接口和地址，避免逐个接口执行ip命令
+use crate::backend::runtime::ipv6_prefix64;
+use crate::model::{Ipv4AddressInfo, Ipv6AddressInfo};
+use anyhow::{Context, Result};
+use neli::attr::Attribute;
+use neli::consts::nl::{NlmF, NlmFFlags};
+use neli::consts::rtnl::{Arphrd, Ifa, IfaF, IfaFFlags, Ifla, Iff, IffFlags, RtAddrFamily, Rtm};
+use neli::consts::socket::NlFamily;
+use neli::nl::{NlPayload, Nlmsghdr};
+use neli::rtnl::{Ifaddrmsg, Ifinfomsg};
+use neli::socket::NlSocketHandle;
+use neli::types::RtBuffer;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+/// IFLA_PROP_LIST / IFLA_ALT_IFNAME（备用名称列表，内核5.5+）
+const IFLA_PROP_LIST: u16 = 52;
+const IFLA_ALT_IFNAME: u16 = 53;
+/// 属性类型中去掉 NLA_F_NESTED / NLA_F_NET_BYTEORDER 标志位
+const NLA_TYPE_MASK: u16 = 0x3fff;
+/// IFA_FLAGS 中的扩展标志（ifa_flags字段只有低8位）
+const IFA_F_TEMPORARY: u32 = 0x01;
+const IFA_F_DEPRECATED: u32 = 0x20;
+const IFA_F_PERMANENT: u32 = 0x80;
+/// 地址生存时间为永久
+const INFINITY_LIFE_TIME: u32 = u32::MAX;
+
+/// RTM_GETLINK 返回的链路信息
+#[derive(Debug, Clone, PartialEq)]
+pub struct Link {
+    pub ifindex: u32,
+    pub name: String,
+    pub up: bool,
+    pub point_to_point: bool,
+    pub mtu: u32,
+    pub mac_address: Option<String>,  // 仅以太网类链路
+    pub altnames: Vec<String>,
+}
+
+/// RTM_GETADDR 返回的地址
+#[derive(Debug, Clone)]
+pub enum Address {
+    V4(Ipv4AddressInfo),
+    V6(Ipv6AddressInfo),
+}
+
+/// 读取所有链路（相当于 ip -o link show）
+pub fn dump_links() -> Result<Vec<Link>> {
+    let mut socket = connect()?;
+    let request = Ifinfomsg::new(
+        RtAddrFamily::Unspecified,
+        Arphrd::None,
+        0,
+        IffFlags::empty(),
+        IffFlags::empty(),
+        RtBuffer::new(),
+    );
+    socket
+        .send(dump_request(Rtm::Getlink, request))
+        .context("发送RTM_GETLINK请求失败")?;
+
+    let mut links = Vec::new();
+    for response in socket.iter::<Rtm, Ifinfomsg>(false) {
+        let response = response.map_err(|e| anyhow::anyhow!("读取链路列表失败: {}", e))?;
+        match response.nl_payload {
+            NlPayload::Payload(msg) if response.nl_type == Rtm::Newlink => links.extend(parse_link(&msg)),
+            NlPayload::Err(e) => anyhow::bail!("读取链路列表失败: {}", e),
+            _ => {}
+        }
+    }
+    Ok(links)
+}
+
+/// 读取所有接口的IPv4/IPv6地址（相当于 ip -o addr show），返回 (接口索引, 地址)
+pub fn dump_addresses() -> Result<Vec<(u32, Address)>> {
+    let mut socket = connect()?;
+    let request = Ifaddrmsg {
+        ifa_family: RtAddrFamily::Unspecified,
+        ifa_prefixlen: 0,
+        ifa_flags: IfaFFlags::empty(),
+        ifa_scope: 0,
+        ifa_index: 0,
+        rtattrs: RtBuffer::new(),
+    };
+    socket
+        .send(dump_request(Rtm::Getaddr, request))
+        .context("发送RTM_GETADDR请求失败")?;
+
+    let mut addresses = Vec::new();
+    for response in socket.iter::<Rtm, Ifaddrmsg>(false) {
+        let response = response.map_err(|e| anyhow::anyhow!("读取地址列表失败: {}", e))?;
+        match response.nl_payload {
+            NlPayload::Payload(msg) if response.nl_type == Rtm::Newaddr => {
+                if let Some(address) = parse_address(&msg) {
+                    addresses.push((msg.ifa_index as u32, address));
+                }
+            }
+            NlPayload::Err(e) => anyhow::bail!("读取地址列表失败: {}", e),
+            _ => {}
+        }
+    }
+    Ok(addresses)
+}
+
+fn connect() -> Result<NlSocketHandle> {
+    NlSocketHandle::connect(NlFamily::Route, None, &[]).context("无法连接rtnetlink")
+}
+
+fn dump_request<P: neli::Size>(rtm: Rtm, payload: P) -> Nlmsghdr<Rtm, P> {
+    Nlmsghdr::new(
+        None,
+        rtm,
+        NlmFFlags::new(&[NlmF::Request, NlmF::Dump]),
+        None,
+        None,
+        NlPayload::Payload(payload),
+    )
+}
+
+fn parse_link(msg: &Ifinfomsg) -> Option<Link> {
+    let mut link = Link {
+        ifindex: msg.ifi_index as u32,
+        name: String::new(),
+        up: msg.ifi_flags.contains(&Iff::Up),
+        point_to_point: msg.ifi_flags.contains(&Iff::Pointopoint),
+        mtu: 0,
+        mac_address: None,
+        altnames: Vec::new(),
+    };
+
+    for attr in msg.rtattrs.iter() {
+        let payload = attr.payload().as_ref();
+        match u16::from(attr.rta_type) & NLA_TYPE_MASK {
+            t if t == u16::from(Ifla::Ifname) => link.name = c_string(payload),
+            t if t == u16::from(Ifla::Mtu) => link.mtu = read_u32(payload).unwrap_or(0),
+            // 与 ip link 的 link/ether 一致，只显示以太网类链路的MAC地址
+            t if t == u16::from(Ifla::Address) && msg.ifi_type == Arphrd::Ether => {
+                link.mac_address = format_mac(payload);
+            }
+            IFLA_PROP_LIST => link.altnames = parse_altnames(payload),
+            _ => {}
+        }
+    }
+
+    Some(link).filter(|link| !link.name.is_empty())
+}
+
+fn parse_address(msg: &Ifaddrmsg) -> Option<Address> {
+    let mut local = None;
+    let mut address = None;
+    // 内核提供IFA_FLAGS时以其为准，否则使用ifa_flags中的低8位
+    let mut flags = [(IfaF::Temporary, IFA_F_TEMPORARY), (IfaF::Deprecated, IFA_F_DEPRECATED), (IfaF::Permanent, IFA_F_PERMANENT)]
+        .iter()
+        .filter(|(flag, _)| msg.ifa_flags.contains(flag))
+        .fold(0, |flags, (_, bit)| flags | bit);
+    let mut lifetimes = (None, None);
+
+    for attr in msg.rtattrs.iter() {
+        let payload = attr.payload().as_ref();
+        match attr.rta_type {
+            Ifa::Local => local = Some(payload),
+            Ifa::Address => address = Some(payload),
+            Ifa::Flags => flags = read_u32(payload).unwrap_or(flags),
+            Ifa::Cacheinfo => lifetimes = parse_cacheinfo(payload),
+            _ => {}
+        }
+    }
+    let (preferred_lft, valid_lft) = lifetimes;
+
+    match msg.ifa_family {
+        // 点对点接口的IFA_ADDRESS是对端地址，本机地址在IFA_LOCAL中
+        RtAddrFamily::Inet => {
+            let bytes: [u8; 4] = local.or(address)?.try_into().ok()?;
+            Some(Address::V4(Ipv4AddressInfo {
+                address: format!("{}/{}", Ipv4Addr::from(bytes), msg.ifa_prefixlen),
+                dynamic: flags & IFA_F_PERMANENT == 0,
+                valid_lft,
+                preferred_lft,
+            }))
+        }
+        RtAddrFamily::Inet6 => {
+            let bytes: [u8; 16] = address.or(local)?.try_into().ok()?;
+            let ip = Ipv6Addr::from(bytes);
+            Some(Address::V6(Ipv6AddressInfo {
+                address: format!("{}/{}", ip, msg.ifa_prefixlen),
+                prefix: ipv6_prefix64(&ip),
+                scope: scope_name(msg.ifa_scope),
+                temporary: flags & IFA_F_TEMPORARY != 0,
+                deprecated: flags & IFA_F_DEPRECATED != 0,
+            }))
+        }
+        _ => None,
+    }
+}
+
+/// 从IFLA_PROP_LIST中取出所有IFLA_ALT_IFNAME
+fn parse_altnames(payload: &[u8]) -> Vec<String> {
+    nested_attributes(payload)
+        .into_iter()
+        .filter(|(attr_type, _)| attr_type & NLA_TYPE_MASK == IFLA_ALT_IFNAME)
+        .map(|(_, value)| c_string(value))
+        .collect()
+}
+
+/// 拆分嵌套属性（每项为 长度u16、类型u16、数据，按4字节对齐）
+fn nested_attributes(mut payload: &[u8]) -> Vec<(u16, &[u8])> {
+    let mut attrs = Vec::new();
+    while payload.len() >= 4 {
+        let len = u16::from_ne_bytes([payload[0], payload[1]]) as usize;
+        let attr_type = u16::from_ne_bytes([payload[2], payload[3]]);
+        if len < 4 || len > payload.len() {
+            break;
+        }
+        attrs.push((attr_type, &payload[4..len]));
+        payload = &payload[((len + 3) & !3).min(payload.len())..];
+    }
+    attrs
+}
+
+/// 解析 struct ifa_cacheinfo，返回 (preferred_lft, valid_lft)，永久为None
+fn parse_cacheinfo(payload: &[u8]) -> (Option<u64>, Option<u64>) {
+    let lifetime = |offset: usize| {
+        payload
+            .get(offset..offset + 4)
+            .and_then(read_u32)
+            .filter(|&secs| secs != INFINITY_LIFE_TIME)
+            .map(u64::from)
+    };
+    (lifetime(0), lifetime(4))
+}
+
+/// 与 ip addr 输出一致的作用域名称
+fn scope_name(scope: u8) -> String {
+    match scope {
+        0 => "global".to_string(),
+        200 => "site".to_string(),
+        253 => "link".to_string(),
+        254 => "host".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn format_mac(bytes: &[u8]) -> Option<String> {
+    if bytes.len() != 6 {
+        return None;
+    }
+    Some(bytes.iter().map(|b| format!("{:02x}", b)).collect::<Vec<_>>().join(":"))
+}
+
+fn c_string(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+fn read_u32(bytes: &[u8]) -> Option<u32> {
+    Some(u32::from_ne_bytes(bytes.get(..4)?.try_into().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attr(attr_type: u16, value: &[u8]) -> Vec<u8> {
+        let mut bytes = ((value.len() + 4) as u16).to_ne_bytes().to_vec();
+        bytes.extend(attr_type.to_ne_bytes());
+        bytes.extend(value);
+        bytes.resize((bytes.len() + 3) & !3, 0);
+        bytes
+    }
+
+    #[test]
+    fn test_parse_altnames() {
+        let mut payload = attr(IFLA_ALT_IFNAME, b"enp0s3f0\0");
+        payload.extend(attr(IFLA_ALT_IFNAME, b"eno1\0"));
+        payload.extend(attr(1, b"x"));
+        assert_eq!(parse_altnames(&payload), ["enp0s3f0", "eno1"]);
+
+        // 长度字段超出数据时停止解析
+        assert!(parse_altnames(&[0xff, 0x00, 53, 0]).is_empty());
+    }
+
+    #[test]
+    fn test_link_attribute_helpers() {
+        assert_eq!(format_mac(&[0x52, 0x54, 0, 0x12, 0x34, 0x56]).as_deref(), Some("52:54:00:12:34:56"));
+        assert_eq!(format_mac(&[10, 0, 0, 1]), None);
+        assert_eq!(c_string(b"eth0\0\0"), "eth0");
+        assert_eq!(scope_name(253), "link");
+    }
+
+    #[test]
+    fn test_parse_cacheinfo() {
+        let mut payload = 3600u32.to_ne_bytes().to_vec();
+        payload.extend(7200u32.to_ne_bytes());
+        payload.extend([0; 8]);
+        assert_eq!(parse_cacheinfo(&payload), (Some(3600), Some(7200)));
+
+        let forever = [u32::MAX.to_ne_bytes(), u32::MAX.to_ne_bytes()].concat();
+        assert_eq!(parse_cacheinfo(&forever), (None, None));
+    }
+}
@@ -1,13 +1,29 @@
 // Netplan配置管理模块 - 管理持久化网络配置
+use crate::model::NetInterface;
+use crate::utils::command::{copy_privileged, execute_privileged, read_privileged, set_mode_privileged, use_sudo, write_privileged};
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use nix::pty::openpty;
+use nix::sys::signal::{kill, Signal};
+use nix::unistd::Pid;
+use std::collections::{BTreeSet, HashMap};
 use std::fs;
+use std::io::Read;
+use std::os::fd::OwnedFd;
 use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+use tracing::info;
+
+/// 记录由nicman管理的接口的清单文件（位于Netplan配置目录，netplan只读取.yaml文件）
+///
+/// serde_yaml重写文件时会丢弃注释，因此不在YAML中写标记注释，而是单独维护清单。
+const MANAGED_MANIFEST: &str = ".nicman-managed";
 
 /// Netplan配置管理器
 pub struct NetplanManager {
     config_dir: PathBuf,
+    preferred_file: Option<PathBuf>,  // 用户指定的目标配置文件
 }
 
 impl NetplanManager {
@@ -15,9 +31,16 @@ impl NetplanManager {
     pub fn new() -> Self {
         Self {
             config_dir: PathBuf::from("/etc/netplan"),
+            preferred_file: None,
         }
     }
 
+    /// 指定新接口配置写入的目标文件（相对路径相对于/etc/netplan）
+    pub fn with_preferred_file(mut self, file: Option<PathBuf>) -> Self {
+        self.preferred_file = file.map(|f| self.config_dir.join(f));
+        self
+    }
+
     /// 列出所有Netplan配置文件
     pub fn list_config_files(&self) -> Result<Vec<PathBuf>> {
         let mut files = Vec::new();
@@ -30,7 +53,7 @@ impl NetplanManager {
             let entry = entry?;
             let path = entry.path();
 
-            if path.is_file() && path.extension().map_or(false, |ext| ext == "yaml" || ext == "yml") {
+            if path.is_file() && path.extension().is_some_and(|ext| ext == "yaml" || ext == "yml") {
                 files.push(path);
             }
         }
@@ -41,7 +64,7 @@ impl NetplanManager {
 
     /// 读取Netplan配置
     pub fn read_config(&self, file_path: &Path) -> Result<NetplanConfig> {
-        let content = fs::read_to_string(file_path)
+        let content = read_privileged(file_path)
             .with_context(|| format!("读取配置文件失败: {:?}", file_path))?;
 
         serde_yaml::from_str(&content)
@@ -53,7 +76,7 @@ impl NetplanManager {
         let yaml = serde_yaml::to_string(config)
             .context("序列化配置失败")?;
 
-        fs::write(file_path, yaml)
+        write_privileged(file_path, &yaml)
             .with_context(|| format!("写入配置文件失败: {:?}", file_path))
     }
 
@@ -62,19 +85,16 @@ impl NetplanManager {
         let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
         let backup_path = file_path.with_extension(format!("yaml.backup.{}", timestamp));
 
-        fs::copy(file_path, &backup_path)
+        copy_privileged(file_path, &backup_path)
             .with_context(|| format!("备份配置文件失败: {:?}", file_path))?;
 
-        println!("✅ 已备份配置到: {:?}", backup_path);
+        info!("已备份配置到: {:?}", backup_path);
         Ok(backup_path)
     }
 
-    /// 应用Netplan配置
-    #[allow(dead_code)]
-    pub fn apply(&self) -> Result<()> {
-        let output = std::process::Command::new("netplan")
-            .arg("apply")
-            .output()
+    /// 应用Netplan配置，返回netplan的输出（包括警告）
+    pub fn apply(&self) -> Result<String> {
+        let output = execute_privileged("netplan", &["apply"])
             .context("执行netplan apply失败")?;
 
         if !output.status.success() {
@@ -82,105 +102,439 @@ impl NetplanManager {
             anyhow::bail!("netplan apply失败: {}", stderr);
         }
 
-        println!("✅ Netplan配置已应用");
-        Ok(())
+        info!("Netplan配置已应用");
+        let mut text = String::from_utf8_lossy(&output.stdout).to_string();
+        text.push_str(&String::from_utf8_lossy(&output.stderr));
+        Ok(text.trim().to_string())
     }
 
-    /// 测试Netplan配置（不实际应用）
-    #[allow(dead_code)]
-    pub fn try_config(&self) -> Result<()> {
-        let output = std::process::Command::new("netplan")
-            .arg("try")
-            .arg("--timeout")
-            .arg("10")
-            .output()
-            .context("执行netplan try失败")?;
+    /// 在后台启动 netplan try，需在超时前确认，否则netplan自动回滚
+    pub fn start_try(&self, timeout: Duration) -> Result<TrySession> {
+        // netplan try 需要终端来保存/恢复终端设置，这里给它分配一个伪终端
+        let pty = openpty(None, None).context("分配伪终端失败")?;
+        let timeout_arg = timeout.as_secs().to_string();
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("netplan try失败: {}", stderr);
-        }
+        // sudo会把确认/回滚用的信号转发给netplan
+        let mut command = if use_sudo() {
+            let mut command = Command::new("sudo");
+            command.args(["-n", "netplan"]);
+            command
+        } else {
+            Command::new("netplan")
+        };
+        let child = command
+            .args(["try", "--timeout", &timeout_arg])
+            .stdin(Stdio::from(pty.slave))
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("执行netplan try失败")?;
 
-        Ok(())
+        info!("已启动 netplan try (pid {})，超时 {} 秒", child.id(), timeout_arg);
+        Ok(TrySession {
+            child,
+            _pty_master: pty.master,
+            started: Instant::now(),
+            timeout,
+        })
     }
 
-    /// 为接口设置静态IP
+    /// 为接口设置静态IP，返回写入的文件和备份路径（原配置不存在时备份为None）
+    ///
+    /// ipv6为None时保留原有的IPv6地址和IPv6默认路由，否则一并替换；mtu不为None时一起写入
     pub fn set_static_ip(
         &self,
         iface_name: &str,
         address: &str,
         gateway: Option<&str>,
-        nameservers: Option<Vec<String>>,
-    ) -> Result<()> {
-        // 查找或创建配置文件
-        let config_file = self.find_or_create_config_file()?;
+        ipv6: Option<&Ipv6Settings>,
+        nameservers: Option<NameserverConfig>,
+        mtu: Option<u32>,
+    ) -> Result<(PathBuf, Option<PathBuf>)> {
+        let (config_file, backup) = self.update_config(iface_name, |config| {
+            // 设置接口配置（保留网络唤醒和策略路由设置）
+            let mut iface_config = preserve_extra_settings(
+                config.network.ethernets.get(iface_name),
+                static_interface_config(address, gateway, nameservers),
+            );
+            if let Some(ipv6) = ipv6 {
+                replace_ipv6_addresses(&mut iface_config, &ipv6.addresses);
+                replace_ipv6_default_route(&mut iface_config, ipv6.gateway.as_deref());
+            }
+            if mtu.is_some() {
+                iface_config.mtu = mtu;
+            }
+            config.network.ethernets.insert(iface_name.to_string(), iface_config);
+            Ok(())
+        })?;
 
-        // 备份原配置
-        if config_file.exists() {
-            self.backup_config(&config_file)?;
-        }
+        info!("已更新Netplan配置: {:?}", config_file);
+        Ok((config_file, backup))
+    }
+
+    /// 为接口设置纯IPv6静态配置（关闭DHCP，不配置IPv4地址），返回写入的文件和备份路径
+    pub fn set_static_ipv6(
+        &self,
+        iface_name: &str,
+        ipv6: &Ipv6Settings,
+        nameservers: Option<NameserverConfig>,
+        mtu: Option<u32>,
+    ) -> Result<(PathBuf, Option<PathBuf>)> {
+        let (config_file, backup) = self.update_config(iface_name, |config| {
+            // 保留网络唤醒和策略路由设置，原有地址全部替换为新的IPv6地址
+            let mut iface_config = preserve_extra_settings(
+                config.network.ethernets.get(iface_name),
+                static_ipv6_interface_config(nameservers),
+            );
+            replace_ipv6_addresses(&mut iface_config, &ipv6.addresses);
+            replace_ipv6_default_route(&mut iface_config, ipv6.gateway.as_deref());
+            if mtu.is_some() {
+                iface_config.mtu = mtu;
+            }
+            config.network.ethernets.insert(iface_name.to_string(), iface_config);
+            Ok(())
+        })?;
+
+        info!("已更新Netplan纯IPv6配置: {:?}", config_file);
+        Ok((config_file, backup))
+    }
 
-        // 读取或创建配置
+    /// 为接口设置DHCP，返回写入的文件和备份路径（原配置不存在时备份为None）
+    ///
+    /// keep_dns为true时保留原有的nameservers，并忽略DHCP下发的DNS
+    pub fn set_dhcp(&self, iface_name: &str, keep_dns: bool) -> Result<(PathBuf, Option<PathBuf>)> {
+        let (config_file, backup) = self.update_config(iface_name, |config| {
+            let existing = config.network.ethernets.get(iface_name);
+            let iface_config = preserve_extra_settings(existing, dhcp_interface_config(existing, keep_dns));
+            config.network.ethernets.insert(iface_name.to_string(), iface_config);
+            Ok(())
+        })?;
+
+        info!("已更新Netplan配置为DHCP: {:?}", config_file);
+        Ok((config_file, backup))
+    }
+
+    /// 读取接口所在的配置文件（不存在时从空配置开始），修改后备份原文件、写回，并把接口记入管理清单
+    ///
+    /// 返回写入的文件和备份路径（文件是新建的时备份为None）；update返回错误时不改动任何文件
+    fn update_config(
+        &self,
+        iface_name: &str,
+        update: impl FnOnce(&mut NetplanConfig) -> Result<()>,
+    ) -> Result<(PathBuf, Option<PathBuf>)> {
+        let config_file = self.target_config_file(iface_name)?;
         let mut config = if config_file.exists() {
             self.read_config(&config_file)?
         } else {
             NetplanConfig::default()
         };
+        update(&mut config)?;
 
-        // 设置接口配置
-        let iface_config = InterfaceConfig {
-            dhcp4: Some(false),
-            dhcp6: Some(false),
-            addresses: Some(vec![address.to_string()]),
-            routes: gateway.map(|gw| {
-                vec![RouteConfig {
-                    to: "default".to_string(),
-                    via: gw.to_string(),
-                }]
-            }),
-            nameservers: nameservers.map(|ns| NameserverConfig { addresses: ns }),
-            ..Default::default()
+        let backup = if config_file.exists() {
+            Some(self.backup_config(&config_file)?)
+        } else {
+            None
         };
-
-        config.network.ethernets.insert(iface_name.to_string(), iface_config);
-
-        // 写入配置
         self.write_config(&config_file, &config)?;
+        self.set_managed(iface_name, true)?;
+        Ok((config_file, backup))
+    }
 
-        println!("✅ 已更新Netplan配置: {:?}", config_file);
-        Ok(())
+    /// 修改接口在ethernets中的条目（不存在时新建），同一文件中的其他设置保持不变
+    fn update_interface(
+        &self,
+        iface_name: &str,
+        update: impl FnOnce(&mut InterfaceConfig),
+    ) -> Result<(PathBuf, Option<PathBuf>)> {
+        self.update_config(iface_name, |config| {
+            update(config.network.ethernets.entry(iface_name.to_string()).or_default());
+            Ok(())
+        })
     }
 
-    /// 为接口设置DHCP
-    pub fn set_dhcp(&self, iface_name: &str) -> Result<()> {
-        let config_file = self.find_or_create_config_file()?;
+    /// 从所有定义了该接口的配置文件中删除其条目（而不是改为DHCP），
+    /// 返回每个被修改文件的备份路径，接口未在Netplan中定义时返回空列表
+    pub fn remove_interface(&self, iface_name: &str) -> Result<Vec<PathBuf>> {
+        let mut backups = Vec::new();
+        for file in self.list_config_files()? {
+            // 未定义该接口的文件（包括无法解析的）不读取也不改动
+            let defined = read_privileged(&file)
+                .map(|content| defined_interfaces(&content).iter().any(|name| name == iface_name))
+                .unwrap_or(false);
+            if !defined {
+                continue;
+            }
 
-        if config_file.exists() {
-            self.backup_config(&config_file)?;
+            let mut config = self.read_config(&file)?;
+            if config.network.ethernets.contains_key(iface_name) {
+                backups.push(self.backup_config(&file)?);
+                config.network.ethernets.remove(iface_name);
+                self.write_config(&file, &config)?;
+                info!("已从Netplan配置中删除接口 {}: {:?}", iface_name, file);
+            }
         }
+        self.set_managed(iface_name, false)?;
+        Ok(backups)
+    }
 
-        let mut config = if config_file.exists() {
-            self.read_config(&config_file)?
+    /// 读取由nicman管理的接口列表，清单不存在时为空
+    pub fn managed_interfaces(&self) -> BTreeSet<String> {
+        read_privileged(&self.config_dir.join(MANAGED_MANIFEST))
+            .map(|content| {
+                content
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// 在清单中添加或移除接口，清单没有变化时不写文件
+    fn set_managed(&self, iface_name: &str, managed: bool) -> Result<()> {
+        let mut names = self.managed_interfaces();
+        let changed = if managed {
+            names.insert(iface_name.to_string())
         } else {
-            NetplanConfig::default()
+            names.remove(iface_name)
         };
+        if !changed {
+            return Ok(());
+        }
 
-        let iface_config = InterfaceConfig {
-            dhcp4: Some(true),
-            dhcp6: Some(false),
-            ..Default::default()
+        let mut content = String::from("# 由nicman管理的接口，nicman只自动修改这些接口的Netplan条目\n");
+        for name in &names {
+            content.push_str(name);
+            content.push('\n');
+        }
+        let path = self.config_dir.join(MANAGED_MANIFEST);
+        write_privileged(&path, &content).with_context(|| format!("写入管理清单失败: {:?}", path))
+    }
+
+    /// 仅修改接口的默认网关，保留地址等其他配置
+    ///
+    /// 接口未在Netplan中定义时拒绝写入：只含网关的条目没有地址也没有DHCP，会让接口失去配置
+    pub fn set_gateway(&self, iface_name: &str, gateway: &str) -> Result<(PathBuf, Option<PathBuf>)> {
+        let (config_file, backup) = self.update_config(iface_name, |config| {
+            let iface_config = defined_interface(config, iface_name)?;
+            let mut routes: Vec<RouteConfig> = iface_config
+                .routes
+                .take()
+                .unwrap_or_default()
+                .into_iter()
+                // 策略路由表中的默认路由不受影响
+                .filter(|route| !is_ipv4_default_route(route))
+                .collect();
+            routes.push(RouteConfig {
+                to: "default".to_string(),
+                via: Some(gateway.to_string()),
+                ..Default::default()
+            });
+            iface_config.routes = Some(routes);
+            Ok(())
+        })?;
+
+        info!("已更新Netplan默认网关: {:?}", config_file);
+        Ok((config_file, backup))
+    }
+
+    /// 在接口的addresses末尾追加地址（已存在时不重复添加），其他地址保持不变
+    pub fn add_address(&self, iface_name: &str, address: &str) -> Result<(PathBuf, Option<PathBuf>)> {
+        self.update_addresses(iface_name, |addresses| {
+            if !addresses.iter().any(|addr| addr == address) {
+                addresses.push(address.to_string());
+            }
+        })
+    }
+
+    /// 从接口的addresses中删除地址，其他地址保持不变
+    pub fn remove_address(&self, iface_name: &str, address: &str) -> Result<(PathBuf, Option<PathBuf>)> {
+        self.update_addresses(iface_name, |addresses| addresses.retain(|addr| addr != address))
+    }
+
+    /// 修改接口的addresses列表并写回，返回写入的文件和备份路径
+    fn update_addresses(&self, iface_name: &str, update: impl FnOnce(&mut Vec<String>)) -> Result<(PathBuf, Option<PathBuf>)> {
+        let (config_file, backup) = self.update_interface(iface_name, |iface_config| {
+            let mut addresses = iface_config.addresses.take().unwrap_or_default();
+            update(&mut addresses);
+            iface_config.addresses = Some(addresses).filter(|addresses| !addresses.is_empty());
+        })?;
+
+        info!("已更新Netplan地址列表: {:?}", config_file);
+        Ok((config_file, backup))
+    }
+
+    /// 持久化策略路由：在指定路由表中添加默认路由，并按源地址选择该表
+    pub fn set_policy_route(
+        &self,
+        iface_name: &str,
+        gateway: &str,
+        source: &str,
+        table: u32,
+    ) -> Result<(PathBuf, Option<PathBuf>)> {
+        // 与set_gateway相同，不为未定义的接口生成只有策略路由的条目
+        let (config_file, backup) = self.update_config(iface_name, |config| {
+            add_policy_route(defined_interface(config, iface_name)?, gateway, source, table);
+            Ok(())
+        })?;
+
+        info!("已更新Netplan策略路由: {:?}", config_file);
+        Ok((config_file, backup))
+    }
+
+    /// 持久化网桥的STP设置（parameters.stp），保留网桥的其他配置
+    ///
+    /// 网桥必须已在bridges中定义，否则netplan会在启动时接管运行时创建的网桥（如virbr0）
+    pub fn set_bridge_stp(&self, bridge: &str, enabled: bool) -> Result<(PathBuf, Option<PathBuf>)> {
+        let (config_file, backup) = self.update_config(bridge, |config| {
+            let bridge_config = config
+                .network
+                .bridges
+                .get_mut(bridge)
+                .with_context(|| format!("网桥 {} 未在Netplan中定义", bridge))?;
+            bridge_config.parameters.get_or_insert_with(Default::default).stp = Some(enabled);
+            Ok(())
+        })?;
+
+        info!("已更新Netplan网桥STP设置: {:?}", config_file);
+        Ok((config_file, backup))
+    }
+
+    /// 持久化网卡的网络唤醒设置（wakeonlan），保留接口的其他配置
+    pub fn set_wakeonlan(&self, iface_name: &str, enabled: bool) -> Result<(PathBuf, Option<PathBuf>)> {
+        let (config_file, backup) = self.update_interface(iface_name, |iface_config| iface_config.wakeonlan = Some(enabled))?;
+
+        info!("已更新Netplan网络唤醒设置: {:?}", config_file);
+        Ok((config_file, backup))
+    }
+
+    /// 设置接口的MAC地址（macaddress），None表示删除该设置以恢复出厂地址
+    pub fn set_macaddress(&self, iface_name: &str, mac: Option<&str>) -> Result<(PathBuf, Option<PathBuf>)> {
+        let (config_file, backup) =
+            self.update_interface(iface_name, |iface_config| iface_config.macaddress = mac.map(str::to_string))?;
+
+        info!("已更新Netplan MAC地址设置: {:?}", config_file);
+        Ok((config_file, backup))
+    }
+
+    /// 持久化接口重命名：把原接口的条目移到新名称下，并按MAC地址匹配设置 set-name
+    ///
+    /// 原接口未在Netplan中定义时创建只包含 match/set-name 的新条目
+    pub fn set_persistent_name(&self, old: &str, new: &str, mac: &str) -> Result<(PathBuf, Option<PathBuf>)> {
+        let (config_file, backup) = self.update_config(old, |config| {
+            let mut iface_config = config.network.ethernets.remove(old).unwrap_or_default();
+            let mut match_rules = serde_yaml::Mapping::new();
+            match_rules.insert("macaddress".into(), mac.to_lowercase().into());
+            iface_config.match_rules = Some(match_rules);
+            iface_config.set_name = Some(new.to_string());
+            config.network.ethernets.insert(new.to_string(), iface_config);
+            Ok(())
+        })?;
+        self.set_managed(old, false)?;
+        self.set_managed(new, true)?;
+
+        info!("已更新Netplan接口名称 {} -> {}: {:?}", old, new, config_file);
+        Ok((config_file, backup))
+    }
+
+    /// 比较写入前后的配置文件，判断 netplan apply 是否足以使修改生效
+    ///
+    /// backup为None表示文件是新建的；任一文件无法解析时按apply处理
+    pub fn apply_advice(&self, file_path: &Path, backup: Option<&Path>) -> ApplyAdvice {
+        let (Some(old), Ok(new)) = (backup, self.read_config(file_path)) else {
+            return ApplyAdvice::Apply;
         };
+        match self.read_config(old) {
+            Ok(old) => advise_apply(&old, &new),
+            Err(_) => ApplyAdvice::Apply,
+        }
+    }
 
-        config.network.ethernets.insert(iface_name.to_string(), iface_config);
+    /// 查找定义了指定接口的配置文件（任意段：ethernets、bridges、vlans等）
+    ///
+    /// 多个文件定义同一接口时返回优先级最高（按文件名排序最后）的文件
+    pub fn find_interface_file(&self, iface_name: &str) -> Option<PathBuf> {
+        self.interface_files().remove(iface_name)
+    }
 
-        self.write_config(&config_file, &config)?;
+    /// 扫描所有配置文件，返回接口名到定义它的配置文件的映射
+    pub fn interface_files(&self) -> HashMap<String, PathBuf> {
+        let mut map = HashMap::new();
+        let Ok(files) = self.list_config_files() else {
+            return map;
+        };
+
+        // netplan按文件名顺序合并，后面的文件覆盖前面的
+        for file in files {
+            // 读取或解析失败的文件跳过，不影响其他文件
+            let Ok(content) = read_privileged(&file) else {
+                continue;
+            };
+            for name in defined_interfaces(&content) {
+                map.insert(name, file.clone());
+            }
+        }
+        map
+    }
+
+    /// 按文件名顺序合并所有配置文件中ethernets下的接口条目，得到netplan实际生效的配置
+    ///
+    /// 读取或解析失败的文件跳过，不影响其他文件
+    pub fn merged_ethernets(&self) -> HashMap<String, InterfaceConfig> {
+        let mut merged: HashMap<String, InterfaceConfig> = HashMap::new();
+        let Ok(files) = self.list_config_files() else {
+            return merged;
+        };
+
+        for file in files {
+            let Ok(config) = self.read_config(&file) else {
+                continue;
+            };
+            for (name, entry) in config.network.ethernets {
+                match merged.get_mut(&name) {
+                    Some(base) => merge_interface_config(base, entry),
+                    None => {
+                        merged.insert(name, entry);
+                    }
+                }
+            }
+        }
+        merged
+    }
+
+    /// 为接口创建一个只包含该接口的新配置文件
+    pub fn create_config_file_for(&self, iface_name: &str) -> Result<PathBuf> {
+        let file = self.config_dir.join(format!("90-nicman-{}.yaml", iface_name));
+        if file.exists() {
+            anyhow::bail!("配置文件已存在: {:?}", file);
+        }
+
+        let mut config = NetplanConfig::default();
+        config.network.ethernets.insert(iface_name.to_string(), InterfaceConfig::default());
+        self.write_config(&file, &config)?;
+        self.set_managed(iface_name, true)?;
+
+        // netplan要求配置文件不能被其他用户读取
+        set_mode_privileged(&file, 0o600)
+            .with_context(|| format!("设置配置文件权限失败: {:?}", file))?;
 
-        println!("✅ 已更新Netplan配置为DHCP: {:?}", config_file);
-        Ok(())
+        info!("已创建Netplan配置: {:?}", file);
+        Ok(file)
     }
 
-    /// 查找或创建配置文件
-    fn find_or_create_config_file(&self) -> Result<PathBuf> {
+    /// 确定接口配置要写入的文件
+    ///
+    /// 依次选择：已定义该接口的文件、用户指定的文件、第一个配置文件、新建 01-netcfg.yaml
+    pub fn target_config_file(&self, iface_name: &str) -> Result<PathBuf> {
+        if let Some(file) = self.find_interface_file(iface_name) {
+            return Ok(file);
+        }
+
+        if let Some(preferred) = &self.preferred_file {
+            return Ok(preferred.clone());
+        }
+
         let files = self.list_config_files()?;
 
         if let Some(first_file) = files.first() {
@@ -191,6 +545,354 @@ impl NetplanManager {
     }
 }
 
+/// 取出接口已有的条目，未定义时返回错误
+fn defined_interface<'a>(config: &'a mut NetplanConfig, iface_name: &str) -> Result<&'a mut InterfaceConfig> {
+    config
+        .network
+        .ethernets
+        .get_mut(iface_name)
+        .with_context(|| format!("接口 {} 未在Netplan中定义，请先通过编辑表单保存完整配置", iface_name))
+}
+
+/// 构造纯IPv6接口配置（地址和网关由调用方填入）
+fn static_ipv6_interface_config(nameservers: Option<NameserverConfig>) -> InterfaceConfig {
+    InterfaceConfig {
+        dhcp4: Some(false),
+        dhcp6: Some(false),
+        nameservers,
+        ..Default::default()
+    }
+}
+
+/// 构造静态IP接口配置，没有网关时不生成routes
+fn static_interface_config(
+    address: &str,
+    gateway: Option<&str>,
+    nameservers: Option<NameserverConfig>,
+) -> InterfaceConfig {
+    InterfaceConfig {
+        dhcp4: Some(false),
+        dhcp6: Some(false),
+        addresses: Some(vec![address.to_string()]),
+        routes: gateway.map(|gw| {
+            vec![RouteConfig {
+                to: "default".to_string(),
+                via: Some(gw.to_string()),
+                ..Default::default()
+            }]
+        }),
+        nameservers,
+        ..Default::default()
+    }
+}
+
+/// 构造DHCP接口配置，keep_dns时沿用原配置中的nameservers
+fn dhcp_interface_config(existing: Option<&InterfaceConfig>, keep_dns: bool) -> InterfaceConfig {
+    let nameservers = if keep_dns {
+        existing
+            .and_then(|cfg| cfg.nameservers.clone())
+            .filter(|ns| !ns.addresses.is_empty())
+    } else {
+        None
+    };
+
+    InterfaceConfig {
+        dhcp4: Some(true),
+        dhcp6: Some(false),
+        // 有静态DNS时不使用DHCP下发的DNS，避免两者混用
+        dhcp4_overrides: nameservers.as_ref().map(|_| DhcpOverrides { use_dns: Some(false) }),
+        nameservers,
+        ..Default::default()
+    }
+}
+
+/// 重写IPv4地址配置时以原条目为基础，只替换DHCP开关、IPv4地址、主路由表中的IPv4默认路由和DNS，
+/// 其余设置（IPv6地址和路由、静态路由、策略路由、未识别的字段等）原样保留
+fn preserve_extra_settings(existing: Option<&InterfaceConfig>, config: InterfaceConfig) -> InterfaceConfig {
+    let Some(existing) = existing else {
+        return config;
+    };
+
+    let mut merged = existing.clone();
+    merged.dhcp4 = config.dhcp4;
+    merged.dhcp6 = config.dhcp6;
+    merged.nameservers = config.nameservers;
+    merged.dhcp4_overrides = config.dhcp4_overrides;
+
+    let mut addresses = config.addresses.unwrap_or_default();
+    addresses.extend(existing.addresses.iter().flatten().filter(|addr| addr.contains(':')).cloned());
+    merged.addresses = Some(addresses).filter(|addresses| !addresses.is_empty());
+
+    let mut routes = config.routes.unwrap_or_default();
+    routes.extend(existing.routes.iter().flatten().filter(|route| !is_ipv4_default_route(route)).cloned());
+    merged.routes = Some(routes).filter(|routes| !routes.is_empty());
+    merged
+}
+
+/// 修改配置后的生效方式
+#[derive(Debug, Clone, PartialEq)]
+pub enum ApplyAdvice {
+    Apply,           // netplan apply 即可生效
+    Reboot(String),  // 建议重启，附带原因
+}
+
+impl ApplyAdvice {
+    pub fn message(&self) -> String {
+        match self {
+            ApplyAdvice::Apply => "执行 netplan apply 即可生效".to_string(),
+            ApplyAdvice::Reboot(reason) => format!("{}，netplan apply 可能无法完全生效，建议重启", reason),
+        }
+    }
+}
+
+/// 对比新旧配置，启发式判断是否需要重启：
+/// 切换渲染器、修改接口的 match 规则或 set-name 时，apply 通常无法完全生效
+pub fn advise_apply(old: &NetplanConfig, new: &NetplanConfig) -> ApplyAdvice {
+    let renderer = |config: &NetplanConfig| config.network.renderer.clone().unwrap_or_else(|| "networkd".to_string());
+    if renderer(old) != renderer(new) {
+        return ApplyAdvice::Reboot(format!("渲染器由 {} 改为 {}", renderer(old), renderer(new)));
+    }
+
+    let mut names: Vec<&String> = old.network.ethernets.keys().chain(new.network.ethernets.keys()).collect();
+    names.sort();
+    names.dedup();
+    for name in names {
+        let old_iface = old.network.ethernets.get(name);
+        let new_iface = new.network.ethernets.get(name);
+        if old_iface.and_then(|cfg| cfg.match_rules.as_ref()) != new_iface.and_then(|cfg| cfg.match_rules.as_ref()) {
+            return ApplyAdvice::Reboot(format!("接口 {} 的 match 规则已修改", name));
+        }
+        if old_iface.and_then(|cfg| cfg.set_name.as_ref()) != new_iface.and_then(|cfg| cfg.set_name.as_ref()) {
+            return ApplyAdvice::Reboot(format!("接口 {} 的 set-name 已修改", name));
+        }
+    }
+
+    ApplyAdvice::Apply
+}
+
+/// 用新的IPv6地址替换addresses中的IPv6条目，IPv4地址保持不变
+fn replace_ipv6_addresses(config: &mut InterfaceConfig, ipv6: &[String]) {
+    let mut addresses: Vec<String> = config
+        .addresses
+        .take()
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|addr| !addr.contains(':'))
+        .collect();
+    addresses.extend(ipv6.iter().cloned());
+    config.addresses = Some(addresses).filter(|addresses| !addresses.is_empty());
+}
+
+/// 主路由表中的IPv4默认路由（to: default 且网关为IPv6地址时属于IPv6默认路由）
+fn is_ipv4_default_route(route: &RouteConfig) -> bool {
+    route.table.is_none()
+        && (route.to == "0.0.0.0/0" || (route.to == "default" && !route.via.as_deref().unwrap_or_default().contains(':')))
+}
+
+/// 主路由表中的IPv6默认路由（包括网关为IPv6地址的 to: default）
+fn is_ipv6_default_route(route: &RouteConfig) -> bool {
+    route.table.is_none()
+        && (route.to == "::/0" || (route.to == "default" && route.via.as_deref().unwrap_or_default().contains(':')))
+}
+
+/// 替换主路由表中的IPv6默认路由，gateway为None时删除
+fn replace_ipv6_default_route(config: &mut InterfaceConfig, gateway: Option<&str>) {
+    let mut routes = config.routes.take().unwrap_or_default();
+    routes.retain(|route| !is_ipv6_default_route(route));
+    if let Some(gateway) = gateway {
+        routes.push(RouteConfig {
+            to: "::/0".to_string(),
+            via: Some(gateway.to_string()),
+            ..Default::default()
+        });
+    }
+    config.routes = Some(routes).filter(|routes| !routes.is_empty());
+}
+
+/// 添加策略路由，替换同一路由表中已有的默认路由和规则
+fn add_policy_route(config: &mut InterfaceConfig, gateway: &str, source: &str, table: u32) {
+    let routes = config.routes.get_or_insert_with(Vec::new);
+    routes.retain(|route| route.table != Some(table));
+    routes.push(RouteConfig {
+        to: "default".to_string(),
+        via: Some(gateway.to_string()),
+        table: Some(table),
+        ..Default::default()
+    });
+
+    let policies = config.routing_policy.get_or_insert_with(Vec::new);
+    policies.retain(|policy| policy.table != table);
+    policies.push(RoutingPolicy {
+        from: Some(source.to_string()),
+        table,
+        ..Default::default()
+    });
+}
+
+/// 把后面文件中的接口条目合并到前面的条目上，与netplan一致：标量后者覆盖，地址、路由等列表追加
+fn merge_interface_config(base: &mut InterfaceConfig, overlay: InterfaceConfig) {
+    fn append<T>(base: &mut Option<Vec<T>>, overlay: Option<Vec<T>>) {
+        if let Some(items) = overlay {
+            base.get_or_insert_with(Vec::new).extend(items);
+        }
+    }
+
+    append(&mut base.addresses, overlay.addresses);
+    append(&mut base.routes, overlay.routes);
+    append(&mut base.routing_policy, overlay.routing_policy);
+    if let Some(nameservers) = overlay.nameservers {
+        let base_nameservers = base.nameservers.get_or_insert_with(NameserverConfig::default);
+        base_nameservers.addresses.extend(nameservers.addresses);
+        base_nameservers.search.extend(nameservers.search);
+    }
+
+    base.dhcp4 = overlay.dhcp4.or(base.dhcp4);
+    base.dhcp6 = overlay.dhcp6.or(base.dhcp6);
+    base.dhcp4_overrides = overlay.dhcp4_overrides.or(base.dhcp4_overrides.take());
+    base.wakeonlan = overlay.wakeonlan.or(base.wakeonlan);
+    base.match_rules = overlay.match_rules.or(base.match_rules.take());
+    base.set_name = overlay.set_name.or(base.set_name.take());
+    base.mtu = overlay.mtu.or(base.mtu);
+    base.macaddress = overlay.macaddress.or(base.macaddress.take());
+    base.extra.extend(overlay.extra);
+}
+
+/// 比较接口的运行时地址/网关与其Netplan条目，返回差异说明（一致时为空）
+///
+/// DHCP获取的地址和SLAAC生成的IPv6地址不会出现在Netplan中，不算差异
+pub fn runtime_differences(config: &InterfaceConfig, iface: &NetInterface) -> Vec<String> {
+    let mut diffs = Vec::new();
+    let dhcp4 = config.dhcp4 == Some(true);
+    let persisted = config.addresses.as_deref().unwrap_or_default();
+
+    // IPv4：开启DHCP时只比较静态地址
+    let runtime_ipv4: Vec<&str> = if dhcp4 {
+        iface.ipv4_details.iter().filter(|info| !info.dynamic).map(|info| info.address.as_str()).collect()
+    } else {
+        iface.ipv4_addresses.iter().map(String::as_str).collect()
+    };
+    for address in persisted.iter().filter(|addr| !addr.contains(':')) {
+        if !iface.ipv4_addresses.contains(address) {
+            diffs.push(format!("Netplan中的地址 {} 未在运行时配置", address));
+        }
+    }
+    for address in runtime_ipv4 {
+        if !persisted.iter().any(|addr| addr == address) {
+            diffs.push(format!("运行时地址 {} 未写入Netplan", address));
+        }
+    }
+
+    // IPv6：只检查Netplan中的静态地址是否生效
+    for address in persisted.iter().filter(|addr| addr.contains(':')) {
+        if !iface.ipv6_addresses.contains(address) {
+            diffs.push(format!("Netplan中的地址 {} 未在运行时配置", address));
+        }
+    }
+
+    // 默认网关（忽略策略路由表中的路由）
+    // 双栈接口的 to: default 可能同时有IPv4和IPv6网关，按网关的地址族区分
+    let default_via = |is_default: fn(&RouteConfig) -> bool| {
+        config.routes.as_deref().unwrap_or_default().iter()
+            .find(|route| is_default(route))
+            .and_then(|route| route.via.clone())
+    };
+    if !dhcp4 {
+        let persisted_gw = default_via(is_ipv4_default_route);
+        let runtime_gw = iface.ipv4_config.as_ref().and_then(|cfg| cfg.gateway.clone());
+        if persisted_gw != runtime_gw {
+            diffs.push(format!(
+                "默认网关: Netplan为 {}，运行时为 {}",
+                persisted_gw.as_deref().unwrap_or("无"),
+                runtime_gw.as_deref().unwrap_or("无"),
+            ));
+        }
+    }
+    if let Some(persisted_gw) = default_via(is_ipv6_default_route)
+        && iface.ipv6_gateway.as_deref() != Some(persisted_gw.as_str()) {
+        diffs.push(format!(
+            "IPv6默认网关: Netplan为 {}，运行时为 {}",
+            persisted_gw,
+            iface.ipv6_gateway.as_deref().unwrap_or("无"),
+        ));
+    }
+
+    diffs
+}
+
+/// 是否为nicman新建的单接口配置文件（90-nicman-<接口>.yaml）
+pub fn created_by_nicman(file: &Path) -> bool {
+    file.file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with("90-nicman-"))
+}
+
+/// 解析配置内容，列出network下各设备段定义的接口名
+fn defined_interfaces(content: &str) -> Vec<String> {
+    let Ok(value) = serde_yaml::from_str::<serde_yaml::Value>(content) else {
+        return Vec::new();
+    };
+    let Some(network) = value.get("network").and_then(|n| n.as_mapping()) else {
+        return Vec::new();
+    };
+
+    network
+        .values()
+        .filter_map(|section| section.as_mapping())
+        .flat_map(|section| section.keys())
+        .filter_map(|key| key.as_str().map(str::to_string))
+        .collect()
+}
+
+/// 进行中的 netplan try 会话
+pub struct TrySession {
+    child: Child,
+    _pty_master: OwnedFd,  // 保持伪终端打开，直到会话结束
+    started: Instant,
+    timeout: Duration,
+}
+
+impl TrySession {
+    /// 距离自动回滚的剩余时间
+    pub fn remaining(&self) -> Duration {
+        self.timeout.saturating_sub(self.started.elapsed())
+    }
+
+    /// 保留新配置（netplan try 收到SIGUSR1时接受配置）
+    pub fn confirm(&self) -> Result<()> {
+        self.signal(Signal::SIGUSR1)
+    }
+
+    /// 立即回滚（netplan try 收到SIGINT时恢复原配置）
+    pub fn revert(&self) -> Result<()> {
+        self.signal(Signal::SIGINT)
+    }
+
+    fn signal(&self, signal: Signal) -> Result<()> {
+        kill(Pid::from_raw(self.child.id() as i32), signal)
+            .with_context(|| format!("向netplan try发送{}失败", signal))
+    }
+
+    /// 检查会话是否结束，结束时返回netplan的输出（失败时为Err）
+    pub fn poll(&mut self) -> Option<Result<String, String>> {
+        let status = match self.child.try_wait() {
+            Ok(Some(status)) => status,
+            Ok(None) => return None,
+            Err(e) => return Some(Err(format!("等待netplan try失败: {}", e))),
+        };
+
+        let mut output = String::new();
+        if let Some(mut stdout) = self.child.stdout.take() {
+            stdout.read_to_string(&mut output).ok();
+        }
+        if let Some(mut stderr) = self.child.stderr.take() {
+            stderr.read_to_string(&mut output).ok();
+        }
+        let output = output.trim().to_string();
+
+        info!("netplan try 结束: {}", status);
+        Some(if status.success() { Ok(output) } else { Err(output) })
+    }
+}
+
 impl Default for NetplanManager {
     fn default() -> Self {
         Self::new()
@@ -210,6 +912,8 @@ impl Default for NetplanConfig {
                 version: 2,
                 renderer: Some("networkd".to_string()),
                 ethernets: HashMap::new(),
+                bridges: HashMap::new(),
+                extra: serde_yaml::Mapping::new(),
             },
         }
     }
@@ -222,6 +926,28 @@ pub struct NetworkConfig {
     pub renderer: Option<String>,
     #[serde(default)]
     pub ethernets: HashMap<String, InterfaceConfig>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub bridges: HashMap<String, BridgeConfig>,
+    // vlans/bonds/tunnels/wifis等未建模的部分原样保留
+    #[serde(flatten)]
+    pub extra: serde_yaml::Mapping,
+}
+
+/// 网桥配置，未识别的字段原样保留
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BridgeConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parameters: Option<BridgeParameters>,
+    #[serde(flatten)]
+    pub extra: serde_yaml::Mapping,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BridgeParameters {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stp: Option<bool>,
+    #[serde(flatten)]
+    pub extra: serde_yaml::Mapping,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -236,16 +962,578 @@ pub struct InterfaceConfig {
     pub routes: Option<Vec<RouteConfig>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub nameservers: Option<NameserverConfig>,
+    #[serde(rename = "dhcp4-overrides", skip_serializing_if = "Option::is_none")]
+    pub dhcp4_overrides: Option<DhcpOverrides>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wakeonlan: Option<bool>,
+    #[serde(rename = "routing-policy", skip_serializing_if = "Option::is_none")]
+    pub routing_policy: Option<Vec<RoutingPolicy>>,
+    #[serde(rename = "match", skip_serializing_if = "Option::is_none")]
+    pub match_rules: Option<serde_yaml::Mapping>,
+    #[serde(rename = "set-name", skip_serializing_if = "Option::is_none")]
+    pub set_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mtu: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub macaddress: Option<String>,
+    #[serde(flatten)]
+    pub extra: serde_yaml::Mapping,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RoutingPolicy {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    pub table: u32,
+    #[serde(flatten)]
+    pub extra: serde_yaml::Mapping,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DhcpOverrides {
+    #[serde(rename = "use-dns", skip_serializing_if = "Option::is_none")]
+    pub use_dns: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RouteConfig {
     pub to: String,
-    pub via: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub via: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub table: Option<u32>,
+    #[serde(flatten)]
+    pub extra: serde_yaml::Mapping,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// 静态IPv6设置，地址为空表示不配置静态IPv6地址
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Ipv6Settings {
+    pub addresses: Vec<String>,   // 地址/前缀 格式
+    pub gateway: Option<String>,  // IPv6默认网关
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct NameserverConfig {
+    #[serde(default)]
     pub addresses: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub search: Vec<String>,  // DNS搜索域
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defined_interfaces_all_sections() {
+        let content = "network:
+  version: 2
+  renderer: networkd
+  ethernets:
+    eth0:
+      dhcp4: true
+  bridges:
+    br0:
+      interfaces: [eth0]
+  vlans:
+    vlan10:
+      id: 10
+      link: eth0
+";
+        let mut names = defined_interfaces(content);
+        names.sort();
+        assert_eq!(names, vec!["br0", "eth0", "vlan10"]);
+
+        assert!(defined_interfaces("not: [valid").is_empty());
+        assert!(defined_interfaces("network:\n  version: 2\n").is_empty());
+    }
+
+    #[test]
+    fn test_static_config_without_gateway() {
+        let mut config = NetplanConfig::default();
+        config.network.ethernets.insert(
+            "eth1".to_string(),
+            static_interface_config("10.0.0.5/24", None, None),
+        );
+
+        let yaml = serde_yaml::to_string(&config).unwrap();
+        assert!(!yaml.contains("routes"));
+
+        let parsed: NetplanConfig = serde_yaml::from_str(&yaml).unwrap();
+        let eth1 = &parsed.network.ethernets["eth1"];
+        assert_eq!(eth1.addresses.as_deref(), Some(&["10.0.0.5/24".to_string()][..]));
+        assert!(eth1.routes.is_none());
+    }
+
+    #[test]
+    fn test_bridge_config_preserves_fields() {
+        let content = "network:
+  version: 2
+  bridges:
+    br0:
+      interfaces: [eth0, eth1]
+      parameters:
+        forward-delay: 4
+";
+        let mut config: NetplanConfig = serde_yaml::from_str(content).unwrap();
+        let br0 = config.network.bridges.get_mut("br0").unwrap();
+        br0.parameters.get_or_insert_with(Default::default).stp = Some(true);
+
+        let yaml = serde_yaml::to_string(&config).unwrap();
+        assert!(yaml.contains("stp: true"));
+        assert!(yaml.contains("forward-delay: 4"));
+        assert!(yaml.contains("- eth1"));
+    }
+
+    #[test]
+    fn test_dhcp_config_keeps_dns() {
+        let existing = static_interface_config(
+            "10.0.0.5/24",
+            Some("10.0.0.1"),
+            Some(NameserverConfig {
+                addresses: vec!["1.1.1.1".to_string()],
+                search: vec!["corp.local".to_string()],
+            }),
+        );
+
+        let yaml = serde_yaml::to_string(&existing).unwrap();
+        assert!(yaml.contains("search:\n  - corp.local"), "{}", yaml);
+
+        let kept = dhcp_interface_config(Some(&existing), true);
+        assert_eq!(kept.dhcp4, Some(true));
+        assert!(kept.addresses.is_none());
+        assert!(kept.routes.is_none());
+        assert_eq!(kept.nameservers.unwrap().addresses, vec!["1.1.1.1"]);
+        assert_eq!(kept.dhcp4_overrides.unwrap().use_dns, Some(false));
+
+        let dropped = dhcp_interface_config(Some(&existing), false);
+        assert!(dropped.nameservers.is_none());
+        assert!(dropped.dhcp4_overrides.is_none());
+
+        // 原配置没有DNS时不添加覆盖项
+        let fresh = dhcp_interface_config(None, true);
+        assert!(fresh.nameservers.is_none());
+        assert!(fresh.dhcp4_overrides.is_none());
+    }
+
+    #[test]
+    fn test_dhcp_config_keeps_wakeonlan() {
+        let existing = InterfaceConfig {
+            wakeonlan: Some(true),
+            ..static_interface_config("10.0.0.5/24", None, None)
+        };
+        let dhcp = preserve_extra_settings(Some(&existing), dhcp_interface_config(Some(&existing), false));
+        assert_eq!(dhcp.wakeonlan, Some(true));
+
+        let yaml = serde_yaml::to_string(&dhcp).unwrap();
+        assert!(yaml.contains("wakeonlan: true"));
+        assert!(!yaml.contains("mtu"));
+
+        let jumbo = InterfaceConfig { mtu: Some(9000), ..existing };
+        let rewritten = preserve_extra_settings(Some(&jumbo), static_interface_config("10.0.0.6/24", None, None));
+        assert!(serde_yaml::to_string(&rewritten).unwrap().contains("mtu: 9000"));
+    }
+
+    #[test]
+    fn test_policy_route_survives_static_rewrite() {
+        let mut existing = static_interface_config("192.168.1.10/24", Some("192.168.1.1"), None);
+        add_policy_route(&mut existing, "192.168.1.1", "192.168.1.10", 100);
+        // 同一路由表重复设置时替换而不是追加
+        add_policy_route(&mut existing, "192.168.1.254", "192.168.1.10", 100);
+
+        let routes = existing.routes.as_ref().unwrap();
+        assert_eq!(routes.len(), 2);
+        assert_eq!(existing.routing_policy.as_ref().unwrap().len(), 1);
+
+        let rewritten = preserve_extra_settings(
+            Some(&existing),
+            static_interface_config("192.168.1.20/24", Some("192.168.1.1"), None),
+        );
+        let routes = rewritten.routes.as_ref().unwrap();
+        assert_eq!(routes.len(), 2);
+        assert!(routes.iter().any(|r| r.table == Some(100) && r.via.as_deref() == Some("192.168.1.254")));
+
+        let yaml = serde_yaml::to_string(&rewritten).unwrap();
+        assert!(yaml.contains("routing-policy:"));
+        assert!(yaml.contains("table: 100"));
+    }
+
+    #[test]
+    fn test_advise_apply() {
+        let parse = |yaml: &str| serde_yaml::from_str::<NetplanConfig>(yaml).unwrap();
+        let old = parse("network:\n  version: 2\n  ethernets:\n    eth0:\n      dhcp4: true\n");
+
+        // 只修改地址：apply 即可
+        let new = parse("network:\n  version: 2\n  ethernets:\n    eth0:\n      addresses: [10.0.0.2/24]\n");
+        assert_eq!(advise_apply(&old, &new), ApplyAdvice::Apply);
+
+        // 未写renderer时默认为networkd
+        let new = parse("network:\n  version: 2\n  renderer: networkd\n  ethernets:\n    eth0:\n      dhcp4: true\n");
+        assert_eq!(advise_apply(&old, &new), ApplyAdvice::Apply);
+
+        let new = parse("network:\n  version: 2\n  renderer: NetworkManager\n  ethernets:\n    eth0:\n      dhcp4: true\n");
+        assert!(matches!(advise_apply(&old, &new), ApplyAdvice::Reboot(reason) if reason.contains("NetworkManager")));
+
+        let new = parse("network:\n  version: 2\n  ethernets:\n    eth0:\n      match:\n        macaddress: 52:54:00:12:34:56\n      set-name: lan0\n      dhcp4: true\n");
+        assert!(matches!(advise_apply(&old, &new), ApplyAdvice::Reboot(reason) if reason.contains("eth0")));
+    }
+
+    #[test]
+    fn test_static_rewrite_keeps_match_and_set_name() {
+        let existing: InterfaceConfig = serde_yaml::from_str("match:\n  macaddress: 52:54:00:12:34:56\nset-name: lan0\nmacaddress: 02:00:00:00:00:01\ndhcp4: true\n").unwrap();
+        let config = preserve_extra_settings(Some(&existing), static_interface_config("10.0.0.2/24", None, None));
+        assert_eq!(config.set_name.as_deref(), Some("lan0"));
+        assert_eq!(config.macaddress.as_deref(), Some("02:00:00:00:00:01"));
+        assert_eq!(config.match_rules, existing.match_rules);
+    }
+
+    #[test]
+    fn test_unmodeled_settings_round_trip() {
+        let yaml = "network:
+  version: 2
+  ethernets:
+    eth0:
+      dhcp4: false
+      addresses: [10.0.0.5/24]
+      optional: true
+      link-local: []
+      routes:
+        - to: default
+          via: 10.0.0.1
+        - to: 172.16.0.0/12
+          via: 10.0.0.254
+          metric: 50
+        - to: 10.1.0.0/16
+          scope: link
+      routing-policy:
+        - to: 192.168.0.0/16
+          table: 200
+  vlans:
+    vlan10:
+      id: 10
+      link: eth0
+  wifis:
+    wlan0:
+      access-points:
+        home:
+          password: secret
+";
+        let config: NetplanConfig = serde_yaml::from_str(yaml).unwrap();
+        let eth0 = &config.network.ethernets["eth0"];
+        assert!(eth0.routing_policy.as_ref().unwrap()[0].from.is_none());
+
+        // 修改IPv4地址和网关后，静态路由和未识别的字段都保留
+        let rewritten = preserve_extra_settings(Some(eth0), static_interface_config("10.0.0.6/24", Some("10.0.0.2"), None));
+        let routes: Vec<(&str, Option<&str>)> = rewritten.routes.iter().flatten().map(|r| (r.to.as_str(), r.via.as_deref())).collect();
+        assert_eq!(routes, [("default", Some("10.0.0.2")), ("172.16.0.0/12", Some("10.0.0.254")), ("10.1.0.0/16", None)]);
+
+        let mut config = config.clone();
+        config.network.ethernets.insert("eth0".to_string(), rewritten);
+        let output = serde_yaml::to_string(&config).unwrap();
+        for expected in ["optional: true", "link-local: []", "metric: 50", "scope: link", "vlans:", "id: 10", "wifis:", "password: secret"] {
+            assert!(output.contains(expected), "缺少 {}: {}", expected, output);
+        }
+    }
+
+    #[test]
+    fn test_ipv6_addresses_alongside_ipv4() {
+        let mut config = static_interface_config("10.0.0.5/24", None, None);
+        replace_ipv6_addresses(&mut config, &["2001:db8::5/64".to_string()]);
+        assert_eq!(config.addresses.as_deref(), Some(&["10.0.0.5/24".to_string(), "2001:db8::5/64".to_string()][..]));
+
+        // 修改IPv4地址时保留IPv6地址
+        let rewritten = preserve_extra_settings(Some(&config), static_interface_config("10.0.0.6/24", None, None));
+        assert_eq!(rewritten.addresses.as_deref(), Some(&["10.0.0.6/24".to_string(), "2001:db8::5/64".to_string()][..]));
+
+        replace_ipv6_addresses(&mut config, &[]);
+        assert_eq!(config.addresses.as_deref(), Some(&["10.0.0.5/24".to_string()][..]));
+    }
+
+    #[test]
+    fn test_ipv6_gateway_alongside_ipv4() {
+        let mut config = static_interface_config("10.0.0.5/24", Some("10.0.0.1"), None);
+        replace_ipv6_default_route(&mut config, Some("2001:db8::1"));
+
+        // 修改IPv4地址和网关时保留IPv6默认路由
+        let rewritten = preserve_extra_settings(Some(&config), static_interface_config("10.0.0.6/24", Some("10.0.0.254"), None));
+        let routes: Vec<(&str, &str)> = rewritten.routes.iter().flatten().map(|r| (r.to.as_str(), r.via.as_deref().unwrap_or_default())).collect();
+        assert_eq!(routes, [("default", "10.0.0.254"), ("::/0", "2001:db8::1")]);
+
+        replace_ipv6_default_route(&mut config, None);
+        assert_eq!(config.routes.as_ref().unwrap().len(), 1);
+        let mut ipv6_only = static_ipv6_interface_config(None);
+        replace_ipv6_default_route(&mut ipv6_only, None);
+        assert!(ipv6_only.routes.is_none());
+    }
+
+    #[test]
+    fn test_remove_interface_from_all_files() {
+        let dir = std::env::temp_dir().join(format!("nicman-netplan-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let base = "network:\n  version: 2\n  ethernets:\n    eth0:\n      dhcp4: true\n    eth1:\n      dhcp4: true\n";
+        let over = "network:\n  version: 2\n  ethernets:\n    eth0:\n      addresses: [10.0.0.5/24]\n";
+        let other = "network:\n  version: 2\n  ethernets:\n    eth1:\n      dhcp4: false\n";
+        fs::write(dir.join("01-base.yaml"), base).unwrap();
+        fs::write(dir.join("50-over.yaml"), over).unwrap();
+        fs::write(dir.join("60-other.yaml"), other).unwrap();
+
+        let manager = NetplanManager { config_dir: dir.clone(), preferred_file: None };
+        let backups = manager.remove_interface("eth0").unwrap();
+        assert_eq!(backups.len(), 2);
+
+        let base = manager.read_config(&dir.join("01-base.yaml")).unwrap();
+        assert!(!base.network.ethernets.contains_key("eth0"));
+        assert!(base.network.ethernets.contains_key("eth1"));
+        assert!(manager.read_config(&dir.join("50-over.yaml")).unwrap().network.ethernets.is_empty());
+        // 未定义eth0的文件不改动
+        assert_eq!(fs::read_to_string(dir.join("60-other.yaml")).unwrap(), other);
+        assert!(manager.find_interface_file("eth0").is_none());
+
+        assert!(manager.remove_interface("eth0").unwrap().is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_managed_manifest() {
+        let dir = std::env::temp_dir().join(format!("nicman-managed-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let manual = "network:\n  version: 2\n  ethernets:\n    eth0:\n      dhcp4: true\n";
+        fs::write(dir.join("01-manual.yaml"), manual).unwrap();
+
+        let manager = NetplanManager { config_dir: dir.clone(), preferred_file: None };
+        let manual_file = dir.join("01-manual.yaml");
+        assert!(manager.managed_interfaces().is_empty());
+        assert!(!created_by_nicman(&manual_file));
+        assert!(created_by_nicman(&dir.join("90-nicman-eth9.yaml")));
+
+        manager.set_static_ip("eth1", "10.0.0.5/24", None, None, None, None).unwrap();
+        assert_eq!(manager.managed_interfaces(), BTreeSet::from(["eth1".to_string()]));
+        // 清单不是yaml文件，不会被当作配置文件
+        assert_eq!(manager.list_config_files().unwrap(), vec![manual_file.clone()]);
+
+        manager.remove_interface("eth1").unwrap();
+        assert!(manager.managed_interfaces().is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_gateway_and_policy_require_existing_entry() {
+        let dir = std::env::temp_dir().join(format!("nicman-gateway-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let existing = "network:\n  version: 2\n  ethernets:\n    eth0:\n      dhcp4: false\n      addresses: [10.0.0.5/24]\n";
+        fs::write(dir.join("01-netcfg.yaml"), existing).unwrap();
+        let manager = NetplanManager { config_dir: dir.clone(), preferred_file: None };
+
+        // 未定义的接口不会被写成只有网关的空条目
+        assert!(manager.set_gateway("eth1", "10.0.1.1").is_err());
+        assert_eq!(fs::read_to_string(dir.join("01-netcfg.yaml")).unwrap(), existing);
+
+        assert!(manager.set_policy_route("eth1", "10.0.1.1", "10.0.1.5", 100).is_err());
+        assert_eq!(fs::read_to_string(dir.join("01-netcfg.yaml")).unwrap(), existing);
+
+        assert!(manager.set_gateway("eth0", "10.0.0.1").unwrap().1.is_some());
+        let config = manager.read_config(&dir.join("01-netcfg.yaml")).unwrap();
+        let eth0 = &config.network.ethernets["eth0"];
+        assert_eq!(eth0.addresses.as_deref(), Some(&["10.0.0.5/24".to_string()][..]));
+        assert_eq!(eth0.routes.as_ref().unwrap()[0].via.as_deref(), Some("10.0.0.1"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_bridge_stp_requires_existing_bridge() {
+        let dir = std::env::temp_dir().join(format!("nicman-bridge-stp-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let existing = "network:\n  version: 2\n  bridges:\n    br0:\n      interfaces: [eth0]\n";
+        fs::write(dir.join("01-netcfg.yaml"), existing).unwrap();
+        let manager = NetplanManager { config_dir: dir.clone(), preferred_file: None };
+
+        // 运行时创建的网桥不会被写成新的bridges条目
+        assert!(manager.set_bridge_stp("virbr0", true).is_err());
+        assert_eq!(fs::read_to_string(dir.join("01-netcfg.yaml")).unwrap(), existing);
+
+        manager.set_bridge_stp("br0", true).unwrap();
+        let config = manager.read_config(&dir.join("01-netcfg.yaml")).unwrap();
+        assert_eq!(config.network.bridges["br0"].parameters.as_ref().unwrap().stp, Some(true));
+        assert!(!config.network.bridges.contains_key("virbr0"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_add_and_remove_address() {
+        let dir = std::env::temp_dir().join(format!("nicman-alias-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let existing = "network:\n  version: 2\n  ethernets:\n    eth0:\n      dhcp4: false\n      addresses: [10.0.0.5/24]\n      routes:\n        - to: default\n          via: 10.0.0.1\n";
+        fs::write(dir.join("01-netcfg.yaml"), existing).unwrap();
+
+        let manager = NetplanManager { config_dir: dir.clone(), preferred_file: None };
+        let addresses = || {
+            let config = manager.read_config(&dir.join("01-netcfg.yaml")).unwrap();
+            config.network.ethernets["eth0"].addresses.clone()
+        };
+
+        // 追加地址不替换已有地址，重复添加不产生重复条目
+        assert!(manager.add_address("eth0", "10.0.0.6/24").unwrap().1.is_some());
+        manager.add_address("eth0", "10.0.0.6/24").unwrap();
+        assert_eq!(addresses().unwrap(), ["10.0.0.5/24", "10.0.0.6/24"]);
+
+        manager.remove_address("eth0", "10.0.0.5/24").unwrap();
+        assert_eq!(addresses().unwrap(), ["10.0.0.6/24"]);
+        manager.remove_address("eth0", "10.0.0.6/24").unwrap();
+        assert!(addresses().is_none());
+
+        let config = manager.read_config(&dir.join("01-netcfg.yaml")).unwrap();
+        assert_eq!(config.network.ethernets["eth0"].routes.as_ref().unwrap()[0].via.as_deref(), Some("10.0.0.1"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_set_persistent_name() {
+        let dir = std::env::temp_dir().join(format!("nicman-rename-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let existing = "network:\n  version: 2\n  ethernets:\n    enp0s3:\n      dhcp4: false\n      addresses: [10.0.0.5/24]\n";
+        fs::write(dir.join("01-netcfg.yaml"), existing).unwrap();
+
+        let manager = NetplanManager { config_dir: dir.clone(), preferred_file: None };
+        assert!(manager.set_persistent_name("enp0s3", "lan0", "52:54:00:AB:CD:EF").unwrap().1.is_some());
+
+        // 原有地址配置随接口移到新名称下
+        let config = manager.read_config(&dir.join("01-netcfg.yaml")).unwrap();
+        assert!(!config.network.ethernets.contains_key("enp0s3"));
+        let lan0 = &config.network.ethernets["lan0"];
+        assert_eq!(lan0.set_name.as_deref(), Some("lan0"));
+        assert_eq!(lan0.addresses.as_deref(), Some(&["10.0.0.5/24".to_string()][..]));
+        let mac = lan0.match_rules.as_ref().unwrap().get("macaddress").unwrap();
+        assert_eq!(mac.as_str(), Some("52:54:00:ab:cd:ef"));
+        assert_eq!(manager.managed_interfaces(), BTreeSet::from(["lan0".to_string()]));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_static_ipv6_only_config() {
+        let dir = std::env::temp_dir().join(format!("nicman-ipv6-only-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let existing = "network:\n  version: 2\n  ethernets:\n    eth0:\n      dhcp4: false\n      addresses: [10.0.0.5/24, 2001:db8::5/64]\n      wakeonlan: true\n";
+        fs::write(dir.join("01-netcfg.yaml"), existing).unwrap();
+
+        let manager = NetplanManager { config_dir: dir.clone(), preferred_file: None };
+        let nameservers = NameserverConfig { addresses: vec!["2001:4860:4860::8888".to_string()], search: Vec::new() };
+        let ipv6 = Ipv6Settings { addresses: vec!["2001:db8::10/64".to_string()], gateway: Some("2001:db8::1".to_string()) };
+        let (file, backup) = manager
+            .set_static_ipv6("eth0", &ipv6, Some(nameservers), Some(9000))
+            .unwrap();
+        assert_eq!(file, dir.join("01-netcfg.yaml"));
+        assert!(backup.is_some());
+
+        let yaml = fs::read_to_string(dir.join("01-netcfg.yaml")).unwrap();
+        let config: NetplanConfig = serde_yaml::from_str(&yaml).unwrap();
+        let eth0 = &config.network.ethernets["eth0"];
+        assert_eq!(eth0.dhcp4, Some(false));
+        assert_eq!(eth0.addresses.as_deref(), Some(&["2001:db8::10/64".to_string()][..]));
+        let routes = eth0.routes.as_deref().unwrap();
+        assert_eq!((routes[0].to.as_str(), routes[0].via.as_deref().unwrap_or_default()), ("::/0", "2001:db8::1"));
+        assert_eq!(eth0.nameservers.as_ref().unwrap().addresses, ["2001:4860:4860::8888"]);
+        assert_eq!(eth0.wakeonlan, Some(true));
+        assert_eq!(eth0.mtu, Some(9000));
+        assert!(!yaml.contains("10.0.0.5"));
+
+        // 地址和MTU一起写入，只产生一个备份
+        let backups = fs::read_dir(&dir).unwrap().filter(|entry| entry.as_ref().unwrap().file_name().to_string_lossy().contains(".backup.")).count();
+        assert_eq!(backups, 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_runtime_differences() {
+        let yaml = "dhcp4: false\naddresses: [192.168.1.10/24, 2001:db8::10/64]\nroutes:\n  - to: default\n    via: 192.168.1.1\n  - to: default\n    via: 10.0.0.1\n    table: 100\n";
+        let config: InterfaceConfig = serde_yaml::from_str(yaml).unwrap();
+
+        let mut iface = NetInterface::new("eth0".to_string(), crate::model::InterfaceKind::Physical);
+        iface.ipv4_addresses = vec!["192.168.1.10/24".to_string()];
+        iface.ipv6_addresses = vec!["2001:db8::10/64".to_string(), "fe80::1/64".to_string()];
+        iface.ipv4_config = Some(crate::model::Ipv4Config {
+            address: "192.168.1.10".to_string(),
+            netmask: "255.255.255.0".to_string(),
+            prefix: 24,
+            gateway: Some("192.168.1.1".to_string()),
+        });
+        assert!(runtime_differences(&config, &iface).is_empty());
+
+        // 运行时改了地址和网关
+        iface.ipv4_addresses = vec!["192.168.1.20/24".to_string()];
+        iface.ipv4_config.as_mut().unwrap().gateway = None;
+        let diffs = runtime_differences(&config, &iface);
+        assert_eq!(diffs.len(), 3);
+        assert!(diffs[0].contains("192.168.1.10/24"));
+        assert!(diffs[1].contains("192.168.1.20/24"));
+        assert!(diffs[2].contains("运行时为 无"));
+
+        // DHCP接口只比较静态地址
+        let dhcp: InterfaceConfig = serde_yaml::from_str("dhcp4: true\n").unwrap();
+        iface.ipv4_details = vec![crate::model::Ipv4AddressInfo {
+            address: "192.168.1.20/24".to_string(),
+            dynamic: true,
+            valid_lft: Some(3600),
+            preferred_lft: Some(3600),
+        }];
+        assert!(runtime_differences(&dhcp, &iface).is_empty());
+    }
+
+    #[test]
+    fn test_runtime_differences_dual_stack_default_routes() {
+        let yaml = "dhcp4: false\naddresses: [192.168.1.10/24, 2001:db8::10/64]\nroutes:\n  - to: default\n    via: 2001:db8::1\n  - to: default\n    via: 192.168.1.1\n";
+        let config: InterfaceConfig = serde_yaml::from_str(yaml).unwrap();
+
+        let mut iface = NetInterface::new("eth0".to_string(), crate::model::InterfaceKind::Physical);
+        iface.ipv4_addresses = vec!["192.168.1.10/24".to_string()];
+        iface.ipv6_addresses = vec!["2001:db8::10/64".to_string()];
+        iface.ipv4_config = Some(crate::model::Ipv4Config {
+            address: "192.168.1.10".to_string(),
+            netmask: "255.255.255.0".to_string(),
+            prefix: 24,
+            gateway: Some("192.168.1.1".to_string()),
+        });
+        iface.ipv6_gateway = Some("2001:db8::1".to_string());
+        // IPv6网关写在前面也不会被当作IPv4网关比较
+        assert!(runtime_differences(&config, &iface).is_empty());
+
+        iface.ipv6_gateway = None;
+        let diffs = runtime_differences(&config, &iface);
+        assert_eq!(diffs.len(), 1);
+        assert!(diffs[0].starts_with("IPv6默认网关"));
+    }
+
+    #[test]
+    fn test_merged_ethernets() {
+        let dir = std::env::temp_dir().join(format!("nicman-merged-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("01-base.yaml"),
+            "network:\n  version: 2\n  ethernets:\n    eth0:\n      dhcp4: true\n      mtu: 1500\n    eth1:\n      dhcp4: true\n",
+        ).unwrap();
+        fs::write(
+            dir.join("50-static.yaml"),
+            "network:\n  version: 2\n  ethernets:\n    eth0:\n      dhcp4: false\n      addresses: [10.0.0.5/24]\n      routes:\n        - to: default\n          via: 10.0.0.1\n",
+        ).unwrap();
+
+        let manager = NetplanManager { config_dir: dir.clone(), preferred_file: None };
+        let merged = manager.merged_ethernets();
+        let eth0 = &merged["eth0"];
+        assert_eq!(eth0.dhcp4, Some(false));
+        assert_eq!(eth0.mtu, Some(1500));
+        assert_eq!(eth0.addresses.as_deref(), Some(&["10.0.0.5/24".to_string()][..]));
+        assert_eq!(merged["eth1"].dhcp4, Some(true));
+
+        // 后面的文件关闭了DHCP，运行时的静态地址和网关与合并结果一致
+        let mut iface = NetInterface::new("eth0".to_string(), crate::model::InterfaceKind::Physical);
+        iface.ipv4_addresses = vec!["10.0.0.5/24".to_string()];
+        iface.ipv4_config = Some(crate::model::Ipv4Config {
+            address: "10.0.0.5".to_string(),
+            netmask: "255.255.255.0".to_string(),
+            prefix: 24,
+            gateway: Some("10.0.0.1".to_string()),
+        });
+        assert!(runtime_differences(eth0, &iface).is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,6 +1,7 @@
 // 创建者检测模块 - 检测systemd服务、Docker容器、进程等创建者
 use crate::model::{InterfaceKind, InterfaceOwner, NetInterface, ServiceStatus};
 use crate::utils::command::{command_success, execute_command_stdout};
+use anyhow::{Context, Result};
 use regex::Regex;
 use std::fs;
 
@@ -11,12 +12,16 @@ impl OwnerDetector {
     /// 检测接口的创建者
     pub fn detect(iface: &NetInterface) -> Option<InterfaceOwner> {
         // 按优先级依次检测
-        None
+        let owner = None
             .or_else(|| Self::check_docker_container(&iface.name, &iface.kind))
+            .or_else(|| Self::check_nspawn_machine(&iface.name))
             .or_else(|| Self::check_systemd_service(&iface.name, &iface.kind))
             .or_else(|| Self::check_process_fd(&iface.name))
             .or_else(|| Self::check_network_manager(&iface.name))
-            .or_else(|| Self::check_kernel_module(&iface.name, &iface.kind))
+            .or_else(|| Self::check_kernel_module(&iface.name, &iface.kind));
+
+        tracing::debug!(iface = %iface.name, owner = ?owner, "创建者检测完成");
+        owner
     }
 
     /// 检测Docker容器
@@ -31,6 +36,11 @@ impl OwnerDetector {
             return None;
         }
 
+        // br-*网桥对应自定义Docker网络（如compose网络），查不到时按系统网桥处理
+        if let Some(network) = Self::check_docker_network(iface_name) {
+            return Some(network);
+        }
+
         // 对于Docker网桥，直接返回
         if iface_name == "docker0" || iface_name.starts_with("br-") {
             return Some(InterfaceOwner::DockerContainer {
@@ -78,26 +88,76 @@ impl OwnerDetector {
         None
     }
 
+    /// 根据 br-<网络ID前缀> 查找对应的Docker网络，Docker守护进程不可用时返回None
+    fn check_docker_network(iface_name: &str) -> Option<InterfaceOwner> {
+        let id_prefix = iface_name.strip_prefix("br-")?;
+        let output = execute_command_stdout("docker", &["network", "ls", "--no-trunc", "--format", "{{.ID}}\t{{.Name}}"]).ok()?;
+        let (id, name) = Self::match_docker_network(id_prefix, &output)?;
+        let containers = Self::docker_network_containers(&id).ok()?;
+        Some(InterfaceOwner::DockerNetwork { id, name, containers })
+    }
+
+    /// 从docker network ls输出中找到ID以指定前缀开头的网络
+    fn match_docker_network(id_prefix: &str, output: &str) -> Option<(String, String)> {
+        output
+            .lines()
+            .filter_map(|line| line.split_once('\t'))
+            .find(|(id, _)| !id_prefix.is_empty() && id.starts_with(id_prefix))
+            .map(|(id, name)| (id.to_string(), name.trim().to_string()))
+    }
+
+    /// 查询连接到Docker网络的容器数
+    pub fn docker_network_containers(id: &str) -> Result<usize> {
+        let output = execute_command_stdout("docker", &["network", "inspect", "-f", "{{len .Containers}}", id])
+            .with_context(|| format!("查询Docker网络 {} 失败", id))?;
+        output.trim().parse().with_context(|| format!("解析Docker网络 {} 的容器数失败", id))
+    }
+
     /// 检查容器是否拥有指定的veth接口
     fn container_has_veth(container_id: &str, _iface_name: &str) -> bool {
         // 获取容器的网络命名空间PID
-        if let Ok(output) = execute_command_stdout("docker", &["inspect", "-f", "{{.State.Pid}}", container_id]) {
-            if let Ok(pid) = output.trim().parse::<u32>() {
-                // 检查容器的网络接口
-                if let Ok(output) = execute_command_stdout("nsenter", &["-t", &pid.to_string(), "-n", "ip", "link", "show"]) {
-                    // 检查veth接口的对端是否在容器内
-                    // veth接口成对出现，主机端的veth对应容器内的eth0等
-                    if output.contains("eth0") || output.contains("eth1") {
-                        // 简化：如果容器有网络接口，就认为这个veth可能属于它
-                        // 更精确的方法需要检查veth的peer index
-                        return true;
-                    }
+        if let Ok(output) = execute_command_stdout("docker", &["inspect", "-f", "{{.State.Pid}}", container_id])
+            && let Ok(pid) = output.trim().parse::<u32>() {
+            // 检查容器的网络接口
+            if let Ok(output) = execute_command_stdout("nsenter", &["-t", &pid.to_string(), "-n", "ip", "link", "show"]) {
+                // 检查veth接口的对端是否在容器内
+                // veth接口成对出现，主机端的veth对应容器内的eth0等
+                if output.contains("eth0") || output.contains("eth1") {
+                    // 简化：如果容器有网络接口，就认为这个veth可能属于它
+                    // 更精确的方法需要检查veth的peer index
+                    return true;
                 }
             }
         }
         false
     }
 
+    /// 检测systemd-nspawn容器（主机端接口为 ve-<机器名> 或 vb-<机器名>）
+    fn check_nspawn_machine(iface_name: &str) -> Option<InterfaceOwner> {
+        if !iface_name.starts_with("ve-") && !iface_name.starts_with("vb-") {
+            return None;
+        }
+
+        // 检查machinectl命令是否可用
+        if !command_success("machinectl", &["--version"]) {
+            return None;
+        }
+
+        let output = execute_command_stdout("machinectl", &["list", "--no-legend"]).ok()?;
+        Self::match_nspawn_machine(iface_name, &output).map(|machine| InterfaceOwner::NspawnMachine { machine })
+    }
+
+    /// 从machinectl list输出中找到接口对应的机器
+    fn match_nspawn_machine(iface_name: &str, output: &str) -> Option<String> {
+        // 接口名受IFNAMSIZ限制最多15字符，机器名可能被截断，按前缀匹配
+        let suffix = &iface_name[3..];
+        output
+            .lines()
+            .filter_map(|line| line.split_whitespace().next())
+            .find(|machine| *machine == suffix || (suffix.len() >= 12 && machine.starts_with(suffix)))
+            .map(|machine| machine.to_string())
+    }
+
     /// 检测systemd服务
     fn check_systemd_service(iface_name: &str, kind: &InterfaceKind) -> Option<InterfaceOwner> {
         // 常见的服务命名模式
@@ -109,10 +169,9 @@ impl OwnerDetector {
         ];
 
         // 对于WireGuard接口，优先检查wg-quick服务
-        if matches!(kind, InterfaceKind::WireGuard) {
-            if let Some(owner) = Self::check_service(&format!("wg-quick@{}.service", iface_name)) {
-                return Some(owner);
-            }
+        if matches!(kind, InterfaceKind::WireGuard)
+            && let Some(owner) = Self::check_service(&format!("wg-quick@{}.service", iface_name)) {
+            return Some(owner);
         }
 
         // 检查其他服务模式
@@ -171,12 +230,10 @@ impl OwnerDetector {
         // 遍历/proc目录查找持有/dev/net/tun的进程
         if let Ok(entries) = fs::read_dir("/proc") {
             for entry in entries.flatten() {
-                if let Ok(file_name) = entry.file_name().into_string() {
-                    if let Ok(pid) = file_name.parse::<u32>() {
-                        if let Some(owner) = Self::check_process_tun(pid, iface_name) {
-                            return Some(owner);
-                        }
-                    }
+                if let Ok(file_name) = entry.file_name().into_string()
+                    && let Ok(pid) = file_name.parse::<u32>()
+                    && let Some(owner) = Self::check_process_tun(pid, iface_name) {
+                    return Some(owner);
                 }
             }
         }
@@ -189,21 +246,20 @@ impl OwnerDetector {
         let fd_dir = format!("/proc/{}/fd", pid);
         if let Ok(entries) = fs::read_dir(&fd_dir) {
             for entry in entries.flatten() {
-                if let Ok(link) = fs::read_link(entry.path()) {
-                    if link.to_string_lossy().contains("/dev/net/tun") {
-                        // 验证这个进程是否真的拥有这个接口
-                        // 通过检查进程的网络命名空间中是否有这个接口
-                        if Self::process_owns_interface(pid, iface_name) {
-                            // 读取进程信息
-                            let name = Self::read_process_name(pid).unwrap_or_else(|| format!("pid-{}", pid));
-                            let cmdline = Self::read_process_cmdline(pid).unwrap_or_default();
-
-                            return Some(InterfaceOwner::Process {
-                                pid,
-                                name,
-                                cmdline,
-                            });
-                        }
+                if let Ok(link) = fs::read_link(entry.path())
+                    && link.to_string_lossy().contains("/dev/net/tun") {
+                    // 验证这个进程是否真的拥有这个接口
+                    // 通过检查进程的网络命名空间中是否有这个接口
+                    if Self::process_owns_interface(pid, iface_name) {
+                        // 读取进程信息
+                        let name = Self::read_process_name(pid).unwrap_or_else(|| format!("pid-{}", pid));
+                        let cmdline = Self::read_process_cmdline(pid).unwrap_or_default();
+
+                        return Some(InterfaceOwner::Process {
+                            pid,
+                            name,
+                            cmdline,
+                        });
                     }
                 }
             }
@@ -244,18 +300,17 @@ impl OwnerDetector {
         }
 
         // 检查接口是否由NetworkManager管理
-        if let Ok(output) = execute_command_stdout("nmcli", &["device", "show", iface_name]) {
-            if output.contains("GENERAL.CONNECTION") {
-                // 提取连接名称和UUID
-                let connection = Self::extract_nm_connection(&output);
-                let uuid = Self::extract_nm_uuid(&output);
-
-                if let (Some(conn), Some(uuid_val)) = (connection, uuid) {
-                    return Some(InterfaceOwner::NetworkManager {
-                        connection: conn,
-                        uuid: uuid_val,
-                    });
-                }
+        if let Ok(output) = execute_command_stdout("nmcli", &["device", "show", iface_name])
+            && output.contains("GENERAL.CONNECTION") {
+            // 提取连接名称和UUID
+            let connection = Self::extract_nm_connection(&output);
+            let uuid = Self::extract_nm_uuid(&output);
+
+            if let (Some(conn), Some(uuid_val)) = (connection, uuid) {
+                return Some(InterfaceOwner::NetworkManager {
+                    connection: conn,
+                    uuid: uuid_val,
+                });
             }
         }
 
@@ -284,19 +339,98 @@ impl OwnerDetector {
             InterfaceKind::Bridge => "bridge",
             InterfaceKind::Vlan => "8021q",
             InterfaceKind::WireGuard => "wireguard",
+            InterfaceKind::Tun | InterfaceKind::Tap => "tun",
+            InterfaceKind::Macvlan => "macvlan",
+            InterfaceKind::Vxlan => "vxlan",
+            InterfaceKind::Gre => "ip_gre",
+            InterfaceKind::Ifb => "ifb",
             _ => return None,
         };
 
         // 检查模块是否加载
-        if let Ok(output) = execute_command_stdout("lsmod", &[]) {
-            if output.contains(module) {
-                return Some(InterfaceOwner::Kernel {
-                    module: module.to_string(),
-                });
-            }
+        if let Ok(output) = execute_command_stdout("lsmod", &[])
+            && Self::lsmod_has_module(&output, module) {
+            return Some(InterfaceOwner::Kernel {
+                module: module.to_string(),
+            });
         }
 
         None
     }
+
+    /// 检查lsmod输出中是否加载了指定模块（逐行比较第一列模块名）
+    fn lsmod_has_module(output: &str, module: &str) -> bool {
+        output
+            .lines()
+            .skip(1) // 跳过 "Module Size Used by" 表头
+            .filter_map(|line| line.split_whitespace().next())
+            .any(|name| name == module)
+    }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_nspawn_machine() {
+        let output = "debian-test container systemd-nspawn debian 12 10.0.0.2\n\
+                      a-very-long-machine-name container systemd-nspawn ubuntu 24.04 -\n";
+
+        assert_eq!(
+            OwnerDetector::match_nspawn_machine("ve-debian-test", output),
+            Some("debian-test".to_string())
+        );
+        assert_eq!(
+            OwnerDetector::match_nspawn_machine("ve-a-very-long-", output),
+            Some("a-very-long-machine-name".to_string())
+        );
+        assert_eq!(OwnerDetector::match_nspawn_machine("ve-debian", output), None);
+    }
+
+    #[test]
+    fn test_match_docker_network() {
+        let output = "3f2a9c1d7e4b5a6c8d9e0f1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d7e8f9a0b1c\tmyapp_default\n\
+                      9b8c7d6e5f4a3b2c1d0e9f8a7b6c5d4e3f2a1b0c9d8e7f6a5b4c3d2e1f0a9b8c\tbridge\n";
+
+        assert_eq!(
+            OwnerDetector::match_docker_network("3f2a9c1d7e4b", output),
+            Some(("3f2a9c1d7e4b5a6c8d9e0f1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d7e8f9a0b1c".to_string(), "myapp_default".to_string()))
+        );
+        assert_eq!(OwnerDetector::match_docker_network("000000000000", output), None);
+        assert_eq!(OwnerDetector::match_docker_network("", output), None);
+    }
+
+    #[test]
+    fn test_lsmod_has_module() {
+        let output = "Module                  Size  Used by\n\
+                      bridge_foo             16384  0\n\
+                      br_netfilter           32768  0\n\
+                      tun                    61440  4\n\
+                      macvlan                32768  0\n\
+                      ip_gre                 32768  0\n";
+
+        assert!(OwnerDetector::lsmod_has_module(output, "tun"));
+        assert!(OwnerDetector::lsmod_has_module(output, "macvlan"));
+        assert!(OwnerDetector::lsmod_has_module(output, "ip_gre"));
+        assert!(!OwnerDetector::lsmod_has_module(output, "bridge"));
+        assert!(!OwnerDetector::lsmod_has_module(output, "vxlan"));
+        assert!(!OwnerDetector::lsmod_has_module(output, "gre"));
+    }
+
+    #[test]
+    fn test_lsmod_exact_module_name() {
+        // Used by 列中出现的模块名和名称更长的模块都不应被误判
+        let output = "Module                  Size  Used by\n\
+                      br_netfilter           32768  0\n\
+                      bridge_stp_helper      16384  1 br_netfilter\n\
+                      nf_conntrack          172032  2 tun_helper,vxlan_offload\n";
+
+        assert!(OwnerDetector::lsmod_has_module(output, "br_netfilter"));
+        assert!(!OwnerDetector::lsmod_has_module(output, "bridge"));
+        assert!(!OwnerDetector::lsmod_has_module(output, "tun_helper"));
+        assert!(!OwnerDetector::lsmod_has_module(output, "vxlan"));
+        assert!(!OwnerDetector::lsmod_has_module(output, "Module"));
+    }
+}
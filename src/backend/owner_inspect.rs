@@ -0,0 +1,211 @@
+// 创建者详情模块 - 查看进程和Docker容器的详细信息（只读）
+use crate::utils::command::execute_command_stdout;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+
+/// 创建者详情，按顺序显示的 (标签, 值) 列表
+#[derive(Debug, Clone, PartialEq)]
+pub struct OwnerDetails {
+    pub title: String,
+    pub fields: Vec<(String, String)>,
+}
+
+/// /proc/<pid>/status 中显示的字段及其标签
+const STATUS_FIELDS: [(&str, &str); 6] = [
+    ("Name", "进程名"),
+    ("State", "状态"),
+    ("PPid", "父进程"),
+    ("Uid", "UID"),
+    ("Threads", "线程数"),
+    ("VmRSS", "内存(RSS)"),
+];
+
+/// 读取进程详情：status摘要、完整命令行、cgroup和打开的文件数
+pub fn inspect_process(pid: u32) -> Result<OwnerDetails> {
+    let status = fs::read_to_string(format!("/proc/{}/status", pid))
+        .with_context(|| format!("读取进程 {} 的状态失败（进程可能已退出）", pid))?;
+
+    let mut fields = parse_proc_status(&status);
+    let cmdline = fs::read_to_string(format!("/proc/{}/cmdline", pid)).unwrap_or_default();
+    fields.push(("命令行".to_string(), cmdline.replace('\0', " ").trim().to_string()));
+    if let Ok(cgroup) = fs::read_to_string(format!("/proc/{}/cgroup", pid)) {
+        fields.push(("cgroup".to_string(), parse_cgroup(&cgroup)));
+    }
+    let open_files = fs::read_dir(format!("/proc/{}/fd", pid))
+        .map(|entries| entries.count().to_string())
+        .unwrap_or_else(|_| "无法读取".to_string());
+    fields.push(("打开的文件".to_string(), open_files));
+
+    Ok(OwnerDetails { title: format!("进程 {}", pid), fields })
+}
+
+/// 读取Docker容器详情：镜像、状态、端口映射和挂载
+pub fn inspect_container(id: &str) -> Result<OwnerDetails> {
+    let output = execute_command_stdout("docker", &["inspect", id])
+        .with_context(|| format!("查询容器 {} 失败", id))?;
+    parse_docker_inspect(&output)
+}
+
+/// 从 /proc/<pid>/status 中挑出常用字段
+fn parse_proc_status(content: &str) -> Vec<(String, String)> {
+    let values: BTreeMap<&str, &str> = content
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.trim(), value.trim()))
+        .collect();
+
+    STATUS_FIELDS
+        .iter()
+        .filter_map(|(key, label)| {
+            // Uid行为 "实际 有效 保存 文件系统" 四列，只显示实际UID
+            let value = values.get(key)?;
+            let value = if *key == "Uid" { value.split_whitespace().next()? } else { value };
+            Some((label.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// cgroup v2只有一行 "0::/path"，v1取第一行的路径
+fn parse_cgroup(content: &str) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    lines
+        .iter()
+        .find(|line| line.starts_with("0::"))
+        .or(lines.first())
+        .and_then(|line| line.splitn(3, ':').nth(2))
+        .unwrap_or("")
+        .to_string()
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct DockerInspect {
+    name: String,
+    config: DockerInspectConfig,
+    state: DockerInspectState,
+    #[serde(default)]
+    mounts: Vec<DockerMount>,
+    network_settings: DockerNetworkSettings,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct DockerInspectConfig {
+    image: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct DockerInspectState {
+    status: String,
+    pid: u32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct DockerMount {
+    source: String,
+    destination: String,
+    #[serde(rename = "RW")]
+    rw: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct DockerNetworkSettings {
+    // 未发布的端口值为null
+    #[serde(default)]
+    ports: BTreeMap<String, Option<Vec<DockerPortBinding>>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct DockerPortBinding {
+    host_ip: String,
+    host_port: String,
+}
+
+/// 解析 docker inspect 的JSON输出（单个容器的数组）
+fn parse_docker_inspect(json: &str) -> Result<OwnerDetails> {
+    let containers: Vec<DockerInspect> = serde_json::from_str(json).context("解析docker inspect输出失败")?;
+    let Some(container) = containers.into_iter().next() else {
+        anyhow::bail!("docker inspect 没有返回容器");
+    };
+
+    let name = container.name.trim_start_matches('/').to_string();
+    let mut fields = vec![
+        ("镜像".to_string(), container.config.image),
+        ("状态".to_string(), container.state.status),
+        ("主进程".to_string(), container.state.pid.to_string()),
+    ];
+
+    let ports: Vec<String> = container
+        .network_settings
+        .ports
+        .iter()
+        .map(|(port, bindings)| match bindings.as_deref() {
+            Some(bindings) if !bindings.is_empty() => {
+                let hosts: Vec<String> = bindings
+                    .iter()
+                    .map(|binding| format!("{}:{}", binding.host_ip, binding.host_port))
+                    .collect();
+                format!("{} → {}", hosts.join(", "), port)
+            }
+            _ => format!("{}（未发布）", port),
+        })
+        .collect();
+    fields.push(("端口".to_string(), if ports.is_empty() { "无".to_string() } else { ports.join("; ") }));
+
+    if container.mounts.is_empty() {
+        fields.push(("挂载".to_string(), "无".to_string()));
+    }
+    for mount in &container.mounts {
+        fields.push((
+            "挂载".to_string(),
+            format!("{} → {}{}", mount.source, mount.destination, if mount.rw { "" } else { " (只读)" }),
+        ));
+    }
+
+    Ok(OwnerDetails { title: format!("容器 {}", name), fields })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_proc_status() {
+        let status = "Name:\topenvpn\nUmask:\t0022\nState:\tS (sleeping)\nPPid:\t1\nUid:\t0\t0\t0\t0\nThreads:\t1\nVmRSS:\t    8124 kB\n";
+        let fields = parse_proc_status(status);
+        assert_eq!(fields[0], ("进程名".to_string(), "openvpn".to_string()));
+        assert!(fields.contains(&("UID".to_string(), "0".to_string())));
+        assert!(fields.contains(&("内存(RSS)".to_string(), "8124 kB".to_string())));
+        assert!(!fields.iter().any(|(label, _)| label == "Umask"));
+
+        assert_eq!(parse_cgroup("0::/system.slice/openvpn.service\n"), "/system.slice/openvpn.service");
+        assert_eq!(parse_cgroup("12:pids:/docker/abc\n11:memory:/docker/abc\n"), "/docker/abc");
+    }
+
+    #[test]
+    fn test_parse_docker_inspect() {
+        let json = r#"[{
+            "Name": "/web",
+            "Config": {"Image": "nginx:1.25"},
+            "State": {"Status": "running", "Pid": 4321},
+            "Mounts": [{"Source": "/srv/www", "Destination": "/usr/share/nginx/html", "RW": false}],
+            "NetworkSettings": {"Ports": {
+                "80/tcp": [{"HostIp": "0.0.0.0", "HostPort": "8080"}],
+                "443/tcp": null
+            }}
+        }]"#;
+        let details = parse_docker_inspect(json).unwrap();
+        assert_eq!(details.title, "容器 web");
+        assert!(details.fields.contains(&("主进程".to_string(), "4321".to_string())));
+        assert!(details.fields.contains(&("端口".to_string(), "443/tcp（未发布）; 0.0.0.0:8080 → 80/tcp".to_string())));
+        assert!(details.fields.contains(&("挂载".to_string(), "/srv/www → /usr/share/nginx/html (只读)".to_string())));
+
+        assert!(parse_docker_inspect("[]").is_err());
+    }
+}
@@ -0,0 +1,81 @@
+// 路径MTU探测模块 - 通过禁止分片的ping二分查找最大可用包长
+use crate::utils::command::command_success;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// IPv4头(20) + ICMP头(8)
+const IP_ICMP_HEADER: u32 = 28;
+
+/// 探测进度（供界面轮询显示）
+#[derive(Debug, Clone, Default)]
+pub struct PmtuProgress {
+    pub target: String,
+    pub low: u32,                           // 已确认可通过的最大载荷
+    pub high: u32,                          // 搜索上界
+    pub probing: u32,                       // 当前探测的载荷大小
+    pub result: Option<Result<u32, String>>, // 完成后的路径MTU或错误
+}
+
+/// 在后台线程中启动路径MTU探测
+pub fn spawn_discovery(iface_name: &str, target: &str, iface_mtu: u32) -> Arc<Mutex<PmtuProgress>> {
+    let progress = Arc::new(Mutex::new(PmtuProgress {
+        target: target.to_string(),
+        high: iface_mtu.saturating_sub(IP_ICMP_HEADER),
+        ..Default::default()
+    }));
+
+    let shared = Arc::clone(&progress);
+    let iface_name = iface_name.to_string();
+    let target = target.to_string();
+    thread::spawn(move || {
+        let result = discover(&iface_name, &target, iface_mtu, |low, high, probing| {
+            if let Ok(mut p) = shared.lock() {
+                p.low = low;
+                p.high = high;
+                p.probing = probing;
+            }
+        });
+        if let Ok(mut p) = shared.lock() {
+            p.result = Some(result);
+        }
+    });
+
+    progress
+}
+
+/// 二分查找最大不分片载荷，返回路径MTU
+fn discover(
+    iface_name: &str,
+    target: &str,
+    iface_mtu: u32,
+    mut on_progress: impl FnMut(u32, u32, u32),
+) -> Result<u32, String> {
+    let mut low = 0;
+    let mut high = iface_mtu.saturating_sub(IP_ICMP_HEADER);
+
+    // 先确认目标可达
+    on_progress(low, high, low);
+    if !probe(iface_name, target, low) {
+        return Err(format!("{} 不可达", target));
+    }
+
+    while low < high {
+        let mid = low + (high - low).div_ceil(2);
+        on_progress(low, high, mid);
+        if probe(iface_name, target, mid) {
+            low = mid;
+        } else {
+            high = mid - 1;
+        }
+    }
+
+    Ok(low + IP_ICMP_HEADER)
+}
+
+/// 发送一个禁止分片的ping包
+fn probe(iface_name: &str, target: &str, payload: u32) -> bool {
+    command_success(
+        "ping",
+        &["-M", "do", "-c", "1", "-W", "1", "-s", &payload.to_string(), "-I", iface_name, target],
+    )
+}
@@ -0,0 +1,125 @@
+// 策略路由模块 - 为多出口主机按源地址选择路由表（ip rule + 独立路由表）
+use crate::backend::runtime;
+use crate::model::PolicyRule;
+use crate::utils::command::{execute_command_stdout, execute_privileged_stdout, privileged_success};
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::net::Ipv4Addr;
+
+/// 添加规则前最多删除的重复规则条数
+const MAX_DUPLICATE_RULES: usize = 32;
+
+/// 列出所有指定了源地址的策略路由规则（忽略 from all 的系统默认规则）
+pub fn list_rules() -> Result<Vec<PolicyRule>> {
+    let output = execute_command_stdout("ip", &["rule", "show"])
+        .context("查询策略路由规则失败")?;
+    Ok(parse_rules(&output))
+}
+
+/// 解析 ip rule show 输出，如 "32765: from 192.168.1.10 lookup 100"
+fn parse_rules(output: &str) -> Vec<PolicyRule> {
+    let re = Regex::new(r"^(\d+):\s+from\s+(\S+)\s+lookup\s+(\S+)").unwrap();
+    output
+        .lines()
+        .filter_map(|line| re.captures(line.trim()))
+        .filter(|caps| &caps[2] != "all")
+        .filter_map(|caps| {
+            Some(PolicyRule {
+                priority: caps[1].parse().ok()?,
+                from: caps[2].to_string(),
+                table: caps[3].to_string(),
+            })
+        })
+        .collect()
+}
+
+/// 规则的源地址是否属于接口的某个地址
+pub fn rule_matches(rule: &PolicyRule, addresses: &[String]) -> bool {
+    addresses.iter().any(|address| {
+        let ip = address.split('/').next().unwrap_or(address);
+        if rule.from.contains('/') {
+            runtime::gateway_in_subnets(ip, std::slice::from_ref(&rule.from))
+        } else {
+            rule.from == ip
+        }
+    })
+}
+
+/// 在独立路由表中添加经指定网关的默认路由，并按源地址选择该表
+pub fn add_policy_route(iface_name: &str, gateway: &str, source: &str, table: u32) -> Result<()> {
+    validate_policy(gateway, source, table)?;
+    let table = table.to_string();
+
+    execute_privileged_stdout(
+        "ip",
+        &["route", "replace", "default", "via", gateway, "dev", iface_name, "table", &table],
+    )
+    .with_context(|| format!("添加路由表 {} 的默认路由失败", table))?;
+
+    // 先删除可能存在的相同规则，避免重复添加；限制次数，防止删除命令异常地一直成功时陷入死循环
+    for _ in 0..MAX_DUPLICATE_RULES {
+        if !privileged_success("ip", &["rule", "del", "from", source, "table", &table]) {
+            break;
+        }
+    }
+    execute_privileged_stdout("ip", &["rule", "add", "from", source, "table", &table])
+        .with_context(|| format!("添加源地址 {} 的策略路由规则失败", source))?;
+    Ok(())
+}
+
+/// 校验网关、源地址和路由表编号
+fn validate_policy(gateway: &str, source: &str, table: u32) -> Result<()> {
+    gateway
+        .parse::<Ipv4Addr>()
+        .with_context(|| format!("无效的网关地址: {}", gateway))?;
+
+    let (source_ip, prefix) = source.split_once('/').unwrap_or((source, "32"));
+    source_ip
+        .parse::<Ipv4Addr>()
+        .with_context(|| format!("无效的源地址: {}", source))?;
+    if !prefix.parse::<u8>().is_ok_and(|p| p <= 32) {
+        anyhow::bail!("无效的源地址前缀: {}", source);
+    }
+
+    // 0和253~255为内核保留的 unspec/default/main/local 表
+    if table == 0 || table >= 253 {
+        anyhow::bail!("路由表编号必须在1~252之间: {}", table);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rules() {
+        let output = "0:\tfrom all lookup local\n\
+                      32764:\tfrom 10.0.2.0/24 lookup 200\n\
+                      32765:\tfrom 192.168.1.10 lookup 100\n\
+                      32766:\tfrom all lookup main\n\
+                      32767:\tfrom all lookup default\n";
+        let rules = parse_rules(output);
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[1], PolicyRule {
+            priority: 32765,
+            from: "192.168.1.10".to_string(),
+            table: "100".to_string(),
+        });
+
+        let addresses = vec!["192.168.1.10/24".to_string()];
+        assert!(rule_matches(&rules[1], &addresses));
+        assert!(!rule_matches(&rules[0], &addresses));
+        assert!(rule_matches(&rules[0], &["10.0.2.15/24".to_string()]));
+    }
+
+    #[test]
+    fn test_validate_policy() {
+        assert!(validate_policy("192.168.1.1", "192.168.1.10", 100).is_ok());
+        assert!(validate_policy("192.168.1.1", "192.168.1.0/24", 100).is_ok());
+        assert!(validate_policy("gw", "192.168.1.10", 100).is_err());
+        assert!(validate_policy("192.168.1.1", "192.168.1.0/33", 100).is_err());
+        assert!(validate_policy("192.168.1.1", "192.168.1.10", 254).is_err());
+        assert!(validate_policy("192.168.1.1", "192.168.1.10", 0).is_err());
+    }
+}
@@ -1,8 +1,14 @@
 // 智能删除模块 - 智能删除虚拟接口并防止自动重启
 use crate::backend::runtime;
-use crate::model::{InterfaceOwner, NetInterface, RemovalStrategy};
-use crate::utils::command::{command_success, execute_command_stdout};
+use crate::model::{InterfaceKind, InterfaceOwner, NetInterface, RemovalStrategy};
+use crate::utils::command::{execute_privileged_stdout, privileged_success};
+use crate::utils::icons::Symbol;
 use anyhow::{Context, Result};
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// SIGTERM后等待进程退出的默认时间，超时后发送SIGKILL
+pub const DEFAULT_KILL_GRACE: Duration = Duration::from_secs(1);
 
 /// 接口删除管理器
 pub struct RemovalManager;
@@ -14,7 +20,7 @@ impl RemovalManager {
             Some(InterfaceOwner::SystemdService { .. }) => {
                 RemovalStrategy::StopAndDisableService
             }
-            Some(InterfaceOwner::DockerContainer { .. }) => {
+            Some(InterfaceOwner::DockerContainer { .. } | InterfaceOwner::NspawnMachine { .. }) => {
                 RemovalStrategy::StopContainer
             }
             Some(InterfaceOwner::Process { .. }) => {
@@ -27,8 +33,35 @@ impl RemovalManager {
         }
     }
 
-    /// 执行删除操作
-    pub fn remove_interface(iface: &NetInterface, strategy: &RemovalStrategy) -> Result<()> {
+    /// 列出接口可用的删除策略
+    pub fn applicable_strategies(iface: &NetInterface) -> Vec<RemovalStrategy> {
+        let mut strategies = vec![RemovalStrategy::InterfaceOnly];
+        match &iface.owner {
+            Some(InterfaceOwner::SystemdService { .. }) => {
+                strategies.push(RemovalStrategy::StopService);
+                strategies.push(RemovalStrategy::StopAndDisableService);
+            }
+            Some(InterfaceOwner::NetworkManager { .. }) => {
+                strategies.push(RemovalStrategy::StopService);
+            }
+            Some(InterfaceOwner::DockerContainer { .. } | InterfaceOwner::NspawnMachine { .. }) => {
+                strategies.push(RemovalStrategy::StopContainer);
+            }
+            Some(InterfaceOwner::Process { .. }) => {
+                strategies.push(RemovalStrategy::KillProcess);
+            }
+            _ => {}
+        }
+        strategies
+    }
+
+    /// 执行删除操作，`kill_grace` 为终止进程时等待SIGTERM生效的最长时间
+    pub fn remove_interface(iface: &NetInterface, strategy: &RemovalStrategy, kill_grace: Duration) -> Result<()> {
+        // 物理网卡和回环接口无法删除，统一在此拒绝，所有调用方共享该检查
+        if matches!(iface.kind, InterfaceKind::Physical | InterfaceKind::Loopback) {
+            anyhow::bail!("拒绝删除接口 {}: {}不能被删除", iface.name, iface.kind.display_name());
+        }
+
         match strategy {
             RemovalStrategy::InterfaceOnly => {
                 Self::remove_interface_only(&iface.name)
@@ -46,7 +79,7 @@ impl RemovalManager {
                 Self::remove_interface_only(&iface.name)
             }
             RemovalStrategy::KillProcess => {
-                Self::kill_process(iface)?;
+                Self::kill_process(iface, kill_grace)?;
                 Self::remove_interface_only(&iface.name)
             }
         }
@@ -61,9 +94,9 @@ impl RemovalManager {
     /// 停止systemd服务
     fn stop_service(iface: &NetInterface) -> Result<()> {
         if let Some(InterfaceOwner::SystemdService { name, .. }) = &iface.owner {
-            execute_command_stdout("systemctl", &["stop", name])
+            execute_privileged_stdout("systemctl", &["stop", name])
                 .with_context(|| format!("停止服务 {} 失败", name))?;
-            println!("✅ 已停止服务: {}", name);
+            info!("已停止服务: {}", name);
         }
         Ok(())
     }
@@ -72,54 +105,60 @@ impl RemovalManager {
     fn stop_and_disable_service(iface: &NetInterface) -> Result<()> {
         if let Some(InterfaceOwner::SystemdService { name, .. }) = &iface.owner {
             // 停止服务
-            execute_command_stdout("systemctl", &["stop", name])
+            execute_privileged_stdout("systemctl", &["stop", name])
                 .with_context(|| format!("停止服务 {} 失败", name))?;
-            println!("✅ 已停止服务: {}", name);
+            info!("已停止服务: {}", name);
 
             // 禁用服务（防止开机自启）
-            execute_command_stdout("systemctl", &["disable", name])
+            execute_privileged_stdout("systemctl", &["disable", name])
                 .with_context(|| format!("禁用服务 {} 失败", name))?;
-            println!("✅ 已禁用服务: {}", name);
+            info!("已禁用服务: {}", name);
         }
         Ok(())
     }
 
-    /// 停止Docker容器
+    /// 停止Docker容器或nspawn容器
     fn stop_container(iface: &NetInterface) -> Result<()> {
+        if let Some(InterfaceOwner::NspawnMachine { machine }) = &iface.owner {
+            if privileged_success("machinectl", &["terminate", machine]) {
+                info!("已终止nspawn容器: {}", machine);
+            } else {
+                warn!("终止nspawn容器失败: {}", machine);
+            }
+            return Ok(());
+        }
+
         if let Some(InterfaceOwner::DockerContainer { id, name, .. }) = &iface.owner {
             if id == "system" {
                 // Docker网桥不能停止
                 return Ok(());
             }
 
-            if command_success("docker", &["stop", id]) {
-                println!("✅ 已停止容器: {} ({})", name, id);
+            if privileged_success("docker", &["stop", id]) {
+                info!("已停止容器: {} ({})", name, id);
             } else {
-                println!("⚠️ 停止容器失败: {} ({})", name, id);
+                warn!("停止容器失败: {} ({})", name, id);
             }
         }
         Ok(())
     }
 
-    /// 终止进程
-    fn kill_process(iface: &NetInterface) -> Result<()> {
+    /// 终止进程：先SIGTERM，进程在 `grace` 内未退出时再SIGKILL
+    fn kill_process(iface: &NetInterface, grace: Duration) -> Result<()> {
         if let Some(InterfaceOwner::Process { pid, name, .. }) = &iface.owner {
             // 先尝试SIGTERM（优雅终止）
-            if command_success("kill", &[&pid.to_string()]) {
-                println!("✅ 已发送SIGTERM信号到进程: {} (PID: {})", name, pid);
+            if privileged_success("kill", &[&pid.to_string()]) {
+                info!("已发送SIGTERM信号到进程: {} (PID: {})", name, pid);
 
-                // 等待1秒
-                std::thread::sleep(std::time::Duration::from_secs(1));
-
-                // 检查进程是否还存在
-                if std::path::Path::new(&format!("/proc/{}", pid)).exists() {
+                // 进程退出后立即继续，超时仍存在时强制终止
+                if !runtime::wait_for_process_exit(*pid, grace) {
                     // 进程仍存在，使用SIGKILL强制终止
-                    if command_success("kill", &["-9", &pid.to_string()]) {
-                        println!("✅ 已发送SIGKILL信号到进程: {} (PID: {})", name, pid);
+                    if privileged_success("kill", &["-9", &pid.to_string()]) {
+                        info!("已发送SIGKILL信号到进程: {} (PID: {})", name, pid);
                     }
                 }
             } else {
-                println!("⚠️ 终止进程失败: {} (PID: {})", name, pid);
+                warn!("终止进程失败: {} (PID: {})", name, pid);
             }
         }
         Ok(())
@@ -131,19 +170,18 @@ impl RemovalManager {
 
         // 检查是否是SSH连接接口
         if runtime::is_ssh_interface(&iface.name) {
-            warnings.push(format!("⚠️ 警告: {} 是SSH连接使用的接口，删除后可能导致远程连接断开！", iface.name));
+            warnings.push(format!("{} 警告: {} 是SSH连接使用的接口，删除后可能导致远程连接断开！", Symbol::Warning, iface.name));
         }
 
         // 检查是否是唯一的默认路由接口
-        if let Ok(Some(default_iface)) = runtime::get_default_route_interface() {
-            if default_iface == iface.name {
-                warnings.push(format!("⚠️ 警告: {} 是默认路由接口，删除后可能无法访问外网！", iface.name));
-            }
+        if let Ok(Some(default_iface)) = runtime::get_default_route_interface()
+            && default_iface == iface.name {
+            warnings.push(format!("{} 警告: {} 是默认路由接口，删除后可能无法访问外网！", Symbol::Warning, iface.name));
         }
 
         // 检查是否有活跃的连接
         if !iface.ipv4_addresses.is_empty() || !iface.ipv6_addresses.is_empty() {
-            warnings.push(format!("⚠️ 提示: {} 配置了IP地址，可能有活跃的网络连接", iface.name));
+            warnings.push(format!("{} 提示: {} 配置了IP地址，可能有活跃的网络连接", Symbol::Warning, iface.name));
         }
 
         warnings
@@ -153,7 +191,6 @@ impl RemovalManager {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::model::{InterfaceKind, InterfaceState};
 
     #[test]
     fn test_determine_strategy() {
@@ -181,5 +218,37 @@ mod tests {
             RemovalStrategy::StopContainer
         ));
     }
-}
 
+    #[test]
+    fn test_applicable_strategies() {
+        let mut iface = NetInterface::new("test0".to_string(), InterfaceKind::Tun);
+        assert_eq!(RemovalManager::applicable_strategies(&iface), [RemovalStrategy::InterfaceOnly]);
+
+        iface.owner = Some(InterfaceOwner::SystemdService {
+            name: "test.service".to_string(),
+            status: crate::model::ServiceStatus::Active,
+            start_time: None,
+        });
+        let strategies = RemovalManager::applicable_strategies(&iface);
+        assert_eq!(
+            strategies,
+            [
+                RemovalStrategy::InterfaceOnly,
+                RemovalStrategy::StopService,
+                RemovalStrategy::StopAndDisableService,
+            ]
+        );
+        // 自动选择的策略总在可选列表中
+        assert!(strategies.contains(&RemovalManager::determine_strategy(&iface)));
+    }
+
+    #[test]
+    fn test_remove_physical_interface_refused() {
+        let iface = NetInterface::new("eth0".to_string(), InterfaceKind::Physical);
+        let err = RemovalManager::remove_interface(&iface, &RemovalStrategy::InterfaceOnly, DEFAULT_KILL_GRACE).unwrap_err();
+        assert!(err.to_string().contains("拒绝删除接口 eth0"));
+
+        let lo = NetInterface::new("lo".to_string(), InterfaceKind::Loopback);
+        assert!(RemovalManager::remove_interface(&lo, &RemovalStrategy::InterfaceOnly, DEFAULT_KILL_GRACE).is_err());
+    }
+}
@@ -0,0 +1,152 @@
+// 路由表模块 - 读取并解析主路由表（ip route / ip -6 route）
+use crate::utils::command::execute_command_stdout;
+use anyhow::{Context, Result};
+
+/// 非单播路由的类型关键字，出现在目标地址之前
+const ROUTE_TYPES: [&str; 8] = ["unicast", "local", "broadcast", "multicast", "blackhole", "unreachable", "prohibit", "throw"];
+
+/// 路由表中的一条路由
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Route {
+    pub to: String,                  // 目标网段，默认路由为 "default"
+    pub route_type: Option<String>,  // 非单播路由的类型（blackhole、unreachable等）
+    pub via: Option<String>,         // 下一跳网关
+    pub dev: Option<String>,         // 出口接口
+    pub metric: Option<u32>,         // 度量值
+    pub proto: Option<String>,       // 来源（kernel、dhcp、static等）
+    pub src: Option<String>,         // 首选源地址
+    pub ipv6: bool,
+}
+
+impl Route {
+    pub fn is_default(&self) -> bool {
+        self.to == "default" || self.to == "0.0.0.0/0" || self.to == "::/0"
+    }
+
+    /// 显示用的目标，非单播路由带上类型
+    pub fn destination(&self) -> String {
+        match &self.route_type {
+            Some(route_type) => format!("{} {}", route_type, self.to),
+            None => self.to.clone(),
+        }
+    }
+}
+
+/// 读取IPv4和IPv6主路由表，IPv6被禁用时只返回IPv4路由
+pub fn list_routes() -> Result<Vec<Route>> {
+    let output = execute_command_stdout("ip", &["route", "show"]).context("查询IPv4路由表失败")?;
+    let mut routes = parse_routes(&output, false);
+    if let Ok(output) = execute_command_stdout("ip", &["-6", "route", "show"]) {
+        routes.extend(parse_routes(&output, true));
+    }
+    Ok(routes)
+}
+
+/// 解析 ip route show 输出，每行一条路由
+///
+/// 多路径路由的 nexthop 续行以空白开头，取第一个下一跳作为网关和出口
+pub fn parse_routes(output: &str, ipv6: bool) -> Vec<Route> {
+    let mut routes: Vec<Route> = Vec::new();
+
+    for line in output.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let mut tokens = line.split_whitespace().peekable();
+
+        if line.starts_with(char::is_whitespace) {
+            if let Some(route) = routes.last_mut()
+                && route.dev.is_none() && tokens.next() == Some("nexthop") {
+                parse_attributes(route, tokens);
+            }
+            continue;
+        }
+
+        let mut route = Route { ipv6, ..Default::default() };
+        if let Some(&first) = tokens.peek()
+            && ROUTE_TYPES.contains(&first) {
+            route.route_type = Some(first.to_string()).filter(|t| t != "unicast");
+            tokens.next();
+        }
+        let Some(to) = tokens.next() else {
+            continue;
+        };
+        route.to = to.to_string();
+        parse_attributes(&mut route, tokens);
+        routes.push(route);
+    }
+
+    routes
+}
+
+/// 解析 "via X dev Y proto Z metric N" 形式的键值对，忽略其他属性
+fn parse_attributes<'a>(route: &mut Route, tokens: impl Iterator<Item = &'a str>) {
+    // "via inet6 fe80::1" 这类写法带地址族，直接跳过
+    let mut tokens = tokens.filter(|token| *token != "inet" && *token != "inet6");
+    while let Some(key) = tokens.next() {
+        let target = match key {
+            "via" => &mut route.via,
+            "dev" => &mut route.dev,
+            "proto" => &mut route.proto,
+            "src" => &mut route.src,
+            "metric" => {
+                route.metric = tokens.next().and_then(|value| value.parse().ok());
+                continue;
+            }
+            _ => continue,
+        };
+        *target = tokens.next().map(str::to_string);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ipv4_routes() {
+        let output = "default via 192.168.1.1 dev eth0 proto dhcp src 192.168.1.10 metric 100
+192.168.1.0/24 dev eth0 proto kernel scope link src 192.168.1.10 metric 100
+blackhole 10.99.0.0/16 proto static
+10.8.0.0/24 proto static metric 50
+\tnexthop via 10.0.0.1 dev eth1 weight 1
+\tnexthop via 10.0.0.2 dev eth2 weight 1
+";
+        let routes = parse_routes(output, false);
+        assert_eq!(routes.len(), 4);
+
+        assert!(routes[0].is_default());
+        assert_eq!(routes[0].via.as_deref(), Some("192.168.1.1"));
+        assert_eq!(routes[0].dev.as_deref(), Some("eth0"));
+        assert_eq!(routes[0].proto.as_deref(), Some("dhcp"));
+        assert_eq!(routes[0].src.as_deref(), Some("192.168.1.10"));
+        assert_eq!(routes[0].metric, Some(100));
+
+        assert!(!routes[1].is_default());
+        assert_eq!(routes[1].via, None);
+        assert_eq!(routes[1].proto.as_deref(), Some("kernel"));
+
+        assert_eq!(routes[2].destination(), "blackhole 10.99.0.0/16");
+        assert_eq!(routes[2].dev, None);
+
+        // 多路径路由取第一个下一跳
+        assert_eq!(routes[3].via.as_deref(), Some("10.0.0.1"));
+        assert_eq!(routes[3].dev.as_deref(), Some("eth1"));
+        assert_eq!(routes[3].metric, Some(50));
+    }
+
+    #[test]
+    fn test_parse_ipv6_routes() {
+        let output = "2001:db8::/64 dev eth0 proto ra metric 100 expires 86390sec pref medium
+fe80::/64 dev eth0 proto kernel metric 256 pref medium
+default via inet6 fe80::1 dev eth0 proto ra metric 1024 pref medium
+";
+        let routes = parse_routes(output, true);
+        assert_eq!(routes.len(), 3);
+        assert!(routes.iter().all(|route| route.ipv6));
+        assert_eq!(routes[0].proto.as_deref(), Some("ra"));
+        assert!(routes[2].is_default());
+        assert_eq!(routes[2].via.as_deref(), Some("fe80::1"));
+        assert_eq!(routes[2].metric, Some(1024));
+    }
+}
@@ -1,69 +1,237 @@
 // 运行时接口管理模块 - 使用ip命令管理网络接口
-use crate::model::{InterfaceKind, InterfaceState, NetInterface};
-use crate::utils::command::execute_command_stdout;
+use crate::backend::policy;
+use crate::model::{BondInfo, BondSlave, BridgeSettings, DnsConfig, DriverInfo, InterfaceKind, InterfaceState, Ipv4AddressInfo, Ipv6AddressInfo, NetInterface, WolInfo};
+use crate::utils::command::{execute_command_stdout, execute_command_stdout_retry, execute_privileged_stdout};
 use anyhow::{Context, Result};
 use regex::Regex;
+use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::time::Duration;
+
+/// 写操作遇到瞬时故障时的最大尝试次数
+const RETRY_ATTEMPTS: u32 = 4;
+/// 首次重试前的等待时间
+const RETRY_DELAY: Duration = Duration::from_millis(100);
+/// 等待状态变化时的轮询间隔
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 列出所有网络接口，`detect_owners` 为false时跳过创建者检测
+pub fn list_interfaces(detect_owners: bool) -> Result<Vec<NetInterface>> {
+    // 优先通过netlink一次读取所有链路和地址，失败时回退到ip命令
+    #[cfg(feature = "netlink")]
+    let mut interfaces = list_links_netlink().or_else(|e| {
+        tracing::debug!("netlink读取接口失败，改用ip命令: {:#}", e);
+        list_links_command()
+    })?;
+    #[cfg(not(feature = "netlink"))]
+    let mut interfaces = list_links_command()?;
+
+    add_gateways_and_dns(&mut interfaces);
+
+    // 关联以接口地址为源地址的策略路由规则
+    if let Ok(rules) = policy::list_rules() {
+        for iface in &mut interfaces {
+            iface.policy_rules = rules
+                .iter()
+                .filter(|rule| policy::rule_matches(rule, &iface.ipv4_addresses))
+                .cloned()
+                .collect();
+        }
+    }
+
+    // 读取物理网卡的驱动信息、网络唤醒设置和链路速率/双工
+    for iface in interfaces.iter_mut().filter(|iface| iface.kind == InterfaceKind::Physical) {
+        iface.driver_info = read_driver_info(&iface.name);
+        let settings = crate::backend::ethtool::get_settings(&iface.name);
+        iface.wol = settings.as_deref().and_then(parse_ethtool_wol);
+        iface.link_info = Some(settings.as_deref().map(crate::backend::ethtool::parse_link_info).unwrap_or_default());
+        iface.permanent_mac = crate::backend::ethtool::get_permanent_address(&iface.name);
+    }
+
+    // 根据链路详情识别VRF、MACVLAN、VXLAN、GRE等类型及VRF成员关系
+    if let Ok(details) = execute_command_stdout("ip", &["-d", "-o", "link", "show"]) {
+        let link_kinds = parse_link_kinds(&details);
+        let (vrf_devices, vrf_members) = parse_vrf_info(&details);
+        for iface in &mut interfaces {
+            if let Some(kind) = link_kinds.get(&iface.name) {
+                iface.kind = kind.clone();
+            }
+            if vrf_devices.contains(&iface.name) {
+                iface.kind = InterfaceKind::Vrf;
+            }
+            iface.vrf = vrf_members.get(&iface.name).cloned();
+        }
+    }
+
+    // docker0存在时，默认路由的出口接口即容器出站流量的NAT出口
+    if interfaces.iter().any(|iface| iface.name == "docker0")
+        && let Some(dev) = execute_command_stdout("ip", &["-4", "route", "show", "default"])
+            .ok()
+            .and_then(|output| parse_default_route_dev(&output))
+        {
+        for iface in interfaces.iter_mut().filter(|iface| iface.name == dev) {
+            iface.docker_nat_egress = true;
+        }
+    }
+
+    // 读取接口备注
+    for iface in &mut interfaces {
+        iface.alias = read_alias(&iface.name);
+    }
+
+    // 读取链路聚合成员状态
+    for iface in interfaces.iter_mut().filter(|iface| iface.kind == InterfaceKind::Bond) {
+        iface.bond_info = read_bond_info(&iface.name);
+    }
+
+    // 读取网桥设置
+    for iface in interfaces.iter_mut().filter(|iface| iface.kind == InterfaceKind::Bridge) {
+        iface.bridge_settings = read_bridge_settings(&iface.name);
+    }
+
+    // 检测接口创建者
+    if detect_owners {
+        use crate::backend::owner_detection::OwnerDetector;
+        for iface in &mut interfaces {
+            iface.owner = OwnerDetector::detect(iface);
+        }
+    }
+
+    Ok(interfaces)
+}
+
+/// 通过RTM_GETLINK/RTM_GETADDR读取接口和地址（两次请求，不启动子进程）
+#[cfg(feature = "netlink")]
+fn list_links_netlink() -> Result<Vec<NetInterface>> {
+    use crate::backend::netlink::{self, Address};
 
-/// 列出所有网络接口
-pub fn list_interfaces() -> Result<Vec<NetInterface>> {
     let mut interfaces = Vec::new();
+    for link in netlink::dump_links()? {
+        let mut iface = link_interface(link.ifindex, link.name, link.up, link.mtu)?;
+        iface.mac_address = link.mac_address;
+        iface.point_to_point = link.point_to_point;
+        iface.altnames = link.altnames;
+        interfaces.push(iface);
+    }
 
-    // 使用 ip -o link show 获取接口列表
-    let output = execute_command_stdout("ip", &["-o", "link", "show"])?;
+    for (ifindex, address) in netlink::dump_addresses()? {
+        let Some(iface) = interfaces.iter_mut().find(|iface| iface.ifindex == ifindex) else {
+            continue;
+        };
+        match address {
+            Address::V4(info) => push_ipv4_address(iface, info),
+            Address::V6(info) => push_ipv6_address(iface, info),
+        }
+    }
+
+    Ok(interfaces)
+}
 
+/// 通过 ip -o link show 和逐个接口的 ip -o addr show 读取接口和地址
+fn list_links_command() -> Result<Vec<NetInterface>> {
+    let mut interfaces = Vec::new();
+
+    let output = execute_command_stdout("ip", &["-o", "link", "show"])?;
     for line in output.lines() {
         if let Some(iface) = parse_interface_from_link(line)? {
             interfaces.push(iface);
         }
     }
 
-    // 为每个接口添加IP地址信息
     for iface in &mut interfaces {
         add_ip_addresses(iface)?;
     }
 
-    // 检测接口创建者
-    use crate::backend::owner_detection::OwnerDetector;
-    for iface in &mut interfaces {
-        iface.owner = OwnerDetector::detect(iface);
-    }
-
     Ok(interfaces)
 }
 
+/// 创建接口并从sysfs识别类型、读取载波
+fn link_interface(ifindex: u32, name: String, up: bool, mtu: u32) -> Result<NetInterface> {
+    let kind = detect_interface_kind(&name)?;
+    let carrier = read_carrier(&name);
+
+    let mut iface = NetInterface::new(name, kind);
+    iface.ifindex = ifindex;
+    iface.state = if up { InterfaceState::Up } else { InterfaceState::Down };
+    iface.carrier = carrier;
+    iface.mtu = mtu;
+    Ok(iface)
+}
+
 /// 从 ip link show 输出解析接口信息
 fn parse_interface_from_link(line: &str) -> Result<Option<NetInterface>> {
     // 示例输出: 2: eth0: <BROADCAST,MULTICAST,UP,LOWER_UP> mtu 1500 qdisc ...
-    let re = Regex::new(r"^\d+:\s+([^:@]+)[@:]?\s*<([^>]*)>\s+.*mtu\s+(\d+)")?;
+    let re = Regex::new(r"^(\d+):\s+([^:@]+)[@:]?\s*<([^>]*)>\s+.*mtu\s+(\d+)")?;
 
     if let Some(caps) = re.captures(line) {
-        let name = caps.get(1).unwrap().as_str().trim().to_string();
-        let flags = caps.get(2).unwrap().as_str();
-        let mtu: u32 = caps.get(3).unwrap().as_str().parse()?;
+        let ifindex: u32 = caps.get(1).unwrap().as_str().parse()?;
+        let name = caps.get(2).unwrap().as_str().trim().to_string();
+        let flags = caps.get(3).unwrap().as_str();
+        let mtu: u32 = caps.get(4).unwrap().as_str().parse()?;
 
-        // 判断接口类型
-        let kind = detect_interface_kind(&name)?;
+        let mut iface = link_interface(ifindex, name, flags.contains("UP"), mtu)?;
+        iface.mac_address = extract_mac_address(line);
+        iface.point_to_point = flags.split(',').any(|flag| flag == "POINTOPOINT");
+        iface.altnames = extract_altnames(line);
 
-        // 判断接口状态
-        let state = if flags.contains("UP") {
-            InterfaceState::Up
-        } else {
-            InterfaceState::Down
+        Ok(Some(iface))
+    } else {
+        Ok(None)
+    }
+}
+
+/// 从 ip -d -o link show 输出中解析VRF设备和成员关系
+///
+/// 返回 (VRF设备名集合, 成员接口名 -> VRF设备名)
+fn parse_vrf_info(output: &str) -> (HashSet<String>, HashMap<String, String>) {
+    let name_re = Regex::new(r"^\d+:\s+([^:@]+)").unwrap();
+    let master_re = Regex::new(r"\smaster\s+(\S+)").unwrap();
+
+    let mut devices = HashSet::new();
+    let mut members = HashMap::new();
+
+    for line in output.lines() {
+        let Some(name) = name_re.captures(line).map(|c| c[1].trim().to_string()) else {
+            continue;
         };
 
-        // 获取MAC地址
-        let mac_address = extract_mac_address(line);
+        // VRF设备的详情为 "vrf table N"，成员为 "vrf_slave table N"
+        if line.contains(" vrf table ") {
+            devices.insert(name);
+        } else if line.contains(" vrf_slave ")
+            && let Some(master) = master_re.captures(line) {
+            members.insert(name, master[1].to_string());
+        }
+    }
 
-        let mut iface = NetInterface::new(name, kind);
-        iface.state = state;
-        iface.mtu = mtu;
-        iface.mac_address = mac_address;
+    (devices, members)
+}
 
-        Ok(Some(iface))
-    } else {
-        Ok(None)
+/// 从 ip -d -o link show 输出中识别只能通过链路详情区分的接口类型
+fn parse_link_kinds(output: &str) -> HashMap<String, InterfaceKind> {
+    let name_re = Regex::new(r"^\d+:\s+([^:@]+)").unwrap();
+    let mut kinds = HashMap::new();
+
+    for line in output.lines() {
+        let Some(name) = name_re.captures(line).map(|c| c[1].trim().to_string()) else {
+            continue;
+        };
+
+        // -o 模式下各部分以反斜杠分隔：接口头、link/ether行、类型详情（如 "vxlan id 42 ..."）
+        for segment in line.split('\\').skip(2) {
+            let kind = match segment.split_whitespace().next() {
+                Some("macvlan") | Some("macvtap") => InterfaceKind::Macvlan,
+                Some("vxlan") => InterfaceKind::Vxlan,
+                Some("gre") | Some("gretap") | Some("ip6gre") | Some("ip6gretap") => InterfaceKind::Gre,
+                Some("ifb") => InterfaceKind::Ifb,
+                _ => continue,
+            };
+            kinds.insert(name.clone(), kind);
+            break;
+        }
     }
+
+    kinds
 }
 
 /// 检测接口类型
@@ -83,12 +251,10 @@ fn detect_interface_kind(name: &str) -> Result<InterfaceKind> {
     }
 
     // 检查是否是WireGuard
-    if name.starts_with("wg") {
-        if let Ok(uevent) = fs::read_to_string(&uevent_path) {
-            if uevent.contains("wireguard") {
-                return Ok(InterfaceKind::WireGuard);
-            }
-        }
+    if name.starts_with("wg")
+        && let Ok(uevent) = fs::read_to_string(&uevent_path)
+        && uevent.contains("wireguard") {
+        return Ok(InterfaceKind::WireGuard);
     }
 
     // 检查是否是veth
@@ -96,11 +262,21 @@ fn detect_interface_kind(name: &str) -> Result<InterfaceKind> {
         return Ok(InterfaceKind::Veth);
     }
 
+    // IFB设备（ifb模块默认创建ifb0/ifb1，自定义名称由 ip -d link 的类型识别）
+    if name.starts_with("ifb") {
+        return Ok(InterfaceKind::Ifb);
+    }
+
     // 检查是否是VLAN (格式: eth0.10)
     if name.contains('.') {
         return Ok(InterfaceKind::Vlan);
     }
 
+    // 检查是否是链路聚合
+    if fs::metadata(format!("/sys/class/net/{}/bonding", name)).is_ok() {
+        return Ok(InterfaceKind::Bond);
+    }
+
     // 检查是否是网桥
     let bridge_path = format!("/sys/class/net/{}/bridge", name);
     if fs::metadata(&bridge_path).is_ok() {
@@ -145,6 +321,157 @@ fn detect_interface_kind(name: &str) -> Result<InterfaceKind> {
     Ok(InterfaceKind::Unknown)
 }
 
+/// 从sysfs读取接口的管理状态和载波（用于每次tick的轻量更新）
+pub fn read_link_state(name: &str) -> (InterfaceState, Option<bool>) {
+    // flags 为十六进制，IFF_UP = 0x1
+    let state = fs::read_to_string(format!("/sys/class/net/{}/flags", name))
+        .ok()
+        .and_then(|s| u32::from_str_radix(s.trim().trim_start_matches("0x"), 16).ok())
+        .map(|flags| if flags & 0x1 != 0 { InterfaceState::Up } else { InterfaceState::Down })
+        .unwrap_or(InterfaceState::Unknown);
+
+    (state, read_carrier(name))
+}
+
+/// 读取接口备注（未设置时内核返回空内容）
+fn read_alias(name: &str) -> Option<String> {
+    fs::read_to_string(format!("/sys/class/net/{}/ifalias", name))
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// 读取接口载波状态（接口DOWN时内核返回EINVAL，视为无法读取）
+fn read_carrier(name: &str) -> Option<bool> {
+    fs::read_to_string(format!("/sys/class/net/{}/carrier", name))
+        .ok()
+        .map(|s| s.trim() == "1")
+}
+
+/// 读取网桥的STP和VLAN过滤设置
+fn read_bridge_settings(name: &str) -> Option<BridgeSettings> {
+    let read_flag = |attr: &str| {
+        fs::read_to_string(format!("/sys/class/net/{}/bridge/{}", name, attr))
+            .ok()
+            .map(|s| parse_bridge_flag(&s))
+    };
+
+    Some(BridgeSettings {
+        stp: read_flag("stp_state")?,
+        vlan_filtering: read_flag("vlan_filtering").unwrap_or(false),
+    })
+}
+
+/// 读取链路聚合的模式和各成员的MII状态
+pub fn read_bond_info(name: &str) -> Option<BondInfo> {
+    let bonding_dir = format!("/sys/class/net/{}/bonding", name);
+    let mode = fs::read_to_string(format!("{}/mode", bonding_dir)).ok()?;
+    let slaves = fs::read_to_string(format!("{}/slaves", bonding_dir)).unwrap_or_default();
+
+    let slaves = slaves
+        .split_whitespace()
+        .map(|slave| BondSlave {
+            name: slave.to_string(),
+            mii_up: fs::read_to_string(format!("/sys/class/net/{}/bonding_slave/mii_status", slave))
+                .is_ok_and(|status| status.trim() == "up"),
+        })
+        .collect();
+
+    Some(BondInfo {
+        mode: parse_bond_mode(&mode),
+        slaves,
+    })
+}
+
+/// 解析bonding/mode（格式如 "802.3ad 4"），只保留模式名称
+fn parse_bond_mode(content: &str) -> String {
+    content.split_whitespace().next().unwrap_or("unknown").to_string()
+}
+
+/// 解析sysfs中的网桥开关值（stp_state为1表示内核STP，2表示用户态STP）
+fn parse_bridge_flag(value: &str) -> bool {
+    value.trim().parse::<u32>().is_ok_and(|v| v != 0)
+}
+
+/// 读取网卡驱动信息，优先使用ethtool，不可用时回退到sysfs
+fn read_driver_info(name: &str) -> Option<DriverInfo> {
+    if let Ok(output) = execute_command_stdout("ethtool", &["-i", name])
+        && let Some(info) = parse_ethtool_driver_info(&output) {
+        return Some(info);
+    }
+
+    let driver_link = fs::read_link(format!("/sys/class/net/{}/device/driver", name)).ok()?;
+    let driver = driver_link.file_name()?.to_string_lossy().to_string();
+    let bus_info = fs::read_link(format!("/sys/class/net/{}/device", name))
+        .ok()
+        .and_then(|device| device.file_name().map(|n| n.to_string_lossy().to_string()));
+
+    Some(DriverInfo {
+        driver,
+        bus_info,
+        ..Default::default()
+    })
+}
+
+/// 解析 ethtool -i 输出
+fn parse_ethtool_driver_info(output: &str) -> Option<DriverInfo> {
+    let mut info = DriverInfo::default();
+
+    for line in output.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        let value = if value.is_empty() || value == "N/A" {
+            None
+        } else {
+            Some(value.to_string())
+        };
+
+        match key.trim() {
+            "driver" => info.driver = value.unwrap_or_default(),
+            "version" => info.version = value,
+            "firmware-version" => info.firmware_version = value,
+            "bus-info" => info.bus_info = value,
+            _ => {}
+        }
+    }
+
+    if info.driver.is_empty() {
+        None
+    } else {
+        Some(info)
+    }
+}
+
+/// 读取网卡的网络唤醒设置
+pub fn read_wol_info(name: &str) -> Option<WolInfo> {
+    let output = crate::backend::ethtool::get_settings(name)?;
+    parse_ethtool_wol(&output)
+}
+
+/// 解析 ethtool 输出中的 Supports Wake-on 和 Wake-on 行
+fn parse_ethtool_wol(output: &str) -> Option<WolInfo> {
+    let mut supported = None;
+    let mut current = None;
+
+    for line in output.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        match key.trim() {
+            "Supports Wake-on" => supported = Some(value.trim().to_string()),
+            "Wake-on" => current = Some(value.trim().to_string()),
+            _ => {}
+        }
+    }
+
+    Some(WolInfo {
+        supported: supported?,
+        current: current.unwrap_or_else(|| "d".to_string()),
+    })
+}
+
 /// 从输出中提取MAC地址
 fn extract_mac_address(line: &str) -> Option<String> {
     let re = Regex::new(r"link/ether\s+([0-9a-f:]{17})").ok()?;
@@ -153,6 +480,14 @@ fn extract_mac_address(line: &str) -> Option<String> {
         .map(|m| m.as_str().to_string())
 }
 
+/// 从输出中提取备用名称（每个altname一项，按出现顺序）
+fn extract_altnames(line: &str) -> Vec<String> {
+    let Ok(re) = Regex::new(r"\baltname\s+([^\s\\]+)") else {
+        return Vec::new();
+    };
+    re.captures_iter(line).map(|caps| caps[1].to_string()).collect()
+}
+
 /// 为接口添加IP地址信息
 fn add_ip_addresses(iface: &mut NetInterface) -> Result<()> {
     let output = execute_command_stdout("ip", &["-o", "addr", "show", "dev", &iface.name])?;
@@ -160,40 +495,61 @@ fn add_ip_addresses(iface: &mut NetInterface) -> Result<()> {
     for line in output.lines() {
         // 示例: 2: eth0    inet 192.168.1.100/24 brd 192.168.1.255 scope global eth0
         if line.contains("inet ") {
-            if let Some(addr) = extract_ipv4_address(line) {
-                iface.ipv4_addresses.push(addr.clone());
-
-                // 解析IP地址和前缀，填充ipv4_config
-                if let Some((ip, prefix_str)) = addr.split_once('/') {
-                    if let Ok(prefix) = prefix_str.parse::<u8>() {
-                        use crate::model::Ipv4Config;
-                        iface.ipv4_config = Some(Ipv4Config {
-                            address: ip.to_string(),
-                            netmask: prefix_to_netmask(prefix),
-                            prefix,
-                            gateway: get_default_gateway(&iface.name).ok(),
-                        });
-                    }
-                }
-            }
-        } else if line.contains("inet6 ") {
-            if let Some(addr) = extract_ipv6_address(line) {
-                iface.ipv6_addresses.push(addr);
+            if let Some(info) = parse_ipv4_address_info(line) {
+                push_ipv4_address(iface, info);
             }
+        } else if line.contains("inet6 ")
+            && let Some(info) = parse_ipv6_address_info(line) {
+            push_ipv6_address(iface, info);
         }
     }
 
-    // 读取DNS配置
-    if let Ok(dns_servers) = get_dns_servers() {
-        if !dns_servers.is_empty() {
-            use crate::model::DnsConfig;
-            iface.dns_config = Some(DnsConfig {
-                nameservers: dns_servers,
-            });
-        }
+    Ok(())
+}
+
+/// 记录一个IPv4地址，并用它填充ipv4_config（网关稍后统一填写）
+fn push_ipv4_address(iface: &mut NetInterface, info: Ipv4AddressInfo) {
+    if let Some((ip, prefix_str)) = info.address.split_once('/')
+        && let Ok(prefix) = prefix_str.parse::<u8>() {
+        use crate::model::Ipv4Config;
+        iface.ipv4_config = Some(Ipv4Config {
+            address: ip.to_string(),
+            netmask: prefix_to_netmask(prefix),
+            prefix,
+            gateway: None,
+        });
     }
+    iface.ipv4_addresses.push(info.address.clone());
+    iface.ipv4_details.push(info);
+}
 
-    Ok(())
+fn push_ipv6_address(iface: &mut NetInterface, info: Ipv6AddressInfo) {
+    iface.ipv6_addresses.push(info.address.clone());
+    iface.ipv6_details.push(info);
+}
+
+/// 填写各接口的IPv4/IPv6默认网关和DNS配置（所有接口共用一次路由查询和resolv.conf读取）
+fn add_gateways_and_dns(interfaces: &mut [NetInterface]) {
+    let gateways = execute_command_stdout("ip", &["-4", "route", "show", "default"])
+        .map(|output| parse_default_gateways(&output, false))
+        .unwrap_or_default();
+    let ipv6_gateways = execute_command_stdout("ip", &["-6", "route", "show", "default"])
+        .map(|output| parse_default_gateways(&output, true))
+        .unwrap_or_default();
+    let dns_config = fs::read_to_string("/etc/resolv.conf")
+        .ok()
+        .map(|content| parse_resolv_conf(&content))
+        .filter(|config| !config.nameservers.is_empty());
+
+    for iface in interfaces {
+        if let Some(config) = &mut iface.ipv4_config {
+            config.gateway = gateways.get(&iface.name).cloned();
+        }
+        if !iface.ipv6_addresses.is_empty() {
+            iface.ipv6_gateway = ipv6_gateways.get(&iface.name).cloned();
+        }
+        iface.dns_config = dns_config.clone();
+    }
 }
 
 /// 提取IPv4地址
@@ -204,6 +560,25 @@ fn extract_ipv4_address(line: &str) -> Option<String> {
         .map(|m| m.as_str().to_string())
 }
 
+/// 解析 ip -o addr 的inet行，提取地址、是否动态及租约时间
+fn parse_ipv4_address_info(line: &str) -> Option<Ipv4AddressInfo> {
+    let address = extract_ipv4_address(line)?;
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+
+    Some(Ipv4AddressInfo {
+        address,
+        dynamic: tokens.contains(&"dynamic"),
+        valid_lft: parse_lifetime(&tokens, "valid_lft"),
+        preferred_lft: parse_lifetime(&tokens, "preferred_lft"),
+    })
+}
+
+/// 解析 "valid_lft 86000sec" 形式的时间，forever或无法解析时返回None
+fn parse_lifetime(tokens: &[&str], key: &str) -> Option<u64> {
+    let value = tokens.iter().position(|t| *t == key).and_then(|i| tokens.get(i + 1))?;
+    value.strip_suffix("sec")?.parse().ok()
+}
+
 /// 提取IPv6地址
 fn extract_ipv6_address(line: &str) -> Option<String> {
     let re = Regex::new(r"inet6\s+([0-9a-f:]+/\d+)").ok()?;
@@ -212,8 +587,37 @@ fn extract_ipv6_address(line: &str) -> Option<String> {
         .map(|m| m.as_str().to_string())
 }
 
+/// 解析 ip -o addr 的inet6行，提取地址、作用域和标志
+fn parse_ipv6_address_info(line: &str) -> Option<Ipv6AddressInfo> {
+    let address = extract_ipv6_address(line)?;
+    let ip: std::net::Ipv6Addr = address.split('/').next()?.parse().ok()?;
+
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let scope = tokens
+        .iter()
+        .position(|t| *t == "scope")
+        .and_then(|i| tokens.get(i + 1))
+        .unwrap_or(&"global")
+        .to_string();
+
+    Some(Ipv6AddressInfo {
+        prefix: ipv6_prefix64(&ip),
+        address,
+        scope,
+        temporary: tokens.contains(&"temporary"),
+        deprecated: tokens.contains(&"deprecated"),
+    })
+}
+
+/// 计算地址所属的/64前缀
+pub fn ipv6_prefix64(ip: &std::net::Ipv6Addr) -> String {
+    let s = ip.segments();
+    let prefix = std::net::Ipv6Addr::new(s[0], s[1], s[2], s[3], 0, 0, 0, 0);
+    format!("{}/64", prefix)
+}
+
 /// 将前缀长度转换为子网掩码
-fn prefix_to_netmask(prefix: u8) -> String {
+pub fn prefix_to_netmask(prefix: u8) -> String {
     if prefix > 32 {
         return "255.255.255.255".to_string();
     }
@@ -233,57 +637,333 @@ fn prefix_to_netmask(prefix: u8) -> String {
     )
 }
 
-/// 获取默认网关
-fn get_default_gateway(iface_name: &str) -> Result<String> {
-    let output = execute_command_stdout("ip", &["route", "show", "default", "dev", iface_name])?;
+/// 从 ip route show default 输出中解析各出口接口的默认网关（同一接口以第一条为准）
+///
+/// 示例输出: default via 192.168.1.1 dev enp4s0 proto static
+fn parse_default_gateways(output: &str, ipv6: bool) -> HashMap<String, String> {
+    let pattern = if ipv6 {
+        r"^default via ([0-9a-fA-F:]+) .*\bdev (\S+)"
+    } else {
+        r"^default via ([0-9.]+) .*\bdev (\S+)"
+    };
+    let Ok(re) = Regex::new(pattern) else {
+        return HashMap::new();
+    };
 
-    // 示例输出: default via 192.168.1.1 dev enp4s0 proto static
-    let re = Regex::new(r"default via ([0-9.]+)")?;
-    if let Some(caps) = re.captures(&output) {
-        if let Some(gateway) = caps.get(1) {
-            return Ok(gateway.as_str().to_string());
-        }
+    let mut gateways = HashMap::new();
+    for caps in output.lines().filter_map(|line| re.captures(line)) {
+        gateways.entry(caps[2].to_string()).or_insert_with(|| caps[1].to_string());
     }
-
-    Err(anyhow::anyhow!("未找到默认网关"))
+    gateways
 }
 
-/// 获取DNS服务器列表
-fn get_dns_servers() -> Result<Vec<String>> {
-    let mut dns_servers = Vec::new();
+/// 从 ip route show default 输出中解析实际生效（metric最小）的默认路由出口接口
+fn parse_default_route_dev(output: &str) -> Option<String> {
+    let dev_re = Regex::new(r"\sdev\s+(\S+)").ok()?;
+    let metric_re = Regex::new(r"\smetric\s+(\d+)").ok()?;
 
-    // 尝试从 /etc/resolv.conf 读取
-    if let Ok(content) = fs::read_to_string("/etc/resolv.conf") {
-        let re = Regex::new(r"nameserver\s+([0-9.]+)")?;
-        for line in content.lines() {
-            if let Some(caps) = re.captures(line) {
-                if let Some(dns) = caps.get(1) {
-                    dns_servers.push(dns.as_str().to_string());
-                }
-            }
+    output
+        .lines()
+        .filter(|line| line.starts_with("default"))
+        .filter_map(|line| {
+            let dev = dev_re.captures(line)?[1].to_string();
+            let metric: u32 = metric_re.captures(line).and_then(|caps| caps[1].parse().ok()).unwrap_or(0);
+            Some((metric, dev))
+        })
+        .min_by_key(|(metric, _)| *metric)
+        .map(|(_, dev)| dev)
+}
+
+/// 从 resolv.conf 内容中解析DNS服务器和搜索域（多条search行以最后一条为准）
+fn parse_resolv_conf(content: &str) -> DnsConfig {
+    let mut config = DnsConfig { nameservers: Vec::new(), search: Vec::new() };
+    for line in content.lines() {
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("nameserver") => config.nameservers.extend(words.next().map(str::to_string)),
+            Some("search") => config.search = words.map(str::to_string).collect(),
+            _ => {}
         }
     }
-
-    Ok(dns_servers)
+    config
 }
 
 /// 设置接口状态为UP
 pub fn set_interface_up(iface_name: &str) -> Result<()> {
-    execute_command_stdout("ip", &["link", "set", "dev", iface_name, "up"])
+    execute_command_stdout_retry("ip", &["link", "set", "dev", iface_name, "up"], RETRY_ATTEMPTS, RETRY_DELAY)
         .with_context(|| format!("启用接口 {} 失败", iface_name))?;
     Ok(())
 }
 
+/// 轮询直到条件满足，超时返回false（超时为0时只检查一次）
+pub fn wait_until(timeout: Duration, mut condition: impl FnMut() -> bool) -> bool {
+    let start = std::time::Instant::now();
+
+    loop {
+        if condition() {
+            return true;
+        }
+
+        if start.elapsed() >= timeout {
+            return false;
+        }
+        std::thread::sleep(WAIT_POLL_INTERVAL);
+    }
+}
+
+/// 等待接口获得载波，超时返回false
+pub fn wait_for_carrier(iface_name: &str, timeout: Duration) -> bool {
+    let carrier_path = format!("/sys/class/net/{}/carrier", iface_name);
+    // 接口DOWN时读取carrier会返回EINVAL，视为无载波
+    wait_until(timeout, || fs::read_to_string(&carrier_path).is_ok_and(|carrier| carrier.trim() == "1"))
+}
+
+/// 等待接口从系统中消失，超时返回false
+pub fn wait_for_interface_removed(iface_name: &str, timeout: Duration) -> bool {
+    let path = format!("/sys/class/net/{}", iface_name);
+    wait_until(timeout, || !std::path::Path::new(&path).exists())
+}
+
+/// 等待进程退出，超时返回false
+pub fn wait_for_process_exit(pid: u32, timeout: Duration) -> bool {
+    let path = format!("/proc/{}", pid);
+    wait_until(timeout, || !std::path::Path::new(&path).exists())
+}
+
 /// 设置接口状态为DOWN
 pub fn set_interface_down(iface_name: &str) -> Result<()> {
-    execute_command_stdout("ip", &["link", "set", "dev", iface_name, "down"])
+    execute_command_stdout_retry("ip", &["link", "set", "dev", iface_name, "down"], RETRY_ATTEMPTS, RETRY_DELAY)
         .with_context(|| format!("禁用接口 {} 失败", iface_name))?;
     Ok(())
 }
 
+/// 接口名称最大长度（IFNAMSIZ - 1）
+const MAX_INTERFACE_NAME_LEN: usize = 15;
+
+/// 检查新接口名称是否合法，并且不与已有接口重名
+pub fn validate_interface_name(name: &str, existing: &[NetInterface]) -> Result<()> {
+    if name.is_empty() {
+        anyhow::bail!("接口名称不能为空");
+    }
+    if name.len() > MAX_INTERFACE_NAME_LEN {
+        anyhow::bail!("接口名称不能超过{}个字符: {}", MAX_INTERFACE_NAME_LEN, name);
+    }
+    if name == "." || name == ".." || name.chars().any(|c| c == '/' || c == ':' || c.is_whitespace()) {
+        anyhow::bail!("接口名称包含非法字符: {}", name);
+    }
+    if existing.iter().any(|iface| iface.name == name || iface.altnames.iter().any(|alt| alt == name)) {
+        anyhow::bail!("接口 {} 已存在", name);
+    }
+    Ok(())
+}
+
+/// 重命名接口，接口必须处于DOWN状态
+pub fn rename_interface(old: &str, new: &str) -> Result<()> {
+    execute_privileged_stdout("ip", &["link", "set", old, "name", new])
+        .with_context(|| format!("把接口 {} 重命名为 {} 失败", old, new))?;
+    Ok(())
+}
+
+/// 创建网桥（不含成员，处于DOWN状态）
+pub fn create_bridge(name: &str) -> Result<()> {
+    execute_privileged_stdout("ip", &["link", "add", "name", name, "type", "bridge"])
+        .with_context(|| format!("创建网桥 {} 失败", name))?;
+    Ok(())
+}
+
+/// 解析VLAN ID，必须在1-4094范围内（0和4095为保留值）
+pub fn parse_vlan_id(value: &str) -> Result<u16> {
+    let vid: u16 = value.trim().parse()
+        .with_context(|| format!("无效的VLAN ID: {}", value))?;
+    if !(1..=4094).contains(&vid) {
+        anyhow::bail!("VLAN ID超出范围 (1-4094): {}", vid);
+    }
+    Ok(vid)
+}
+
+/// 解析并规范化MAC地址（六组十六进制，小写），拒绝组播和全零地址
+pub fn parse_mac_address(value: &str) -> Result<String> {
+    let octets = value
+        .trim()
+        .split([':', '-'])
+        .map(|octet| {
+            let valid = octet.len() == 2 && octet.chars().all(|c| c.is_ascii_hexdigit());
+            valid.then(|| u8::from_str_radix(octet, 16).ok()).flatten()
+        })
+        .collect::<Option<Vec<u8>>>()
+        .filter(|octets| octets.len() == 6)
+        .with_context(|| format!("无效的MAC地址: {}", value))?;
+
+    if octets.iter().all(|octet| *octet == 0) {
+        anyhow::bail!("MAC地址不能全为0");
+    }
+    if octets[0] & 0x01 != 0 {
+        anyhow::bail!("不能使用组播MAC地址: {}", value.trim());
+    }
+    Ok(octets.iter().map(|octet| format!("{:02x}", octet)).collect::<Vec<_>>().join(":"))
+}
+
+/// 修改接口的MAC地址：先禁用接口，修改后（包括修改失败时）恢复原来的启用状态
+pub fn set_mac(iface_name: &str, mac: &str) -> Result<()> {
+    let was_up = read_link_state(iface_name).0 == InterfaceState::Up;
+    set_interface_down(iface_name)?;
+    let result = execute_privileged_stdout("ip", &["link", "set", "dev", iface_name, "address", mac])
+        .with_context(|| format!("设置 {} 的MAC地址失败", iface_name));
+    let restored = if was_up { set_interface_up(iface_name) } else { Ok(()) };
+    result?;
+    restored
+}
+
+/// VLAN子接口的名称，如 eth0.10
+pub fn vlan_interface_name(parent: &str, vid: u16) -> String {
+    format!("{}.{}", parent, vid)
+}
+
+/// 在父接口上创建VLAN子接口（处于DOWN状态），返回子接口名称
+pub fn create_vlan(parent: &str, vid: u16) -> Result<String> {
+    let name = vlan_interface_name(parent, vid);
+    let vid_str = vid.to_string();
+    execute_privileged_stdout("ip", &["link", "add", "link", parent, "name", &name, "type", "vlan", "id", &vid_str])
+        .with_context(|| format!("创建VLAN子接口 {} 失败", name))?;
+    Ok(name)
+}
+
+/// 内核bonding驱动支持的聚合模式
+pub const BOND_MODES: [&str; 7] = [
+    "balance-rr",
+    "active-backup",
+    "balance-xor",
+    "broadcast",
+    "802.3ad",
+    "balance-tlb",
+    "balance-alb",
+];
+
+/// 创建链路聚合接口（不含成员，处于DOWN状态）
+pub fn create_bond(name: &str, mode: &str) -> Result<()> {
+    if !BOND_MODES.contains(&mode) {
+        anyhow::bail!("不支持的聚合模式: {}", mode);
+    }
+    execute_privileged_stdout("ip", &["link", "add", "name", name, "type", "bond", "mode", mode])
+        .with_context(|| format!("创建链路聚合 {} 失败", name))?;
+    Ok(())
+}
+
+/// 把接口加入链路聚合（成员必须先处于DOWN状态），加入失败时恢复成员原来的启用状态
+pub fn enslave(bond: &str, member: &str) -> Result<()> {
+    let was_up = read_link_state(member).0 == InterfaceState::Up;
+    set_interface_down(member)?;
+    let result = execute_privileged_stdout("ip", &["link", "set", member, "master", bond])
+        .with_context(|| format!("把 {} 加入链路聚合 {} 失败", member, bond));
+    let restored = if result.is_err() && was_up { set_interface_up(member) } else { Ok(()) };
+    result?;
+    restored
+}
+
+/// 创建链路聚合、加入成员并启用；任一步失败时撤销已做的修改并重新启用原来UP的成员
+pub fn create_bond_with_members(name: &str, mode: &str, members: &[String]) -> Result<()> {
+    create_bond(name, mode)?;
+    let up_members = up_interfaces(members);
+    let result = members
+        .iter()
+        .try_for_each(|member| enslave(name, member))
+        .and_then(|_| set_interface_up(name));
+    result.map_err(|e| rollback_master(name, members, &up_members, e))
+}
+
+/// 把接口加入网桥
+pub fn add_bridge_member(bridge: &str, member: &str) -> Result<()> {
+    execute_privileged_stdout("ip", &["link", "set", member, "master", bridge])
+        .with_context(|| format!("把 {} 加入网桥 {} 失败", member, bridge))?;
+    Ok(())
+}
+
+/// 创建网桥、加入成员并启用；任一步失败时撤销已做的修改，不留下半成品网桥
+pub fn create_bridge_with_members(name: &str, members: &[String]) -> Result<()> {
+    create_bridge(name)?;
+    let up_members = up_interfaces(members);
+    let result = members
+        .iter()
+        .try_for_each(|member| add_bridge_member(name, member))
+        .and_then(|_| set_interface_up(name));
+    result.map_err(|e| rollback_master(name, members, &up_members, e))
+}
+
+/// 把接口移出所在的网桥或链路聚合
+pub fn release_member(member: &str) -> Result<()> {
+    execute_privileged_stdout("ip", &["link", "set", member, "nomaster"])
+        .with_context(|| format!("把 {} 移出所属主接口失败", member))?;
+    Ok(())
+}
+
+/// 成员中当前处于UP状态的接口
+fn up_interfaces(members: &[String]) -> Vec<String> {
+    members
+        .iter()
+        .filter(|member| read_link_state(member).0 == InterfaceState::Up)
+        .cloned()
+        .collect()
+}
+
+/// 撤销部分完成的网桥/链路聚合创建：释放成员、删除新建的主接口，再启用原来处于UP状态的成员
+///
+/// 返回附带撤销结果的原始错误
+fn rollback_master(name: &str, members: &[String], up_members: &[String], error: anyhow::Error) -> anyhow::Error {
+    // 尚未加入的成员执行nomaster也会成功，撤销失败只记录第一个错误
+    let mut rollback = Ok(());
+    for member in members {
+        rollback = rollback.and(release_member(member));
+    }
+    rollback = rollback.and(delete_interface(name));
+    for member in up_members {
+        rollback = rollback.and(set_interface_up(member));
+    }
+
+    match rollback {
+        Ok(()) => error.context(format!("已撤销 {} 的创建", name)),
+        Err(e) => error.context(format!("撤销 {} 的创建未完成（{:#}）", name, e)),
+    }
+}
+
+/// 开启或关闭网桥的生成树协议
+pub fn set_bridge_stp(bridge: &str, enabled: bool) -> Result<()> {
+    let state = if enabled { "1" } else { "0" };
+    execute_privileged_stdout("ip", &["link", "set", bridge, "type", "bridge", "stp_state", state])
+        .with_context(|| format!("设置网桥 {} 的STP失败", bridge))?;
+    Ok(())
+}
+
+/// 设置接口的MTU（立即生效）
+pub fn set_mtu(iface_name: &str, mtu: u32) -> Result<()> {
+    execute_privileged_stdout("ip", &["link", "set", "dev", iface_name, "mtu", &mtu.to_string()])
+        .with_context(|| format!("设置接口 {} 的MTU失败", iface_name))?;
+    Ok(())
+}
+
+/// 开启或关闭网卡的魔术包唤醒
+pub fn set_wake_on_lan(iface_name: &str, enabled: bool) -> Result<()> {
+    let supported = read_wol_info(iface_name).is_some_and(|wol| wol.supports_magic_packet());
+    if !supported {
+        anyhow::bail!("接口 {} 不支持魔术包唤醒", iface_name);
+    }
+
+    let mode = if enabled { "g" } else { "d" };
+    execute_privileged_stdout("ethtool", &["-s", iface_name, "wol", mode])
+        .with_context(|| format!("设置接口 {} 的网络唤醒失败", iface_name))?;
+    Ok(())
+}
+
+/// 开启或关闭网桥的VLAN过滤
+pub fn set_bridge_vlan_filtering(bridge: &str, enabled: bool) -> Result<()> {
+    let state = if enabled { "1" } else { "0" };
+    execute_privileged_stdout("ip", &["link", "set", bridge, "type", "bridge", "vlan_filtering", state])
+        .with_context(|| format!("设置网桥 {} 的VLAN过滤失败", bridge))?;
+    Ok(())
+}
+
 /// 删除接口
 pub fn delete_interface(iface_name: &str) -> Result<()> {
-    execute_command_stdout("ip", &["link", "delete", iface_name])
+    execute_command_stdout_retry("ip", &["link", "delete", iface_name], RETRY_ATTEMPTS, RETRY_DELAY)
         .with_context(|| format!("删除接口 {} 失败", iface_name))?;
     Ok(())
 }
@@ -291,25 +971,118 @@ pub fn delete_interface(iface_name: &str) -> Result<()> {
 /// 为接口设置IPv4地址
 pub fn set_ipv4_address(iface_name: &str, address: &str, prefix: u8) -> Result<()> {
     let addr_with_prefix = format!("{}/{}", address, prefix);
-    execute_command_stdout("ip", &["addr", "add", &addr_with_prefix, "dev", iface_name])
+    execute_privileged_stdout("ip", &["addr", "add", &addr_with_prefix, "dev", iface_name])
         .with_context(|| format!("设置接口 {} 的IP地址失败", iface_name))?;
     Ok(())
 }
 
-/// 清除接口的所有IPv4地址
+/// 为接口追加IPv4地址（CIDR格式），不影响已有地址
+pub fn add_ipv4_alias(iface_name: &str, cidr: &str) -> Result<()> {
+    execute_privileged_stdout("ip", &["addr", "add", cidr, "dev", iface_name])
+        .with_context(|| format!("为接口 {} 添加地址 {} 失败", iface_name, cidr))?;
+    Ok(())
+}
+
+/// 删除接口上的一个IPv4地址（CIDR格式），不影响其他地址
+pub fn remove_ipv4_address(iface_name: &str, cidr: &str) -> Result<()> {
+    execute_privileged_stdout("ip", &["addr", "del", cidr, "dev", iface_name])
+        .with_context(|| format!("删除接口 {} 的地址 {} 失败", iface_name, cidr))?;
+    Ok(())
+}
+
+/// 清除接口的所有IPv4地址（IPv6地址不受影响）
 pub fn flush_ipv4_addresses(iface_name: &str) -> Result<()> {
-    execute_command_stdout("ip", &["addr", "flush", "dev", iface_name])
+    execute_privileged_stdout("ip", &["-4", "addr", "flush", "dev", iface_name])
         .with_context(|| format!("清除接口 {} 的IP地址失败", iface_name))?;
     Ok(())
 }
 
+/// 为接口添加IPv6地址（格式如 2001:db8::10/64，省略前缀长度时为/64）
+pub fn set_ipv6_address(iface_name: &str, address: &str) -> Result<()> {
+    let (ip, prefix) = parse_ipv6_cidr(address)?;
+    execute_privileged_stdout("ip", &["-6", "addr", "add", &format!("{}/{}", ip, prefix), "dev", iface_name])
+        .with_context(|| format!("设置接口 {} 的IPv6地址失败", iface_name))?;
+    Ok(())
+}
+
+/// 清除接口的全局IPv6地址，保留内核自动配置的链路本地地址（fe80::/10）
+pub fn flush_ipv6_addresses(iface_name: &str) -> Result<()> {
+    execute_privileged_stdout("ip", &["-6", "addr", "flush", "dev", iface_name, "scope", "global"])
+        .with_context(|| format!("清除接口 {} 的IPv6地址失败", iface_name))?;
+    Ok(())
+}
+
+/// 解析并验证IPv6地址，拒绝链路本地地址（由内核自动配置）
+pub fn parse_ipv6_cidr(address: &str) -> Result<(std::net::Ipv6Addr, u8)> {
+    let address = address.trim();
+    let (ip, prefix) = match address.split_once('/') {
+        Some((ip, prefix)) => (ip, prefix.trim()),
+        None => (address, "64"),
+    };
+
+    let ip: std::net::Ipv6Addr = ip.trim().parse()
+        .with_context(|| format!("无效的IPv6地址: {}", ip))?;
+    let prefix: u8 = prefix.parse()
+        .ok()
+        .filter(|prefix| *prefix <= 128)
+        .with_context(|| format!("无效的IPv6前缀长度: {}", prefix))?;
+
+    if ip.segments()[0] & 0xffc0 == 0xfe80 {
+        anyhow::bail!("{} 是链路本地地址，由内核自动配置，不能手动设置", ip);
+    }
+    Ok((ip, prefix))
+}
+
 /// 设置默认网关
 pub fn set_default_gateway(gateway: &str, iface_name: &str) -> Result<()> {
-    execute_command_stdout("ip", &["route", "replace", "default", "via", gateway, "dev", iface_name])
-        .with_context(|| format!("设置默认网关失败"))?;
+    execute_privileged_stdout("ip", &["route", "replace", "default", "via", gateway, "dev", iface_name])
+        .context("设置默认网关失败")?;
     Ok(())
 }
 
+/// 设置IPv6默认网关
+pub fn set_ipv6_gateway(gateway: &str, iface_name: &str) -> Result<()> {
+    execute_privileged_stdout("ip", &["-6", "route", "replace", "default", "via", gateway, "dev", iface_name])
+        .context("设置IPv6默认网关失败")?;
+    Ok(())
+}
+
+/// 删除接口上的IPv6默认路由（没有默认路由时忽略）
+pub fn remove_ipv6_gateway(iface_name: &str) {
+    if execute_privileged_stdout("ip", &["-6", "route", "del", "default", "dev", iface_name]).is_err() {
+        tracing::debug!("接口 {} 没有可删除的IPv6默认路由", iface_name);
+    }
+}
+
+/// 检查网关是否位于接口某个IPv4子网内（地址格式如 192.168.1.10/24）
+pub fn gateway_in_subnets(gateway: &str, addresses: &[String]) -> bool {
+    let Ok(gateway) = gateway.parse::<std::net::Ipv4Addr>() else {
+        return false;
+    };
+
+    addresses.iter().any(|addr| {
+        let Some((ip, prefix)) = addr.split_once('/') else {
+            return false;
+        };
+        match (ip.parse::<std::net::Ipv4Addr>(), prefix.parse::<u32>()) {
+            (Ok(ip), Ok(prefix)) if prefix <= 32 => {
+                let mask = if prefix == 0 { 0 } else { !0u32 << (32 - prefix) };
+                (u32::from(ip) & mask) == (u32::from(gateway) & mask)
+            }
+            _ => false,
+        }
+    })
+}
+
+/// 计算地址所属的IPv4网络（如 192.168.1.10/24 -> 192.168.1.0/24）
+pub fn ipv4_network(address: &str) -> Option<String> {
+    let (ip, prefix) = address.split_once('/')?;
+    let ip = ip.parse::<std::net::Ipv4Addr>().ok()?;
+    let prefix = prefix.parse::<u32>().ok().filter(|p| *p <= 32)?;
+    let mask = if prefix == 0 { 0 } else { !0u32 << (32 - prefix) };
+    Some(format!("{}/{}", std::net::Ipv4Addr::from(u32::from(ip) & mask), prefix))
+}
+
 /// 获取默认路由接口
 pub fn get_default_route_interface() -> Result<Option<String>> {
     let output = execute_command_stdout("ip", &["route", "show", "default"])?;
@@ -355,6 +1128,29 @@ mod tests {
         assert_eq!(detect_interface_kind("docker0").unwrap(), InterfaceKind::Docker);
         assert_eq!(detect_interface_kind("veth1234").unwrap(), InterfaceKind::Veth);
         assert_eq!(detect_interface_kind("eth0.10").unwrap(), InterfaceKind::Vlan);
+        assert_eq!(detect_interface_kind("ifb0").unwrap(), InterfaceKind::Ifb);
+    }
+
+    #[test]
+    fn test_parse_interface_without_mac() {
+        let line = "7: tun0: <POINTOPOINT,MULTICAST,NOARP,UP,LOWER_UP> mtu 1500 qdisc fq_codel state UNKNOWN mode DEFAULT group default qlen 500\\    link/none ";
+        let iface = parse_interface_from_link(line).unwrap().unwrap();
+        assert_eq!(iface.mac_address, None);
+        assert!(iface.point_to_point);
+
+        let line = "2: eth0: <BROADCAST,MULTICAST,UP,LOWER_UP> mtu 1500 qdisc fq_codel state UP mode DEFAULT group default qlen 1000\\    link/ether 52:54:00:12:34:56 brd ff:ff:ff:ff:ff:ff";
+        let iface = parse_interface_from_link(line).unwrap().unwrap();
+        assert_eq!(iface.mac_address.as_deref(), Some("52:54:00:12:34:56"));
+        assert!(!iface.point_to_point);
+        assert!(iface.altnames.is_empty());
+    }
+
+    #[test]
+    fn test_parse_altnames() {
+        let line = "2: enp3s0: <BROADCAST,MULTICAST,UP,LOWER_UP> mtu 1500 qdisc mq state UP mode DEFAULT group default qlen 1000\\    link/ether 52:54:00:12:34:56 brd ff:ff:ff:ff:ff:ff\\    altname enp0s3f0\\    altname eno1";
+        let iface = parse_interface_from_link(line).unwrap().unwrap();
+        assert_eq!(iface.name, "enp3s0");
+        assert_eq!(iface.altnames, vec!["enp0s3f0", "eno1"]);
     }
 
     #[test]
@@ -362,5 +1158,235 @@ mod tests {
         let line = "2: eth0    inet 192.168.1.100/24 brd 192.168.1.255 scope global eth0";
         assert_eq!(extract_ipv4_address(line), Some("192.168.1.100/24".to_string()));
     }
-}
 
+    #[test]
+    fn test_parse_ethtool_driver_info() {
+        let output = "driver: igb\nversion: 6.8.0-45-generic\nfirmware-version: 3.30, 0x8000079c\n\
+                      expansion-rom-version: \nbus-info: 0000:03:00.0\nsupports-statistics: yes\n";
+        let info = parse_ethtool_driver_info(output).unwrap();
+        assert_eq!(info.driver, "igb");
+        assert_eq!(info.version.as_deref(), Some("6.8.0-45-generic"));
+        assert_eq!(info.firmware_version.as_deref(), Some("3.30, 0x8000079c"));
+        assert_eq!(info.bus_info.as_deref(), Some("0000:03:00.0"));
+
+        let virtio = "driver: virtio_net\nversion: 1.0.0\nfirmware-version: \nbus-info: 0000:00:03.0\n";
+        assert_eq!(parse_ethtool_driver_info(virtio).unwrap().firmware_version, None);
+
+        assert!(parse_ethtool_driver_info("").is_none());
+    }
+
+    #[test]
+    fn test_parse_ipv4_address_info() {
+        let line = "2: eth0    inet 192.168.1.100/24 brd 192.168.1.255 scope global dynamic noprefixroute eth0\\       valid_lft 4980sec preferred_lft 4980sec";
+        let info = parse_ipv4_address_info(line).unwrap();
+        assert_eq!(info.address, "192.168.1.100/24");
+        assert!(info.dynamic);
+        assert_eq!(info.valid_lft, Some(4980));
+        assert_eq!(info.preferred_lft, Some(4980));
+
+        let line = "3: eth1    inet 10.0.0.5/8 brd 10.255.255.255 scope global eth1\\       valid_lft forever preferred_lft forever";
+        let info = parse_ipv4_address_info(line).unwrap();
+        assert!(!info.dynamic);
+        assert_eq!(info.valid_lft, None);
+    }
+
+    #[test]
+    fn test_parse_ethtool_wol() {
+        let output = "Settings for eth0:\n\
+                      \tSupported ports: [ TP ]\n\
+                      \tSpeed: 1000Mb/s\n\
+                      \tSupports Wake-on: pumbg\n\
+                      \tWake-on: d\n\
+                      \tLink detected: yes\n";
+        let wol = parse_ethtool_wol(output).unwrap();
+        assert_eq!(wol.supported, "pumbg");
+        assert!(wol.supports_magic_packet());
+        assert!(!wol.is_enabled());
+
+        // 虚拟网卡没有 Wake-on 相关行
+        assert!(parse_ethtool_wol("Settings for veth0:\n\tSpeed: 10000Mb/s\n").is_none());
+    }
+
+    #[test]
+    fn test_parse_ipv6_address_info() {
+        let line = "2: eth0    inet6 2001:db8:1:2:a1b2:c3d4:e5f6:1234/64 scope global temporary dynamic \\       valid_lft 86000sec preferred_lft 14000sec";
+        let info = parse_ipv6_address_info(line).unwrap();
+        assert_eq!(info.address, "2001:db8:1:2:a1b2:c3d4:e5f6:1234/64");
+        assert_eq!(info.prefix, "2001:db8:1:2::/64");
+        assert_eq!(info.scope, "global");
+        assert!(info.temporary);
+        assert!(!info.deprecated);
+
+        let line = "2: eth0    inet6 fe80::fc:ff:fe00:1/64 scope link \\       valid_lft forever preferred_lft forever";
+        let info = parse_ipv6_address_info(line).unwrap();
+        assert_eq!(info.prefix, "fe80::/64");
+        assert_eq!(info.scope, "link");
+        assert!(!info.temporary);
+    }
+
+    #[test]
+    fn test_parse_ipv6_cidr() {
+        let (ip, prefix) = parse_ipv6_cidr("2001:db8::10/56").unwrap();
+        assert_eq!(ip.to_string(), "2001:db8::10");
+        assert_eq!(prefix, 56);
+
+        assert_eq!(parse_ipv6_cidr(" fd00::1 ").unwrap().1, 64);
+        assert!(parse_ipv6_cidr("2001:db8::10/129").is_err());
+        assert!(parse_ipv6_cidr("192.168.1.10/24").is_err());
+        assert!(parse_ipv6_cidr("fe80::1/64").is_err());
+    }
+
+    #[test]
+    fn test_gateway_in_subnets() {
+        let addrs = vec!["192.168.1.10/24".to_string(), "10.0.0.5/8".to_string()];
+        assert!(gateway_in_subnets("192.168.1.1", &addrs));
+        assert!(gateway_in_subnets("10.200.0.1", &addrs));
+        assert!(!gateway_in_subnets("192.168.2.1", &addrs));
+        assert!(!gateway_in_subnets("not-an-ip", &addrs));
+        assert!(!gateway_in_subnets("192.168.1.1", &[]));
+    }
+
+    #[test]
+    fn test_parse_default_gateways() {
+        let v4 = "default via 192.168.1.1 dev enp4s0 proto static\n\
+                  default via 10.0.0.1 dev wg0 metric 200\n\
+                  default via 192.168.1.254 dev enp4s0 metric 300";
+        let gateways = parse_default_gateways(v4, false);
+        assert_eq!(gateways.get("enp4s0").map(String::as_str), Some("192.168.1.1"));
+        assert_eq!(gateways.get("wg0").map(String::as_str), Some("10.0.0.1"));
+
+        let v6 = "default via fe80::1 dev enp4s0 proto ra metric 1024 expires 1798sec hoplimit 64 pref medium";
+        assert_eq!(parse_default_gateways(v6, true).get("enp4s0").map(String::as_str), Some("fe80::1"));
+        assert!(parse_default_gateways(v6, false).is_empty());
+        assert!(parse_default_gateways("", true).is_empty());
+    }
+
+    #[test]
+    fn test_parse_default_route_dev() {
+        let output = "default via 192.168.1.1 dev wlan0 proto dhcp metric 600\n\
+                      default via 10.0.0.1 dev enp4s0 proto static metric 100\n";
+        assert_eq!(parse_default_route_dev(output), Some("enp4s0".to_string()));
+
+        // 未指定metric时为0，优先于其他默认路由
+        let output = "default via 10.0.0.1 dev enp4s0 metric 100\ndefault dev wg0 scope link\n";
+        assert_eq!(parse_default_route_dev(output), Some("wg0".to_string()));
+        assert_eq!(parse_default_route_dev(""), None);
+    }
+
+    #[test]
+    fn test_parse_resolv_conf() {
+        let content = "# generated\nnameserver 127.0.0.53\nnameserver 2606:4700::1111\noptions edns0\nsearch old.lan\nsearch corp.local example.com\n";
+        let config = parse_resolv_conf(content);
+        assert_eq!(config.nameservers, vec!["127.0.0.53", "2606:4700::1111"]);
+        assert_eq!(config.search, vec!["corp.local", "example.com"]);
+    }
+
+    #[test]
+    fn test_validate_interface_name() {
+        let mut eth0 = NetInterface::new("eth0".to_string(), InterfaceKind::Physical);
+        eth0.altnames = vec!["enp0s3".to_string()];
+        let existing = [eth0];
+
+        assert!(validate_interface_name("br0", &existing).is_ok());
+        assert!(validate_interface_name("", &existing).is_err());
+        assert!(validate_interface_name("eth0", &existing).is_err());
+        assert!(validate_interface_name("enp0s3", &existing).is_err());
+        assert!(validate_interface_name("br 0", &existing).is_err());
+        assert!(validate_interface_name("br/0", &existing).is_err());
+        assert!(validate_interface_name("a-very-long-bridge", &existing).is_err());
+    }
+
+    #[test]
+    fn test_parse_vlan_id() {
+        assert_eq!(parse_vlan_id(" 10 ").unwrap(), 10);
+        assert_eq!(parse_vlan_id("4094").unwrap(), 4094);
+        assert!(parse_vlan_id("0").is_err());
+        assert!(parse_vlan_id("4095").is_err());
+        assert!(parse_vlan_id("").is_err());
+        assert_eq!(vlan_interface_name("eth0", 10), "eth0.10");
+    }
+
+    #[test]
+    fn test_parse_mac_address() {
+        assert_eq!(parse_mac_address(" 52:54:00:AB:cd:EF ").unwrap(), "52:54:00:ab:cd:ef");
+        assert_eq!(parse_mac_address("52-54-00-ab-cd-ef").unwrap(), "52:54:00:ab:cd:ef");
+        assert!(parse_mac_address("00:00:00:00:00:00").is_err());
+        // 第一个字节最低位为1表示组播
+        assert!(parse_mac_address("01:00:5e:00:00:01").is_err());
+        assert!(parse_mac_address("52:54:00:ab:cd").is_err());
+        assert!(parse_mac_address("52:54:00:ab:cd:eg").is_err());
+        assert!(parse_mac_address("525:4:00:ab:cd:ef").is_err());
+        assert!(parse_mac_address("+2:54:00:ab:cd:ef").is_err());
+    }
+
+    #[test]
+    fn test_parse_bridge_flag() {
+        assert!(!parse_bridge_flag("0\n"));
+        assert!(parse_bridge_flag("1\n"));
+        assert!(parse_bridge_flag("2\n"));
+        assert!(!parse_bridge_flag(""));
+    }
+
+    #[test]
+    fn test_parse_vrf_info() {
+        let output = "\
+1: lo: <LOOPBACK,UP,LOWER_UP> mtu 65536 qdisc noqueue state UNKNOWN mode DEFAULT group default qlen 1000\\    link/loopback 00:00:00:00:00:00 brd 00:00:00:00:00:00 promiscuity 0
+2: eth1: <BROADCAST,MULTICAST,UP,LOWER_UP> mtu 1500 qdisc fq_codel master vrf-blue state UP mode DEFAULT group default qlen 1000\\    link/ether 52:54:00:12:34:56 brd ff:ff:ff:ff:ff:ff promiscuity 0 \\    vrf_slave table 10 addrgenmode eui64
+3: vrf-blue: <NOARP,MASTER,UP,LOWER_UP> mtu 65575 qdisc noqueue state UP mode DEFAULT group default qlen 1000\\    link/ether 2a:6f:1c:00:00:01 brd ff:ff:ff:ff:ff:ff promiscuity 0 \\    vrf table 10 addrgenmode eui64
+4: eth2: <BROADCAST,MULTICAST,UP,LOWER_UP> mtu 1500 qdisc fq_codel master br0 state UP\\    link/ether 52:54:00:12:34:57 brd ff:ff:ff:ff:ff:ff promiscuity 1 \\    bridge_slave state forwarding
+";
+        let (devices, members) = parse_vrf_info(output);
+        assert_eq!(devices, HashSet::from(["vrf-blue".to_string()]));
+        assert_eq!(members.get("eth1").map(String::as_str), Some("vrf-blue"));
+        // 网桥成员不算VRF成员
+        assert!(!members.contains_key("eth2"));
+    }
+
+    #[test]
+    fn test_parse_bond_mode() {
+        assert_eq!(parse_bond_mode("802.3ad 4\n"), "802.3ad");
+        assert_eq!(parse_bond_mode("active-backup 1"), "active-backup");
+        assert_eq!(parse_bond_mode(""), "unknown");
+    }
+
+    #[test]
+    fn test_ipv4_network() {
+        assert_eq!(ipv4_network("192.168.1.10/24").as_deref(), Some("192.168.1.0/24"));
+        assert_eq!(ipv4_network("10.1.2.3/8").as_deref(), Some("10.0.0.0/8"));
+        assert_eq!(ipv4_network("10.1.2.3/33"), None);
+        assert_eq!(ipv4_network("10.1.2.3"), None);
+    }
+
+    #[test]
+    fn test_parse_link_kinds() {
+        let output = "\
+5: mv0@eth0: <BROADCAST,MULTICAST,UP,LOWER_UP> mtu 1500 qdisc noqueue state UP\\    link/ether 8a:2b:00:00:00:01 brd ff:ff:ff:ff:ff:ff promiscuity 0 \\    macvlan mode bridge bcqueuelen 1000
+6: vx42: <BROADCAST,MULTICAST,UP,LOWER_UP> mtu 1450 qdisc noqueue state UNKNOWN\\    link/ether 9e:00:00:00:00:02 brd ff:ff:ff:ff:ff:ff promiscuity 0 \\    vxlan id 42 remote 10.0.0.2 dstport 4789
+7: gre1@NONE: <POINTOPOINT,NOARP,UP,LOWER_UP> mtu 1476 qdisc noqueue state UNKNOWN\\    link/gre 10.0.0.1 peer 10.0.0.2 promiscuity 0 \\    gre remote 10.0.0.2 local 10.0.0.1 ttl inherit
+8: veth1@if7: <BROADCAST,MULTICAST,UP,LOWER_UP> mtu 1500 qdisc noqueue master br0 state UP\\    link/ether 4e:00:00:00:00:03 brd ff:ff:ff:ff:ff:ff promiscuity 1 \\    veth \\    bridge_slave state forwarding
+9: shaper-in: <BROADCAST,NOARP,UP,LOWER_UP> mtu 1500 qdisc htb state UNKNOWN\\    link/ether 1a:00:00:00:00:04 brd ff:ff:ff:ff:ff:ff promiscuity 0 \\    ifb addrgenmode eui64
+";
+        let kinds = parse_link_kinds(output);
+        assert_eq!(kinds.get("mv0"), Some(&InterfaceKind::Macvlan));
+        assert_eq!(kinds.get("vx42"), Some(&InterfaceKind::Vxlan));
+        assert_eq!(kinds.get("gre1"), Some(&InterfaceKind::Gre));
+        assert_eq!(kinds.get("veth1"), None);
+        assert_eq!(kinds.get("shaper-in"), Some(&InterfaceKind::Ifb));
+    }
+
+    #[test]
+    fn test_wait_until_returns_early() {
+        let start = std::time::Instant::now();
+        let mut polls = 0;
+        assert!(wait_until(Duration::from_secs(5), || {
+            polls += 1;
+            polls == 3
+        }));
+        assert!(start.elapsed() < Duration::from_secs(1));
+
+        // 超时为0时只检查一次
+        assert!(!wait_until(Duration::ZERO, || false));
+        assert!(wait_for_interface_removed("nicman-test-absent0", Duration::ZERO));
+        assert!(!wait_for_process_exit(std::process::id(), Duration::ZERO));
+    }
+}
@@ -0,0 +1,83 @@
+// 流量控制模块 - 使用tc tbf队列为接口设置出口限速
+use crate::utils::command::{execute_command_stdout, execute_privileged_stdout};
+use anyhow::{Context, Result};
+use regex::Regex;
+
+/// 查看接口当前的qdisc配置
+pub fn show_qdisc(iface_name: &str) -> Result<String> {
+    execute_command_stdout("tc", &["qdisc", "show", "dev", iface_name])
+        .with_context(|| format!("查询接口 {} 的qdisc失败", iface_name))
+}
+
+/// 设置令牌桶限速（替换现有root qdisc）
+pub fn set_rate_limit(iface_name: &str, rate: &str, burst: &str, latency: &str) -> Result<()> {
+    validate_rate(rate)?;
+    validate_size(burst)?;
+    validate_time(latency)?;
+
+    execute_privileged_stdout(
+        "tc",
+        &["qdisc", "replace", "dev", iface_name, "root", "tbf", "rate", rate, "burst", burst, "latency", latency],
+    )
+    .with_context(|| format!("设置接口 {} 限速失败", iface_name))?;
+    Ok(())
+}
+
+/// 清除限速（删除root qdisc，恢复默认队列）
+pub fn clear_rate_limit(iface_name: &str) -> Result<()> {
+    execute_privileged_stdout("tc", &["qdisc", "del", "dev", iface_name, "root"])
+        .with_context(|| format!("清除接口 {} 限速失败", iface_name))?;
+    Ok(())
+}
+
+/// 校验速率格式，如 10mbit、512kbit、1gbit
+pub fn validate_rate(rate: &str) -> Result<()> {
+    let re = Regex::new(r"(?i)^\d+(\.\d+)?([kmgt]?(bit|bps))$")?;
+    if !re.is_match(rate.trim()) {
+        anyhow::bail!("无效的速率: {}（示例: 10mbit、512kbit）", rate);
+    }
+    Ok(())
+}
+
+/// 校验突发大小格式，如 32kbit、64kb、1600
+fn validate_size(size: &str) -> Result<()> {
+    let re = Regex::new(r"(?i)^\d+([kmg]?(b|bit))?$")?;
+    if !re.is_match(size.trim()) {
+        anyhow::bail!("无效的突发大小: {}（示例: 32kbit）", size);
+    }
+    Ok(())
+}
+
+/// 校验延迟格式，如 400ms、1s
+fn validate_time(time: &str) -> Result<()> {
+    let re = Regex::new(r"(?i)^\d+(\.\d+)?(us|ms|s)$")?;
+    if !re.is_match(time.trim()) {
+        anyhow::bail!("无效的延迟: {}（示例: 400ms）", time);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_rate() {
+        assert!(validate_rate("10mbit").is_ok());
+        assert!(validate_rate("512kbit").is_ok());
+        assert!(validate_rate("1.5Gbit").is_ok());
+        assert!(validate_rate("100kbps").is_ok());
+        assert!(validate_rate("10").is_err());
+        assert!(validate_rate("fast").is_err());
+        assert!(validate_rate("").is_err());
+    }
+
+    #[test]
+    fn test_validate_burst_and_latency() {
+        assert!(validate_size("32kbit").is_ok());
+        assert!(validate_size("1600").is_ok());
+        assert!(validate_size("abc").is_err());
+        assert!(validate_time("400ms").is_ok());
+        assert!(validate_time("400").is_err());
+    }
+}
@@ -1,13 +1,23 @@
 // 流量监控模块 - 读取/sys/class/net统计数据，计算实时速率
 use crate::model::{NetInterface, TrafficStats};
+use crate::utils::command::execute_command_stdout;
 use anyhow::{Context, Result};
-use std::collections::HashMap;
+use serde::Deserialize;
+use std::collections::{HashMap, VecDeque};
 use std::fs;
 use std::time::{Duration, Instant};
 
+/// 速率平滑系数默认值（越大越接近瞬时速率）
+pub const DEFAULT_SMOOTHING_ALPHA: f64 = 0.3;
+/// 每个接口保留的速率采样数（每秒一个）
+pub const RATE_HISTORY_LEN: usize = 120;
+
 /// 流量监控器
 pub struct TrafficMonitor {
     stats_cache: HashMap<String, TrafficStats>,
+    smoothed: HashMap<String, (f64, f64)>,  // 平滑后的接收/发送速率
+    history: HashMap<String, VecDeque<(f64, f64)>>,  // 最近的平滑接收/发送速率，用于绘制趋势
+    alpha: f64,  // 指数移动平均系数
     #[allow(dead_code)]
     update_interval: Duration,
 }
@@ -17,12 +27,24 @@ impl TrafficMonitor {
     pub fn new() -> Self {
         Self {
             stats_cache: HashMap::new(),
+            smoothed: HashMap::new(),
+            history: HashMap::new(),
+            alpha: DEFAULT_SMOOTHING_ALPHA,
             update_interval: Duration::from_secs(1),
         }
     }
 
-    /// 更新所有接口的流量统计
+    /// 设置速率平滑系数（0~1，1表示不平滑）
+    pub fn with_alpha(mut self, alpha: f64) -> Self {
+        if alpha > 0.0 && alpha <= 1.0 {
+            self.alpha = alpha;
+        }
+        self
+    }
+
+    /// 更新所有接口的流量统计，并丢弃已消失接口的速率历史
     pub fn update_all(&mut self, interfaces: &mut [NetInterface]) -> Result<()> {
+        self.history.retain(|name, _| interfaces.iter().any(|iface| iface.name == *name));
         for iface in interfaces {
             self.update_interface(iface)?;
         }
@@ -32,30 +54,68 @@ impl TrafficMonitor {
     /// 更新单个接口的流量统计
     pub fn update_interface(&mut self, iface: &mut NetInterface) -> Result<()> {
         let new_stats = self.read_stats(&iface.name)?;
+        iface.traffic_stats = self.apply_sample(&iface.name, new_stats);
+        Ok(())
+    }
+
+    /// 接口最近的接收/发送速率（字节/秒），最旧的在前
+    pub fn history(&self, iface_name: &str) -> Option<&VecDeque<(f64, f64)>> {
+        self.history.get(iface_name)
+    }
+
+    /// 通过 ip -s -j link 读取sysfs不稳定提供的扩展计数（组播、冲突）
+    ///
+    /// 比读取sysfs慢，只在需要展示扩展统计时调用；结果会保留到下次读取
+    pub fn update_extended(&mut self, iface: &mut NetInterface) -> Result<()> {
+        let output = execute_command_stdout("ip", &["-s", "-j", "link", "show", &iface.name])
+            .with_context(|| format!("读取接口 {} 的扩展统计失败", iface.name))?;
+        let extended = parse_ip_link_stats(&output)?;
+
+        apply_extended(&mut iface.traffic_stats, &extended);
+        if let Some(cached) = self.stats_cache.get_mut(&iface.name) {
+            apply_extended(cached, &extended);
+        }
+        Ok(())
+    }
+
+    /// 根据新采样计算瞬时速率和平滑速率，并更新缓存
+    fn apply_sample(&mut self, iface_name: &str, new_stats: TrafficStats) -> TrafficStats {
+        let mut updated_stats = new_stats;
+
+        // 如果有缓存的旧数据，计算速率；第一次读取时没有速率数据
+        if let Some(old_stats) = self.stats_cache.get(iface_name) {
+            // sysfs采样不含扩展计数，沿用上次 ip -s -j 的结果
+            updated_stats.multicast = updated_stats.multicast.or(old_stats.multicast);
+            updated_stats.collisions = updated_stats.collisions.or(old_stats.collisions);
 
-        // 如果有缓存的旧数据，计算速率
-        if let Some(old_stats) = self.stats_cache.get(&iface.name) {
-            let duration = new_stats.last_update.duration_since(old_stats.last_update);
-            let secs = duration.as_secs_f64();
+            let secs = updated_stats.last_update.duration_since(old_stats.last_update).as_secs_f64();
 
             if secs > 0.0 {
-                let mut updated_stats = new_stats.clone();
-                updated_stats.rx_speed = (new_stats.rx_bytes.saturating_sub(old_stats.rx_bytes)) as f64 / secs;
-                updated_stats.tx_speed = (new_stats.tx_bytes.saturating_sub(old_stats.tx_bytes)) as f64 / secs;
-
-                iface.traffic_stats = updated_stats.clone();
-                self.stats_cache.insert(iface.name.clone(), updated_stats);
-            } else {
-                iface.traffic_stats = new_stats.clone();
-                self.stats_cache.insert(iface.name.clone(), new_stats);
+                let rx_raw = updated_stats.rx_bytes.saturating_sub(old_stats.rx_bytes) as f64 / secs;
+                let tx_raw = updated_stats.tx_bytes.saturating_sub(old_stats.tx_bytes) as f64 / secs;
+
+                // 第一个速率样本直接作为初始值，之后按指数移动平均平滑
+                let (rx_speed, tx_speed) = match self.smoothed.get(iface_name) {
+                    Some(&(rx, tx)) => (ema(rx, rx_raw, self.alpha), ema(tx, tx_raw, self.alpha)),
+                    None => (rx_raw, tx_raw),
+                };
+                self.smoothed.insert(iface_name.to_string(), (rx_speed, tx_speed));
+
+                let history = self.history.entry(iface_name.to_string()).or_default();
+                if history.len() == RATE_HISTORY_LEN {
+                    history.pop_front();
+                }
+                history.push_back((rx_speed, tx_speed));
+
+                updated_stats.rx_speed_raw = rx_raw;
+                updated_stats.tx_speed_raw = tx_raw;
+                updated_stats.rx_speed = rx_speed;
+                updated_stats.tx_speed = tx_speed;
             }
-        } else {
-            // 第一次读取，没有速率数据
-            iface.traffic_stats = new_stats.clone();
-            self.stats_cache.insert(iface.name.clone(), new_stats);
         }
 
-        Ok(())
+        self.stats_cache.insert(iface_name.to_string(), updated_stats.clone());
+        updated_stats
     }
 
     /// 从/sys/class/net读取接口统计数据
@@ -80,9 +140,8 @@ impl TrafficMonitor {
             tx_errors,
             rx_dropped,
             tx_dropped,
-            rx_speed: 0.0,
-            tx_speed: 0.0,
             last_update: Instant::now(),
+            ..Default::default()
         })
     }
 }
@@ -93,6 +152,58 @@ impl Default for TrafficMonitor {
     }
 }
 
+/// `ip -s -j link show` 输出中的单个接口
+#[derive(Debug, Deserialize)]
+struct IpLink {
+    stats64: IpLinkStats64,
+}
+
+#[derive(Debug, Deserialize)]
+struct IpLinkStats64 {
+    rx: IpLinkCounters,
+    tx: IpLinkCounters,
+}
+
+/// 单方向计数，缺失的字段按0处理
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct IpLinkCounters {
+    multicast: u64,
+    collisions: u64,
+}
+
+/// 从 ip -s -j 读取到的扩展计数
+#[derive(Debug, Clone, PartialEq)]
+struct ExtendedStats {
+    multicast: u64,
+    collisions: u64,
+}
+
+/// 解析 `ip -s -j link show <iface>` 的JSON输出
+fn parse_ip_link_stats(json: &str) -> Result<ExtendedStats> {
+    let links: Vec<IpLink> = serde_json::from_str(json).context("解析ip -s -j输出失败")?;
+    let Some(link) = links.into_iter().next() else {
+        anyhow::bail!("ip -s -j输出中没有接口");
+    };
+
+    let IpLinkStats64 { rx, tx } = link.stats64;
+    Ok(ExtendedStats {
+        multicast: rx.multicast,
+        collisions: tx.collisions,
+    })
+}
+
+/// 把扩展计数写入流量统计（字节、包、错误等仍以sysfs为准）
+fn apply_extended(stats: &mut TrafficStats, extended: &ExtendedStats) {
+    stats.multicast = Some(extended.multicast);
+    stats.collisions = Some(extended.collisions);
+}
+
+/// 指数移动平均
+fn ema(previous: f64, sample: f64, alpha: f64) -> f64 {
+    alpha * sample + (1.0 - alpha) * previous
+}
+
 /// 读取统计文件中的数值
 fn read_stat_file(path: &str) -> Result<u64> {
     let content = fs::read_to_string(path)
@@ -106,7 +217,6 @@ fn read_stat_file(path: &str) -> Result<u64> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::model::InterfaceKind;
 
     #[test]
     fn test_traffic_monitor_creation() {
@@ -122,5 +232,82 @@ mod tests {
             assert!(stats.rx_bytes > 0 || stats.tx_bytes > 0);
         }
     }
-}
 
+    #[test]
+    fn test_stats_since_baseline() {
+        let baseline = TrafficStats { rx_bytes: 1000, tx_bytes: 500, ..Default::default() };
+        let current = TrafficStats { rx_bytes: 1500, tx_bytes: 400, rx_speed: 10.0, ..Default::default() };
+
+        let session = current.since(&baseline);
+        assert_eq!(session.rx_bytes, 500);
+        // 计数器被重置（如接口重建）时不会下溢
+        assert_eq!(session.tx_bytes, 0);
+        assert_eq!(session.rx_speed, 10.0);
+    }
+
+    #[test]
+    fn test_rate_smoothing_converges() {
+        let mut monitor = TrafficMonitor::new().with_alpha(0.3);
+        let start = Instant::now();
+        let sample = |secs: u64, rx_bytes: u64| TrafficStats {
+            rx_bytes,
+            last_update: start + Duration::from_secs(secs),
+            ..Default::default()
+        };
+
+        monitor.apply_sample("eth0", sample(0, 0));
+        // 第一秒突发10000字节，之后每秒稳定1000字节
+        let first = monitor.apply_sample("eth0", sample(1, 10_000));
+        assert_eq!(first.rx_speed, 10_000.0);
+
+        let mut bytes = 10_000;
+        let mut last = first;
+        for secs in 2..30 {
+            bytes += 1000;
+            let stats = monitor.apply_sample("eth0", sample(secs, bytes));
+            // 平滑速率单调逼近稳定值，瞬时速率始终为单次采样
+            assert!(stats.rx_speed < last.rx_speed);
+            assert_eq!(stats.rx_speed_raw, 1000.0);
+            last = stats;
+        }
+        assert!((last.rx_speed - 1000.0).abs() < 1.0);
+
+        // 历史按采样顺序保存平滑速率，长度有上限
+        let history = monitor.history("eth0").unwrap();
+        assert_eq!(history.len(), 29);
+        assert_eq!(history.front(), Some(&(10_000.0, 0.0)));
+        for secs in 30..200 {
+            bytes += 1000;
+            monitor.apply_sample("eth0", sample(secs, bytes));
+        }
+        assert_eq!(monitor.history("eth0").unwrap().len(), RATE_HISTORY_LEN);
+    }
+
+    #[test]
+    fn test_parse_ip_link_stats() {
+        let json = r#"[{"ifindex":2,"ifname":"eth0","flags":["BROADCAST","MULTICAST","UP","LOWER_UP"],"mtu":1500,"qdisc":"fq_codel","operstate":"UP","linkmode":"DEFAULT","group":"default","txqlen":1000,"link_type":"ether","address":"52:54:00:12:34:56","broadcast":"ff:ff:ff:ff:ff:ff","stats64":{"rx":{"bytes":90496189,"packets":7687,"errors":3,"dropped":12,"over_errors":0,"multicast":421},"tx":{"bytes":1204455,"packets":5120,"errors":0,"dropped":0,"carrier_errors":0,"collisions":7}}}]"#;
+
+        let extended = parse_ip_link_stats(json).unwrap();
+        assert_eq!(extended, ExtendedStats { multicast: 421, collisions: 7 });
+
+        let mut stats = TrafficStats { rx_errors: 3, ..Default::default() };
+        apply_extended(&mut stats, &extended);
+        assert_eq!(stats.multicast, Some(421));
+        assert_eq!(stats.collisions, Some(7));
+        assert_eq!(stats.rx_errors, 3);
+
+        assert!(parse_ip_link_stats("[]").is_err());
+        assert!(parse_ip_link_stats("Device \"nope\" does not exist.").is_err());
+    }
+
+    #[test]
+    fn test_extended_counters_survive_sysfs_sample() {
+        let mut monitor = TrafficMonitor::new();
+        let first = TrafficStats { multicast: Some(5), collisions: Some(1), ..Default::default() };
+        monitor.apply_sample("eth0", first);
+
+        let next = monitor.apply_sample("eth0", TrafficStats::default());
+        assert_eq!(next.multicast, Some(5));
+        assert_eq!(next.collisions, Some(1));
+    }
+}
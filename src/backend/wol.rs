@@ -0,0 +1,58 @@
+// 网络唤醒模块 - 构造并广播Wake-on-LAN魔术包
+use crate::backend::runtime;
+use anyhow::{Context, Result};
+use std::net::{Ipv4Addr, UdpSocket};
+
+/// 魔术包的目标UDP端口（discard）
+pub const WOL_PORT: u16 = 9;
+/// 魔术包长度：6字节0xFF + 16次重复的MAC地址
+pub const MAGIC_PACKET_LEN: usize = 102;
+
+/// 构造魔术包
+pub fn magic_packet(mac: [u8; 6]) -> [u8; MAGIC_PACKET_LEN] {
+    let mut packet = [0xFF; MAGIC_PACKET_LEN];
+    for chunk in packet[6..].chunks_exact_mut(6) {
+        chunk.copy_from_slice(&mac);
+    }
+    packet
+}
+
+/// 解析目标MAC地址为字节
+fn parse_mac_bytes(mac: &str) -> Result<[u8; 6]> {
+    let normalized = runtime::parse_mac_address(mac)?;
+    let mut bytes = [0u8; 6];
+    for (byte, octet) in bytes.iter_mut().zip(normalized.split(':')) {
+        *byte = u8::from_str_radix(octet, 16)?;
+    }
+    Ok(bytes)
+}
+
+/// 向广播地址的9号端口发送魔术包，唤醒指定MAC地址的机器
+pub fn send_magic_packet(mac: &str, broadcast_addr: Ipv4Addr) -> Result<()> {
+    let packet = magic_packet(parse_mac_bytes(mac)?);
+
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).context("创建UDP套接字失败")?;
+    socket.set_broadcast(true).context("启用广播失败")?;
+    socket
+        .send_to(&packet, (broadcast_addr, WOL_PORT))
+        .with_context(|| format!("发送魔术包到 {}:{} 失败", broadcast_addr, WOL_PORT))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_magic_packet() {
+        let mac = parse_mac_bytes("52:54:00:ab:cd:ef").unwrap();
+        assert_eq!(mac, [0x52, 0x54, 0x00, 0xab, 0xcd, 0xef]);
+
+        let packet = magic_packet(mac);
+        assert_eq!(&packet[..6], &[0xFF; 6]);
+        assert!(packet[6..].chunks(6).all(|chunk| chunk == mac));
+        assert_eq!(packet[6..].chunks(6).count(), 16);
+
+        assert!(parse_mac_bytes("52:54:00:ab:cd").is_err());
+    }
+}
@@ -0,0 +1,256 @@
+// 命令行子命令模块 - 为脚本提供无交互的DHCP/静态IP配置和网络唤醒
+use crate::backend::apply::{self, StaticIpv4};
+use crate::backend::dns;
+use crate::backend::netplan::{NameserverConfig, NetplanManager};
+use crate::backend::wol;
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::net::Ipv4Addr;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+/// --try 时等待确认的时间，超时后netplan自动回滚
+const TRY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 子命令
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// 将接口切换为DHCP模式
+    #[command(name = "set-dhcp")]
+    Dhcp {
+        /// 接口名称
+        iface: String,
+        /// 保留原有的静态DNS，不使用DHCP下发的DNS
+        #[arg(long)]
+        keep_dns: bool,
+        #[command(flatten)]
+        apply: ApplyArgs,
+    },
+    /// 为接口设置静态IP
+    #[command(name = "set-static")]
+    Static {
+        /// 接口名称
+        iface: String,
+        /// IP地址（CIDR格式，如 192.168.1.10/24）
+        cidr: String,
+        /// 默认网关
+        #[arg(long)]
+        gateway: Option<String>,
+        /// DNS服务器列表（逗号分隔，IPv4或IPv6）
+        #[arg(long, value_delimiter = ',')]
+        dns: Vec<String>,
+        /// DNS搜索域列表（逗号分隔）
+        #[arg(long, value_delimiter = ',')]
+        search: Vec<String>,
+        #[command(flatten)]
+        apply: ApplyArgs,
+    },
+    /// 立即为接口设置静态IP（运行时生效），并写入Netplan
+    #[command(name = "set-ip")]
+    Ip {
+        /// 接口名称
+        iface: String,
+        /// IP地址（CIDR格式，如 192.168.1.50/24）
+        #[arg(long)]
+        address: String,
+        /// 默认网关
+        #[arg(long)]
+        gateway: Option<String>,
+        /// DNS服务器列表（逗号分隔，IPv4或IPv6）
+        #[arg(long, value_delimiter = ',')]
+        dns: Vec<String>,
+        /// DNS搜索域列表（逗号分隔）
+        #[arg(long, value_delimiter = ',')]
+        search: Vec<String>,
+        /// 只修改运行时配置，不写入Netplan（重启后失效）
+        #[arg(long, conflicts_with_all = ["apply", "no_apply", "try_config"])]
+        no_persist: bool,
+        #[command(flatten)]
+        apply: ApplyArgs,
+    },
+    /// 发送Wake-on-LAN魔术包唤醒局域网中的机器
+    Wol {
+        /// 目标机器的MAC地址
+        mac: String,
+        /// 广播地址（可指定子网广播地址，如 192.168.1.255）
+        #[arg(long, default_value = "255.255.255.255")]
+        broadcast: Ipv4Addr,
+    },
+}
+
+impl Command {
+    /// 是否修改本机网络配置（需要root权限，安全模式下禁止）
+    pub fn modifies_system(&self) -> bool {
+        !matches!(self, Command::Wol { .. })
+    }
+}
+
+/// 配置写入后的应用方式
+#[derive(Args, Debug)]
+pub struct ApplyArgs {
+    /// 写入后执行 netplan apply（默认）
+    #[arg(long, overrides_with = "no_apply")]
+    apply: bool,
+    /// 仅写入配置文件，不应用
+    #[arg(long)]
+    no_apply: bool,
+    /// 使用 netplan try 测试配置（超时自动回滚）
+    #[arg(long = "try", conflicts_with_all = ["apply", "no_apply"])]
+    try_config: bool,
+}
+
+/// 执行子命令，返回进程退出码
+pub fn run(command: Command, netplan: NetplanManager) -> i32 {
+    match execute(command, netplan) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("错误: {:#}", e);
+            1
+        }
+    }
+}
+
+fn execute(command: Command, netplan: NetplanManager) -> Result<()> {
+    let (backup, apply) = match command {
+        Command::Wol { mac, broadcast } => {
+            wol::send_magic_packet(&mac, broadcast)?;
+            println!("✅ 已向 {}:{} 发送 {} 的魔术包", broadcast, wol::WOL_PORT, mac);
+            return Ok(());
+        }
+        Command::Dhcp { iface, keep_dns, apply } => {
+            println!("目标配置文件: {}", netplan.target_config_file(&iface)?.display());
+            (netplan.set_dhcp(&iface, keep_dns)?.1, apply)
+        }
+        Command::Static { iface, cidr, gateway, dns, search, apply } => {
+            let address = parse_cidr(&cidr)?;
+            if let Some(gw) = &gateway {
+                gw.parse::<Ipv4Addr>()
+                    .with_context(|| format!("无效的网关地址: {}", gw))?;
+            }
+            let nameservers = NameserverConfig {
+                addresses: dns::parse_nameservers(&dns.join(","))?,
+                search: dns::parse_search_domains(&search.join(","))?,
+            };
+            let nameservers = Some(nameservers).filter(|ns| !ns.addresses.is_empty() || !ns.search.is_empty());
+            println!("目标配置文件: {}", netplan.target_config_file(&iface)?.display());
+            (netplan.set_static_ip(&iface, &address, gateway.as_deref(), None, nameservers, None)?.1, apply)
+        }
+        Command::Ip { iface, address, gateway, dns, search, no_persist, apply } => {
+            // 修改系统之前完成全部验证
+            let (ip, prefix) = apply::parse_ipv4_cidr(&address)?;
+            let nameservers = NameserverConfig {
+                addresses: dns::parse_nameservers(&dns.join(","))?,
+                search: dns::parse_search_domains(&search.join(","))?,
+            };
+            let config = StaticIpv4::new(&ip.to_string(), prefix, gateway.as_deref(), Some(nameservers))?;
+            if !no_persist {
+                println!("目标配置文件: {}", netplan.target_config_file(&iface)?.display());
+            }
+
+            let written = apply::apply_static_ipv4(&netplan, &iface, &config, None, None, !no_persist)?;
+            println!("✅ 已为 {} 设置 {}（运行时已生效）", iface, config.cidr());
+            if no_persist {
+                println!("未写入Netplan，重启后失效");
+                return Ok(());
+            }
+            (written.and_then(|(_, backup)| backup), apply)
+        }
+    };
+
+    if let Some(path) = backup {
+        println!("✅ 已备份配置到: {}", path.display());
+    }
+
+    if apply.try_config {
+        try_with_confirmation(&netplan)?;
+        println!("✅ 已确认并保留新配置");
+    } else if apply.apply || !apply.no_apply {
+        let output = netplan.apply()?;
+        if !output.is_empty() {
+            println!("{}", output);
+        }
+        println!("✅ Netplan配置已应用");
+    }
+
+    Ok(())
+}
+
+/// 执行 netplan try，在控制终端上等待用户确认；未确认（拒绝或超时）时配置被回滚并返回错误
+fn try_with_confirmation(netplan: &NetplanManager) -> Result<()> {
+    // 先打开控制终端，没有终端时不启动 netplan try
+    let mut tty = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .context("打开控制终端失败，--try 需要在终端中交互确认")?;
+    let input = tty.try_clone().context("打开控制终端失败")?;
+
+    let mut session = netplan.start_try(TRY_TIMEOUT)?;
+    write!(
+        tty,
+        "新配置已生效，{}秒内输入 y 并回车保留，其他输入或超时将回滚: ",
+        TRY_TIMEOUT.as_secs()
+    )?;
+    tty.flush()?;
+
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut line = String::new();
+        if BufReader::new(input).read_line(&mut line).is_ok() {
+            sender.send(line).ok();
+        }
+    });
+
+    let confirmed = loop {
+        if let Some(result) = session.poll() {
+            // 用户确认前netplan已退出（出错或已超时回滚）
+            let output = result.unwrap_or_else(|output| output);
+            anyhow::bail!("netplan try 已结束，配置未保留: {}", output);
+        }
+        match receiver.recv_timeout(Duration::from_millis(200)) {
+            Ok(line) => break line.trim().eq_ignore_ascii_case("y"),
+            Err(RecvTimeoutError::Timeout) => continue,
+            Err(RecvTimeoutError::Disconnected) => break false,
+        }
+    };
+
+    if confirmed {
+        session.confirm()?;
+    } else {
+        session.revert()?;
+    }
+    let result = loop {
+        if let Some(result) = session.poll() {
+            break result;
+        }
+        thread::sleep(Duration::from_millis(100));
+    };
+
+    match (confirmed, result) {
+        (true, Ok(_)) => Ok(()),
+        (true, Err(output)) => anyhow::bail!("netplan try 失败: {}", output),
+        (false, _) => anyhow::bail!("未确认，配置已回滚"),
+    }
+}
+
+/// 校验CIDR格式的地址，返回规范化后的字符串
+fn parse_cidr(cidr: &str) -> Result<String> {
+    let (ip, prefix) = apply::parse_ipv4_cidr(cidr)?;
+    Ok(format!("{}/{}", ip, prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cidr() {
+        assert_eq!(parse_cidr("192.168.1.10/24").unwrap(), "192.168.1.10/24");
+        assert!(parse_cidr("192.168.1.10").is_err());
+        assert!(parse_cidr("192.168.1.300/24").is_err());
+        assert!(parse_cidr("192.168.1.10/33").is_err());
+    }
+}
@@ -0,0 +1,197 @@
+// 配置文件模块 - 读写 /etc/nicman/config.yaml
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::utils::format::TrafficUnit;
+
+/// 默认配置文件路径
+pub const CONFIG_PATH: &str = "/etc/nicman/config.yaml";
+
+/// 用户配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// 使用ASCII符号代替emoji
+    pub ascii: bool,
+    /// 新接口配置写入的netplan文件（如 99-custom.yaml）
+    pub netplan_file: Option<PathBuf>,
+    /// 接口列表占界面宽度的百分比（默认40）
+    pub list_width: Option<u16>,
+    /// 置顶显示的接口名称
+    pub pinned: Vec<String>,
+    /// 速率平滑系数（0~1，1表示显示瞬时速率，默认0.3）
+    pub rate_smoothing: Option<f64>,
+    /// 流量统计面板的显示内容
+    pub stats_display: StatsDisplay,
+    /// 编辑表单中子网掩码的输入格式
+    pub netmask_format: NetmaskFormat,
+    /// 流量显示单位（auto/kb/mb/gb/kbit/mbit/gbit，默认auto自动切换）
+    pub traffic_unit: TrafficUnit,
+    /// 接口列表每项多显示一行主IP和创建者
+    pub list_details: bool,
+    /// 每秒新增错误+丢包数超过此值时告警（默认10，0表示不启用）
+    pub error_rate_threshold: Option<u64>,
+    /// 错误+丢包速率超过近期平均值的倍数时告警（默认5，0表示不启用）
+    pub error_spike_factor: Option<f64>,
+    /// 创建者操作后等待接口消失的最长时间（毫秒，默认3000，0表示不等待）
+    pub owner_action_timeout_ms: Option<u64>,
+    /// 终止进程时SIGTERM后等待进程退出的最长时间（毫秒，默认1000）
+    pub kill_grace_ms: Option<u64>,
+    /// 未识别的字段（如更新版本的设置），保存时原样写回
+    #[serde(flatten)]
+    pub extra: serde_yaml::Mapping,
+}
+
+/// 子网掩码以点分十进制（255.255.255.0）还是前缀长度（24）输入
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NetmaskFormat {
+    #[default]
+    Dotted,
+    Prefix,
+}
+
+impl NetmaskFormat {
+    pub fn toggle(self) -> Self {
+        match self {
+            NetmaskFormat::Dotted => NetmaskFormat::Prefix,
+            NetmaskFormat::Prefix => NetmaskFormat::Dotted,
+        }
+    }
+}
+
+/// 流量统计面板显示速率、累计值还是两者
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatsDisplay {
+    #[default]
+    Both,
+    RateOnly,
+    TotalsOnly,
+}
+
+impl StatsDisplay {
+    /// 按 两者 → 仅速率 → 仅累计 循环切换
+    pub fn next(self) -> Self {
+        match self {
+            StatsDisplay::Both => StatsDisplay::RateOnly,
+            StatsDisplay::RateOnly => StatsDisplay::TotalsOnly,
+            StatsDisplay::TotalsOnly => StatsDisplay::Both,
+        }
+    }
+
+    pub fn shows_rate(self) -> bool {
+        self != StatsDisplay::TotalsOnly
+    }
+
+    pub fn shows_totals(self) -> bool {
+        self != StatsDisplay::RateOnly
+    }
+}
+
+impl Config {
+    /// 加载配置文件，文件不存在时使用默认配置
+    pub fn load() -> Result<Self> {
+        Self::load_from(Path::new(CONFIG_PATH))
+    }
+
+    fn load_from(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("读取配置文件失败: {:?}", path))?;
+        if content.trim().is_empty() {
+            return Ok(Self::default());
+        }
+
+        serde_yaml::from_str(&content)
+            .with_context(|| format!("解析配置文件失败: {:?}", path))
+    }
+
+    /// 保存配置文件
+    pub fn save(&self) -> Result<()> {
+        self.save_to(Path::new(CONFIG_PATH))
+    }
+
+    /// 先写入同目录下的临时文件再重命名，写入中断时原配置保持完整（注释不会保留）
+    fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("创建配置目录失败: {:?}", dir))?;
+        }
+
+        let yaml = serde_yaml::to_string(self).context("序列化配置失败")?;
+        let file_name = path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+        let tmp = path.with_file_name(format!(".{}.tmp.{}", file_name, std::process::id()));
+        fs::write(&tmp, yaml)
+            .with_context(|| format!("写入配置文件失败: {:?}", tmp))?;
+        fs::rename(&tmp, path).with_context(|| {
+            fs::remove_file(&tmp).ok();
+            format!("替换配置文件失败: {:?}", path)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_config() {
+        let path = std::env::temp_dir().join(format!("nicman-config-{}.yaml", std::process::id()));
+
+        assert!(!Config::load_from(&path).unwrap().ascii);
+
+        fs::write(&path, "ascii: true\n").unwrap();
+        assert!(Config::load_from(&path).unwrap().ascii);
+
+        fs::write(&path, "").unwrap();
+        assert!(!Config::load_from(&path).unwrap().ascii);
+
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_save_config_roundtrip() {
+        let path = std::env::temp_dir().join(format!("nicman-save-{}.yaml", std::process::id()));
+
+        let config = Config {
+            list_width: Some(55),
+            ..Default::default()
+        };
+        config.save_to(&path).unwrap();
+        assert_eq!(Config::load_from(&path).unwrap().list_width, Some(55));
+
+        // 未识别的字段在读取-修改-保存后保留
+        fs::write(&path, "ascii: true\nfuture_option: [a, b]\n").unwrap();
+        let mut config = Config::load_from(&path).unwrap();
+        config.list_width = Some(60);
+        config.save_to(&path).unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains("future_option:"));
+        assert!(content.contains("list_width: 60"));
+        assert!(Config::load_from(&path).unwrap().ascii);
+
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_stats_display_config() {
+        let path = std::env::temp_dir().join(format!("nicman-stats-{}.yaml", std::process::id()));
+
+        // 旧配置文件没有该字段时默认显示两者
+        fs::write(&path, "ascii: false\n").unwrap();
+        assert_eq!(Config::load_from(&path).unwrap().stats_display, StatsDisplay::Both);
+
+        fs::write(&path, "stats_display: rate_only\n").unwrap();
+        assert_eq!(Config::load_from(&path).unwrap().stats_display, StatsDisplay::RateOnly);
+
+        assert_eq!(StatsDisplay::Both.next().next().next(), StatsDisplay::Both);
+
+        fs::remove_file(&path).ok();
+    }
+}
@@ -1,11 +1,17 @@
 // 网卡管理工具主程序
+mod cli;
+mod config;
 mod model;
 mod backend;
 mod ui;
 mod utils;
 
 use clap::Parser;
+use std::fs::OpenOptions;
+use std::path::{Path, PathBuf};
 use std::process;
+use std::sync::Mutex;
+use std::time::Duration;
 
 /// 网卡管理工具 - TUI终端界面
 #[derive(Parser, Debug)]
@@ -15,6 +21,49 @@ struct Args {
     /// 显示版本信息
     #[arg(short, long)]
     version: bool,
+
+    /// 将日志写入指定文件（TUI运行时可在另一窗口 tail -f 查看）
+    #[arg(long, value_name = "PATH")]
+    log: Option<PathBuf>,
+
+    /// 输出调试级别日志（需配合 --log）
+    #[arg(long, requires = "log")]
+    verbose: bool,
+
+    /// 使用ASCII符号代替emoji（适用于不支持emoji的终端）
+    #[arg(long)]
+    ascii: bool,
+
+    /// 固定流量显示单位（auto/kb/mb/gb/kbit/mbit/gbit），便于比较多个接口
+    #[arg(long, value_name = "UNIT")]
+    unit: Option<utils::format::TrafficUnit>,
+
+    /// 新接口配置写入的netplan文件（默认为/etc/netplan下第一个文件）
+    #[arg(long, value_name = "FILE", global = true)]
+    netplan_file: Option<PathBuf>,
+
+    /// 跳过创建者检测（docker/systemctl/nsenter等），加快启动
+    #[arg(long)]
+    no_owner_detection: bool,
+
+    /// 不启动TUI，每秒向stdout输出一行JSON（接口状态与流量）
+    #[arg(long)]
+    stream_json: bool,
+
+    /// 安全模式：可以浏览所有操作对话框，但阻止执行任何修改（TUI中按 ! 切换）
+    #[arg(long)]
+    safe: bool,
+
+    /// 非root运行TUI，修改类操作（ip/tc/resolvectl命令、Netplan配置的读写和应用、创建者操作等）通过sudo执行
+    #[arg(long)]
+    use_sudo: bool,
+
+    /// 只显示/输出指定类型的接口（逗号分隔，不区分大小写，如 physical,bond）
+    #[arg(long, value_name = "KIND", value_delimiter = ',')]
+    kind: Vec<model::InterfaceKind>,
+
+    #[command(subcommand)]
+    command: Option<cli::Command>,
 }
 
 fn main() {
@@ -26,17 +75,80 @@ fn main() {
         return;
     }
 
-    // 检查root权限
-    if !is_root() {
-        eprintln!("错误: 此程序需要root权限运行");
-        eprintln!("请使用: sudo nicman");
+    if let Some(path) = &args.log
+        && let Err(e) = init_logging(path, args.verbose) {
+        eprintln!("初始化日志失败: {}", e);
         process::exit(1);
     }
 
+    let config = match config::Config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("加载配置失败: {:#}", e);
+            process::exit(1);
+        }
+    };
+    utils::icons::set_ascii_mode(args.ascii || config.ascii);
+    utils::format::set_traffic_unit(args.unit.unwrap_or(config.traffic_unit));
+    let netplan_file = args.netplan_file.or(config.netplan_file);
+
+    // 检查root权限；--use-sudo时TUI可以非root运行，启动前先缓存sudo凭据
+    let modifies_system = args.command.as_ref().is_none_or(cli::Command::modifies_system);
+    if modifies_system && !is_root() {
+        if args.use_sudo && args.command.is_none() {
+            if !cache_sudo_credentials() {
+                eprintln!("错误: sudo认证失败");
+                process::exit(1);
+            }
+            utils::command::set_use_sudo(true);
+        } else {
+            eprintln!("错误: 此程序需要root权限运行");
+            eprintln!("请使用: sudo nicman（或 nicman --use-sudo 仅在执行操作时使用sudo）");
+            process::exit(1);
+        }
+    }
+
+    // 子命令模式：无交互执行后退出
+    if let Some(command) = args.command {
+        if args.safe && command.modifies_system() {
+            eprintln!("错误: 安全模式下不能执行修改配置的子命令");
+            process::exit(1);
+        }
+        let netplan = backend::netplan::NetplanManager::new().with_preferred_file(netplan_file);
+        process::exit(cli::run(command, netplan));
+    }
+
     // 运行TUI应用
-    match ui::App::new() {
+    let options = ui::AppOptions {
+        owner_detection: !args.no_owner_detection,
+        netplan_file,
+        list_width: config.list_width.unwrap_or(ui::DEFAULT_LIST_WIDTH),
+        pinned: config.pinned,
+        rate_smoothing: config.rate_smoothing.unwrap_or(backend::traffic::DEFAULT_SMOOTHING_ALPHA),
+        kinds: args.kind,
+        stats_display: config.stats_display,
+        netmask_format: config.netmask_format,
+        safe_mode: args.safe,
+        list_details: config.list_details,
+        error_rate_threshold: config.error_rate_threshold.unwrap_or(backend::error_rate::DEFAULT_ERROR_RATE_THRESHOLD),
+        error_spike_factor: config.error_spike_factor.unwrap_or(backend::error_rate::DEFAULT_ERROR_SPIKE_FACTOR),
+        owner_action_timeout: config.owner_action_timeout_ms
+            .map(Duration::from_millis)
+            .unwrap_or(ui::DEFAULT_OWNER_ACTION_TIMEOUT),
+        kill_grace: config.kill_grace_ms
+            .map(Duration::from_millis)
+            .unwrap_or(backend::removal::DEFAULT_KILL_GRACE),
+    };
+
+    match ui::App::new(options) {
         Ok(mut app) => {
-            if let Err(e) = app.run() {
+            let result = if args.stream_json {
+                app.run_json_stream()
+            } else {
+                app.run()
+            };
+            if let Err(e) = result {
+                tracing::error!("应用运行错误: {:#}", e);
                 eprintln!("应用运行错误: {}", e);
                 process::exit(1);
             }
@@ -48,6 +160,35 @@ fn main() {
     }
 }
 
+/// 初始化文件日志，默认info级别，verbose时为debug级别
+fn init_logging(path: &Path, verbose: bool) -> std::io::Result<()> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let level = if verbose {
+        tracing::Level::DEBUG
+    } else {
+        tracing::Level::INFO
+    };
+
+    tracing_subscriber::fmt()
+        .with_writer(Mutex::new(file))
+        .with_ansi(false)
+        .with_max_level(level)
+        .init();
+
+    tracing::info!("nicman 启动，日志级别: {}", level);
+    Ok(())
+}
+
+/// 运行 sudo -v 提示输入密码并缓存凭据，之后的命令用 sudo -n 执行
+fn cache_sudo_credentials() -> bool {
+    println!("--use-sudo: 修改类操作将通过sudo执行，请先完成sudo认证");
+    process::Command::new("sudo")
+        .arg("-v")
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
 /// 检查是否以root权限运行
 fn is_root() -> bool {
     use nix::unistd::Uid;
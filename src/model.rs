@@ -1,5 +1,7 @@
 // 数据模型定义
+use crate::utils::icons::pick;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use std::time::Instant;
 
 /// 网络接口类型
@@ -11,13 +13,39 @@ pub enum InterfaceKind {
     Tap,           // TAP设备
     WireGuard,     // WireGuard VPN
     Bridge,        // 网桥
+    Bond,          // 链路聚合
     Veth,          // 虚拟以太网对
     Vlan,          // VLAN接口
+    Macvlan,       // MACVLAN接口
+    Vxlan,         // VXLAN隧道
+    Gre,           // GRE隧道
     Docker,        // Docker网桥
+    Vrf,           // VRF设备
+    Ifb,           // IFB中间功能块（入向流量整形）
     Unknown,       // 未知类型
 }
 
 impl InterfaceKind {
+    /// 所有接口类型（用于命令行参数解析和提示）
+    pub const ALL: [InterfaceKind; 16] = [
+        InterfaceKind::Physical,
+        InterfaceKind::Loopback,
+        InterfaceKind::Tun,
+        InterfaceKind::Tap,
+        InterfaceKind::WireGuard,
+        InterfaceKind::Bridge,
+        InterfaceKind::Bond,
+        InterfaceKind::Veth,
+        InterfaceKind::Vlan,
+        InterfaceKind::Macvlan,
+        InterfaceKind::Vxlan,
+        InterfaceKind::Gre,
+        InterfaceKind::Docker,
+        InterfaceKind::Vrf,
+        InterfaceKind::Ifb,
+        InterfaceKind::Unknown,
+    ];
+
     /// 判断是否为虚拟接口
     #[allow(dead_code)]
     pub fn is_virtual(&self) -> bool {
@@ -34,26 +62,38 @@ impl InterfaceKind {
             InterfaceKind::Tap => "TAP设备",
             InterfaceKind::WireGuard => "WireGuard",
             InterfaceKind::Bridge => "网桥",
+            InterfaceKind::Bond => "链路聚合",
             InterfaceKind::Veth => "虚拟以太网",
             InterfaceKind::Vlan => "VLAN",
+            InterfaceKind::Macvlan => "MACVLAN",
+            InterfaceKind::Vxlan => "VXLAN",
+            InterfaceKind::Gre => "GRE隧道",
             InterfaceKind::Docker => "Docker网桥",
+            InterfaceKind::Vrf => "VRF",
+            InterfaceKind::Ifb => "IFB(入向整形)",
             InterfaceKind::Unknown => "未知",
         }
     }
 
     /// 获取类型的图标
-    #[allow(dead_code)]
-    pub fn icon(&self) -> &str {
+    pub fn icon(&self) -> &'static str {
         match self {
-            InterfaceKind::Physical => "🔌",
-            InterfaceKind::Loopback => "🔄",
-            InterfaceKind::Tun | InterfaceKind::Tap => "🔐",
-            InterfaceKind::WireGuard => "🔒",
-            InterfaceKind::Bridge => "🌉",
-            InterfaceKind::Veth => "🔗",
-            InterfaceKind::Vlan => "🏷️",
-            InterfaceKind::Docker => "🐳",
-            InterfaceKind::Unknown => "❓",
+            InterfaceKind::Physical => pick("🔌", "[P]"),
+            InterfaceKind::Loopback => pick("🔄", "[LO]"),
+            InterfaceKind::Tun => pick("🚇", "[TUN]"),
+            InterfaceKind::Tap => pick("🚰", "[TAP]"),
+            InterfaceKind::WireGuard => pick("🔐", "[WG]"),
+            InterfaceKind::Bridge => pick("🌉", "[BR]"),
+            InterfaceKind::Bond => pick("🪢", "[BOND]"),
+            InterfaceKind::Veth => pick("🔗", "[VETH]"),
+            InterfaceKind::Vlan => pick("📡", "[VLAN]"),
+            InterfaceKind::Macvlan => pick("🔀", "[MACV]"),
+            InterfaceKind::Vxlan => pick("🛰", "[VXLAN]"),
+            InterfaceKind::Gre => pick("🕳", "[GRE]"),
+            InterfaceKind::Docker => pick("🐳", "[DKR]"),
+            InterfaceKind::Vrf => pick("🧭", "[VRF]"),
+            InterfaceKind::Ifb => pick("⏬", "[IFB]"),
+            InterfaceKind::Unknown => pick("❓", "[?]"),
         }
     }
 }
@@ -77,23 +117,45 @@ impl InterfaceState {
     }
 }
 
+/// 链路健康状态（综合管理状态与载波）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkHealth {
+    Up,        // 已启用且有载波
+    NoCarrier, // 已启用但无载波
+    Down,      // 已禁用
+    Unknown,   // 未知
+}
+
+impl LinkHealth {
+    /// 获取状态指示图标
+    pub fn icon(&self) -> &'static str {
+        match self {
+            LinkHealth::Up => pick("🟢", "[UP]"),
+            LinkHealth::NoCarrier => pick("🟡", "[NC]"),
+            LinkHealth::Down => pick("🔴", "[DN]"),
+            LinkHealth::Unknown => pick("⚪", "[??]"),
+        }
+    }
+}
+
 /// 流量统计数据
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct TrafficStats {
     pub rx_bytes: u64,       // 接收字节数
     pub tx_bytes: u64,       // 发送字节数
     pub rx_packets: u64,     // 接收包数
     pub tx_packets: u64,     // 发送包数
-    #[allow(dead_code)]
     pub rx_errors: u64,      // 接收错误
-    #[allow(dead_code)]
     pub tx_errors: u64,      // 发送错误
-    #[allow(dead_code)]
     pub rx_dropped: u64,     // 接收丢包
-    #[allow(dead_code)]
     pub tx_dropped: u64,     // 发送丢包
-    pub rx_speed: f64,       // 接收速率 (bytes/sec)
-    pub tx_speed: f64,       // 发送速率 (bytes/sec)
+    pub multicast: Option<u64>,  // 接收组播包数（来自 ip -s -j，未读取时为None）
+    pub collisions: Option<u64>, // 发送冲突次数（来自 ip -s -j，未读取时为None）
+    pub rx_speed: f64,       // 接收速率 (bytes/sec，平滑后)
+    pub tx_speed: f64,       // 发送速率 (bytes/sec，平滑后)
+    pub rx_speed_raw: f64,   // 接收瞬时速率（单次采样）
+    pub tx_speed_raw: f64,   // 发送瞬时速率（单次采样）
+    #[serde(skip)]
     pub last_update: Instant, // 最后更新时间
 }
 
@@ -108,13 +170,51 @@ impl Default for TrafficStats {
             tx_errors: 0,
             rx_dropped: 0,
             tx_dropped: 0,
+            multicast: None,
+            collisions: None,
             rx_speed: 0.0,
             tx_speed: 0.0,
+            rx_speed_raw: 0.0,
+            tx_speed_raw: 0.0,
             last_update: Instant::now(),
         }
     }
 }
 
+impl TrafficStats {
+    /// 计算相对于基线的计数（计数器被重置时按0处理），速率保持不变
+    pub fn since(&self, baseline: &TrafficStats) -> TrafficStats {
+        TrafficStats {
+            rx_bytes: self.rx_bytes.saturating_sub(baseline.rx_bytes),
+            tx_bytes: self.tx_bytes.saturating_sub(baseline.tx_bytes),
+            rx_packets: self.rx_packets.saturating_sub(baseline.rx_packets),
+            tx_packets: self.tx_packets.saturating_sub(baseline.tx_packets),
+            rx_errors: self.rx_errors.saturating_sub(baseline.rx_errors),
+            tx_errors: self.tx_errors.saturating_sub(baseline.tx_errors),
+            rx_dropped: self.rx_dropped.saturating_sub(baseline.rx_dropped),
+            tx_dropped: self.tx_dropped.saturating_sub(baseline.tx_dropped),
+            multicast: self.multicast.map(|n| n.saturating_sub(baseline.multicast.unwrap_or(0))),
+            collisions: self.collisions.map(|n| n.saturating_sub(baseline.collisions.unwrap_or(0))),
+            ..self.clone()
+        }
+    }
+}
+
+impl std::str::FromStr for InterfaceKind {
+    type Err = String;
+
+    /// 按类型名解析（不区分大小写），如 physical、Docker
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| format!("{:?}", kind).eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| {
+                let valid: Vec<String> = Self::ALL.iter().map(|kind| format!("{:?}", kind)).collect();
+                format!("未知的接口类型: {}（可选: {}）", s, valid.join(", "))
+            })
+    }
+}
+
 /// 服务状态
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ServiceStatus {
@@ -137,6 +237,11 @@ pub enum InterfaceOwner {
         name: String,
         image: String,
     },
+    DockerNetwork {
+        id: String,
+        name: String,
+        containers: usize,  // 已连接的容器数
+    },
     Process {
         pid: u32,
         name: String,
@@ -146,6 +251,9 @@ pub enum InterfaceOwner {
         connection: String,
         uuid: String,
     },
+    NspawnMachine {
+        machine: String,
+    },
     Kernel {
         module: String,
     },
@@ -158,8 +266,10 @@ impl InterfaceOwner {
         match self {
             InterfaceOwner::SystemdService { name, .. } => format!("systemd: {}", name),
             InterfaceOwner::DockerContainer { name, .. } => format!("Docker: {}", name),
+            InterfaceOwner::DockerNetwork { name, .. } => format!("Docker网络: {}", name),
             InterfaceOwner::Process { name, pid, .. } => format!("进程: {} (PID: {})", name, pid),
             InterfaceOwner::NetworkManager { connection, .. } => format!("NetworkManager: {}", connection),
+            InterfaceOwner::NspawnMachine { machine } => format!("nspawn容器: {}", machine),
             InterfaceOwner::Kernel { module } => format!("内核模块: {}", module),
             InterfaceOwner::Unknown => "未知".to_string(),
         }
@@ -167,14 +277,16 @@ impl InterfaceOwner {
 
     /// 获取创建者的图标
     #[allow(dead_code)]
-    pub fn icon(&self) -> &str {
+    pub fn icon(&self) -> &'static str {
         match self {
-            InterfaceOwner::SystemdService { .. } => "📦",
-            InterfaceOwner::DockerContainer { .. } => "🐳",
-            InterfaceOwner::Process { .. } => "⚙️",
-            InterfaceOwner::NetworkManager { .. } => "🔧",
-            InterfaceOwner::Kernel { .. } => "🐧",
-            InterfaceOwner::Unknown => "❓",
+            InterfaceOwner::SystemdService { .. } => pick("📦", "[SVC]"),
+            InterfaceOwner::DockerContainer { .. } => pick("🐳", "[DKR]"),
+            InterfaceOwner::DockerNetwork { .. } => pick("🐳", "[DNET]"),
+            InterfaceOwner::Process { .. } => pick("⚙️", "[PROC]"),
+            InterfaceOwner::NetworkManager { .. } => pick("🔧", "[NM]"),
+            InterfaceOwner::NspawnMachine { .. } => pick("📦", "[NSPAWN]"),
+            InterfaceOwner::Kernel { .. } => pick("🐧", "[KMOD]"),
+            InterfaceOwner::Unknown => pick("❓", "[?]"),
         }
     }
 }
@@ -200,24 +312,145 @@ pub struct Ipv4Config {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DnsConfig {
     pub nameservers: Vec<String>, // DNS服务器列表
+    #[serde(default)]
+    pub search: Vec<String>,      // DNS搜索域
+}
+
+/// IPv6地址详情
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ipv6AddressInfo {
+    pub address: String,  // 地址（含前缀长度）
+    pub prefix: String,   // 所属/64前缀，如 2001:db8::/64
+    pub scope: String,    // 作用域（global/link/host）
+    pub temporary: bool,  // 隐私扩展临时地址
+    pub deprecated: bool, // 已弃用（不再作为首选源地址）
+}
+
+/// IPv4地址详情（来自 ip -o addr）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Ipv4AddressInfo {
+    pub address: String,            // 地址（含前缀长度）
+    pub dynamic: bool,              // 动态获取（DHCP）
+    pub valid_lft: Option<u64>,     // 剩余有效时间（秒），None表示永久
+    pub preferred_lft: Option<u64>, // 剩余首选时间（秒），None表示永久
+}
+
+/// 网卡驱动信息（来自 ethtool -i 或 sysfs）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DriverInfo {
+    pub driver: String,                   // 驱动名称
+    pub version: Option<String>,          // 驱动版本
+    pub firmware_version: Option<String>, // 固件版本
+    pub bus_info: Option<String>,         // 总线地址（如PCI地址）
+}
+
+/// 双工模式
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Duplex {
+    Full,
+    Half,
+}
+
+/// 物理网卡的链路协商信息（来自 ethtool），无法读取的字段为None
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LinkInfo {
+    pub speed_mbps: Option<u32>,      // 协商速率（Mb/s）
+    pub duplex: Option<Duplex>,       // 双工模式
+    pub autoneg: Option<bool>,        // 自动协商
+    pub link_detected: Option<bool>,  // 检测到链路
+}
+
+/// 策略路由规则（来自 ip rule show）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PolicyRule {
+    pub priority: u32, // 优先级
+    pub from: String,  // 源地址或网段
+    pub table: String, // 查找的路由表
+}
+
+/// 网络唤醒设置（来自 ethtool 的 Supports Wake-on / Wake-on 行）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WolInfo {
+    pub supported: String, // 支持的唤醒方式，如 "pumbg"
+    pub current: String,   // 当前唤醒方式，"d" 表示关闭
+}
+
+impl WolInfo {
+    /// 是否支持魔术包唤醒
+    pub fn supports_magic_packet(&self) -> bool {
+        self.supported.contains('g')
+    }
+
+    /// 是否已开启魔术包唤醒
+    pub fn is_enabled(&self) -> bool {
+        self.current.contains('g')
+    }
+}
+
+/// 网桥设置（来自 /sys/class/net/<br>/bridge/）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct BridgeSettings {
+    pub stp: bool,            // 生成树协议
+    pub vlan_filtering: bool, // VLAN过滤
+}
+
+/// 聚合成员接口状态
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BondSlave {
+    pub name: String,  // 成员接口名称
+    pub mii_up: bool,  // MII链路状态是否为up
+}
+
+/// 链路聚合信息（来自 /sys/class/net/<bond>/bonding/）
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BondInfo {
+    pub mode: String,            // 聚合模式，如 802.3ad
+    pub slaves: Vec<BondSlave>,  // 成员接口
+}
+
+impl BondInfo {
+    /// 没有成员或有成员链路断开时视为降级
+    pub fn is_degraded(&self) -> bool {
+        self.slaves.is_empty() || self.slaves.iter().any(|slave| !slave.mii_up)
+    }
 }
 
 /// 网络接口完整信息
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct NetInterface {
     pub name: String,                    // 接口名称
+    pub ifindex: u32,                    // 内核接口索引
     pub kind: InterfaceKind,             // 接口类型
     pub state: InterfaceState,           // 接口状态
-    pub mac_address: Option<String>,     // MAC地址
+    pub carrier: Option<bool>,           // 载波状态（无法读取时为None）
+    pub mac_address: Option<String>,     // MAC地址（tun、gre等三层接口没有）
+    pub permanent_mac: Option<String>,   // 出厂MAC地址（仅物理网卡，ethtool -P）
+    pub point_to_point: bool,            // 点对点接口（POINTOPOINT标志）
     pub mtu: u32,                        // MTU
+    pub driver_info: Option<DriverInfo>, // 驱动信息（仅物理网卡）
+    pub wol: Option<WolInfo>,            // 网络唤醒设置（仅物理网卡）
+    pub link_info: Option<LinkInfo>,     // 速率/双工等链路信息（仅物理网卡）
+    pub bridge_settings: Option<BridgeSettings>, // 网桥设置（仅网桥）
+    pub bond_info: Option<BondInfo>,     // 链路聚合信息（仅bond）
+    pub vrf: Option<String>,             // 所属VRF设备
+    pub alias: Option<String>,           // 接口备注（内核ifalias，ip link set dev X alias ...）
+    pub altnames: Vec<String>,           // 备用名称（ip link 的 altname，如固件/驱动使用的名称）
+    pub docker_nat_egress: bool,         // 承载docker0容器出站流量（NAT出口）的接口
     pub ipv4_addresses: Vec<String>,     // IPv4地址列表
+    pub ipv4_details: Vec<Ipv4AddressInfo>, // IPv4地址详情（含租约时间）
     pub ipv6_addresses: Vec<String>,     // IPv6地址列表
+    pub ipv6_details: Vec<Ipv6AddressInfo>, // IPv6地址详情（含前缀和标志）
     pub traffic_stats: TrafficStats,     // 流量统计
     pub owner: Option<InterfaceOwner>,   // 创建者信息
     #[allow(dead_code)]
     pub config_mode: IpConfigMode,       // 配置模式
     #[allow(dead_code)]
     pub ipv4_config: Option<Ipv4Config>, // IPv4配置
+    pub ipv6_gateway: Option<String>,    // IPv6默认网关
+    pub policy_rules: Vec<PolicyRule>,   // 以接口地址为源地址的策略路由规则
+    pub netplan_file: Option<PathBuf>,   // 定义该接口的netplan文件
+    pub nicman_managed: bool,            // netplan条目由nicman创建/维护
+    pub config_drift: Vec<String>,       // 运行时地址/网关与netplan条目的差异
     #[allow(dead_code)]
     pub dns_config: Option<DnsConfig>,   // DNS配置
 }
@@ -227,20 +460,50 @@ impl NetInterface {
     pub fn new(name: String, kind: InterfaceKind) -> Self {
         Self {
             name,
+            ifindex: 0,
             kind,
             state: InterfaceState::Unknown,
+            carrier: None,
             mac_address: None,
+            permanent_mac: None,
+            point_to_point: false,
             mtu: 1500,
+            driver_info: None,
+            wol: None,
+            link_info: None,
+            bridge_settings: None,
+            bond_info: None,
+            vrf: None,
+            alias: None,
+            altnames: Vec::new(),
+            docker_nat_egress: false,
             ipv4_addresses: Vec::new(),
+            ipv4_details: Vec::new(),
             ipv6_addresses: Vec::new(),
+            ipv6_details: Vec::new(),
             traffic_stats: TrafficStats::default(),
             owner: None,
             config_mode: IpConfigMode::None,
             ipv4_config: None,
+            ipv6_gateway: None,
+            policy_rules: Vec::new(),
+            netplan_file: None,
+            nicman_managed: false,
+            config_drift: Vec::new(),
             dns_config: None,
         }
     }
 
+    /// 综合管理状态和载波判断链路健康状态
+    pub fn link_health(&self) -> LinkHealth {
+        match (&self.state, self.carrier) {
+            (InterfaceState::Up, Some(true)) => LinkHealth::Up,
+            (InterfaceState::Up, Some(false)) => LinkHealth::NoCarrier,
+            (InterfaceState::Down, _) => LinkHealth::Down,
+            _ => LinkHealth::Unknown,
+        }
+    }
+
     /// 获取第一个IPv4地址（用于列表显示）
     #[allow(dead_code)]
     pub fn primary_ipv4(&self) -> Option<&String> {
@@ -276,7 +539,6 @@ pub enum RemovalStrategy {
 }
 
 impl RemovalStrategy {
-    #[allow(dead_code)]
     pub fn display_name(&self) -> &str {
         match self {
             RemovalStrategy::InterfaceOnly => "仅删除接口（临时）",
@@ -287,7 +549,6 @@ impl RemovalStrategy {
         }
     }
 
-    #[allow(dead_code)]
     pub fn description(&self) -> &str {
         match self {
             RemovalStrategy::InterfaceOnly => "仅删除接口，服务仍在运行，接口可能立即重建",
@@ -299,3 +560,19 @@ impl RemovalStrategy {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_interface_kind() {
+        assert_eq!("physical".parse::<InterfaceKind>(), Ok(InterfaceKind::Physical));
+        assert_eq!("DOCKER".parse::<InterfaceKind>(), Ok(InterfaceKind::Docker));
+        assert_eq!("WireGuard".parse::<InterfaceKind>(), Ok(InterfaceKind::WireGuard));
+        assert_eq!("ifb".parse::<InterfaceKind>(), Ok(InterfaceKind::Ifb));
+
+        let err = "ethernet".parse::<InterfaceKind>().unwrap_err();
+        assert!(err.contains("ethernet"));
+        assert!(err.contains("Physical, Loopback"));
+    }
+}
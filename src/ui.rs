@@ -1,10 +1,20 @@
 // TUI界面模块 - 使用ratatui实现终端用户界面
-use crate::backend::{owner_detection, runtime, traffic};
-use crate::model::{InterfaceKind, InterfaceState, NetInterface};
-use crate::utils::format::{format_bytes, format_speed};
+use crate::backend::netplan::{self, ApplyAdvice, Ipv6Settings, NameserverConfig, NetplanManager, TrySession};
+use crate::backend::removal::RemovalManager;
+use crate::config::{Config, NetmaskFormat, StatsDisplay};
+use crate::backend::owner_inspect::{self, OwnerDetails};
+use crate::backend::apply::{self, StaticIpv4};
+use crate::backend::{diagnostics, dns, error_rate, flap, health, hotplug, neighbors, pmtu, policy, routes, runtime, tc, traffic};
+use crate::model::{DnsConfig, Duplex, InterfaceKind, InterfaceState, LinkHealth, LinkInfo, NetInterface, TrafficStats};
+use crate::utils::command;
+use crate::utils::format::{self as format, format_bytes, format_duration, format_speed, format_speed_bits, TrafficUnit};
+use crate::utils::icons::Symbol;
 use anyhow::Result;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers},
+    event::{
+        self, DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture, Event, KeyCode,
+        KeyModifiers,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -13,14 +23,86 @@ use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, BorderType, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
+    widgets::{Block, BorderType, Borders, Cell, Clear, List, ListItem, ListState, Paragraph, Row, Sparkline, Table, Wrap},
     Frame, Terminal,
 };
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::io;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
+/// 应用启动选项
+#[derive(Debug, Clone)]
+pub struct AppOptions {
+    pub owner_detection: bool,  // 是否检测接口创建者
+    pub netplan_file: Option<PathBuf>,  // 首选的netplan目标文件
+    pub list_width: u16,  // 接口列表宽度百分比
+    pub pinned: Vec<String>,  // 置顶的接口名称
+    pub rate_smoothing: f64,  // 速率平滑系数
+    pub kinds: Vec<InterfaceKind>,  // 只显示这些类型的接口（为空时显示全部）
+    pub stats_display: StatsDisplay,  // 流量统计面板的显示内容
+    pub netmask_format: NetmaskFormat,  // 编辑表单中子网掩码的输入格式
+    pub safe_mode: bool,  // 以安全模式启动
+    pub list_details: bool,  // 接口列表每项显示两行
+    pub error_rate_threshold: u64,  // 错误/丢包速率告警阈值（每秒）
+    pub error_spike_factor: f64,  // 错误/丢包速率突增倍数
+    pub owner_action_timeout: Duration,  // 创建者操作后等待接口消失的最长时间
+    pub kill_grace: Duration,  // 终止进程时等待SIGTERM生效的最长时间
+}
+
+/// 安全模式下尝试执行修改操作时的提示
+const SAFE_MODE_BLOCKED: &str = "安全模式：已阻止执行修改操作（按 ! 关闭安全模式）";
+
+/// 扩展统计（ip -s -j）的刷新间隔，比sysfs采样慢得多
+const EXTENDED_STATS_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 创建者操作后等待接口消失的默认最长时间
+pub const DEFAULT_OWNER_ACTION_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// 接口列表默认宽度百分比
+pub const DEFAULT_LIST_WIDTH: u16 = 40;
+/// 接口列表宽度的调整范围和步长
+const MIN_LIST_WIDTH: u16 = 20;
+const MAX_LIST_WIDTH: u16 = 80;
+const LIST_WIDTH_STEP: u16 = 5;
+
+/// netplan try 的自动回滚超时
+const NETPLAN_TRY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 能正常显示的最小终端尺寸
+const MIN_TERMINAL_WIDTH: u16 = 60;
+const MIN_TERMINAL_HEIGHT: u16 = 20;
+/// 宽度小于此值时列表和详情改为上下排列
+const SINGLE_COLUMN_WIDTH: u16 = 100;
+/// 弹窗的最小尺寸
+const MIN_POPUP_WIDTH: u16 = 56;
+const MIN_POPUP_HEIGHT: u16 = 16;
+
+impl Default for AppOptions {
+    fn default() -> Self {
+        Self {
+            owner_detection: true,
+            netplan_file: None,
+            list_width: DEFAULT_LIST_WIDTH,
+            pinned: Vec::new(),
+            rate_smoothing: traffic::DEFAULT_SMOOTHING_ALPHA,
+            kinds: Vec::new(),
+            stats_display: StatsDisplay::Both,
+            netmask_format: NetmaskFormat::Dotted,
+            safe_mode: false,
+            list_details: false,
+            error_rate_threshold: error_rate::DEFAULT_ERROR_RATE_THRESHOLD,
+            error_spike_factor: error_rate::DEFAULT_ERROR_SPIKE_FACTOR,
+            owner_action_timeout: DEFAULT_OWNER_ACTION_TIMEOUT,
+            kill_grace: crate::backend::removal::DEFAULT_KILL_GRACE,
+        }
+    }
+}
+
 /// 应用状态
 pub struct App {
+    options: AppOptions,
     interfaces: Vec<NetInterface>,
     list_state: ListState,
     traffic_monitor: traffic::TrafficMonitor,
@@ -28,7 +110,178 @@ pub struct App {
     screen: Screen,
     should_quit: bool,
     edit_form: Option<EditFormState>,  // 编辑表单状态
+    target_file: String,  // 当前表单保存时写入的netplan文件，打开表单时确定
     action_menu_state: usize,  // 操作菜单选中项
+    delete_strategy_index: usize,  // 删除确认中选中的删除策略
+    log_scroll: u16,  // 调试日志滚动位置
+    status_message: Option<String>,  // 状态栏消息
+    pmtu_progress: Option<Arc<Mutex<pmtu::PmtuProgress>>>,  // 路径MTU探测进度
+    health_report: Option<Arc<Mutex<health::HealthReport>>>,  // 连通性检查进度
+    rate_limit_form: Option<RateLimitFormState>,  // 限速表单状态
+    policy_form: Option<PolicyRouteFormState>,  // 策略路由表单状态
+    gateway_form: Option<GatewayFormState>,  // 修改网关表单状态
+    address_form: Option<AddressFormState>,  // 添加/删除IP地址表单状态
+    rename_form: Option<RenameFormState>,  // 重命名接口表单状态
+    mac_form: Option<MacFormState>,  // 修改MAC地址表单状态
+    bridge_form: Option<BridgeFormState>,  // 创建网桥表单状态
+    vlan_form: Option<VlanFormState>,  // 创建VLAN子接口表单状态
+    bond_form: Option<BondFormState>,  // 创建链路聚合表单状态
+    freeze_preview: Option<FrozenConfig>,  // 待写入netplan的运行时配置
+    flap_detector: flap::FlapDetector,  // 链路抖动检测
+    error_rate_monitor: error_rate::ErrorRateMonitor,  // 错误/丢包速率告警
+    runtime_only_changes: HashSet<String>,  // 有未持久化的临时运行时修改的接口
+    pending_editor: Option<PathBuf>,  // 等待在$EDITOR中打开的文件
+    overview_sort: OverviewSort,  // 流量总览的排序方式
+    routes: Vec<routes::Route>,  // 路由表（打开路由表界面时读取）
+    routes_error: Option<String>,  // 读取路由表失败的原因
+    routes_scroll: usize,  // 路由表滚动位置
+    apply_result: Option<Result<String, String>>,  // netplan apply的输出，None表示等待确认
+    try_session: Option<TrySession>,  // 进行中的netplan try
+    try_result: Option<Result<String, String>>,  // netplan try结束后的输出
+    dhcp_keep_dns: bool,  // 切换到DHCP时保留静态DNS
+    clear_link_down: bool,  // 清除配置后同时禁用接口
+    edit_history: EditHistory,  // 编辑表单各字段的历史输入
+    list_details: bool,  // 列表每项第二行显示主IP和创建者
+    safe_mode: bool,  // 安全模式：可以查看操作对话框，但阻止执行任何修改
+    diag_redact: bool,  // 诊断信息中隐藏IP/MAC地址
+    diag_result: Option<Result<PathBuf, String>>,  // 诊断文件的写入结果
+    owner_details: Option<Result<OwnerDetails, String>>,  // 创建者进程/容器的详情
+    neighbors: Option<Result<Vec<neighbors::Neighbor>, String>>,  // 选中接口的邻居表
+    hotplug: hotplug::HotplugWatcher,  // 接口增删检测
+    traffic_baselines: HashMap<String, TrafficStats>,  // 按接口记录的会话流量基线
+    unmanaged_gate: UnmanagedGate,  // 写入不由nicman管理的Netplan条目前的确认
+    compare_pair: Vec<String>,  // 标记用于对比的接口（最多两个）
+    show_absolute_traffic: bool,  // 忽略基线显示内核累计值
+    stats_display: StatsDisplay,  // 流量统计面板显示速率/累计值/两者
+    speed_in_bits: bool,  // 速率以比特/秒显示（否则为字节/秒）
+    netmask_format: NetmaskFormat,  // 编辑表单中子网掩码的输入格式
+    expanded_stats: bool,  // 显示错误/丢包/组播/冲突等扩展统计
+    last_extended_update: Option<Instant>,  // 上次读取扩展统计的时间
+}
+
+/// 流量总览的排序方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OverviewSort {
+    TotalRate,  // 收发速率之和
+    RxRate,     // 接收速率
+    TxRate,     // 发送速率
+    Name,       // 接口名称
+    Ifindex,    // 内核接口索引（与 ip link 输出顺序一致）
+}
+
+impl OverviewSort {
+    fn next(self) -> Self {
+        match self {
+            OverviewSort::TotalRate => OverviewSort::RxRate,
+            OverviewSort::RxRate => OverviewSort::TxRate,
+            OverviewSort::TxRate => OverviewSort::Name,
+            OverviewSort::Name => OverviewSort::Ifindex,
+            OverviewSort::Ifindex => OverviewSort::TotalRate,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            OverviewSort::TotalRate => "总速率",
+            OverviewSort::RxRate => "接收速率",
+            OverviewSort::TxRate => "发送速率",
+            OverviewSort::Name => "名称",
+            OverviewSort::Ifindex => "接口索引",
+        }
+    }
+}
+
+/// 详情面板中物理网卡的速率、双工、自动协商和链路检测行，无法读取的显示"未知"
+fn link_info_lines(link: &LinkInfo) -> Vec<Line<'static>> {
+    let unknown = || "未知".to_string();
+    let speed = link.speed_mbps.map(|mbps| match mbps {
+        mbps if mbps >= 1000 && mbps % 1000 == 0 => format!("{} Gb/s", mbps / 1000),
+        mbps => format!("{} Mb/s", mbps),
+    });
+    let duplex = link.duplex.map(|duplex| match duplex {
+        Duplex::Full => "全双工".to_string(),
+        Duplex::Half => "半双工".to_string(),
+    });
+    let on_off = |value: Option<bool>, on: &str, off: &str| value.map(|v| if v { on } else { off }.to_string());
+
+    vec![
+        Line::from(vec![
+            Span::styled("链路速率: ", Style::default().fg(Color::Cyan)),
+            Span::raw(speed.unwrap_or_else(unknown)),
+            Span::styled("  双工: ", Style::default().fg(Color::Cyan)),
+            Span::styled(
+                duplex.unwrap_or_else(unknown),
+                // 半双工通常是协商异常
+                Style::default().fg(if link.duplex == Some(Duplex::Half) { Color::Yellow } else { Color::White }),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("自动协商: ", Style::default().fg(Color::Cyan)),
+            Span::raw(on_off(link.autoneg, "开启", "关闭").unwrap_or_else(unknown)),
+            Span::styled("  检测到链路: ", Style::default().fg(Color::Cyan)),
+            Span::raw(on_off(link.link_detected, "是", "否").unwrap_or_else(unknown)),
+        ]),
+    ]
+}
+
+/// 取速率历史中最近 `width` 个接收或发送采样，转换为Sparkline使用的整数（自动缩放到最大值）
+fn rate_history_data(history: Option<&VecDeque<(f64, f64)>>, rx: bool, width: usize) -> Vec<u64> {
+    let Some(history) = history else {
+        return Vec::new();
+    };
+    history
+        .iter()
+        .skip(history.len().saturating_sub(width))
+        .map(|&(rx_speed, tx_speed)| (if rx { rx_speed } else { tx_speed }) as u64)
+        .collect()
+}
+
+/// 成员选择列表，`cursor` 为None时列表没有焦点
+fn member_list_lines(candidates: &[MemberCandidate], cursor: Option<usize>) -> Vec<Line<'_>> {
+    candidates
+        .iter()
+        .enumerate()
+        .map(|(i, candidate)| {
+            let is_current = cursor == Some(i);
+            let style = if is_current {
+                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD).bg(Color::DarkGray)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            let mut spans = vec![
+                Span::raw(if is_current { format!("{} ", Symbol::Selected) } else { "  ".to_string() }),
+                Span::styled(format!("[{}] {}", if candidate.selected { "x" } else { " " }, candidate.name), style),
+            ];
+            if !candidate.addresses.is_empty() {
+                spans.push(Span::styled(
+                    format!("  {} 有地址 {}", Symbol::Warning, candidate.addresses.join(", ")),
+                    Style::default().fg(Color::Yellow),
+                ));
+            }
+            Line::from(spans)
+        })
+        .collect()
+}
+
+/// 按排序方式排列接口，速率从高到低
+fn sort_overview(interfaces: &[NetInterface], sort: OverviewSort) -> Vec<&NetInterface> {
+    let mut sorted: Vec<&NetInterface> = interfaces.iter().collect();
+    let rate = |iface: &NetInterface| {
+        let stats = &iface.traffic_stats;
+        match sort {
+            OverviewSort::TotalRate => stats.rx_speed + stats.tx_speed,
+            OverviewSort::RxRate => stats.rx_speed,
+            OverviewSort::TxRate => stats.tx_speed,
+            OverviewSort::Name | OverviewSort::Ifindex => 0.0,
+        }
+    };
+
+    match sort {
+        OverviewSort::Name => sorted.sort_by(|a, b| a.name.cmp(&b.name)),
+        OverviewSort::Ifindex => sorted.sort_by_key(|iface| iface.ifindex),
+        _ => sorted.sort_by(|a, b| rate(b).total_cmp(&rate(a)).then_with(|| a.name.cmp(&b.name))),
+    }
+    sorted
 }
 
 /// 屏幕类型
@@ -41,6 +294,58 @@ enum Screen {
     ToggleDhcp,     // 切换DHCP/静态确认
     OwnerActions,   // 创建者操作对话框
     InterfaceActions, // 接口操作菜单
+    CommandLog,     // 调试/命令日志
+    Overview,       // 所有接口流量总览
+    Routes,         // 路由表
+    PathMtu,        // 路径MTU探测
+    HealthCheck,    // 连通性检查
+    RateLimit,      // 限速设置
+    PolicyRoute,    // 策略路由设置
+    EditGateway,    // 修改网关
+    ManageAddresses, // 添加/删除单个IPv4地址
+    RenameIface,    // 重命名接口
+    EditMac,        // 修改MAC地址
+    CreateBridge,   // 创建网桥
+    CreateVlan,     // 创建VLAN子接口
+    CreateBond,     // 创建链路聚合
+    ConfirmCreateNetplan, // 确认创建netplan文件
+    ApplyNetplan,   // 应用netplan配置
+    TryNetplan,     // netplan try 倒计时确认
+    Diagnostics,    // 生成诊断信息
+    Compare,        // 两个接口的流量对比
+    FreezeConfig,   // 把运行时配置写入netplan的预览
+    ClearConfig,    // 清除接口配置确认
+    ConfirmSafeModeOff, // 关闭安全模式确认
+    OwnerDetails,   // 创建者进程/容器详情
+    Neighbors,      // 邻居表（ARP/NDP缓存）
+}
+
+/// 编辑表单每个字段保留的历史值数量
+const EDIT_HISTORY_LEN: usize = 10;
+
+/// 编辑表单各字段最近保存过的值（仅本次会话，最新的在前）
+#[derive(Debug, Default)]
+struct EditHistory {
+    fields: [Vec<String>; 8],
+}
+
+impl EditHistory {
+    /// 记录表单中各字段的非空值，重复的值移到最前
+    fn record(&mut self, form: &EditFormState) {
+        for (values, value) in self.fields.iter_mut().zip(form.field_values()) {
+            let value = value.trim();
+            if value.is_empty() {
+                continue;
+            }
+            values.retain(|v| v != value);
+            values.insert(0, value.to_string());
+            values.truncate(EDIT_HISTORY_LEN);
+        }
+    }
+
+    fn values(&self, field: usize) -> &[String] {
+        self.fields.get(field).map(Vec::as_slice).unwrap_or(&[])
+    }
 }
 
 /// 编辑表单状态
@@ -53,10 +358,35 @@ struct EditFormState {
     netmask: String,
     gateway: String,
     dns: String,
+    search_domains: String, // DNS搜索域，逗号分隔
+    ipv6_addresses: String, // 静态IPv6地址，逗号分隔（不含链路本地地址）
+    gateway6: String,      // IPv6默认网关
+    mtu: String,           // MTU，默认为接口当前值
+    netmask_format: NetmaskFormat, // 子网掩码字段的显示/输入格式
+    ipv6_only: bool,       // 纯IPv6模式：隐藏IPv4字段
+    history_pos: Option<usize>, // 正在翻阅的历史值下标（0为最近一次）
+    history_draft: String, // 开始翻阅历史前字段中的内容
+    refresh_dns: bool,     // 保存后刷新DNS解析器
+    runtime_only: bool,    // 临时修改：只执行运行时命令，不写入Netplan
+    gateway_confirmed: bool, // 已确认保存不在子网内的网关
+    address_confirmed: bool, // 已确认保存与其他接口重复的地址
+    original: [String; 8], // 打开表单时的字段值，用于保存前对比
+    confirming_save: bool, // 正在显示保存确认
     error_message: Option<String>,
 }
 
 impl EditFormState {
+    const FIELD_NAMES: [&'static str; 8] =
+        ["IP地址", "子网掩码", "网关(可选)", "DNS", "搜索域(可选)", "IPv6(可选)", "IPv6网关(可选)", "MTU"];
+    const NETMASK_FIELD: usize = 1;
+    const DNS_FIELD: usize = 3;
+    const IPV6_FIELD: usize = 5;
+    const GATEWAY6_FIELD: usize = 6;
+    const MTU_FIELD: usize = 7;
+    const DEFAULT_DNS: &'static str = "223.5.5.5,114.114.114.114";
+    const ALL_FIELDS: [usize; 8] = [0, 1, 2, 3, 4, 5, 6, Self::MTU_FIELD];
+    const IPV6_ONLY_FIELDS: [usize; 5] = [Self::IPV6_FIELD, Self::GATEWAY6_FIELD, Self::DNS_FIELD, 4, Self::MTU_FIELD];
+
     fn new(iface: &NetInterface) -> Self {
         // 从当前接口获取默认值
         let ip_address = iface.ipv4_addresses.first()
@@ -78,167 +408,1111 @@ impl EditFormState {
         // 从dns_config读取DNS服务器
         let dns = iface.dns_config.as_ref()
             .map(|cfg| cfg.nameservers.join(","))
-            .unwrap_or_else(|| String::from("223.5.5.5,114.114.114.114"));
+            .unwrap_or_else(|| Self::DEFAULT_DNS.to_string());
+        let search_domains = iface.dns_config.as_ref()
+            .map(|cfg| cfg.search.join(","))
+            .unwrap_or_default();
+
+        // 全局IPv6地址（隐私扩展临时地址由内核生成，不在表单中编辑）
+        let ipv6_addresses = iface.ipv6_details.iter()
+            .filter(|info| info.scope == "global" && !info.temporary)
+            .map(|info| info.address.clone())
+            .collect::<Vec<_>>()
+            .join(",");
+
+        let gateway6 = iface.ipv6_gateway.clone().unwrap_or_default();
+        let mtu = iface.mtu.to_string();
+
+        // 没有IPv4地址、只有全局IPv6地址的接口默认进入纯IPv6模式
+        let ipv6_only = iface.ipv4_addresses.is_empty() && !ipv6_addresses.is_empty();
 
         Self {
             interface_name: iface.name.clone(),
-            current_field: 0,
+            current_field: if ipv6_only { Self::IPV6_FIELD } else { 0 },
             is_editing: false,
+            original: [
+                ip_address.clone(),
+                netmask.clone(),
+                gateway.clone(),
+                dns.clone(),
+                search_domains.clone(),
+                ipv6_addresses.clone(),
+                gateway6.clone(),
+                mtu.clone(),
+            ],
             ip_address,
             netmask,
             gateway,
             dns,
+            search_domains,
+            ipv6_addresses,
+            gateway6,
+            mtu,
+            netmask_format: NetmaskFormat::Dotted,
+            ipv6_only,
+            history_pos: None,
+            history_draft: String::new(),
+            refresh_dns: true,
+            runtime_only: false,
+            gateway_confirmed: false,
+            address_confirmed: false,
+            confirming_save: false,
             error_message: None,
         }
     }
 
-    fn field_count() -> usize {
-        4  // IP、掩码、网关、DNS
+    /// 按指定格式显示子网掩码字段（打开表单时的原值一并转换）
+    fn with_netmask_format(mut self, format: NetmaskFormat) -> Self {
+        if format != self.netmask_format {
+            self.toggle_netmask_format();
+        }
+        self
+    }
+
+    /// 接口没有DNS配置时，用最近一次保存的DNS代替内置默认值
+    fn with_history(mut self, history: &EditHistory) -> Self {
+        if self.dns == Self::DEFAULT_DNS
+            && let Some(dns) = history.values(Self::DNS_FIELD).first() {
+            self.dns = dns.clone();
+        }
+        self
+    }
+
+    /// 在正在编辑的字段上翻阅历史值，older为true时取更早的值，
+    /// 翻回最新值之后再往前则恢复翻阅前输入的内容
+    fn recall_history(&mut self, history: &[String], older: bool) {
+        let pos = match (self.history_pos, older) {
+            (None, true) if !history.is_empty() => {
+                self.history_draft = self.current_field_value().to_string();
+                Some(0)
+            }
+            (Some(pos), true) => Some((pos + 1).min(history.len().saturating_sub(1))),
+            (Some(0), false) => None,
+            (Some(pos), false) => Some(pos - 1),
+            (None, _) => return,
+        };
+
+        let value = match pos {
+            Some(pos) => history[pos].clone(),
+            None => std::mem::take(&mut self.history_draft),
+        };
+        self.history_pos = pos;
+        *self.current_field_value_mut() = value;
+        self.sync_cidr();
+    }
+
+    /// 在点分十进制和前缀长度之间切换，无法解析的值保持不变
+    fn toggle_netmask_format(&mut self) {
+        self.netmask_format = self.netmask_format.toggle();
+        let format = self.netmask_format;
+        for value in [&mut self.netmask, &mut self.original[Self::NETMASK_FIELD]] {
+            if let Ok(prefix) = App::netmask_to_prefix(value) {
+                *value = format_netmask(prefix, format);
+            }
+        }
+    }
+
+    /// 切换纯IPv6模式
+    fn toggle_ipv6_only(&mut self) {
+        self.ipv6_only = !self.ipv6_only;
+        if !self.visible_fields().contains(&self.current_field) {
+            self.current_field = self.visible_fields()[0];
+        }
+        self.gateway_confirmed = false;
+        self.address_confirmed = false;
+        self.error_message = None;
+    }
+
+    /// 当前模式下显示的字段，按显示顺序排列
+    fn visible_fields(&self) -> &'static [usize] {
+        if self.ipv6_only { &Self::IPV6_ONLY_FIELDS } else { &Self::ALL_FIELDS }
+    }
+
+    fn field_names(&self) -> [&'static str; 8] {
+        let mut names = Self::FIELD_NAMES;
+        if self.netmask_format == NetmaskFormat::Prefix {
+            names[Self::NETMASK_FIELD] = "前缀长度";
+        }
+        if self.ipv6_only {
+            names[Self::IPV6_FIELD] = "IPv6地址";
+        }
+        names
+    }
+
+    fn field_values(&self) -> [&str; 8] {
+        [
+            &self.ip_address,
+            &self.netmask,
+            &self.gateway,
+            &self.dns,
+            &self.search_domains,
+            &self.ipv6_addresses,
+            &self.gateway6,
+            &self.mtu,
+        ]
+    }
+
+    /// IPv6字段中的地址列表
+    fn ipv6_list(&self) -> Vec<String> {
+        self.ipv6_addresses
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect()
+    }
+
+    /// 验证IPv6地址和IPv6网关字段，两者都为空时表示只配置IPv4
+    fn ipv6_settings(&self) -> Result<Ipv6Settings> {
+        apply::parse_ipv6_settings(&self.ipv6_list(), Some(&self.gateway6))
+    }
+
+    /// 验证MTU字段，未修改时返回None（不重新设置MTU）
+    fn mtu_change(&self) -> Result<Option<u32>> {
+        if self.mtu.trim() == self.original[Self::MTU_FIELD].trim() {
+            return Ok(None);
+        }
+        apply::parse_mtu(&self.mtu).map(Some)
+    }
+
+    /// 验证DNS和搜索域字段，两者都为空时返回None
+    fn nameserver_config(&self) -> Result<Option<NameserverConfig>> {
+        let nameservers = NameserverConfig {
+            addresses: dns::parse_nameservers(&self.dns)?,
+            search: dns::parse_search_domains(&self.search_domains)?,
+        };
+        Ok(Some(nameservers).filter(|ns| !ns.addresses.is_empty() || !ns.search.is_empty()))
+    }
+
+    /// 与打开表单时相比发生变化的字段（只比较当前模式下显示的字段）
+    fn changed_fields(&self) -> Vec<usize> {
+        let values = self.field_values();
+        self.visible_fields()
+            .iter()
+            .copied()
+            .filter(|&i| values[i].trim() != self.original[i].trim())
+            .collect()
     }
 
     fn next_field(&mut self) {
-        self.current_field = (self.current_field + 1) % Self::field_count();
+        let fields = self.visible_fields();
+        let pos = fields.iter().position(|&i| i == self.current_field).unwrap_or(0);
+        self.current_field = fields[(pos + 1) % fields.len()];
     }
 
     fn prev_field(&mut self) {
-        if self.current_field == 0 {
-            self.current_field = Self::field_count() - 1;
-        } else {
-            self.current_field -= 1;
-        }
+        let fields = self.visible_fields();
+        let pos = fields.iter().position(|&i| i == self.current_field).unwrap_or(0);
+        self.current_field = fields[(pos + fields.len() - 1) % fields.len()];
     }
 
-    #[allow(dead_code)]
     fn current_field_value(&self) -> &str {
         match self.current_field {
             0 => &self.ip_address,
             1 => &self.netmask,
             2 => &self.gateway,
             3 => &self.dns,
+            4 => &self.search_domains,
+            5 => &self.ipv6_addresses,
+            6 => &self.gateway6,
+            7 => &self.mtu,
             _ => "",
         }
     }
 
+    /// IP字段中的地址部分（IP字段可以直接输入CIDR）
+    fn ip_only(&self) -> &str {
+        self.ip_address.split('/').next().unwrap_or("").trim()
+    }
+
+    /// IP字段为CIDR格式时，保持前缀长度与子网掩码一致
+    fn sync_cidr(&mut self) {
+        let Some((ip, prefix)) = self.ip_address.split_once('/') else {
+            return;
+        };
+
+        match self.current_field {
+            // 编辑IP字段：根据前缀长度填写子网掩码
+            0 => {
+                if let Ok(prefix) = prefix.trim().parse::<u8>()
+                    && prefix <= 32 {
+                    self.netmask = format_netmask(prefix, self.netmask_format);
+                }
+            }
+            // 编辑子网掩码：同步更新IP字段中的前缀长度
+            1 => {
+                if let Ok(prefix) = App::netmask_to_prefix(&self.netmask) {
+                    self.ip_address = format!("{}/{}", ip, prefix);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// 网关不在地址所属子网内时返回警告信息
+    fn gateway_subnet_warning(&self) -> Option<String> {
+        let gateway = self.gateway.trim();
+        if gateway.is_empty() || self.ipv6_only {
+            return None;
+        }
+
+        let prefix = App::netmask_to_prefix(&self.netmask).ok()?;
+        let address = format!("{}/{}", self.ip_only(), prefix);
+        if runtime::gateway_in_subnets(gateway, std::slice::from_ref(&address)) {
+            return None;
+        }
+
+        let network = runtime::ipv4_network(&address).unwrap_or(address);
+        Some(format!("网关 {} 不在子网 {} 内，路由将无法工作。再次按 s 仍然保存", gateway, network))
+    }
+
+    /// 地址已配置在其他本地接口上时返回警告信息（只检查本机，不探测网络）
+    fn duplicate_address_warning(&self, interfaces: &[NetInterface]) -> Option<String> {
+        let ip = self.ip_only();
+        if ip.is_empty() || self.ipv6_only {
+            return None;
+        }
+
+        let owner = interfaces
+            .iter()
+            .filter(|iface| iface.name != self.interface_name)
+            .find(|iface| iface.ipv4_addresses.iter().any(|addr| addr.split('/').next() == Some(ip)))?;
+        Some(format!("地址 {} 已配置在接口 {} 上，重复地址会导致路由异常。再次按 s 仍然保存", ip, owner.name))
+    }
+
     fn current_field_value_mut(&mut self) -> &mut String {
+        // 字段被修改后需要重新确认网关和重复地址
+        self.gateway_confirmed = false;
+        self.address_confirmed = false;
         match self.current_field {
             0 => &mut self.ip_address,
             1 => &mut self.netmask,
             2 => &mut self.gateway,
             3 => &mut self.dns,
+            4 => &mut self.search_domains,
+            5 => &mut self.ipv6_addresses,
+            6 => &mut self.gateway6,
+            7 => &mut self.mtu,
             _ => &mut self.ip_address,
         }
     }
 }
 
-impl App {
-    pub fn new() -> Result<Self> {
-        let interfaces = runtime::list_interfaces()?;
-        let mut list_state = ListState::default();
-        if !interfaces.is_empty() {
-            list_state.select(Some(0));
+/// 修改网关表单状态
+#[derive(Debug, Clone)]
+struct GatewayFormState {
+    interface_name: String,
+    addresses: Vec<String>,  // 接口当前的IPv4地址（用于校验网关）
+    gateway: String,
+    error_message: Option<String>,
+}
+
+/// 重命名接口表单状态
+#[derive(Debug, Clone)]
+struct RenameFormState {
+    interface_name: String,
+    mac_address: Option<String>,  // 用于持久化时的 match 条件
+    name: String,
+    persist: bool,                // 同时写入Netplan的 match/set-name
+    error_message: Option<String>,
+}
+
+impl RenameFormState {
+    fn new(iface: &NetInterface) -> Self {
+        Self {
+            interface_name: iface.name.clone(),
+            mac_address: iface.mac_address.clone(),
+            name: String::new(),
+            // 没有MAC地址时无法写入 match 条件
+            persist: iface.mac_address.is_some(),
+            error_message: None,
         }
+    }
+}
 
-        Ok(Self {
-            interfaces,
-            list_state,
-            traffic_monitor: traffic::TrafficMonitor::new(),
-            last_update: Instant::now(),
-            screen: Screen::Main,
-            should_quit: false,
-            edit_form: None,
-            action_menu_state: 0,
-        })
+/// 修改MAC地址表单状态
+#[derive(Debug, Clone)]
+struct MacFormState {
+    interface_name: String,
+    current_mac: Option<String>,
+    permanent_mac: Option<String>,  // 出厂MAC地址
+    mac: String,
+    persist: bool,                  // 接口已在Netplan中定义，修改同时写入
+    error_message: Option<String>,
+}
+
+impl MacFormState {
+    fn new(iface: &NetInterface) -> Self {
+        Self {
+            interface_name: iface.name.clone(),
+            current_mac: iface.mac_address.clone(),
+            permanent_mac: iface.permanent_mac.clone(),
+            mac: String::new(),
+            persist: iface.netplan_file.is_some(),
+            error_message: None,
+        }
     }
+}
 
-    pub fn run(&mut self) -> Result<()> {
-        enable_raw_mode()?;
-        let mut stdout = io::stdout();
-        execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-        let backend = CrosstermBackend::new(stdout);
-        let mut terminal = Terminal::new(backend)?;
+/// 添加/删除IPv4地址表单状态（只增删单个地址，不清除其他地址）
+#[derive(Debug, Clone)]
+struct AddressFormState {
+    interface_name: String,
+    addresses: Vec<String>,  // 接口当前的IPv4地址
+    selected: usize,         // 选中的地址
+    input: String,           // 要添加的地址（CIDR）
+    adding: bool,            // 正在输入新地址
+    persist: bool,           // 接口已在Netplan中定义，修改同时写入
+    confirm_delete: bool,    // 已按过一次d，再按一次才删除
+    error_message: Option<String>,
+}
 
-        let tick_rate = Duration::from_millis(250);
-        let mut last_tick = Instant::now();
+impl AddressFormState {
+    fn new(iface: &NetInterface, adding: bool) -> Self {
+        Self {
+            interface_name: iface.name.clone(),
+            addresses: iface.ipv4_addresses.clone(),
+            selected: 0,
+            input: String::new(),
+            adding,
+            persist: iface.netplan_file.is_some(),
+            confirm_delete: false,
+            error_message: None,
+        }
+    }
+}
 
-        loop {
-            terminal.draw(|f| self.ui(f))?;
+/// 创建网桥表单状态
+#[derive(Debug, Clone)]
+struct BridgeFormState {
+    name: String,
+    candidates: Vec<MemberCandidate>, // 可以加入网桥的接口
+    cursor: usize,                    // 成员列表中的光标
+    editing_name: bool,               // 焦点在名称字段（否则在成员列表）
+    address_confirmed: bool,          // 已确认加入有地址的接口
+    error_message: Option<String>,
+}
 
-            let timeout = tick_rate
-                .checked_sub(last_tick.elapsed())
-                .unwrap_or_else(|| Duration::from_secs(0));
+/// 可加入网桥或链路聚合的接口
+#[derive(Debug, Clone)]
+struct MemberCandidate {
+    name: String,
+    addresses: Vec<String>,  // 接口上的IPv4地址和全局IPv6地址，加入后不再可用
+    selected: bool,
+}
 
-            if crossterm::event::poll(timeout)? {
-                if let Event::Key(key) = event::read()? {
-                    self.handle_key(key.code, key.modifiers)?;
-                }
-            }
+impl MemberCandidate {
+    fn from_interface(iface: &NetInterface) -> Self {
+        Self {
+            name: iface.name.clone(),
+            addresses: iface
+                .ipv4_addresses
+                .iter()
+                .cloned()
+                .chain(iface.ipv6_details.iter().filter(|info| info.scope == "global").map(|info| info.address.clone()))
+                .collect(),
+            selected: false,
+        }
+    }
+}
 
-            if last_tick.elapsed() >= tick_rate {
-                self.on_tick()?;
-                last_tick = Instant::now();
-            }
+/// 选中的成员
+fn selected_members(candidates: &[MemberCandidate]) -> Vec<&MemberCandidate> {
+    candidates.iter().filter(|candidate| candidate.selected).collect()
+}
 
-            if self.should_quit {
-                break;
+/// 选中的成员有地址时返回警告信息，`master` 为"网桥"或"链路聚合"
+fn member_address_warning(candidates: &[MemberCandidate], master: &str) -> Option<String> {
+    let members: Vec<String> = selected_members(candidates)
+        .into_iter()
+        .filter(|member| !member.addresses.is_empty())
+        .map(|member| format!("{} ({})", member.name, member.addresses.join(", ")))
+        .collect();
+    if members.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "{} 上的地址在加入{}后将无法使用（需要把地址移到{}上）。再次按 Enter 仍然创建",
+        members.join("、"), master, master
+    ))
+}
+
+impl BridgeFormState {
+    fn new(interfaces: &[NetInterface]) -> Self {
+        let candidates = interfaces
+            .iter()
+            .filter(|iface| {
+                !matches!(iface.kind, InterfaceKind::Loopback | InterfaceKind::Bridge | InterfaceKind::Docker)
+            })
+            .map(MemberCandidate::from_interface)
+            .collect();
+
+        Self {
+            name: String::new(),
+            candidates,
+            cursor: 0,
+            editing_name: true,
+            address_confirmed: false,
+            error_message: None,
+        }
+    }
+}
+
+/// 创建链路聚合表单状态
+#[derive(Debug, Clone)]
+struct BondFormState {
+    name: String,
+    mode: usize,                      // runtime::BOND_MODES 中的下标
+    candidates: Vec<MemberCandidate>, // 可以加入链路聚合的物理网卡
+    cursor: usize,                    // 成员列表中的光标
+    current_field: usize,             // 焦点：0 名称、1 模式、2 成员列表
+    address_confirmed: bool,          // 已确认加入有地址的接口
+    error_message: Option<String>,
+}
+
+impl BondFormState {
+    const NAME_FIELD: usize = 0;
+    const MODE_FIELD: usize = 1;
+    const MEMBERS_FIELD: usize = 2;
+
+    fn new(interfaces: &[NetInterface]) -> Self {
+        let candidates = interfaces
+            .iter()
+            .filter(|iface| iface.kind == InterfaceKind::Physical)
+            .map(MemberCandidate::from_interface)
+            .collect();
+
+        Self {
+            name: String::new(),
+            // 默认主备模式，不需要交换机配合
+            mode: runtime::BOND_MODES.iter().position(|mode| *mode == "active-backup").unwrap_or(0),
+            candidates,
+            cursor: 0,
+            current_field: Self::NAME_FIELD,
+            address_confirmed: false,
+            error_message: None,
+        }
+    }
+
+    fn mode_name(&self) -> &'static str {
+        runtime::BOND_MODES[self.mode]
+    }
+
+    fn cycle_mode(&mut self, forward: bool) {
+        let count = runtime::BOND_MODES.len();
+        self.mode = if forward { (self.mode + 1) % count } else { (self.mode + count - 1) % count };
+    }
+}
+
+/// 创建VLAN子接口表单状态
+#[derive(Debug, Clone)]
+struct VlanFormState {
+    parents: Vec<String>,  // 可作为父接口的物理网卡
+    parent: usize,         // 选中的父接口
+    vid: String,           // VLAN ID（只接受数字）
+    edit_after: bool,      // 创建后打开编辑表单设置IP
+    error_message: Option<String>,
+}
+
+impl VlanFormState {
+    /// 父接口从物理网卡中选择，默认选中 `preferred`
+    fn new(interfaces: &[NetInterface], preferred: Option<&str>) -> Self {
+        let parents: Vec<String> = interfaces
+            .iter()
+            .filter(|iface| iface.kind == InterfaceKind::Physical)
+            .map(|iface| iface.name.clone())
+            .collect();
+        let parent = preferred
+            .and_then(|name| parents.iter().position(|parent| parent == name))
+            .unwrap_or(0);
+
+        Self {
+            parents,
+            parent,
+            vid: String::new(),
+            edit_after: true,
+            error_message: None,
+        }
+    }
+
+    /// 验证父接口和VLAN ID，返回 (父接口, VLAN ID)
+    fn validate(&self, interfaces: &[NetInterface]) -> Result<(String, u16)> {
+        let vid = runtime::parse_vlan_id(&self.vid)?;
+        let parent = self.parents.get(self.parent).ok_or_else(|| anyhow::anyhow!("没有可用的父接口"))?;
+        match interfaces.iter().find(|iface| &iface.name == parent) {
+            None => anyhow::bail!("父接口 {} 不存在", parent),
+            Some(iface) if iface.state != InterfaceState::Up => {
+                anyhow::bail!("父接口 {} 未启用，请先启用 (u)", parent)
             }
+            Some(_) => {}
         }
+        runtime::validate_interface_name(&runtime::vlan_interface_name(parent, vid), interfaces)?;
+        Ok((parent.clone(), vid))
+    }
+}
 
-        disable_raw_mode()?;
-        execute!(
-            terminal.backend_mut(),
-            LeaveAlternateScreen,
-            DisableMouseCapture
-        )?;
-        terminal.show_cursor()?;
+/// 把接口当前的运行时配置固化为静态netplan配置时写入的内容
+#[derive(Debug, Clone, PartialEq)]
+struct FrozenConfig {
+    interface_name: String,
+    address: String,           // 写入的地址（CIDR）
+    gateway: Option<String>,
+    nameservers: Vec<String>,
+    search: Vec<String>,
+    skipped: Vec<String>,      // 静态配置只写入一个IPv4地址，其余地址不会保存
+}
 
-        Ok(())
+impl FrozenConfig {
+    /// 从接口的运行时状态和该链路的DNS配置生成，没有IPv4地址时返回None
+    fn from_interface(iface: &NetInterface, dns: DnsConfig) -> Option<Self> {
+        let (address, skipped) = iface.ipv4_addresses.split_first()?;
+        Some(Self {
+            interface_name: iface.name.clone(),
+            address: address.clone(),
+            gateway: iface.ipv4_config.as_ref().and_then(|cfg| cfg.gateway.clone()),
+            nameservers: dns.nameservers,
+            search: dns.search,
+            skipped: skipped.to_vec(),
+        })
     }
+}
 
-    fn handle_key(&mut self, key: KeyCode, _modifiers: KeyModifiers) -> Result<()> {
-        match self.screen {
-            Screen::Main => {
-                match key {
-                    KeyCode::Char('q') => self.should_quit = true,
-                    KeyCode::Char('?') => self.screen = Screen::Help,
-                    KeyCode::Char('r') => self.refresh()?,
-                    KeyCode::Up | KeyCode::Char('k') => self.previous(),
-                    KeyCode::Down | KeyCode::Char('j') => self.next(),
-                    KeyCode::Enter => {
-                        // 回车键：打开接口操作菜单
-                        if self.list_state.selected().is_some() {
-                            self.action_menu_state = 0;
-                            self.screen = Screen::InterfaceActions;
+/// 限速表单状态
+#[derive(Debug, Clone)]
+struct RateLimitFormState {
+    interface_name: String,
+    current_field: usize,
+    is_editing: bool,
+    rate: String,
+    burst: String,
+    latency: String,
+    current_qdisc: String,  // tc qdisc show 输出
+    error_message: Option<String>,
+}
+
+impl RateLimitFormState {
+    fn new(iface_name: &str) -> Self {
+        let current_qdisc = tc::show_qdisc(iface_name)
+            .unwrap_or_else(|e| format!("查询失败: {}", e));
+
+        Self {
+            interface_name: iface_name.to_string(),
+            current_field: 0,
+            is_editing: false,
+            rate: String::from("10mbit"),
+            burst: String::from("32kbit"),
+            latency: String::from("400ms"),
+            current_qdisc,
+            error_message: None,
+        }
+    }
+
+    fn field_count() -> usize {
+        3  // 速率、突发、延迟
+    }
+
+    fn current_field_value_mut(&mut self) -> &mut String {
+        match self.current_field {
+            0 => &mut self.rate,
+            1 => &mut self.burst,
+            _ => &mut self.latency,
+        }
+    }
+}
+
+/// 策略路由表单状态
+#[derive(Debug, Clone)]
+struct PolicyRouteFormState {
+    interface_name: String,
+    current_field: usize,
+    is_editing: bool,
+    gateway: String,
+    source: String,
+    table: String,
+    error_message: Option<String>,
+}
+
+impl PolicyRouteFormState {
+    /// 默认使用接口的第一个IPv4地址作为源地址、当前网关作为表内默认路由
+    fn new(iface: &NetInterface) -> Self {
+        Self {
+            interface_name: iface.name.clone(),
+            current_field: 0,
+            is_editing: false,
+            gateway: iface.ipv4_config.as_ref()
+                .and_then(|cfg| cfg.gateway.clone())
+                .unwrap_or_default(),
+            source: iface.ipv4_addresses.first()
+                .and_then(|addr| addr.split('/').next())
+                .unwrap_or_default()
+                .to_string(),
+            table: String::from("100"),
+            error_message: None,
+        }
+    }
+
+    fn field_count() -> usize {
+        3  // 网关、源地址、路由表
+    }
+
+    fn current_field_value_mut(&mut self) -> &mut String {
+        match self.current_field {
+            0 => &mut self.gateway,
+            1 => &mut self.source,
+            _ => &mut self.table,
+        }
+    }
+}
+
+impl App {
+    pub fn new(options: AppOptions) -> Result<Self> {
+        let mut app = Self::empty(options);
+
+        // 首次加载与手动刷新走同一流程（置顶排序、netplan文件等），首次加载失败时直接报错
+        app.reload_interfaces()?;
+
+        // 启动时优先选中默认路由接口，其次是第一个非回环接口
+        let default_route = runtime::get_default_route_interface().ok().flatten();
+        app.list_state.select(initial_selection(&app.interfaces, default_route.as_deref()));
+        Ok(app)
+    }
+
+    /// 创建尚未加载接口的应用状态
+    fn empty(mut options: AppOptions) -> Self {
+        options.list_width = options.list_width.clamp(MIN_LIST_WIDTH, MAX_LIST_WIDTH);
+
+        let traffic_monitor = traffic::TrafficMonitor::new().with_alpha(options.rate_smoothing);
+        let error_rate_monitor = error_rate::ErrorRateMonitor::new()
+            .with_limits(options.error_rate_threshold, options.error_spike_factor);
+        let stats_display = options.stats_display;
+        let netmask_format = options.netmask_format;
+        let safe_mode = options.safe_mode;
+        let list_details = options.list_details;
+        Self {
+            options,
+            interfaces: Vec::new(),
+            list_state: ListState::default(),
+            traffic_monitor,
+            last_update: Instant::now(),
+            screen: Screen::Main,
+            should_quit: false,
+            edit_form: None,
+            target_file: String::new(),
+            action_menu_state: 0,
+            delete_strategy_index: 0,
+            log_scroll: 0,
+            status_message: None,
+            pmtu_progress: None,
+            health_report: None,
+            rate_limit_form: None,
+            policy_form: None,
+            gateway_form: None,
+            address_form: None,
+            rename_form: None,
+            mac_form: None,
+            bridge_form: None,
+            vlan_form: None,
+            bond_form: None,
+            freeze_preview: None,
+            flap_detector: flap::FlapDetector::new(),
+            error_rate_monitor,
+            runtime_only_changes: HashSet::new(),
+            pending_editor: None,
+            overview_sort: OverviewSort::TotalRate,
+            routes: Vec::new(),
+            routes_error: None,
+            routes_scroll: 0,
+            apply_result: None,
+            try_session: None,
+            try_result: None,
+            dhcp_keep_dns: false,
+            clear_link_down: false,
+            edit_history: EditHistory::default(),
+            list_details,
+            safe_mode,
+            diag_redact: true,
+            diag_result: None,
+            owner_details: None,
+            neighbors: None,
+            hotplug: hotplug::HotplugWatcher::new(),
+            traffic_baselines: HashMap::new(),
+            unmanaged_gate: UnmanagedGate::default(),
+            compare_pair: Vec::new(),
+            show_absolute_traffic: false,
+            stats_display,
+            speed_in_bits: format::traffic_unit().is_bits(),
+            netmask_format,
+            expanded_stats: false,
+            last_extended_update: None,
+        }
+    }
+
+    pub fn run(&mut self) -> Result<()> {
+        install_panic_hook();
+        enable_raw_mode()?;
+        // 从这里开始任何返回路径（包括后续初始化失败和事件循环出错）都会在drop时恢复终端
+        let _guard = TerminalGuard;
+        let mut stdout = io::stdout();
+        execute!(stdout, EnterAlternateScreen, EnableMouseCapture, EnableFocusChange)?;
+        let backend = CrosstermBackend::new(stdout);
+        let mut terminal = Terminal::new(backend)?;
+
+        self.event_loop(&mut terminal)
+    }
+
+    fn event_loop(&mut self, terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
+        let tick_rate = Duration::from_millis(250);
+        let mut last_tick = Instant::now();
+
+        loop {
+            terminal.draw(|f| self.ui(f))?;
+
+            let timeout = tick_rate
+                .checked_sub(last_tick.elapsed())
+                .unwrap_or_else(|| Duration::from_secs(0));
+
+            if crossterm::event::poll(timeout)? {
+                match event::read()? {
+                    Event::Key(key) => self.handle_key(key.code, key.modifiers)?,
+                    // 切回终端时接口可能已被外部修改，做一次完整刷新
+                    Event::FocusGained if self.screen == Screen::Main => self.refresh(),
+                    _ => {}
+                }
+            }
+
+            if let Some(path) = self.pending_editor.take() {
+                self.run_editor(terminal, &path)?;
+            }
+
+            if last_tick.elapsed() >= tick_rate {
+                self.on_tick()?;
+                last_tick = Instant::now();
+            }
+
+            if self.should_quit {
+                return Ok(());
+            }
+        }
+    }
+
+    /// 暂停TUI并在$EDITOR中打开文件，编辑器退出（包括异常退出）后恢复界面
+    fn run_editor(&mut self, terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, path: &std::path::Path) -> Result<()> {
+        disable_raw_mode()?;
+        execute!(terminal.backend_mut(), LeaveAlternateScreen, DisableMouseCapture, DisableFocusChange)?;
+
+        let editor = std::env::var("VISUAL")
+            .or_else(|_| std::env::var("EDITOR"))
+            .unwrap_or_else(|_| "vi".to_string());
+        let before = self.netplan().read_config(path).ok();
+        // sudo模式下用sudoedit编辑root所有的配置文件（此时终端已恢复，可以输入密码）
+        let status = if command::use_sudo() {
+            std::process::Command::new("sudo").arg("-e").arg(path).env("SUDO_EDITOR", &editor).status()
+        } else {
+            std::process::Command::new(&editor).arg(path).status()
+        };
+
+        // 无论编辑器是否成功退出都先恢复终端
+        enable_raw_mode()?;
+        execute!(terminal.backend_mut(), EnterAlternateScreen, EnableMouseCapture, EnableFocusChange)?;
+        terminal.clear()?;
+
+        self.status_message = Some(match status {
+            Ok(s) if s.success() => {
+                let advice = match (before, self.netplan().read_config(path)) {
+                    (Some(old), Ok(new)) => netplan::advise_apply(&old, &new),
+                    _ => ApplyAdvice::Apply,
+                };
+                format!("{} 已编辑 {}，{}", advice_symbol(&advice), path.display(), advice.message())
+            }
+            Ok(s) => format!("{} 编辑器异常退出 ({})", Symbol::Warning, s),
+            Err(e) => format!("{} 无法启动编辑器 {}: {}", Symbol::Error, editor, e),
+        });
+        self.refresh();
+        Ok(())
+    }
+
+    /// 调整列表/详情分栏比例，并保存到配置文件
+    fn resize_list(&mut self, delta: i32) {
+        let width = (self.options.list_width as i32 + delta)
+            .clamp(MIN_LIST_WIDTH as i32, MAX_LIST_WIDTH as i32) as u16;
+        if width == self.options.list_width {
+            return;
+        }
+        self.options.list_width = width;
+
+        self.save_config(|config| config.list_width = Some(width));
+    }
+
+    /// 置顶/取消置顶选中的接口，并保存到配置文件
+    fn toggle_pin(&mut self) {
+        let Some(name) = self.list_state.selected()
+            .and_then(|i| self.interfaces.get(i))
+            .map(|iface| iface.name.clone())
+        else {
+            return;
+        };
+
+        if let Some(pos) = self.options.pinned.iter().position(|pinned| *pinned == name) {
+            self.options.pinned.remove(pos);
+        } else {
+            self.options.pinned.push(name.clone());
+        }
+
+        order_pinned(&mut self.interfaces, &self.options.pinned);
+        self.list_state.select(self.interfaces.iter().position(|iface| iface.name == name));
+
+        let pinned = self.options.pinned.clone();
+        self.save_config(|config| config.pinned = pinned);
+    }
+
+    /// 切换接口列表的单行/两行显示并保存到配置
+    fn toggle_list_details(&mut self) {
+        self.list_details = !self.list_details;
+        let enabled = self.list_details;
+        self.save_config(|config| config.list_details = enabled);
+    }
+
+    /// 切换速率显示单位（字节/秒 ↔ 比特/秒）
+    fn toggle_speed_unit(&mut self) {
+        self.speed_in_bits = !self.speed_in_bits;
+        self.status_message = Some(format!(
+            "{} 速率显示单位: {}",
+            Symbol::Ok,
+            if self.speed_in_bits { "比特/秒 (Mbit/s)" } else { "字节/秒 (MB/s)" }
+        ));
+    }
+
+    /// 按当前的比特/字节设置格式化速率；固定单位（--unit）与设置一致时沿用固定单位
+    fn format_rate(&self, bytes_per_sec: f64) -> String {
+        match (self.speed_in_bits, format::traffic_unit().is_bits()) {
+            (true, false) => format_speed_bits(bytes_per_sec),
+            (false, true) => format::format_speed_in(bytes_per_sec, TrafficUnit::Auto),
+            _ => format_speed(bytes_per_sec),
+        }
+    }
+
+    /// 循环切换流量统计面板的显示内容，并保存到配置文件
+    fn cycle_stats_display(&mut self) {
+        self.stats_display = self.stats_display.next();
+        let display = self.stats_display;
+        self.save_config(|config| config.stats_display = display);
+    }
+
+    /// 修改并保存配置文件，失败时在状态栏提示
+    fn save_config(&mut self, update: impl FnOnce(&mut Config)) {
+        let saved = Config::load().and_then(|mut config| {
+            update(&mut config);
+            config.save()
+        });
+        if let Err(e) = saved {
+            self.status_message = Some(format!("{} 保存配置失败: {}", Symbol::Warning, e));
+        }
+    }
+
+    /// 启动netplan try，在倒计时内等待用户确认
+    fn start_netplan_try(&mut self) {
+        if self.blocked_by_safe_mode() {
+            return;
+        }
+        self.try_result = None;
+        match self.netplan().start_try(NETPLAN_TRY_TIMEOUT) {
+            Ok(session) => {
+                self.try_session = Some(session);
+                self.screen = Screen::TryNetplan;
+            }
+            Err(e) => {
+                self.status_message = Some(format!("{} {:#}", Symbol::Error, e));
+                self.screen = Screen::Main;
+            }
+        }
+    }
+
+    /// 执行netplan apply并保存输出，完成后刷新接口列表
+    fn apply_netplan(&mut self) -> Result<()> {
+        if self.blocked_by_safe_mode() {
+            return Ok(());
+        }
+        self.apply_result = Some(self.netplan().apply().map_err(|e| format!("{:#}", e)));
+        self.refresh();
+        Ok(())
+    }
+
+    /// 按启动选项创建Netplan管理器
+    fn netplan(&self) -> NetplanManager {
+        NetplanManager::new().with_preferred_file(self.options.netplan_file.clone())
+    }
+
+    /// 打开选中接口的netplan文件，不存在时询问是否创建
+    fn open_netplan_in_editor(&mut self) {
+        if self.blocked_by_safe_mode() {
+            return;
+        }
+        let Some(iface) = self.list_state.selected().and_then(|i| self.interfaces.get(i)) else {
+            return;
+        };
+
+        match self.netplan().find_interface_file(&iface.name) {
+            Some(path) => {
+                self.pending_editor = Some(path);
+                self.screen = Screen::Main;
+            }
+            None => self.screen = Screen::ConfirmCreateNetplan,
+        }
+    }
+
+    /// JSON事件流模式：不启动TUI，每次流量更新后向stdout输出一行JSON
+    pub fn run_json_stream(&mut self) -> Result<()> {
+        use std::io::Write;
+
+        #[derive(serde::Serialize)]
+        struct StreamEvent<'a> {
+            seq: u64,
+            timestamp: String,
+            interfaces: &'a [NetInterface],
+        }
+
+        let tick_rate = Duration::from_millis(250);
+        let mut seq = 0;
+        let mut last_emit = None;
+
+        loop {
+            self.on_tick()?;
+
+            // on_tick每秒才更新一次流量，只在数据更新后输出
+            if last_emit != Some(self.last_update) {
+                last_emit = Some(self.last_update);
+                seq += 1;
+
+                let event = StreamEvent {
+                    seq,
+                    timestamp: chrono::Local::now().to_rfc3339(),
+                    interfaces: &self.interfaces,
+                };
+                let line = serde_json::to_string(&event)?;
+
+                let mut stdout = io::stdout().lock();
+                // 下游关闭管道时正常退出
+                if writeln!(stdout, "{}", line).and_then(|_| stdout.flush()).is_err() {
+                    return Ok(());
+                }
+            }
+
+            std::thread::sleep(tick_rate);
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyCode, modifiers: KeyModifiers) -> Result<()> {
+        match self.screen {
+            Screen::Main => {
+                // 任意按键清除上一条状态消息
+                self.status_message = None;
+                match key {
+                    KeyCode::Char('q') => self.should_quit = true,
+                    KeyCode::Char('?') => self.screen = Screen::Help,
+                    KeyCode::Char('r') => self.refresh(),
+                    KeyCode::Char('l') => {
+                        self.log_scroll = 0;
+                        self.screen = Screen::CommandLog;
+                    }
+                    KeyCode::Char('O') => self.screen = Screen::Overview,
+                    KeyCode::Char('R') => {
+                        self.routes_scroll = 0;
+                        self.load_routes();
+                        self.screen = Screen::Routes;
+                    }
+                    KeyCode::Char('T') => self.start_netplan_try(),
+                    KeyCode::Char('B') => {
+                        self.bridge_form = Some(BridgeFormState::new(&self.interfaces));
+                        self.screen = Screen::CreateBridge;
+                    }
+                    KeyCode::Char('L') => {
+                        self.bond_form = Some(BondFormState::new(&self.interfaces));
+                        self.screen = Screen::CreateBond;
+                    }
+                    KeyCode::Char('V') => {
+                        let selected = self.selected_interface().map(|iface| iface.name.clone());
+                        self.vlan_form = Some(VlanFormState::new(&self.interfaces, selected.as_deref()));
+                        self.screen = Screen::CreateVlan;
+                    }
+                    KeyCode::Char('D') => {
+                        self.diag_result = None;
+                        self.screen = Screen::Diagnostics;
+                    }
+                    KeyCode::Char('A') => {
+                        self.apply_result = None;
+                        self.screen = Screen::ApplyNetplan;
+                    }
+                    KeyCode::Char('*') => self.toggle_pin(),
+                    KeyCode::Char('!') => {
+                        if self.safe_mode {
+                            self.screen = Screen::ConfirmSafeModeOff;
+                        } else {
+                            self.safe_mode = true;
+                            self.status_message = Some(format!("{} 已开启安全模式，所有修改操作将被阻止", Symbol::Ok));
+                        }
+                    }
+                    KeyCode::Char('m') => {
+                        // 标记/取消标记对比接口
+                        if let Some(iface) = self.list_state.selected().and_then(|i| self.interfaces.get(i)) {
+                            toggle_compare(&mut self.compare_pair, &iface.name);
+                            self.status_message = Some(if self.compare_pair.is_empty() {
+                                "已清除对比接口".to_string()
+                            } else {
+                                format!("对比接口: {}（标记两个后按 C 打开对比视图）", self.compare_pair.join(", "))
+                            });
+                        }
+                    }
+                    KeyCode::Char('C') => {
+                        if self.compare_pair.len() == 2 {
+                            self.screen = Screen::Compare;
+                        } else {
+                            self.status_message = Some(format!("{} 请先用 m 标记两个接口", Symbol::Warning));
                         }
                     }
+                    KeyCode::Char('<') => self.resize_list(-(LIST_WIDTH_STEP as i32)),
+                    KeyCode::Char('>') => self.resize_list(LIST_WIDTH_STEP as i32),
+                    KeyCode::Up | KeyCode::Char('k') => self.previous(),
+                    KeyCode::Down | KeyCode::Char('j') => self.next(),
+                    KeyCode::Enter if self.list_state.selected().is_some() => {
+                        // 回车键：打开接口操作菜单
+                        self.action_menu_state = 0;
+                        self.screen = Screen::InterfaceActions;
+                    }
                     KeyCode::Char('e') => {
                         // e键：快速编辑接口配置（仅物理接口）
-                        if let Some(i) = self.list_state.selected() {
-                            if let Some(iface) = self.interfaces.get(i) {
-                                if matches!(iface.kind, InterfaceKind::Physical) {
-                                    self.edit_form = Some(EditFormState::new(iface));
-                                    self.screen = Screen::EditIface;
-                                }
-                            }
+                        if let Some(i) = self.list_state.selected()
+                            && let Some(iface) = self.interfaces.get(i)
+                            && matches!(iface.kind, InterfaceKind::Physical) {
+                            let name = iface.name.clone();
+                            self.edit_form = Some(
+                                EditFormState::new(iface)
+                                    .with_netmask_format(self.netmask_format)
+                                    .with_history(&self.edit_history),
+                            );
+                            self.open_form(Screen::EditIface, &name);
+                        }
+                    }
+                    KeyCode::Char('w') => {
+                        // 把运行时配置写入netplan（仅物理接口）
+                        if let Some(iface) = self.selected_interface().filter(|iface| iface.kind == InterfaceKind::Physical) {
+                            let iface = iface.clone();
+                            self.open_freeze_preview(&iface);
                         }
                     }
                     KeyCode::Char('t') => {
                         // 切换DHCP/静态（仅物理接口）
-                        if let Some(i) = self.list_state.selected() {
-                            if let Some(iface) = self.interfaces.get(i) {
-                                if matches!(iface.kind, InterfaceKind::Physical) {
-                                    self.screen = Screen::ToggleDhcp;
-                                }
-                            }
+                        if let Some(i) = self.list_state.selected()
+                            && let Some(iface) = self.interfaces.get(i)
+                            && matches!(iface.kind, InterfaceKind::Physical) {
+                            let name = iface.name.clone();
+                            self.open_form(Screen::ToggleDhcp, &name);
                         }
                     }
                     KeyCode::Char('x') | KeyCode::Delete => {
                         // 删除接口（仅虚拟接口）
-                        if let Some(i) = self.list_state.selected() {
-                            if let Some(iface) = self.interfaces.get(i) {
-                                if iface.kind != InterfaceKind::Physical && iface.kind != InterfaceKind::Loopback {
-                                    self.screen = Screen::ConfirmDelete;
-                                }
-                            }
+                        if let Some(i) = self.list_state.selected()
+                            && let Some(iface) = self.interfaces.get(i)
+                            && iface.kind != InterfaceKind::Physical && iface.kind != InterfaceKind::Loopback {
+                            self.open_confirm_delete();
                         }
                     }
                     KeyCode::Char('u') => {
@@ -249,24 +1523,111 @@ impl App {
                         // 禁用接口 (down)
                         self.toggle_interface_down()?;
                     }
+                    KeyCode::Char('E') => {
+                        // 在$EDITOR中编辑netplan文件
+                        self.open_netplan_in_editor();
+                    }
+                    KeyCode::Char('c') => {
+                        // 连通性检查
+                        if let Some(iface) = self.list_state.selected().and_then(|i| self.interfaces.get(i)).cloned() {
+                            self.start_health_check(&iface);
+                        }
+                    }
+                    KeyCode::Char('b') => {
+                        // 以当前计数作为会话基线
+                        if let Some(iface) = self.list_state.selected().and_then(|i| self.interfaces.get(i)) {
+                            self.traffic_baselines.insert(iface.name.clone(), iface.traffic_stats.clone());
+                            self.show_absolute_traffic = false;
+                        }
+                    }
+                    KeyCode::Char('a') => {
+                        // 切换累计值/会话值
+                        self.show_absolute_traffic = !self.show_absolute_traffic;
+                    }
+                    KeyCode::Char('v') => self.cycle_stats_display(),
+                    KeyCode::Char('U') => self.toggle_speed_unit(),
+                    KeyCode::Char('i') => self.toggle_list_details(),
+                    KeyCode::Char('I') => self.open_owner_details(),
+                    KeyCode::Char('S') => {
+                        // 展开/收起扩展统计
+                        self.expanded_stats = !self.expanded_stats;
+                        if self.expanded_stats {
+                            self.update_extended_stats();
+                        }
+                    }
+                    KeyCode::Char('z') => {
+                        // 清零抖动计数
+                        if let Some(iface) = self.list_state.selected().and_then(|i| self.interfaces.get(i)) {
+                            self.flap_detector.reset(&iface.name);
+                        }
+                    }
                     KeyCode::Char('o') => {
                         // 创建者操作（停止服务/容器/进程等）
-                        if let Some(i) = self.list_state.selected() {
-                            if let Some(iface) = self.interfaces.get(i) {
-                                if iface.owner.is_some() {
-                                    self.screen = Screen::OwnerActions;
-                                }
-                            }
+                        if let Some(i) = self.list_state.selected()
+                            && let Some(iface) = self.interfaces.get(i)
+                            && iface.owner.is_some() {
+                            self.screen = Screen::OwnerActions;
                         }
                     }
                     _ => {}
                 }
             }
+            Screen::Compare => {
+                if matches!(key, KeyCode::Char('q') | KeyCode::Esc | KeyCode::Char('C')) {
+                    self.screen = Screen::Main;
+                }
+            }
+            Screen::Overview => {
+                match key {
+                    KeyCode::Char('s') => self.overview_sort = self.overview_sort.next(),
+                    KeyCode::Char('q') | KeyCode::Esc | KeyCode::Char('O') => {
+                        self.screen = Screen::Main;
+                    }
+                    _ => {}
+                }
+            }
+            Screen::Routes => {
+                match key {
+                    KeyCode::Up | KeyCode::Char('k') => self.routes_scroll = self.routes_scroll.saturating_sub(1),
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        self.routes_scroll = (self.routes_scroll + 1).min(self.routes.len().saturating_sub(1));
+                    }
+                    KeyCode::PageUp => self.routes_scroll = self.routes_scroll.saturating_sub(10),
+                    KeyCode::PageDown => {
+                        self.routes_scroll = (self.routes_scroll + 10).min(self.routes.len().saturating_sub(1));
+                    }
+                    KeyCode::Char('r') => self.load_routes(),
+                    KeyCode::Char('q') | KeyCode::Esc | KeyCode::Char('R') => {
+                        self.screen = Screen::Main;
+                    }
+                    _ => {}
+                }
+            }
             Screen::Help => {
                 if matches!(key, KeyCode::Char('q') | KeyCode::Esc | KeyCode::Char('?')) {
                     self.screen = Screen::Main;
                 }
             }
+            Screen::CommandLog => {
+                match key {
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        self.log_scroll = self.log_scroll.saturating_sub(1);
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        self.log_scroll = self.log_scroll.saturating_add(1);
+                    }
+                    KeyCode::PageUp => {
+                        self.log_scroll = self.log_scroll.saturating_sub(10);
+                    }
+                    KeyCode::PageDown => {
+                        self.log_scroll = self.log_scroll.saturating_add(10);
+                    }
+                    KeyCode::Char('q') | KeyCode::Esc | KeyCode::Char('l') => {
+                        self.screen = Screen::Main;
+                    }
+                    _ => {}
+                }
+            }
             Screen::OwnerActions => {
                 match key {
                     KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
@@ -283,10 +1644,8 @@ impl App {
             }
             Screen::InterfaceActions => {
                 match key {
-                    KeyCode::Up | KeyCode::Char('k') => {
-                        if self.action_menu_state > 0 {
-                            self.action_menu_state -= 1;
-                        }
+                    KeyCode::Up | KeyCode::Char('k') if self.action_menu_state > 0 => {
+                        self.action_menu_state -= 1;
                     }
                     KeyCode::Down | KeyCode::Char('j') => {
                         let max_items = self.get_action_menu_items().len();
@@ -305,27 +1664,201 @@ impl App {
                 }
             }
             Screen::EditIface => {
-                self.handle_edit_form_key(key)?;
+                self.handle_edit_form_key(key, modifiers)?;
             }
-            Screen::ToggleDhcp => {
+            Screen::RateLimit => {
+                self.handle_rate_limit_key(key);
+            }
+            Screen::PolicyRoute => {
+                self.handle_policy_form_key(key)?;
+            }
+            Screen::EditGateway => {
+                self.handle_gateway_form_key(key)?;
+            }
+            Screen::ManageAddresses => {
+                self.handle_address_form_key(key)?;
+            }
+            Screen::RenameIface => {
+                self.handle_rename_form_key(key)?;
+            }
+            Screen::EditMac => {
+                self.handle_mac_form_key(key)?;
+            }
+            Screen::CreateBridge => {
+                self.handle_bridge_form_key(key)?;
+            }
+            Screen::CreateVlan => {
+                self.handle_vlan_form_key(key)?;
+            }
+            Screen::CreateBond => {
+                self.handle_bond_form_key(key)?;
+            }
+            Screen::FreezeConfig => {
                 match key {
                     KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
-                        // 确认切换到DHCP（Y键或Enter键）
-                        self.toggle_dhcp()?;
-                        self.screen = Screen::Main;
+                        self.write_frozen_config()?;
                     }
                     KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc | KeyCode::Char('q') => {
-                        // 取消（N键、Esc键或q键）
+                        self.freeze_preview = None;
                         self.screen = Screen::Main;
                     }
                     _ => {}
                 }
             }
-            Screen::ConfirmDelete => {
+            Screen::ConfirmCreateNetplan => {
                 match key {
                     KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
-                        // 确认删除（Y键或Enter键）
-                        self.delete_selected_interface()?;
+                        if self.blocked_by_safe_mode() {
+                            return Ok(());
+                        }
+                        if let Some(iface) = self.list_state.selected().and_then(|i| self.interfaces.get(i)) {
+                            match self.netplan().create_config_file_for(&iface.name) {
+                                Ok(path) => self.pending_editor = Some(path),
+                                Err(e) => {
+                                    self.status_message = Some(format!("{} 创建netplan文件失败: {}", Symbol::Error, e));
+                                }
+                            }
+                        }
+                        self.screen = Screen::Main;
+                    }
+                    KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc | KeyCode::Char('q') => {
+                        self.screen = Screen::Main;
+                    }
+                    _ => {}
+                }
+            }
+            Screen::OwnerDetails => {
+                if matches!(key, KeyCode::Esc | KeyCode::Char('q') | KeyCode::Enter | KeyCode::Char('I')) {
+                    self.owner_details = None;
+                    self.screen = Screen::Main;
+                }
+            }
+            Screen::Neighbors => {
+                match key {
+                    KeyCode::Char('r') => self.open_neighbors(),
+                    KeyCode::Esc | KeyCode::Char('q') | KeyCode::Enter => {
+                        self.neighbors = None;
+                        self.screen = Screen::Main;
+                    }
+                    _ => {}
+                }
+            }
+            Screen::Diagnostics => {
+                match key {
+                    KeyCode::Char('r') if self.diag_result.is_none() => self.diag_redact = !self.diag_redact,
+                    KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') if self.diag_result.is_none() => {
+                        let content = diagnostics::collect(&self.interfaces, &self.netplan(), self.diag_redact);
+                        self.diag_result = Some(diagnostics::write_bundle(&content).map_err(|e| format!("{:#}", e)));
+                    }
+                    KeyCode::Esc | KeyCode::Char('q') | KeyCode::Enter | KeyCode::Char('n') | KeyCode::Char('N') => {
+                        self.diag_result = None;
+                        self.screen = Screen::Main;
+                    }
+                    _ => {}
+                }
+            }
+            Screen::TryNetplan => {
+                if let Some(session) = &self.try_session {
+                    let result = match key {
+                        KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => session.confirm(),
+                        KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => session.revert(),
+                        _ => Ok(()),
+                    };
+                    if let Err(e) = result {
+                        self.status_message = Some(format!("{} {:#}", Symbol::Error, e));
+                    }
+                } else if matches!(key, KeyCode::Char('q') | KeyCode::Esc | KeyCode::Enter) {
+                    self.try_result = None;
+                    self.screen = Screen::Main;
+                }
+            }
+            Screen::ApplyNetplan => {
+                match key {
+                    KeyCode::Char('y') | KeyCode::Char('Y') if self.apply_result.is_none() => {
+                        self.apply_netplan()?;
+                    }
+                    KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc | KeyCode::Char('q') | KeyCode::Enter => {
+                        self.apply_result = None;
+                        self.screen = Screen::Main;
+                    }
+                    _ => {}
+                }
+            }
+            Screen::PathMtu => {
+                if matches!(key, KeyCode::Char('q') | KeyCode::Esc | KeyCode::Enter) {
+                    self.pmtu_progress = None;
+                    self.screen = Screen::Main;
+                }
+            }
+            Screen::HealthCheck => {
+                if matches!(key, KeyCode::Char('q') | KeyCode::Esc | KeyCode::Enter) {
+                    self.health_report = None;
+                    self.screen = Screen::Main;
+                }
+            }
+            Screen::ToggleDhcp => {
+                match key {
+                    KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
+                        // 确认切换到DHCP（Y键或Enter键）
+                        self.toggle_dhcp()?;
+                        self.screen = Screen::Main;
+                    }
+                    KeyCode::Char('k') | KeyCode::Char('K') => {
+                        // 切换是否保留DNS
+                        self.dhcp_keep_dns = !self.dhcp_keep_dns;
+                    }
+                    KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc | KeyCode::Char('q') => {
+                        // 取消（N键、Esc键或q键）
+                        self.screen = Screen::Main;
+                    }
+                    _ => {}
+                }
+            }
+            Screen::ConfirmSafeModeOff => {
+                match key {
+                    KeyCode::Char('y') | KeyCode::Char('Y') => {
+                        self.safe_mode = false;
+                        self.status_message = Some(format!("{} 已关闭安全模式，修改操作将被执行", Symbol::Warning));
+                        self.screen = Screen::Main;
+                    }
+                    KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc | KeyCode::Char('q') | KeyCode::Enter => {
+                        self.screen = Screen::Main;
+                    }
+                    _ => {}
+                }
+            }
+            Screen::ClearConfig => {
+                match key {
+                    KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
+                        self.screen = Screen::Main;
+                        self.clear_interface_config();
+                    }
+                    KeyCode::Char('d') | KeyCode::Char('D') => {
+                        // 切换清除后是否禁用接口
+                        self.clear_link_down = !self.clear_link_down;
+                    }
+                    KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc | KeyCode::Char('q') => {
+                        self.screen = Screen::Main;
+                    }
+                    _ => {}
+                }
+            }
+            Screen::ConfirmDelete => {
+                match key {
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        self.delete_strategy_index = self.delete_strategy_index.saturating_sub(1);
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        let count = self.selected_interface()
+                            .map(|iface| RemovalManager::applicable_strategies(iface).len())
+                            .unwrap_or(0);
+                        if self.delete_strategy_index + 1 < count {
+                            self.delete_strategy_index += 1;
+                        }
+                    }
+                    KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
+                        // 确认删除（Y键或Enter键）
+                        self.delete_selected_interface()?;
                         self.screen = Screen::Main;
                     }
                     KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc | KeyCode::Char('q') => {
@@ -339,10 +1872,39 @@ impl App {
         Ok(())
     }
 
-    fn handle_edit_form_key(&mut self, key: KeyCode) -> Result<()> {
+    fn handle_edit_form_key(&mut self, key: KeyCode, modifiers: KeyModifiers) -> Result<()> {
         if let Some(form) = &mut self.edit_form {
+            if form.confirming_save {
+                // 保存确认弹窗
+                match key {
+                    KeyCode::Char('y') | KeyCode::Char('Y') => {
+                        form.confirming_save = false;
+                        self.commit_edit_form()?;
+                    }
+                    KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc | KeyCode::Char('q') => {
+                        form.confirming_save = false;
+                    }
+                    _ => {}
+                }
+                return Ok(());
+            }
+
             if form.is_editing {
                 // 正在编辑字段内容
+                // 上下键或Ctrl+P/N翻阅历史值
+                let ctrl = modifiers.contains(KeyModifiers::CONTROL);
+                let recall = match key {
+                    KeyCode::Up => Some(true),
+                    KeyCode::Down => Some(false),
+                    KeyCode::Char('p') if ctrl => Some(true),
+                    KeyCode::Char('n') if ctrl => Some(false),
+                    _ => None,
+                };
+                if let Some(older) = recall {
+                    form.recall_history(self.edit_history.values(form.current_field), older);
+                    return Ok(());
+                }
+
                 match key {
                     KeyCode::Esc => {
                         // 退出编辑模式
@@ -356,14 +1918,18 @@ impl App {
                         // 删除字符
                         let value = form.current_field_value_mut();
                         value.pop();
+                        form.sync_cidr();
                     }
                     KeyCode::Char(c) => {
                         // 输入字符
                         let value = form.current_field_value_mut();
                         value.push(c);
+                        form.sync_cidr();
                     }
                     _ => {}
                 }
+                // 手动修改或离开字段后重新从最近的历史值开始翻阅
+                form.history_pos = None;
             } else {
                 // 导航模式
                 match key {
@@ -385,16 +1951,56 @@ impl App {
                         form.is_editing = true;
                     }
                     KeyCode::Char('s') | KeyCode::Char('S') => {
-                        // 保存配置
-                        if let Err(e) = self.save_interface_config() {
-                            if let Some(form) = &mut self.edit_form {
-                                form.error_message = Some(format!("保存失败: {}", e));
-                            }
-                        } else {
-                            self.edit_form = None;
-                            self.screen = Screen::Main;
-                            self.refresh()?;
+                        // 网关不在子网内时先警告，再次按s确认后才保存
+                        if !form.gateway_confirmed
+                            && let Some(warning) = form.gateway_subnet_warning() {
+                            form.error_message = Some(warning);
+                            form.gateway_confirmed = true;
+                            return Ok(());
                         }
+                        // DNS格式错误时直接提示，不进入保存确认
+                        let dns_check = dns::parse_nameservers(&form.dns)
+                            .and_then(|_| dns::parse_search_domains(&form.search_domains));
+                        if let Err(e) = dns_check {
+                            form.error_message = Some(e.to_string());
+                            return Ok(());
+                        }
+                        // 地址已在其他接口上时同样需要再次确认
+                        if !form.address_confirmed
+                            && let Some(warning) = form.duplicate_address_warning(&self.interfaces) {
+                            form.error_message = Some(warning);
+                            form.address_confirmed = true;
+                            return Ok(());
+                        }
+                        // 接口由其他方式维护时提醒保存会覆盖其条目
+                        if !form.runtime_only
+                            && let Some(warning) = self.unmanaged_gate.check(&self.interfaces, &form.interface_name) {
+                            form.error_message = Some(warning);
+                            return Ok(());
+                        }
+
+                        // 显示修改前后对比，按y后才真正保存
+                        form.error_message = None;
+                        form.confirming_save = true;
+                    }
+                    KeyCode::Char('f') | KeyCode::Char('F') => {
+                        // 切换保存后是否刷新DNS
+                        form.refresh_dns = !form.refresh_dns;
+                    }
+                    KeyCode::Char('p') | KeyCode::Char('P') => {
+                        // 切换子网掩码/前缀长度输入格式，并记住偏好
+                        form.toggle_netmask_format();
+                        self.netmask_format = form.netmask_format;
+                        let format = self.netmask_format;
+                        self.save_config(|config| config.netmask_format = format);
+                    }
+                    KeyCode::Char('6') => {
+                        // 切换纯IPv6模式（隐藏IPv4字段）
+                        form.toggle_ipv6_only();
+                    }
+                    KeyCode::Char('t') | KeyCode::Char('T') => {
+                        // 切换临时修改（不写入Netplan）
+                        form.runtime_only = !form.runtime_only;
                     }
                     _ => {}
                 }
@@ -403,344 +2009,1938 @@ impl App {
         Ok(())
     }
 
-    fn on_tick(&mut self) -> Result<()> {
-        if self.last_update.elapsed() >= Duration::from_secs(1) {
-            self.traffic_monitor.update_all(&mut self.interfaces)?;
-            self.last_update = Instant::now();
+    /// 保存编辑表单中的配置
+    fn commit_edit_form(&mut self) -> Result<()> {
+        if self.blocked_by_safe_mode() {
+            return Ok(());
+        }
+        match self.save_interface_config() {
+            Err(e) => {
+                tracing::error!("保存接口配置失败: {:#}", e);
+                if let Some(form) = &mut self.edit_form {
+                    form.error_message = Some(format!("保存失败: {}", e));
+                }
+            }
+            Ok(advice) => {
+                if let Some(form) = &self.edit_form {
+                    self.edit_history.record(form);
+                    // 持久化保存后运行时与Netplan重新一致
+                    if form.runtime_only {
+                        self.runtime_only_changes.insert(form.interface_name.clone());
+                    } else {
+                        self.runtime_only_changes.remove(&form.interface_name);
+                    }
+                }
+                self.refresh_dns_after_save();
+                match advice {
+                    None => {
+                        let notice = "临时配置已生效，未写入Netplan，重启后失效";
+                        self.status_message = Some(match self.status_message.take() {
+                            Some(message) => format!("{}；{}", message, notice),
+                            None => format!("{} {}", Symbol::Ok, notice),
+                        });
+                    }
+                    // 运行时已生效，只在需要重启时提示
+                    Some(advice @ ApplyAdvice::Reboot(_)) => {
+                        self.status_message = Some(format!("{} 配置已保存，{}", advice_symbol(&advice), advice.message()));
+                    }
+                    Some(ApplyAdvice::Apply) => {}
+                }
+                self.edit_form = None;
+                self.screen = Screen::Main;
+                self.refresh();
+            }
         }
         Ok(())
     }
 
-    fn refresh(&mut self) -> Result<()> {
-        self.interfaces = runtime::list_interfaces()?;
-        for iface in &mut self.interfaces {
-            iface.owner = owner_detection::OwnerDetector::detect(iface);
+    fn handle_rate_limit_key(&mut self, key: KeyCode) {
+        let Some(form) = &mut self.rate_limit_form else {
+            return;
+        };
+
+        if form.is_editing {
+            match key {
+                KeyCode::Esc | KeyCode::Enter => form.is_editing = false,
+                KeyCode::Backspace => {
+                    form.current_field_value_mut().pop();
+                }
+                KeyCode::Char(c) => form.current_field_value_mut().push(c),
+                _ => {}
+            }
+            return;
         }
-        self.traffic_monitor.update_all(&mut self.interfaces)?;
-        Ok(())
-    }
 
-    fn next(&mut self) {
-        let i = match self.list_state.selected() {
-            Some(i) => {
-                if i >= self.interfaces.len() - 1 {
-                    0
-                } else {
-                    i + 1
+        match key {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.rate_limit_form = None;
+                self.screen = Screen::Main;
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                form.current_field = (form.current_field + RateLimitFormState::field_count() - 1)
+                    % RateLimitFormState::field_count();
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                form.current_field = (form.current_field + 1) % RateLimitFormState::field_count();
+            }
+            KeyCode::Enter => form.is_editing = true,
+            KeyCode::Char('s') | KeyCode::Char('S') => {
+                if self.blocked_by_safe_mode() {
+                    return;
+                }
+                let Some(form) = &mut self.rate_limit_form else {
+                    return;
+                };
+                match tc::set_rate_limit(&form.interface_name, &form.rate, &form.burst, &form.latency) {
+                    Ok(()) => {
+                        self.status_message = Some(format!(
+                            "{} 接口 {} 已限速 {}",
+                            Symbol::Ok, form.interface_name, form.rate
+                        ));
+                        self.rate_limit_form = None;
+                        self.screen = Screen::Main;
+                    }
+                    Err(e) => form.error_message = Some(format!("{:#}", e)),
                 }
             }
-            None => 0,
-        };
-        self.list_state.select(Some(i));
+            KeyCode::Char('c') | KeyCode::Char('C') => {
+                // 清除限速
+                if self.blocked_by_safe_mode() {
+                    return;
+                }
+                let Some(form) = &mut self.rate_limit_form else {
+                    return;
+                };
+                match tc::clear_rate_limit(&form.interface_name) {
+                    Ok(()) => {
+                        self.status_message = Some(format!(
+                            "{} 已清除接口 {} 的限速",
+                            Symbol::Ok, form.interface_name
+                        ));
+                        self.rate_limit_form = None;
+                        self.screen = Screen::Main;
+                    }
+                    Err(e) => form.error_message = Some(format!("{:#}", e)),
+                }
+            }
+            _ => {}
+        }
     }
 
-    fn previous(&mut self) {
-        let i = match self.list_state.selected() {
-            Some(i) => {
-                if i == 0 {
-                    self.interfaces.len() - 1
-                } else {
-                    i - 1
+    fn handle_policy_form_key(&mut self, key: KeyCode) -> Result<()> {
+        let Some(form) = &mut self.policy_form else {
+            return Ok(());
+        };
+
+        if form.is_editing {
+            match key {
+                KeyCode::Esc | KeyCode::Enter => form.is_editing = false,
+                KeyCode::Backspace => {
+                    form.current_field_value_mut().pop();
                 }
+                KeyCode::Char(c) => form.current_field_value_mut().push(c),
+                _ => {}
             }
-            None => 0,
-        };
-        self.list_state.select(Some(i));
-    }
+            return Ok(());
+        }
 
-    fn toggle_interface_up(&mut self) -> Result<()> {
-        if let Some(i) = self.list_state.selected() {
-            if let Some(iface) = self.interfaces.get(i) {
-                runtime::set_interface_up(&iface.name)?;
-                self.refresh()?;
+        match key {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.policy_form = None;
+                self.screen = Screen::Main;
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                form.current_field = (form.current_field + PolicyRouteFormState::field_count() - 1)
+                    % PolicyRouteFormState::field_count();
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                form.current_field = (form.current_field + 1) % PolicyRouteFormState::field_count();
+            }
+            KeyCode::Enter => form.is_editing = true,
+            KeyCode::Char('s') | KeyCode::Char('S') => {
+                if self.blocked_by_safe_mode() {
+                    return Ok(());
+                }
+                let Some(form) = &mut self.policy_form else {
+                    return Ok(());
+                };
+                let Ok(table) = form.table.trim().parse::<u32>() else {
+                    form.error_message = Some(format!("无效的路由表编号: {}", form.table));
+                    return Ok(());
+                };
+                let (name, gateway, source) = (
+                    form.interface_name.clone(),
+                    form.gateway.trim().to_string(),
+                    form.source.trim().to_string(),
+                );
+                if let Some(warning) = self.unmanaged_gate.check(&self.interfaces, &name) {
+                    form.error_message = Some(warning);
+                    return Ok(());
+                }
+
+                if let Err(e) = policy::add_policy_route(&name, &gateway, &source, table) {
+                    form.error_message = Some(format!("{:#}", e));
+                    return Ok(());
+                }
+
+                // 立即生效后持久化到Netplan
+                self.status_message = Some(match self.netplan().set_policy_route(&name, &gateway, &source, table) {
+                    Ok(_) => format!("{} 来自 {} 的流量已使用路由表 {}（经 {}）", Symbol::Ok, source, table, gateway),
+                    Err(e) => {
+                        self.runtime_only_changes.insert(name.clone());
+                        format!("{} 策略路由已生效，但写入Netplan失败: {:#}", Symbol::Warning, e)
+                    }
+                });
+                self.policy_form = None;
+                self.screen = Screen::Main;
+                self.refresh();
             }
+            _ => {}
         }
         Ok(())
     }
 
-    fn toggle_interface_down(&mut self) -> Result<()> {
-        if let Some(i) = self.list_state.selected() {
-            if let Some(iface) = self.interfaces.get(i) {
-                runtime::set_interface_down(&iface.name)?;
-                self.refresh()?;
+    fn handle_gateway_form_key(&mut self, key: KeyCode) -> Result<()> {
+        let Some(form) = &mut self.gateway_form else {
+            return Ok(());
+        };
+
+        match key {
+            KeyCode::Esc => {
+                self.gateway_form = None;
+                self.screen = Screen::Main;
+            }
+            KeyCode::Backspace => {
+                form.gateway.pop();
+            }
+            KeyCode::Char(c) => form.gateway.push(c),
+            KeyCode::Enter => {
+                if self.blocked_by_safe_mode() {
+                    return Ok(());
+                }
+                let Some(form) = &mut self.gateway_form else {
+                    return Ok(());
+                };
+                let gateway = form.gateway.trim().to_string();
+                if !runtime::gateway_in_subnets(&gateway, &form.addresses) {
+                    form.error_message = Some(format!("网关 {} 不在接口的任何子网内", gateway));
+                    return Ok(());
+                }
+                if let Some(warning) = self.unmanaged_gate.check(&self.interfaces, &form.interface_name) {
+                    form.error_message = Some(warning);
+                    return Ok(());
+                }
+
+                let iface_name = form.interface_name.clone();
+                if let Err(e) = runtime::set_default_gateway(&gateway, &iface_name) {
+                    form.error_message = Some(format!("保存失败: {:#}", e));
+                    return Ok(());
+                }
+
+                // 已生效的网关写入Netplan失败时（如接口未定义）记为未持久化的临时修改
+                self.status_message = Some(match self.netplan().set_gateway(&iface_name, &gateway) {
+                    Ok(_) => format!("{} 接口 {} 的默认网关已改为 {}", Symbol::Ok, iface_name, gateway),
+                    Err(e) => {
+                        self.runtime_only_changes.insert(iface_name.clone());
+                        format!("{} 默认网关已生效，但写入Netplan失败: {:#}", Symbol::Warning, e)
+                    }
+                });
+                self.gateway_form = None;
+                self.screen = Screen::Main;
+                self.refresh();
             }
+            _ => {}
         }
         Ok(())
     }
 
-    fn save_interface_config(&mut self) -> Result<()> {
-        if let Some(form) = &self.edit_form {
-            let iface_name = &form.interface_name;
+    fn handle_rename_form_key(&mut self, key: KeyCode) -> Result<()> {
+        let Some(form) = &mut self.rename_form else {
+            return Ok(());
+        };
 
-            // 验证输入
-            if form.ip_address.is_empty() {
-                return Err(anyhow::anyhow!("IP地址不能为空"));
+        match key {
+            KeyCode::Esc => {
+                self.rename_form = None;
+                self.screen = Screen::Main;
             }
-            if form.gateway.is_empty() {
-                return Err(anyhow::anyhow!("网关不能为空"));
+            KeyCode::Tab | KeyCode::BackTab if form.mac_address.is_some() => form.persist = !form.persist,
+            KeyCode::Backspace => {
+                form.name.pop();
             }
+            KeyCode::Char(c) => form.name.push(c),
+            KeyCode::Enter => self.rename_interface()?,
+            _ => {}
+        }
+        Ok(())
+    }
 
-            // 将子网掩码转换为前缀长度
-            let prefix = Self::netmask_to_prefix(&form.netmask)?;
+    /// 重命名处于DOWN状态的接口，按需写入Netplan使重启后保持新名称
+    fn rename_interface(&mut self) -> Result<()> {
+        if self.blocked_by_safe_mode() {
+            return Ok(());
+        }
+        let Some(form) = &mut self.rename_form else {
+            return Ok(());
+        };
 
-            // 1. 运行时修改（立即生效）
-            runtime::flush_ipv4_addresses(iface_name)?;
-            runtime::set_ipv4_address(iface_name, &form.ip_address, prefix)?;
-            runtime::set_default_gateway(&form.gateway, iface_name)?;
+        let old = form.interface_name.clone();
+        let new = form.name.trim().to_string();
+        if let Err(e) = runtime::validate_interface_name(&new, &self.interfaces) {
+            form.error_message = Some(format!("{:#}", e));
+            return Ok(());
+        }
+        if form.persist
+            && form.mac_address.is_some()
+            && let Some(warning) = self.unmanaged_gate.check(&self.interfaces, &old) {
+            form.error_message = Some(warning);
+            return Ok(());
+        }
 
-            // 2. 持久化到Netplan
-            use crate::backend::netplan::NetplanManager;
-            let netplan = NetplanManager::new();
+        if let Err(e) = runtime::rename_interface(&old, &new) {
+            form.error_message = Some(format!("重命名失败: {:#}", e));
+            return Ok(());
+        }
 
-            // 解析DNS列表
-            let dns_list: Vec<String> = form.dns
-                .split(',')
-                .map(|s| s.trim().to_string())
-                .filter(|s| !s.is_empty())
-                .collect();
+        let persist_mac = form.mac_address.clone().filter(|_| form.persist);
+        let persisted = persist_mac.map(|mac| self.netplan().set_persistent_name(&old, &new, &mac));
+        self.status_message = Some(match persisted {
+            None => format!("{} 已将接口 {} 重命名为 {}（仅运行时，重启后恢复）", Symbol::Ok, old, new),
+            Some(Ok(_)) => format!("{} 已将接口 {} 重命名为 {}，并写入Netplan set-name", Symbol::Ok, old, new),
+            Some(Err(e)) => format!("{} 已将接口 {} 重命名为 {}，但写入Netplan失败: {:#}", Symbol::Warning, old, new, e),
+        });
+        self.rename_form = None;
+        self.screen = Screen::Main;
+        self.refresh();
+        Ok(())
+    }
 
-            netplan.set_static_ip(
-                iface_name,
-                &format!("{}/{}", form.ip_address, prefix),
-                Some(&form.gateway),
-                Some(dns_list),
-            )?;
+    fn handle_mac_form_key(&mut self, key: KeyCode) -> Result<()> {
+        let Some(form) = &mut self.mac_form else {
+            return Ok(());
+        };
 
-            Ok(())
-        } else {
-            Err(anyhow::anyhow!("编辑表单状态丢失"))
+        match key {
+            KeyCode::Esc => {
+                self.mac_form = None;
+                self.screen = Screen::Main;
+            }
+            KeyCode::Backspace => {
+                form.mac.pop();
+            }
+            KeyCode::Char(c) if c.is_ascii_hexdigit() || c == ':' || c == '-' => form.mac.push(c),
+            KeyCode::Enter => {
+                if self.blocked_by_safe_mode() {
+                    return Ok(());
+                }
+                let Some(form) = &mut self.mac_form else {
+                    return Ok(());
+                };
+                let mac = match runtime::parse_mac_address(&form.mac) {
+                    Ok(mac) => mac,
+                    Err(e) => {
+                        form.error_message = Some(format!("{:#}", e));
+                        return Ok(());
+                    }
+                };
+                let iface_name = form.interface_name.clone();
+                let persist = form.persist;
+                if persist && let Some(warning) = self.unmanaged_gate.check(&self.interfaces, &iface_name) {
+                    form.error_message = Some(warning);
+                    return Ok(());
+                }
+                if let Err(e) = runtime::set_mac(&iface_name, &mac) {
+                    form.error_message = Some(format!("修改失败: {:#}", e));
+                    return Ok(());
+                }
+                self.status_message = Some(self.persist_mac_address(&iface_name, &mac, persist, Some(&mac)));
+                self.mac_form = None;
+                self.screen = Screen::Main;
+                self.refresh();
+            }
+            _ => {}
         }
+        Ok(())
     }
 
-    fn toggle_dhcp(&mut self) -> Result<()> {
-        if let Some(i) = self.list_state.selected() {
-            if let Some(iface) = self.interfaces.get(i) {
-                use crate::backend::netplan::NetplanManager;
-                let netplan = NetplanManager::new();
-                netplan.set_dhcp(&iface.name)?;
-            }
+    /// 恢复网卡的出厂MAC地址，并删除Netplan中的macaddress设置
+    fn restore_permanent_mac(&mut self, iface: &NetInterface) -> Result<()> {
+        if self.blocked_by_safe_mode() {
+            return Ok(());
+        }
+        let Some(permanent) = &iface.permanent_mac else {
+            return Ok(());
+        };
+        if iface.netplan_file.is_some() && self.blocked_by_unmanaged(&iface.name) {
+            return Ok(());
+        }
+        if let Err(e) = runtime::set_mac(&iface.name, permanent) {
+            self.status_message = Some(format!("{} 恢复 {} 的原始MAC地址失败: {:#}", Symbol::Error, iface.name, e));
+            return Ok(());
         }
+        self.status_message = Some(self.persist_mac_address(&iface.name, permanent, iface.netplan_file.is_some(), None));
+        self.refresh();
         Ok(())
     }
 
-    fn netmask_to_prefix(netmask: &str) -> Result<u8> {
-        let parts: Vec<u8> = netmask
-            .split('.')
-            .map(|s| s.parse::<u8>())
-            .collect::<Result<Vec<_>, _>>()?;
-
-        if parts.len() != 4 {
-            return Err(anyhow::anyhow!("无效的子网掩码格式"));
+    /// MAC地址已在运行时修改，按需写入Netplan的macaddress，返回状态栏消息
+    fn persist_mac_address(&self, iface_name: &str, mac: &str, persist: bool, netplan_mac: Option<&str>) -> String {
+        if !persist {
+            return format!("{} 接口 {} 的MAC地址已改为 {}（仅运行时，接口未在Netplan中定义）", Symbol::Ok, iface_name, mac);
+        }
+        match self.netplan().set_macaddress(iface_name, netplan_mac) {
+            Ok(_) => format!("{} 接口 {} 的MAC地址已改为 {}", Symbol::Ok, iface_name, mac),
+            Err(e) => format!("{} 接口 {} 的MAC地址已改为 {}，但写入Netplan失败: {:#}", Symbol::Warning, iface_name, mac, e),
         }
-
-        let mask = ((parts[0] as u32) << 24)
-            | ((parts[1] as u32) << 16)
-            | ((parts[2] as u32) << 8)
-            | (parts[3] as u32);
-
-        Ok(mask.count_ones() as u8)
     }
 
-    fn delete_selected_interface(&mut self) -> Result<()> {
-        if let Some(i) = self.list_state.selected() {
-            if let Some(iface) = self.interfaces.get(i).cloned() {
-                // 使用智能删除
-                use crate::backend::removal::RemovalManager;
-                let strategy = RemovalManager::determine_strategy(&iface);
-                RemovalManager::remove_interface(&iface, &strategy)?;
-                self.refresh()?;
+    fn handle_bridge_form_key(&mut self, key: KeyCode) -> Result<()> {
+        let Some(form) = &mut self.bridge_form else {
+            return Ok(());
+        };
 
-                // 调整选中项
-                if self.interfaces.is_empty() {
-                    self.list_state.select(None);
-                } else if i >= self.interfaces.len() {
-                    self.list_state.select(Some(self.interfaces.len() - 1));
+        match key {
+            KeyCode::Esc => {
+                self.bridge_form = None;
+                self.screen = Screen::Main;
+            }
+            KeyCode::Tab | KeyCode::BackTab => form.editing_name = !form.editing_name,
+            KeyCode::Enter => self.create_bridge()?,
+            KeyCode::Backspace if form.editing_name => {
+                form.name.pop();
+            }
+            KeyCode::Char(c) if form.editing_name => form.name.push(c),
+            KeyCode::Up | KeyCode::Char('k') => form.cursor = form.cursor.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') if form.cursor + 1 < form.candidates.len() => {
+                form.cursor += 1;
+            }
+            KeyCode::Char(' ') => {
+                if let Some(candidate) = form.candidates.get_mut(form.cursor) {
+                    candidate.selected = !candidate.selected;
+                    form.address_confirmed = false;
+                    form.error_message = None;
                 }
             }
+            _ => {}
         }
         Ok(())
     }
 
-    fn ui(&mut self, f: &mut Frame) {
-        match self.screen {
-            Screen::Main => self.draw_main(f),
-            Screen::Help => self.draw_help(f),
-            Screen::EditIface => {
-                self.draw_main(f);
-                self.draw_edit_form(f);
+    /// 创建网桥、加入选中的成员并启用网桥
+    fn create_bridge(&mut self) -> Result<()> {
+        if self.blocked_by_safe_mode() {
+            return Ok(());
+        }
+        let Some(form) = &mut self.bridge_form else {
+            return Ok(());
+        };
+
+        let name = form.name.trim().to_string();
+        if let Err(e) = runtime::validate_interface_name(&name, &self.interfaces) {
+            form.address_confirmed = false;
+            form.error_message = Some(format!("{:#}", e));
+            return Ok(());
+        }
+        if !form.address_confirmed
+            && let Some(warning) = member_address_warning(&form.candidates, "网桥") {
+            form.address_confirmed = true;
+            form.error_message = Some(warning);
+            return Ok(());
+        }
+
+        let members: Vec<String> = selected_members(&form.candidates).iter().map(|member| member.name.clone()).collect();
+        match runtime::create_bridge_with_members(&name, &members) {
+            Ok(()) => {
+                self.status_message = Some(if members.is_empty() {
+                    format!("{} 已创建网桥 {}（没有成员）", Symbol::Ok, name)
+                } else {
+                    format!("{} 已创建网桥 {}，成员: {}", Symbol::Ok, name, members.join(", "))
+                });
+                self.bridge_form = None;
+                self.screen = Screen::Main;
             }
-            Screen::ToggleDhcp => {
-                self.draw_main(f);
-                self.draw_toggle_dhcp(f);
+            // 失败时已撤销创建，撤销未完成时错误信息中会说明
+            Err(e) => {
+                form.address_confirmed = false;
+                form.error_message = Some(format!("创建失败: {:#}", e));
             }
-            Screen::ConfirmDelete => {
-                self.draw_main(f);
-                self.draw_confirm_delete(f);
+        }
+        self.refresh();
+        Ok(())
+    }
+
+    fn handle_bond_form_key(&mut self, key: KeyCode) -> Result<()> {
+        let Some(form) = &mut self.bond_form else {
+            return Ok(());
+        };
+
+        match (form.current_field, key) {
+            (_, KeyCode::Esc) => {
+                self.bond_form = None;
+                self.screen = Screen::Main;
             }
-            Screen::OwnerActions => {
-                self.draw_main(f);
-                self.draw_owner_actions(f);
+            (_, KeyCode::Tab) => form.current_field = (form.current_field + 1) % 3,
+            (_, KeyCode::BackTab) => form.current_field = (form.current_field + 2) % 3,
+            (_, KeyCode::Enter) => self.create_bond()?,
+            (BondFormState::NAME_FIELD, KeyCode::Backspace) => {
+                form.name.pop();
             }
-            Screen::InterfaceActions => {
-                self.draw_main(f);
-                self.draw_interface_actions(f);
+            (BondFormState::NAME_FIELD, KeyCode::Char(c)) => form.name.push(c),
+            (BondFormState::MODE_FIELD, KeyCode::Left | KeyCode::Up | KeyCode::Char('k')) => form.cycle_mode(false),
+            (BondFormState::MODE_FIELD, KeyCode::Right | KeyCode::Down | KeyCode::Char('j') | KeyCode::Char(' ')) => {
+                form.cycle_mode(true)
+            }
+            (BondFormState::MEMBERS_FIELD, KeyCode::Up | KeyCode::Char('k')) => form.cursor = form.cursor.saturating_sub(1),
+            (BondFormState::MEMBERS_FIELD, KeyCode::Down | KeyCode::Char('j')) if form.cursor + 1 < form.candidates.len() => {
+                form.cursor += 1;
             }
+            (BondFormState::MEMBERS_FIELD, KeyCode::Char(' ')) => {
+                if let Some(candidate) = form.candidates.get_mut(form.cursor) {
+                    candidate.selected = !candidate.selected;
+                    form.address_confirmed = false;
+                    form.error_message = None;
+                }
+            }
+            _ => {}
         }
+        Ok(())
     }
 
-    fn draw_main(&mut self, f: &mut Frame) {
-        let chunks = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
-            .split(f.size());
+    /// 创建链路聚合、加入选中的成员并启用
+    fn create_bond(&mut self) -> Result<()> {
+        if self.blocked_by_safe_mode() {
+            return Ok(());
+        }
+        let Some(form) = &mut self.bond_form else {
+            return Ok(());
+        };
 
-        self.draw_interface_list(f, chunks[0]);
-        self.draw_details(f, chunks[1]);
+        let name = form.name.trim().to_string();
+        if let Err(e) = runtime::validate_interface_name(&name, &self.interfaces) {
+            form.address_confirmed = false;
+            form.error_message = Some(format!("{:#}", e));
+            return Ok(());
+        }
+        if !form.address_confirmed
+            && let Some(warning) = member_address_warning(&form.candidates, "链路聚合") {
+            form.address_confirmed = true;
+            form.error_message = Some(warning);
+            return Ok(());
+        }
+
+        let mode = form.mode_name();
+        let members: Vec<String> = selected_members(&form.candidates).iter().map(|member| member.name.clone()).collect();
+        match runtime::create_bond_with_members(&name, mode, &members) {
+            Ok(()) => {
+                self.status_message = Some(if members.is_empty() {
+                    format!("{} 已创建链路聚合 {} ({})，没有成员", Symbol::Ok, name, mode)
+                } else {
+                    format!("{} 已创建链路聚合 {} ({})，成员: {}", Symbol::Ok, name, mode, members.join(", "))
+                });
+                self.bond_form = None;
+                self.screen = Screen::Main;
+            }
+            // 失败时已撤销创建，撤销未完成时错误信息中会说明
+            Err(e) => {
+                form.address_confirmed = false;
+                form.error_message = Some(format!("创建失败: {:#}", e));
+            }
+        }
+        self.refresh();
+        Ok(())
     }
 
-    fn draw_interface_list(&mut self, f: &mut Frame, area: Rect) {
-        let items: Vec<ListItem> = self
-            .interfaces
-            .iter()
-            .map(|iface| {
-                let icon = match iface.kind {
-                    InterfaceKind::Physical => "🔌",
-                    InterfaceKind::Loopback => "🔄",
-                    InterfaceKind::Docker => "🐳",
-                    InterfaceKind::WireGuard => "🔐",
-                    InterfaceKind::Bridge => "🌉",
-                    InterfaceKind::Veth => "🔗",
-                    InterfaceKind::Vlan => "📡",
-                    InterfaceKind::Tun => "🚇",
-                    InterfaceKind::Tap => "🚰",
-                    InterfaceKind::Unknown => "❓",
-                };
+    fn handle_vlan_form_key(&mut self, key: KeyCode) -> Result<()> {
+        let Some(form) = &mut self.vlan_form else {
+            return Ok(());
+        };
 
-                let state_icon = match iface.state {
-                    InterfaceState::Up => "✅",
-                    InterfaceState::Down => "❌",
-                    InterfaceState::Unknown => "❓",
-                };
+        // VLAN ID只能输入数字，其他字母键用作操作
+        match key {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.vlan_form = None;
+                self.screen = Screen::Main;
+            }
+            KeyCode::Up | KeyCode::Char('k') => form.parent = form.parent.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') if form.parent + 1 < form.parents.len() => {
+                form.parent += 1;
+            }
+            KeyCode::Char(c) if c.is_ascii_digit() => form.vid.push(c),
+            KeyCode::Backspace => {
+                form.vid.pop();
+            }
+            KeyCode::Char('e') => form.edit_after = !form.edit_after,
+            KeyCode::Enter => self.create_vlan()?,
+            _ => {}
+        }
+        Ok(())
+    }
 
-                let speed_info = format!(
-                    "↓ {} ↑ {}",
-                    format_speed(iface.traffic_stats.rx_speed),
-                    format_speed(iface.traffic_stats.tx_speed)
-                );
+    /// 创建并启用VLAN子接口，按需打开编辑表单设置IP
+    fn create_vlan(&mut self) -> Result<()> {
+        if self.blocked_by_safe_mode() {
+            return Ok(());
+        }
+        let Some(form) = &mut self.vlan_form else {
+            return Ok(());
+        };
+        let (parent, vid) = match form.validate(&self.interfaces) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                form.error_message = Some(format!("{:#}", e));
+                return Ok(());
+            }
+        };
 
-                let content = format!("{} {} {} - {}", icon, state_icon, iface.name, speed_info);
-                ListItem::new(content)
-            })
-            .collect();
+        let result = runtime::create_vlan(&parent, vid)
+            .and_then(|name| runtime::set_interface_up(&name).map(|_| name));
+        let name = match result {
+            Ok(name) => name,
+            Err(e) => {
+                form.error_message = Some(format!("创建失败: {:#}", e));
+                self.refresh();
+                return Ok(());
+            }
+        };
 
-        let list = List::new(items)
-            .block(
-                Block::default()
-                    .title("网络接口 (↑↓:选择 r:刷新 q:退出 ?:帮助)")
-                    .borders(Borders::ALL)
-                    .border_type(BorderType::Rounded),
-            )
-            .highlight_style(Style::default().bg(Color::Blue).add_modifier(Modifier::BOLD))
-            .highlight_symbol(">> ");
+        let edit_after = form.edit_after;
+        self.vlan_form = None;
+        self.screen = Screen::Main;
+        self.status_message = Some(format!("{} 已在 {} 上创建VLAN子接口 {}", Symbol::Ok, parent, name));
+        self.refresh();
+
+        if edit_after
+            && let Some(index) = self.interfaces.iter().position(|iface| iface.name == name) {
+            self.list_state.select(Some(index));
+            let mut form = EditFormState::new(&self.interfaces[index])
+                .with_netmask_format(self.netmask_format)
+                .with_history(&self.edit_history);
+            // VLAN子接口本身只在运行时创建，地址默认也不写入Netplan的ethernets
+            form.runtime_only = true;
+            self.edit_form = Some(form);
+            self.open_form(Screen::EditIface, &name);
+        }
+        Ok(())
+    }
 
-        f.render_stateful_widget(list, area, &mut self.list_state);
+    fn handle_address_form_key(&mut self, key: KeyCode) -> Result<()> {
+        let Some(form) = &mut self.address_form else {
+            return Ok(());
+        };
+
+        if form.adding {
+            match key {
+                KeyCode::Esc => {
+                    form.adding = false;
+                    form.input.clear();
+                    form.error_message = None;
+                }
+                KeyCode::Backspace => {
+                    form.input.pop();
+                }
+                KeyCode::Char(c) => form.input.push(c),
+                KeyCode::Enter => self.add_address()?,
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        // 除d以外的按键都会取消待确认的删除
+        if !matches!(key, KeyCode::Char('d') | KeyCode::Delete) {
+            form.confirm_delete = false;
+        }
+        match key {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.address_form = None;
+                self.screen = Screen::Main;
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                form.selected = form.selected.saturating_sub(1);
+            }
+            KeyCode::Down | KeyCode::Char('j') if form.selected + 1 < form.addresses.len() => {
+                form.selected += 1;
+            }
+            KeyCode::Char('a') => {
+                form.adding = true;
+                form.error_message = None;
+            }
+            KeyCode::Char('d') | KeyCode::Delete => {
+                if form.addresses.is_empty() {
+                    return Ok(());
+                }
+                if !form.confirm_delete {
+                    form.confirm_delete = true;
+                    form.error_message = Some(format!("再次按 d 删除 {}", form.addresses[form.selected]));
+                    return Ok(());
+                }
+                self.delete_selected_address()?;
+            }
+            _ => {}
+        }
+        Ok(())
     }
 
-    fn draw_details(&self, f: &mut Frame, area: Rect) {
-        let selected = self.list_state.selected();
+    /// 追加表单中输入的地址，不清除接口上已有的地址
+    fn add_address(&mut self) -> Result<()> {
+        if self.blocked_by_safe_mode() {
+            return Ok(());
+        }
+        let Some(form) = &mut self.address_form else {
+            return Ok(());
+        };
+        let cidr = match apply::parse_ipv4_cidr(form.input.trim()) {
+            Ok((ip, prefix)) => format!("{}/{}", ip, prefix),
+            Err(e) => {
+                form.error_message = Some(format!("{:#}", e));
+                return Ok(());
+            }
+        };
+
+        let iface_name = form.interface_name.clone();
+        let persist = form.persist;
+        if persist && let Some(warning) = self.unmanaged_gate.check(&self.interfaces, &iface_name) {
+            form.error_message = Some(warning);
+            return Ok(());
+        }
+        let netplan = self.netplan();
+        let result = runtime::add_ipv4_alias(&iface_name, &cidr)
+            .and_then(|_| if persist { netplan.add_address(&iface_name, &cidr).map(|_| ()) } else { Ok(()) });
+        self.finish_address_change(result, format!("已为 {} 添加地址 {}", iface_name, cidr))
+    }
+
+    /// 删除表单中选中的地址，其他地址保持不变
+    fn delete_selected_address(&mut self) -> Result<()> {
+        if self.blocked_by_safe_mode() {
+            return Ok(());
+        }
+        let Some(form) = &mut self.address_form else {
+            return Ok(());
+        };
+        form.confirm_delete = false;
+        let Some(cidr) = form.addresses.get(form.selected).cloned() else {
+            return Ok(());
+        };
 
-        if let Some(i) = selected {
-            if let Some(iface) = self.interfaces.get(i) {
-                let chunks = Layout::default()
-                    .direction(Direction::Vertical)
-                    .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
-                    .split(area);
+        let iface_name = form.interface_name.clone();
+        let persist = form.persist;
+        if persist && let Some(warning) = self.unmanaged_gate.check(&self.interfaces, &iface_name) {
+            form.error_message = Some(warning);
+            return Ok(());
+        }
+        let netplan = self.netplan();
+        let result = runtime::remove_ipv4_address(&iface_name, &cidr)
+            .and_then(|_| if persist { netplan.remove_address(&iface_name, &cidr).map(|_| ()) } else { Ok(()) });
+        self.finish_address_change(result, format!("已删除 {} 的地址 {}", iface_name, cidr))
+    }
 
-                self.draw_interface_info(f, chunks[0], iface);
-                self.draw_traffic_stats(f, chunks[1], iface);
+    /// 地址增删后刷新接口和表单中的地址列表，并显示结果
+    fn finish_address_change(&mut self, result: Result<()>, message: String) -> Result<()> {
+        if let Err(e) = result {
+            if let Some(form) = &mut self.address_form {
+                form.error_message = Some(format!("操作失败: {:#}", e));
             }
+            return Ok(());
+        }
+
+        self.refresh();
+        let Some(form) = &mut self.address_form else {
+            return Ok(());
+        };
+        self.status_message = Some(if form.persist {
+            format!("{} {}，已写入Netplan", Symbol::Ok, message)
+        } else {
+            format!("{} {}（仅运行时，接口未在Netplan中定义）", Symbol::Ok, message)
+        });
+        if let Some(iface) = self.interfaces.iter().find(|iface| iface.name == form.interface_name) {
+            form.addresses = iface.ipv4_addresses.clone();
         }
+        form.selected = form.selected.min(form.addresses.len().saturating_sub(1));
+        form.input.clear();
+        form.adding = false;
+        form.error_message = None;
+        Ok(())
     }
 
-    fn draw_interface_info(&self, f: &mut Frame, area: Rect, iface: &NetInterface) {
-        let mut lines = vec![
-            Line::from(vec![
-                Span::styled("接口名称: ", Style::default().fg(Color::Cyan)),
-                Span::raw(&iface.name),
-            ]),
-            Line::from(vec![
-                Span::styled("类型: ", Style::default().fg(Color::Cyan)),
-                Span::raw(format!("{:?}", iface.kind)),
-            ]),
-            Line::from(vec![
-                Span::styled("状态: ", Style::default().fg(Color::Cyan)),
-                Span::raw(format!("{:?}", iface.state)),
-            ]),
-        ];
+    fn on_tick(&mut self) -> Result<()> {
+        // 接口增加或删除后自动刷新列表
+        if self.hotplug.poll() {
+            self.refresh();
+        }
 
-        if let Some(mac) = &iface.mac_address {
-            lines.push(Line::from(vec![
-                Span::styled("MAC地址: ", Style::default().fg(Color::Cyan)),
-                Span::raw(mac),
-            ]));
+        // netplan try 结束后保存输出并刷新
+        if let Some(result) = self.try_session.as_mut().and_then(|session| session.poll()) {
+            self.try_session = None;
+            self.try_result = Some(result);
+            self.refresh();
         }
 
-        if !iface.ipv4_addresses.is_empty() {
-            lines.push(Line::from(vec![
-                Span::styled("IPv4地址: ", Style::default().fg(Color::Cyan)),
-                Span::raw(iface.ipv4_addresses.join(", ")),
-            ]));
+        if self.last_update.elapsed() >= Duration::from_secs(1) {
+            // 轻量更新链路状态，用于抖动检测
+            for iface in &mut self.interfaces {
+                let (state, carrier) = runtime::read_link_state(&iface.name);
+                iface.state = state;
+                iface.carrier = carrier;
+                self.flap_detector.observe(&iface.name, iface.link_health());
+                if iface.kind == InterfaceKind::Bond {
+                    iface.bond_info = runtime::read_bond_info(&iface.name);
+                }
+            }
+
+            // 接口可能在两次热插拔检测之间被删除，读取失败时只提示，下次刷新后恢复
+            if let Err(e) = self.traffic_monitor.update_all(&mut self.interfaces) {
+                self.status_message = Some(format!("{} 读取流量统计失败: {:#}", Symbol::Warning, e));
+            }
+            self.check_error_rates();
+            self.last_update = Instant::now();
+
+            if self.expanded_stats
+                && self.last_extended_update.is_none_or(|at| at.elapsed() >= EXTENDED_STATS_INTERVAL)
+            {
+                self.update_extended_stats();
+            }
         }
+        Ok(())
+    }
 
-        // 显示子网掩码
-        if let Some(ipv4_config) = &iface.ipv4_config {
-            lines.push(Line::from(vec![
-                Span::styled("子网掩码: ", Style::default().fg(Color::Cyan)),
-                Span::raw(&ipv4_config.netmask),
-            ]));
+    /// 为选中接口读取扩展统计（组播、冲突），失败时只记录日志
+    fn update_extended_stats(&mut self) {
+        self.last_extended_update = Some(Instant::now());
+        let Some(iface) = self.list_state.selected().and_then(|i| self.interfaces.get_mut(i)) else {
+            return;
+        };
+        if let Err(e) = self.traffic_monitor.update_extended(iface) {
+            tracing::warn!("{:#}", e);
+        }
+    }
 
-            // 显示网关
-            if let Some(gateway) = &ipv4_config.gateway {
-                lines.push(Line::from(vec![
-                    Span::styled("网关: ", Style::default().fg(Color::Cyan)),
-                    Span::raw(gateway),
-                ]));
+    /// 检查错误/丢包速率，接口新进入告警状态时在状态栏提示
+    fn check_error_rates(&mut self) {
+        let alerts: Vec<String> = self
+            .interfaces
+            .iter()
+            .filter_map(|iface| {
+                self.error_rate_monitor
+                    .observe(&iface.name, &iface.traffic_stats)
+                    .map(|alert| alert.message(&iface.name))
+            })
+            .collect();
+        if !alerts.is_empty() {
+            for alert in &alerts {
+                tracing::warn!("{}", alert);
             }
+            self.status_message = Some(format!("{} {}", Symbol::Warning, alerts.join("；")));
         }
+    }
 
-        // 显示DNS
-        if let Some(dns_config) = &iface.dns_config {
-            if !dns_config.nameservers.is_empty() {
-                lines.push(Line::from(vec![
-                    Span::styled("DNS: ", Style::default().fg(Color::Cyan)),
-                    Span::raw(dns_config.nameservers.join(",")),
-                ]));
+    /// 刷新接口列表，失败时（如ip命令瞬时出错）保留原列表并在状态栏提示，不退出界面
+    fn refresh(&mut self) {
+        if let Err(e) = self.reload_interfaces() {
+            self.status_message = Some(format!("{} 刷新接口列表失败: {:#}", Symbol::Error, e));
+        }
+    }
+
+    /// 重新读取接口列表、流量统计和Netplan信息
+    fn reload_interfaces(&mut self) -> Result<()> {
+        // 记住选中接口的名称，刷新后按名称重新定位
+        let selected_index = self.list_state.selected();
+        let selected_name = selected_index
+            .and_then(|i| self.interfaces.get(i))
+            .map(|iface| iface.name.clone());
+
+        self.interfaces = runtime::list_interfaces(self.options.owner_detection)?;
+        if !self.options.kinds.is_empty() {
+            self.interfaces.retain(|iface| self.options.kinds.contains(&iface.kind));
+        }
+        self.traffic_monitor.update_all(&mut self.interfaces)?;
+
+        order_pinned(&mut self.interfaces, &self.options.pinned);
+
+        let netplan = self.netplan();
+        let mut netplan_files = netplan.interface_files();
+        let managed = netplan.managed_interfaces();
+        let merged = netplan.merged_ethernets();
+        for iface in &mut self.interfaces {
+            iface.netplan_file = netplan_files.remove(&iface.name);
+            iface.nicman_managed = managed.contains(&iface.name)
+                || iface.netplan_file.as_deref().is_some_and(netplan::created_by_nicman);
+
+            // 与所有文件合并后的ethernets条目对比
+            iface.config_drift = merged
+                .get(&iface.name)
+                .map(|entry| netplan::runtime_differences(entry, iface))
+                .unwrap_or_default();
+        }
+
+        self.list_state.select(reselect_index(
+            &self.interfaces,
+            selected_name.as_deref(),
+            selected_index,
+        ));
+        Ok(())
+    }
+
+    fn next(&mut self) {
+        // 列表为空（如所有接口都被过滤掉）时不移动
+        if self.interfaces.is_empty() {
+            self.list_state.select(None);
+            return;
+        }
+        let i = match self.list_state.selected() {
+            Some(i) => {
+                if i >= self.interfaces.len() - 1 {
+                    0
+                } else {
+                    i + 1
+                }
             }
+            None => 0,
+        };
+        self.list_state.select(Some(i));
+    }
+
+    fn previous(&mut self) {
+        if self.interfaces.is_empty() {
+            self.list_state.select(None);
+            return;
         }
+        let i = match self.list_state.selected() {
+            Some(i) => {
+                if i == 0 {
+                    self.interfaces.len() - 1
+                } else {
+                    i - 1
+                }
+            }
+            None => 0,
+        };
+        self.list_state.select(Some(i));
+    }
 
-        if !iface.ipv6_addresses.is_empty() {
-            lines.push(Line::from(vec![
-                Span::styled("IPv6地址: ", Style::default().fg(Color::Cyan)),
-                Span::raw(iface.ipv6_addresses.join(", ")),
-            ]));
+    fn toggle_interface_up(&mut self) -> Result<()> {
+        if self.blocked_by_safe_mode() {
+            return Ok(());
+        }
+        if let Some(i) = self.list_state.selected()
+            && let Some(iface) = self.interfaces.get(i) {
+            let name = iface.name.clone();
+            runtime::set_interface_up(&name)?;
+
+            // 等待载波，给出链路是否真正连通的反馈
+            let has_carrier = runtime::wait_for_carrier(&name, Duration::from_secs(3));
+            self.status_message = Some(if has_carrier {
+                format!("{} 接口 {} 已启用，已获得载波", Symbol::Ok, name)
+            } else {
+                format!("{} 接口 {} 启用成功但无载波", Symbol::Warning, name)
+            });
+            self.refresh();
         }
+        Ok(())
+    }
 
-        if let Some(owner) = &iface.owner {
-            lines.push(Line::from(""));
-            lines.push(Line::from(vec![
-                Span::styled("创建者: ", Style::default().fg(Color::Yellow)),
-                Span::raw(owner.display_name()),
-            ]));
+    /// 设置网络唤醒：立即生效，接口已在Netplan中定义时同时持久化
+    fn set_wake_on_lan(&mut self, iface: &NetInterface, enabled: bool) -> Result<()> {
+        if self.blocked_by_safe_mode() {
+            return Ok(());
+        }
+        if iface.netplan_file.is_some() && self.blocked_by_unmanaged(&iface.name) {
+            return Ok(());
+        }
+        let state = if enabled { "开启" } else { "关闭" };
+        // 部分网卡声明支持魔术包唤醒但 ethtool -s wol 仍会失败
+        if let Err(e) = runtime::set_wake_on_lan(&iface.name, enabled) {
+            self.status_message = Some(format!("{} {}接口 {} 的网络唤醒失败: {:#}", Symbol::Error, state, iface.name, e));
+            return Ok(());
+        }
+        self.status_message = Some(if iface.netplan_file.is_none() {
+            format!("{} 接口 {} 的网络唤醒已{}（仅运行时，接口未在Netplan中定义）", Symbol::Ok, iface.name, state)
+        } else {
+            match self.netplan().set_wakeonlan(&iface.name, enabled) {
+                Ok(_) => format!("{} 接口 {} 的网络唤醒已{}", Symbol::Ok, iface.name, state),
+                Err(e) => format!("{} 接口 {} 的网络唤醒已{}，但写入Netplan失败: {}", Symbol::Warning, iface.name, state, e),
+            }
+        });
+        self.refresh();
+        Ok(())
+    }
 
-            // 显示详细信息和操作提示
-            use crate::model::InterfaceOwner;
-            match owner {
-                InterfaceOwner::SystemdService { name, status, .. } => {
-                    lines.push(Line::from(vec![
-                        Span::styled("  服务名: ", Style::default().fg(Color::Cyan)),
-                        Span::raw(name),
-                    ]));
-                    lines.push(Line::from(vec![
+    /// 设置网桥STP：立即生效，网桥已在Netplan中定义时同时持久化
+    fn set_bridge_stp(&mut self, bridge: &NetInterface, enabled: bool) -> Result<()> {
+        if self.blocked_by_safe_mode() {
+            return Ok(());
+        }
+        if bridge.netplan_file.is_some() && self.blocked_by_unmanaged(&bridge.name) {
+            return Ok(());
+        }
+        let state = if enabled { "开启" } else { "关闭" };
+        if let Err(e) = runtime::set_bridge_stp(&bridge.name, enabled) {
+            self.status_message = Some(format!("{} {}网桥 {} 的STP失败: {:#}", Symbol::Error, state, bridge.name, e));
+            return Ok(());
+        }
+        self.status_message = Some(if bridge.netplan_file.is_none() {
+            format!("{} 网桥 {} 的STP已{}（仅运行时，网桥未在Netplan中定义）", Symbol::Ok, bridge.name, state)
+        } else {
+            match self.netplan().set_bridge_stp(&bridge.name, enabled) {
+                Ok(_) => format!("{} 网桥 {} 的STP已{}", Symbol::Ok, bridge.name, state),
+                Err(e) => format!("{} 网桥 {} 的STP已{}，但写入Netplan失败: {}", Symbol::Warning, bridge.name, state, e),
+            }
+        });
+        self.refresh();
+        Ok(())
+    }
+
+    /// 设置网桥VLAN过滤（仅运行时）
+    fn set_bridge_vlan_filtering(&mut self, bridge: &str, enabled: bool) {
+        if self.blocked_by_safe_mode() {
+            return;
+        }
+        let state = if enabled { "开启" } else { "关闭" };
+        self.status_message = Some(match runtime::set_bridge_vlan_filtering(bridge, enabled) {
+            Ok(()) => format!("{} 网桥 {} 的VLAN过滤已{}", Symbol::Ok, bridge, state),
+            Err(e) => format!("{} {}网桥 {} 的VLAN过滤失败: {:#}", Symbol::Error, state, bridge, e),
+        });
+        self.refresh();
+    }
+
+    fn toggle_interface_down(&mut self) -> Result<()> {
+        if self.blocked_by_safe_mode() {
+            return Ok(());
+        }
+        if let Some(i) = self.list_state.selected()
+            && let Some(iface) = self.interfaces.get(i) {
+            runtime::set_interface_down(&iface.name)?;
+            self.refresh();
+        }
+        Ok(())
+    }
+
+    /// 运行时修改并持久化编辑表单，返回netplan修改的生效方式（临时修改时为None）
+    fn save_interface_config(&mut self) -> Result<Option<ApplyAdvice>> {
+        if let Some(form) = &self.edit_form {
+            let iface_name = &form.interface_name;
+
+            // 纯IPv6模式：清除IPv4地址，只配置IPv6地址和IPv6网关
+            if form.ipv6_only {
+                return self.save_ipv6_only_config();
+            }
+
+            // 验证输入（IP字段为CIDR时前缀已同步到子网掩码）
+            let ip_address = form.ip_only();
+            if ip_address.is_empty() {
+                return Err(anyhow::anyhow!("IP地址不能为空"));
+            }
+
+            // 将子网掩码转换为前缀长度
+            let prefix = Self::netmask_to_prefix(&form.netmask)?;
+
+            // 地址、网关（可以为空，点对点/内部网络没有默认路由）、DNS和搜索域在修改系统之前验证
+            let config = StaticIpv4::new(ip_address, prefix, Some(&form.gateway), form.nameserver_config()?)?;
+
+            // IPv6地址和网关只在修改过时才重新设置，先全部验证再动手（IPv6部分可以为空）
+            let changed = form.changed_fields();
+            let ipv6_changed = changed.contains(&EditFormState::IPV6_FIELD) || changed.contains(&EditFormState::GATEWAY6_FIELD);
+            let ipv6 = form.ipv6_settings()?;
+            let mtu = form.mtu_change()?;
+
+            // 与命令行 set-ip 走同一流程：运行时修改后按需写入Netplan
+            let netplan = self.netplan();
+            let written = apply::apply_static_ipv4(
+                &netplan,
+                iface_name,
+                &config,
+                Some(&ipv6).filter(|_| ipv6_changed),
+                mtu,
+                !form.runtime_only,
+            )?;
+
+            // 临时修改不写入Netplan，没有生效方式建议
+            Ok(written.map(|(file, backup)| netplan.apply_advice(&file, backup.as_deref())))
+        } else {
+            Err(anyhow::anyhow!("编辑表单状态丢失"))
+        }
+    }
+
+    /// 纯IPv6模式的保存：清除IPv4地址，设置IPv6地址和网关，并写入不含IPv4的Netplan配置
+    fn save_ipv6_only_config(&self) -> Result<Option<ApplyAdvice>> {
+        let Some(form) = &self.edit_form else {
+            return Err(anyhow::anyhow!("编辑表单状态丢失"));
+        };
+        let iface_name = &form.interface_name;
+
+        // 先全部验证再动手
+        let ipv6 = form.ipv6_settings()?;
+        if ipv6.addresses.is_empty() {
+            return Err(anyhow::anyhow!("纯IPv6模式下IPv6地址不能为空"));
+        }
+        let nameservers = form.nameserver_config()?;
+        let mtu = form.mtu_change()?;
+
+        // 1. 运行时修改（立即生效）
+        runtime::flush_ipv4_addresses(iface_name)?;
+        apply::apply_runtime_ipv6(iface_name, &ipv6)?;
+        if let Some(mtu) = mtu {
+            runtime::set_mtu(iface_name, mtu)?;
+        }
+        if form.runtime_only {
+            return Ok(None);
+        }
+
+        // 2. 持久化到Netplan（MTU与地址一起写入，只备份和写入一次）
+        let netplan = self.netplan();
+        let (file, backup) = netplan.set_static_ipv6(iface_name, &ipv6, nameservers, mtu)?;
+        Ok(Some(netplan.apply_advice(&file, backup.as_deref())))
+    }
+
+    /// 保存成功后按需刷新DNS解析器，并在状态栏显示结果
+    fn refresh_dns_after_save(&mut self) {
+        let Some(form) = &self.edit_form else {
+            return;
+        };
+        if !form.refresh_dns {
+            return;
+        }
+
+        // 保存前已验证过，这里不会失败
+        let dns_list = dns::parse_nameservers(&form.dns).unwrap_or_default();
+        let search = dns::parse_search_domains(&form.search_domains).unwrap_or_default();
+
+        self.status_message = Some(match dns::refresh_resolver(&form.interface_name, &dns_list, &search) {
+            Ok(message) => format!("{} 配置已保存，{}", Symbol::Ok, message),
+            Err(e) => format!("{} 配置已保存，但DNS刷新失败: {}", Symbol::Warning, e),
+        });
+    }
+
+    /// 查看选中接口创建者（进程或Docker容器）的详情，其他创建者类型只提示
+    fn open_owner_details(&mut self) {
+        use crate::model::InterfaceOwner;
+
+        let result = match self.selected_interface().and_then(|iface| iface.owner.as_ref()) {
+            Some(InterfaceOwner::Process { pid, .. }) => owner_inspect::inspect_process(*pid),
+            // docker0等系统网桥的id是"system"，没有对应的容器
+            Some(InterfaceOwner::DockerContainer { id, .. }) if id != "system" => owner_inspect::inspect_container(id),
+            _ => {
+                self.status_message = Some(format!("{} 只能查看进程或Docker容器创建者的详情", Symbol::Warning));
+                self.screen = Screen::Main;
+                return;
+            }
+        };
+        self.owner_details = Some(result.map_err(|e| format!("{:#}", e)));
+        self.screen = Screen::OwnerDetails;
+    }
+
+    /// 读取选中接口的邻居表
+    fn open_neighbors(&mut self) {
+        let Some(iface) = self.selected_interface() else {
+            return;
+        };
+        self.neighbors = Some(neighbors::list_neighbors(&iface.name).map_err(|e| format!("{:#}", e)));
+        self.screen = Screen::Neighbors;
+    }
+
+    /// 打开接口的表单或确认页，并确定保存时写入的netplan文件（绘制时不再重复扫描配置目录）
+    fn open_form(&mut self, screen: Screen, iface_name: &str) {
+        self.target_file = match self.netplan().target_config_file(iface_name) {
+            Ok(path) => path.display().to_string(),
+            Err(e) => format!("无法确定 ({})", e),
+        };
+        self.screen = screen;
+    }
+
+    /// 预览把运行时配置写入netplan的内容
+    fn open_freeze_preview(&mut self, iface: &NetInterface) {
+        match FrozenConfig::from_interface(iface, dns::link_dns_config(iface)) {
+            Some(frozen) => {
+                self.freeze_preview = Some(frozen);
+                self.open_form(Screen::FreezeConfig, &iface.name);
+            }
+            None => {
+                self.status_message = Some(format!("{} {} 没有IPv4地址，无法写入静态配置", Symbol::Warning, iface.name));
+                self.screen = Screen::Main;
+            }
+        }
+    }
+
+    /// 把预览中的运行时配置通过 set_static_ip 写入netplan
+    fn write_frozen_config(&mut self) -> Result<()> {
+        if self.blocked_by_safe_mode() {
+            self.freeze_preview = None;
+            return Ok(());
+        }
+        let Some(frozen) = self.freeze_preview.take() else {
+            return Ok(());
+        };
+        self.screen = Screen::Main;
+
+        let netplan = self.netplan();
+        let nameservers = Some(NameserverConfig { addresses: frozen.nameservers.clone(), search: frozen.search.clone() })
+            .filter(|ns| !ns.addresses.is_empty() || !ns.search.is_empty());
+        self.status_message = Some(
+            match netplan.set_static_ip(&frozen.interface_name, &frozen.address, frozen.gateway.as_deref(), None, nameservers, None) {
+                Ok((file, backup)) => {
+                    self.runtime_only_changes.remove(&frozen.interface_name);
+                    let advice = netplan.apply_advice(&file, backup.as_deref());
+                    format!("{} 已将 {} 的当前配置写入Netplan，{}", advice_symbol(&advice), frozen.interface_name, advice.message())
+                }
+                Err(e) => format!("{} 写入Netplan失败: {:#}", Symbol::Error, e),
+            },
+        );
+        self.refresh();
+        Ok(())
+    }
+
+    fn toggle_dhcp(&mut self) -> Result<()> {
+        if self.blocked_by_safe_mode() {
+            return Ok(());
+        }
+        if let Some(i) = self.list_state.selected()
+            && let Some(iface) = self.interfaces.get(i) {
+            let netplan = self.netplan();
+            let (file, backup) = netplan.set_dhcp(&iface.name, self.dhcp_keep_dns)?;
+            let advice = netplan.apply_advice(&file, backup.as_deref());
+            // Netplan中的DHCP设置取代了之前未持久化的静态修改
+            self.runtime_only_changes.remove(&iface.name);
+            self.status_message = Some(format!("{} 已切换为DHCP，{}", advice_symbol(&advice), advice.message()));
+        }
+        Ok(())
+    }
+
+    /// 从Netplan中删除选中接口的条目并清除运行时地址，可选择同时禁用接口
+    fn clear_interface_config(&mut self) {
+        if self.blocked_by_safe_mode() {
+            return;
+        }
+        let Some(iface_name) = self.selected_interface().map(|iface| iface.name.clone()) else {
+            return;
+        };
+
+        // 逐步执行并记录结果，中途失败时Netplan可能已被修改，需要告知备份位置
+        let mut done = Vec::new();
+        let mut failed = Vec::new();
+        let mut netplan_changed = false;
+        match self.netplan().remove_interface(&iface_name) {
+            Ok(backups) if backups.is_empty() => done.push("Netplan中未定义该接口".to_string()),
+            Ok(backups) => {
+                netplan_changed = true;
+                let backups: Vec<String> = backups.iter().map(|backup| backup.display().to_string()).collect();
+                done.push(format!("已从 {} 个Netplan文件中删除（备份: {}）", backups.len(), backups.join(", ")));
+            }
+            Err(e) => failed.push(format!("删除Netplan条目失败: {:#}", e)),
+        }
+        match runtime::flush_ipv4_addresses(&iface_name) {
+            Ok(()) => done.push("已清除IPv4地址".to_string()),
+            Err(e) => failed.push(format!("清除IPv4地址失败: {:#}", e)),
+        }
+        match runtime::flush_ipv6_addresses(&iface_name) {
+            Ok(()) => done.push("已清除IPv6地址".to_string()),
+            Err(e) => failed.push(format!("清除IPv6地址失败: {:#}", e)),
+        }
+        if self.clear_link_down {
+            match runtime::set_interface_down(&iface_name) {
+                Ok(()) => done.push("已禁用接口".to_string()),
+                Err(e) => failed.push(format!("禁用接口失败: {:#}", e)),
+            }
+        }
+        self.runtime_only_changes.remove(&iface_name);
+
+        let advice = if netplan_changed { format!("，{}", ApplyAdvice::Apply.message()) } else { String::new() };
+        self.status_message = Some(if failed.is_empty() {
+            format!("{} 已清除 {}: {}{}", Symbol::Ok, iface_name, done.join("，"), advice)
+        } else {
+            format!(
+                "{} 清除 {} 未完成: {}；已完成: {}{}",
+                Symbol::Error,
+                iface_name,
+                failed.join("；"),
+                if done.is_empty() { "无".to_string() } else { done.join("，") },
+                advice
+            )
+        });
+        self.refresh();
+    }
+
+    /// 安全模式下阻止执行修改操作并提示，返回true表示已阻止
+    fn blocked_by_safe_mode(&mut self) -> bool {
+        if self.safe_mode {
+            self.status_message = Some(format!("{} {}", Symbol::Warning, SAFE_MODE_BLOCKED));
+            self.screen = Screen::Main;
+        }
+        self.safe_mode
+    }
+
+    /// 直接执行的操作在写入不由nicman管理的Netplan条目前先提示，再次执行才真正写入
+    fn blocked_by_unmanaged(&mut self, iface_name: &str) -> bool {
+        let Some(warning) = self.unmanaged_gate.check(&self.interfaces, iface_name) else {
+            return false;
+        };
+        self.status_message = Some(format!("{} {}", Symbol::Warning, warning));
+        self.screen = Screen::Main;
+        true
+    }
+
+    /// 解析子网掩码，也接受前缀长度（如 24 或 /24）
+    fn netmask_to_prefix(netmask: &str) -> Result<u8> {
+        let netmask = netmask.trim();
+        if !netmask.contains('.') {
+            return netmask
+                .trim_start_matches('/')
+                .parse::<u8>()
+                .ok()
+                .filter(|prefix| *prefix <= 32)
+                .ok_or_else(|| anyhow::anyhow!("无效的前缀长度: {}", netmask));
+        }
+
+        let parts: Vec<u8> = netmask
+            .split('.')
+            .map(|s| s.parse::<u8>())
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if parts.len() != 4 {
+            return Err(anyhow::anyhow!("无效的子网掩码格式"));
+        }
+
+        let mask = ((parts[0] as u32) << 24)
+            | ((parts[1] as u32) << 16)
+            | ((parts[2] as u32) << 8)
+            | (parts[3] as u32);
+
+        Ok(mask.count_ones() as u8)
+    }
+
+    fn selected_interface(&self) -> Option<&NetInterface> {
+        self.list_state.selected().and_then(|i| self.interfaces.get(i))
+    }
+
+    /// 打开删除确认，默认选中自动判断的删除策略
+    fn open_confirm_delete(&mut self) {
+        if let Some(iface) = self.selected_interface() {
+            let default = RemovalManager::determine_strategy(iface);
+            self.delete_strategy_index = RemovalManager::applicable_strategies(iface)
+                .iter()
+                .position(|strategy| *strategy == default)
+                .unwrap_or(0);
+            self.screen = Screen::ConfirmDelete;
+        }
+    }
+
+    fn delete_selected_interface(&mut self) -> Result<()> {
+        if self.blocked_by_safe_mode() {
+            return Ok(());
+        }
+        if let Some(i) = self.list_state.selected()
+            && let Some(iface) = self.interfaces.get(i).cloned() {
+            // 使用用户选择的删除策略
+            let strategies = RemovalManager::applicable_strategies(&iface);
+            let strategy = strategies
+                .get(self.delete_strategy_index)
+                .cloned()
+                .unwrap_or_else(|| RemovalManager::determine_strategy(&iface));
+            RemovalManager::remove_interface(&iface, &strategy, self.options.kill_grace)?;
+            self.refresh();
+        }
+        Ok(())
+    }
+
+    fn ui(&mut self, f: &mut Frame) {
+        let size = f.size();
+        if size.width < MIN_TERMINAL_WIDTH || size.height < MIN_TERMINAL_HEIGHT {
+            draw_too_small(f);
+            return;
+        }
+
+        match self.screen {
+            Screen::Main => self.draw_main(f),
+            Screen::Help => self.draw_help(f),
+            Screen::CommandLog => self.draw_command_log(f),
+            Screen::Overview => self.draw_overview(f),
+            Screen::Routes => self.draw_routes(f),
+            Screen::Compare => self.draw_compare(f),
+            Screen::EditIface => {
+                self.draw_main(f);
+                self.draw_edit_form(f);
+                self.draw_save_confirm(f);
+            }
+            Screen::ToggleDhcp => {
+                self.draw_main(f);
+                self.draw_toggle_dhcp(f);
+            }
+            Screen::ConfirmDelete => {
+                self.draw_main(f);
+                self.draw_confirm_delete(f);
+            }
+            Screen::ClearConfig => {
+                self.draw_main(f);
+                self.draw_clear_config(f);
+            }
+            Screen::ConfirmSafeModeOff => {
+                self.draw_main(f);
+                self.draw_confirm_safe_mode_off(f);
+            }
+            Screen::OwnerActions => {
+                self.draw_main(f);
+                self.draw_owner_actions(f);
+            }
+            Screen::InterfaceActions => {
+                self.draw_main(f);
+                self.draw_interface_actions(f);
+            }
+            Screen::PathMtu => {
+                self.draw_main(f);
+                self.draw_path_mtu(f);
+            }
+            Screen::HealthCheck => {
+                self.draw_main(f);
+                self.draw_health_check(f);
+            }
+            Screen::RateLimit => {
+                self.draw_main(f);
+                self.draw_rate_limit(f);
+            }
+            Screen::PolicyRoute => {
+                self.draw_main(f);
+                self.draw_policy_form(f);
+            }
+            Screen::EditGateway => {
+                self.draw_main(f);
+                self.draw_gateway_form(f);
+            }
+            Screen::ManageAddresses => {
+                self.draw_main(f);
+                self.draw_address_form(f);
+            }
+            Screen::RenameIface => {
+                self.draw_main(f);
+                self.draw_rename_form(f);
+            }
+            Screen::EditMac => {
+                self.draw_main(f);
+                self.draw_mac_form(f);
+            }
+            Screen::CreateBridge => {
+                self.draw_main(f);
+                self.draw_bridge_form(f);
+            }
+            Screen::CreateVlan => {
+                self.draw_main(f);
+                self.draw_vlan_form(f);
+            }
+            Screen::CreateBond => {
+                self.draw_main(f);
+                self.draw_bond_form(f);
+            }
+            Screen::FreezeConfig => {
+                self.draw_main(f);
+                self.draw_freeze_preview(f);
+            }
+            Screen::ApplyNetplan => {
+                self.draw_main(f);
+                self.draw_apply_netplan(f);
+            }
+            Screen::TryNetplan => {
+                self.draw_main(f);
+                self.draw_try_netplan(f);
+            }
+            Screen::OwnerDetails => {
+                self.draw_main(f);
+                self.draw_owner_details(f);
+            }
+            Screen::Neighbors => {
+                self.draw_main(f);
+                self.draw_neighbors(f);
+            }
+            Screen::Diagnostics => {
+                self.draw_main(f);
+                self.draw_diagnostics(f);
+            }
+            Screen::ConfirmCreateNetplan => {
+                self.draw_main(f);
+                self.draw_confirm_create_netplan(f);
+            }
+        }
+    }
+
+    fn draw_main(&mut self, f: &mut Frame) {
+        let mut area = f.size();
+
+        // 有状态消息时在底部留出一行状态栏
+        if let Some(message) = &self.status_message {
+            let rows = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(1)])
+                .split(area);
+            area = rows[0];
+            f.render_widget(
+                Paragraph::new(message.as_str()).style(Style::default().fg(Color::Yellow)),
+                rows[1],
+            );
+        }
+
+        // 安全模式下顶部常驻横幅
+        if self.safe_mode {
+            let rows = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(1), Constraint::Min(0)])
+                .split(area);
+            area = rows[1];
+            f.render_widget(
+                Paragraph::new(format!("{} SAFE MODE 安全模式：可以查看操作对话框，所有修改操作均被阻止（! 关闭）", Symbol::Warning))
+                    .style(Style::default().fg(Color::Black).bg(Color::Yellow).add_modifier(Modifier::BOLD)),
+                rows[0],
+            );
+        }
+
+        // 窄终端上列表和详情上下排列
+        let direction = if area.width < SINGLE_COLUMN_WIDTH {
+            Direction::Vertical
+        } else {
+            Direction::Horizontal
+        };
+        let chunks = Layout::default()
+            .direction(direction)
+            .constraints([
+                Constraint::Percentage(self.options.list_width),
+                Constraint::Percentage(100 - self.options.list_width),
+            ])
+            .split(area);
+
+        self.draw_interface_list(f, chunks[0]);
+        self.draw_details(f, chunks[1]);
+    }
+
+    fn draw_interface_list(&mut self, f: &mut Frame, area: Rect) {
+        let items: Vec<ListItem> = self
+            .interfaces
+            .iter()
+            .map(|iface| {
+                let icon = iface.kind.icon();
+
+                let health = iface.link_health();
+                let state_color = match health {
+                    LinkHealth::Up => Color::Green,
+                    LinkHealth::NoCarrier => Color::Yellow,
+                    LinkHealth::Down => Color::Red,
+                    LinkHealth::Unknown => Color::DarkGray,
+                };
+
+                let speed_info = format!(
+                    "{} {} {} {}",
+                    Symbol::Rx,
+                    self.format_rate(iface.traffic_stats.rx_speed),
+                    Symbol::Tx,
+                    self.format_rate(iface.traffic_stats.tx_speed)
+                );
+
+                let mut spans = Vec::new();
+                if let Some(index) = self.compare_pair.iter().position(|name| *name == iface.name) {
+                    spans.push(Span::styled(format!("[{}] ", index + 1), Style::default().fg(Color::Magenta)));
+                }
+                if self.options.pinned.contains(&iface.name) {
+                    spans.push(Span::styled(format!("{} ", Symbol::Pinned), Style::default().fg(Color::Yellow)));
+                }
+                spans.extend([
+                    Span::raw(format!("{} ", icon)),
+                    Span::styled(health.icon(), Style::default().fg(state_color)),
+                ]);
+                // 降级的聚合接口整行标红
+                if iface.bond_info.as_ref().is_some_and(|bond| bond.is_degraded()) {
+                    spans.push(Span::styled(
+                        format!(" {} [降级] - {}", iface.name, speed_info),
+                        Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                    ));
+                } else {
+                    spans.push(Span::raw(format!(" {} - {}", iface.name, speed_info)));
+                }
+                if self.runtime_only_changes.contains(&iface.name) {
+                    spans.push(Span::styled(" [临时]", Style::default().fg(Color::Yellow)));
+                }
+                if self.error_rate_monitor.is_alerting(&iface.name) {
+                    spans.push(Span::styled(
+                        format!(" {}错误", Symbol::Warning),
+                        Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                    ));
+                }
+
+                if self.list_details {
+                    ListItem::new(vec![Line::from(spans), list_details_line(iface)])
+                } else {
+                    ListItem::new(Line::from(spans))
+                }
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .title(format!(
+                        "网络接口{} ({}{}:选择 r:刷新 q:退出 ?:帮助)",
+                        if command::use_sudo() { " [sudo]" } else { "" },
+                        Symbol::Up,
+                        Symbol::Down,
+                    ))
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded),
+            )
+            .highlight_style(Style::default().bg(Color::Blue).add_modifier(Modifier::BOLD))
+            .highlight_symbol(">> ");
+
+        f.render_stateful_widget(list, area, &mut self.list_state);
+    }
+
+    fn draw_details(&self, f: &mut Frame, area: Rect) {
+        let selected = self.list_state.selected();
+
+        if let Some(i) = selected
+            && let Some(iface) = self.interfaces.get(i) {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+                .split(area);
+
+            self.draw_interface_info(f, chunks[0], iface);
+            self.draw_traffic_stats(f, chunks[1], iface);
+        }
+    }
+
+    fn draw_interface_info(&self, f: &mut Frame, area: Rect, iface: &NetInterface) {
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled("接口名称: ", Style::default().fg(Color::Cyan)),
+                Span::raw(&iface.name),
+                Span::styled(format!("  (索引 {})", iface.ifindex), Style::default().fg(Color::DarkGray)),
+            ]),
+            Line::from(vec![
+                Span::styled("类型: ", Style::default().fg(Color::Cyan)),
+                Span::raw(format!("{:?}", iface.kind)),
+            ]),
+            Line::from(vec![
+                Span::styled("状态: ", Style::default().fg(Color::Cyan)),
+                Span::raw(format!("{:?}", iface.state)),
+            ]),
+        ];
+
+        if let Some(alias) = &iface.alias {
+            lines.push(Line::from(vec![
+                Span::styled("备注: ", Style::default().fg(Color::Cyan)),
+                Span::raw(alias),
+            ]));
+        }
+
+        if !iface.altnames.is_empty() {
+            lines.push(Line::from(vec![
+                Span::styled("备用名称: ", Style::default().fg(Color::Cyan)),
+                Span::raw(iface.altnames.join(", ")),
+            ]));
+        }
+
+        if let Some(carrier) = iface.carrier {
+            lines.push(Line::from(vec![
+                Span::styled("载波: ", Style::default().fg(Color::Cyan)),
+                Span::raw(if carrier { "有" } else { "无" }),
+            ]));
+        }
+
+        lines.push(Line::from(vec![
+            Span::styled("MTU: ", Style::default().fg(Color::Cyan)),
+            Span::raw(iface.mtu.to_string()),
+        ]));
+
+        if self.runtime_only_changes.contains(&iface.name) {
+            lines.push(Line::from(Span::styled(
+                format!("{} 有未持久化的临时修改，运行时配置与Netplan不同（w:写入Netplan）", Symbol::Warning),
+                Style::default().fg(Color::Yellow),
+            )));
+        }
+
+        if !iface.config_drift.is_empty() {
+            lines.push(Line::from(Span::styled(
+                format!("{} 运行时与持久化配置不一致", Symbol::Warning),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            )));
+            for diff in &iface.config_drift {
+                lines.push(Line::from(Span::styled(format!("  - {}", diff), Style::default().fg(Color::Yellow))));
+            }
+        }
+
+        let flaps = self.flap_detector.count(&iface.name);
+        lines.push(Line::from(vec![
+            Span::styled("抖动: ", Style::default().fg(Color::Cyan)),
+            Span::styled(
+                format!("{} 次", flaps),
+                Style::default().fg(if flaps > 0 { Color::Yellow } else { Color::White }),
+            ),
+            Span::styled("  (z:清零)", Style::default().fg(Color::DarkGray)),
+        ]));
+
+        if let Some(rate) = self.error_rate_monitor.rate(&iface.name) {
+            let alerting = self.error_rate_monitor.is_alerting(&iface.name);
+            lines.push(Line::from(vec![
+                Span::styled("错误/丢包速率: ", Style::default().fg(Color::Cyan)),
+                Span::styled(
+                    format!("{}/s{}", rate, if alerting { "（告警）" } else { "" }),
+                    Style::default().fg(if alerting { Color::Red } else if rate > 0 { Color::Yellow } else { Color::White }),
+                ),
+            ]));
+        }
+
+        // 三层接口没有MAC地址，明确显示原因而不是省略
+        lines.push(Line::from(vec![
+            Span::styled("MAC地址: ", Style::default().fg(Color::Cyan)),
+            match &iface.mac_address {
+                Some(mac) => Span::raw(match &iface.permanent_mac {
+                    Some(permanent) if permanent != mac => format!("{} (已修改，出厂: {})", mac, permanent),
+                    _ => mac.clone(),
+                }),
+                None => Span::styled(
+                    if iface.kind == InterfaceKind::Loopback {
+                        "无 (回环接口)"
+                    } else if iface.point_to_point {
+                        "无 (点对点接口)"
+                    } else {
+                        "无"
+                    },
+                    Style::default().fg(Color::DarkGray),
+                ),
+            },
+        ]));
+
+        if let Some(driver) = &iface.driver_info {
+            let mut driver_text = driver.driver.clone();
+            if let Some(version) = &driver.version {
+                driver_text.push_str(&format!(" ({})", version));
+            }
+            lines.push(Line::from(vec![
+                Span::styled("驱动: ", Style::default().fg(Color::Cyan)),
+                Span::raw(driver_text),
+            ]));
+            if let Some(firmware) = &driver.firmware_version {
+                lines.push(Line::from(vec![
+                    Span::styled("固件: ", Style::default().fg(Color::Cyan)),
+                    Span::raw(firmware),
+                ]));
+            }
+            if let Some(bus) = &driver.bus_info {
+                lines.push(Line::from(vec![
+                    Span::styled("总线: ", Style::default().fg(Color::Cyan)),
+                    Span::raw(bus),
+                ]));
+            }
+        }
+
+        if let Some(link) = iface.link_info.as_ref().filter(|_| iface.kind == InterfaceKind::Physical) {
+            lines.extend(link_info_lines(link));
+        }
+
+        if let Some(wol) = &iface.wol {
+            let state = if !wol.supports_magic_packet() {
+                "不支持".to_string()
+            } else if wol.is_enabled() {
+                format!("开启 ({})", wol.current)
+            } else {
+                "关闭".to_string()
+            };
+            lines.push(Line::from(vec![
+                Span::styled("网络唤醒: ", Style::default().fg(Color::Cyan)),
+                Span::raw(state),
+            ]));
+        }
+
+        if let Some(vrf) = &iface.vrf {
+            lines.push(Line::from(vec![
+                Span::styled("VRF: ", Style::default().fg(Color::Cyan)),
+                Span::raw(vrf),
+            ]));
+        }
+
+        if iface.kind == InterfaceKind::Ifb {
+            lines.push(Line::from(vec![
+                Span::styled("用途: ", Style::default().fg(Color::Cyan)),
+                Span::raw("入向流量整形，其他接口的ingress流量经 tc mirred 重定向到此设备"),
+            ]));
+        }
+
+        if iface.kind == InterfaceKind::Vrf {
+            let members: Vec<&str> = self.interfaces
+                .iter()
+                .filter(|other| other.vrf.as_deref() == Some(iface.name.as_str()))
+                .map(|other| other.name.as_str())
+                .collect();
+            lines.push(Line::from(vec![
+                Span::styled("VRF成员: ", Style::default().fg(Color::Cyan)),
+                Span::raw(if members.is_empty() { "无".to_string() } else { members.join(", ") }),
+            ]));
+        }
+
+        if let Some(bond) = &iface.bond_info {
+            lines.push(Line::from(vec![
+                Span::styled("聚合模式: ", Style::default().fg(Color::Cyan)),
+                Span::raw(bond.mode.as_str()),
+            ]));
+            if bond.slaves.is_empty() {
+                lines.push(Line::from(Span::styled(
+                    format!("{} 没有成员接口", Symbol::Error),
+                    Style::default().fg(Color::Red),
+                )));
+            }
+            for slave in &bond.slaves {
+                let (label, color) = if slave.mii_up { ("up", Color::Green) } else { ("down", Color::Red) };
+                lines.push(Line::from(vec![
+                    Span::raw(format!("  成员 {}: ", slave.name)),
+                    Span::styled(label, Style::default().fg(color)),
+                ]));
+            }
+        }
+
+        if let Some(settings) = &iface.bridge_settings {
+            let on_off = |enabled: bool| if enabled { "开启" } else { "关闭" };
+            lines.push(Line::from(vec![
+                Span::styled("STP: ", Style::default().fg(Color::Cyan)),
+                Span::raw(on_off(settings.stp)),
+                Span::styled("  VLAN过滤: ", Style::default().fg(Color::Cyan)),
+                Span::raw(on_off(settings.vlan_filtering)),
+            ]));
+        }
+
+        if !iface.ipv4_addresses.is_empty() {
+            lines.push(Line::from(vec![
+                Span::styled("IPv4地址: ", Style::default().fg(Color::Cyan)),
+                Span::raw(iface.ipv4_addresses.join(", ")),
+                Span::styled("  (操作菜单: 添加IP/删除IP)", Style::default().fg(Color::DarkGray)),
+            ]));
+
+            // 动态地址显示租约剩余时间
+            for info in &iface.ipv4_details {
+                let lease = match info.valid_lft {
+                    Some(secs) => format!("租约剩余: {}", format_duration(secs)),
+                    None => "永久".to_string(),
+                };
+                lines.push(Line::from(vec![
+                    Span::raw(format!("  {} ", info.address)),
+                    Span::styled(lease, Style::default().fg(if info.dynamic { Color::Yellow } else { Color::DarkGray })),
+                ]));
+            }
+        }
+
+        // 显示子网掩码
+        if let Some(ipv4_config) = &iface.ipv4_config {
+            lines.push(Line::from(vec![
+                Span::styled("子网掩码: ", Style::default().fg(Color::Cyan)),
+                Span::raw(&ipv4_config.netmask),
+            ]));
+
+            // 显示网关
+            if let Some(gateway) = &ipv4_config.gateway {
+                lines.push(Line::from(vec![
+                    Span::styled("网关: ", Style::default().fg(Color::Cyan)),
+                    Span::raw(gateway),
+                ]));
+            }
+        }
+
+        if iface.docker_nat_egress {
+            lines.push(Line::from(vec![
+                Span::styled("Docker: ", Style::default().fg(Color::Cyan)),
+                Span::raw("NAT 出口（docker0 容器的出站流量经此接口）"),
+            ]));
+        }
+
+        if !iface.policy_rules.is_empty() {
+            lines.push(Line::from(Span::styled("策略路由:", Style::default().fg(Color::Cyan))));
+            for rule in &iface.policy_rules {
+                lines.push(Line::from(format!(
+                    "  from {} 查表 {} (优先级 {})",
+                    rule.from, rule.table, rule.priority
+                )));
+            }
+        }
+
+        // 显示DNS
+        if let Some(dns_config) = &iface.dns_config {
+            if !dns_config.nameservers.is_empty() {
+                lines.push(Line::from(vec![
+                    Span::styled("DNS: ", Style::default().fg(Color::Cyan)),
+                    Span::raw(dns_config.nameservers.join(",")),
+                ]));
+            }
+            if !dns_config.search.is_empty() {
+                lines.push(Line::from(vec![
+                    Span::styled("搜索域: ", Style::default().fg(Color::Cyan)),
+                    Span::raw(dns_config.search.join(",")),
+                ]));
+            }
+        }
+
+        if !iface.ipv6_details.is_empty() {
+            // 按/64前缀分组显示
+            let mut prefixes: Vec<&str> = Vec::new();
+            for info in &iface.ipv6_details {
+                if !prefixes.contains(&info.prefix.as_str()) {
+                    prefixes.push(&info.prefix);
+                }
+            }
+
+            lines.push(Line::from(Span::styled("IPv6地址: ", Style::default().fg(Color::Cyan))));
+            for prefix in prefixes {
+                lines.push(Line::from(Span::styled(
+                    format!("  {}", prefix),
+                    Style::default().fg(Color::Blue),
+                )));
+                for info in iface.ipv6_details.iter().filter(|info| info.prefix == prefix) {
+                    let mut spans = vec![Span::raw(format!("    {}", info.address))];
+                    if info.temporary {
+                        spans.push(Span::styled(" [临时]", Style::default().fg(Color::Yellow)));
+                    }
+                    if info.deprecated {
+                        spans.push(Span::styled(" [已弃用]", Style::default().fg(Color::DarkGray)));
+                    } else if info.scope == "global" && !info.temporary {
+                        spans.push(Span::styled(" [首选]", Style::default().fg(Color::Green)));
+                    }
+                    lines.push(Line::from(spans));
+                }
+            }
+        } else if !iface.ipv6_addresses.is_empty() {
+            lines.push(Line::from(vec![
+                Span::styled("IPv6地址: ", Style::default().fg(Color::Cyan)),
+                Span::raw(iface.ipv6_addresses.join(", ")),
+            ]));
+        }
+
+        if let Some(gateway) = &iface.ipv6_gateway {
+            lines.push(Line::from(vec![
+                Span::styled("IPv6网关: ", Style::default().fg(Color::Cyan)),
+                Span::raw(gateway),
+            ]));
+        }
+
+        if let Some(file) = &iface.netplan_file {
+            let file_name = file
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| file.display().to_string());
+            lines.push(Line::from(vec![
+                Span::styled("配置文件: ", Style::default().fg(Color::Cyan)),
+                Span::raw(file_name),
+                if iface.nicman_managed {
+                    Span::styled("  由 nicman 管理", Style::default().fg(Color::Green))
+                } else {
+                    Span::styled("  手动维护", Style::default().fg(Color::DarkGray))
+                },
+            ]));
+        }
+
+        if let Some(owner) = &iface.owner {
+            lines.push(Line::from(""));
+            lines.push(Line::from(vec![
+                Span::styled("创建者: ", Style::default().fg(Color::Yellow)),
+                Span::raw(owner.display_name()),
+            ]));
+
+            // 显示详细信息和操作提示
+            use crate::model::InterfaceOwner;
+            match owner {
+                InterfaceOwner::SystemdService { name, status, .. } => {
+                    lines.push(Line::from(vec![
+                        Span::styled("  服务名: ", Style::default().fg(Color::Cyan)),
+                        Span::raw(name),
+                    ]));
+                    lines.push(Line::from(vec![
                         Span::styled("  状态: ", Style::default().fg(Color::Cyan)),
                         Span::raw(format!("{:?}", status)),
                     ]));
@@ -749,729 +3949,3358 @@ impl App {
                         Span::raw("按 'o' 键停止服务"),
                     ]));
                 },
-                InterfaceOwner::DockerContainer { id, name, image } => {
-                    lines.push(Line::from(vec![
-                        Span::styled("  容器ID: ", Style::default().fg(Color::Cyan)),
-                        Span::raw(&id[..12.min(id.len())]),  // 显示前12位
-                    ]));
-                    lines.push(Line::from(vec![
-                        Span::styled("  容器名: ", Style::default().fg(Color::Cyan)),
-                        Span::raw(name),
-                    ]));
-                    lines.push(Line::from(vec![
-                        Span::styled("  镜像: ", Style::default().fg(Color::Cyan)),
-                        Span::raw(image),
-                    ]));
-                    lines.push(Line::from(vec![
-                        Span::styled("  操作: ", Style::default().fg(Color::Green)),
-                        Span::raw("按 'o' 键停止容器"),
-                    ]));
+                InterfaceOwner::DockerContainer { id, name, image } => {
+                    lines.push(Line::from(vec![
+                        Span::styled("  容器ID: ", Style::default().fg(Color::Cyan)),
+                        Span::raw(&id[..12.min(id.len())]),  // 显示前12位
+                    ]));
+                    lines.push(Line::from(vec![
+                        Span::styled("  容器名: ", Style::default().fg(Color::Cyan)),
+                        Span::raw(name),
+                    ]));
+                    lines.push(Line::from(vec![
+                        Span::styled("  镜像: ", Style::default().fg(Color::Cyan)),
+                        Span::raw(image),
+                    ]));
+                    lines.push(Line::from(vec![
+                        Span::styled("  操作: ", Style::default().fg(Color::Green)),
+                        Span::raw("按 'o' 键停止容器"),
+                    ]));
+                },
+                InterfaceOwner::DockerNetwork { id, name, containers } => {
+                    lines.push(Line::from(vec![
+                        Span::styled("  网络名: ", Style::default().fg(Color::Cyan)),
+                        Span::raw(name),
+                    ]));
+                    lines.push(Line::from(vec![
+                        Span::styled("  网络ID: ", Style::default().fg(Color::Cyan)),
+                        Span::raw(&id[..12.min(id.len())]),
+                    ]));
+                    lines.push(Line::from(vec![
+                        Span::styled("  容器数: ", Style::default().fg(Color::Cyan)),
+                        Span::raw(containers.to_string()),
+                    ]));
+                    lines.push(Line::from(vec![
+                        Span::styled("  操作: ", Style::default().fg(Color::Green)),
+                        Span::raw(if *containers == 0 { "按 'o' 键删除Docker网络" } else { "仍有容器连接，无法删除" }),
+                    ]));
+                },
+                InterfaceOwner::Process { pid, name, cmdline } => {
+                    lines.push(Line::from(vec![
+                        Span::styled("  进程ID: ", Style::default().fg(Color::Cyan)),
+                        Span::raw(format!("{}", pid)),
+                    ]));
+                    lines.push(Line::from(vec![
+                        Span::styled("  进程名: ", Style::default().fg(Color::Cyan)),
+                        Span::raw(name),
+                    ]));
+                    if !cmdline.is_empty() {
+                        lines.push(Line::from(vec![
+                            Span::styled("  命令行: ", Style::default().fg(Color::Cyan)),
+                            Span::raw(cmdline),
+                        ]));
+                    }
+                    lines.push(Line::from(vec![
+                        Span::styled("  操作: ", Style::default().fg(Color::Green)),
+                        Span::raw("按 'o' 键终止进程"),
+                    ]));
+                },
+                InterfaceOwner::NetworkManager { connection, .. } => {
+                    lines.push(Line::from(vec![
+                        Span::styled("  连接名: ", Style::default().fg(Color::Cyan)),
+                        Span::raw(connection),
+                    ]));
+                    lines.push(Line::from(vec![
+                        Span::styled("  操作: ", Style::default().fg(Color::Green)),
+                        Span::raw("按 'o' 键断开连接"),
+                    ]));
+                },
+                InterfaceOwner::NspawnMachine { machine } => {
+                    lines.push(Line::from(vec![
+                        Span::styled("  机器名: ", Style::default().fg(Color::Cyan)),
+                        Span::raw(machine),
+                    ]));
+                    lines.push(Line::from(vec![
+                        Span::styled("  操作: ", Style::default().fg(Color::Green)),
+                        Span::raw("按 'o' 键终止容器"),
+                    ]));
+                },
+                InterfaceOwner::Kernel { module } => {
+                    lines.push(Line::from(vec![
+                        Span::styled("  内核模块: ", Style::default().fg(Color::Cyan)),
+                        Span::raw(module),
+                    ]));
+                    lines.push(Line::from(vec![
+                        Span::styled("  操作: ", Style::default().fg(Color::Green)),
+                        Span::raw("按 'o' 键卸载模块"),
+                    ]));
+                },
+                InterfaceOwner::Unknown => {},
+            }
+        } else if !self.options.owner_detection {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                "创建者: 未检测（已禁用创建者检测）",
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+
+        let paragraph = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .title("接口详情")
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+            )
+            .wrap(Wrap { trim: true });
+
+        f.render_widget(paragraph, area);
+    }
+
+    /// 要显示的流量统计：有基线且未切换到累计值时显示基线以来的增量
+    fn displayed_traffic(&self, iface: &NetInterface) -> TrafficStats {
+        match self.traffic_baselines.get(&iface.name) {
+            Some(baseline) if !self.show_absolute_traffic => iface.traffic_stats.since(baseline),
+            _ => iface.traffic_stats.clone(),
+        }
+    }
+
+    fn draw_traffic_stats(&self, f: &mut Frame, area: Rect, iface: &NetInterface) {
+        let stats = &self.displayed_traffic(iface);
+        let title = if !self.traffic_baselines.contains_key(&iface.name) {
+            "流量统计"
+        } else if self.show_absolute_traffic {
+            "流量统计 (累计, a 切换)"
+        } else {
+            "流量统计 (会话, a 切换)"
+        };
+
+        let mut lines = Vec::new();
+        if self.stats_display.shows_totals() {
+            lines.extend([
+                Line::from(vec![
+                    Span::styled("接收: ", Style::default().fg(Color::Green)),
+                    Span::raw(format!("{} ({} 包)", format_bytes(stats.rx_bytes), stats.rx_packets)),
+                ]),
+                Line::from(vec![
+                    Span::styled("发送: ", Style::default().fg(Color::Blue)),
+                    Span::raw(format!("{} ({} 包)", format_bytes(stats.tx_bytes), stats.tx_packets)),
+                ]),
+            ]);
+        }
+        if self.stats_display.shows_rate() {
+            lines.extend([
+                Line::from(vec![
+                    Span::styled("速率: ", Style::default().fg(Color::Magenta)),
+                    Span::raw(format!("{} {}  {} {}", Symbol::Rx, self.format_rate(stats.rx_speed), Symbol::Tx, self.format_rate(stats.tx_speed))),
+                ]),
+                Line::from(vec![
+                    Span::styled("瞬时: ", Style::default().fg(Color::DarkGray)),
+                    Span::styled(
+                        format!("{} {}  {} {}", Symbol::Rx, self.format_rate(stats.rx_speed_raw), Symbol::Tx, self.format_rate(stats.tx_speed_raw)),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ]),
+            ]);
+        }
+
+        if self.expanded_stats {
+            let counter = |value: Option<u64>| value.map_or_else(|| "读取中".to_string(), |n| n.to_string());
+            lines.extend([
+                Line::from(vec![
+                    Span::styled("错误: ", Style::default().fg(Color::Red)),
+                    Span::raw(format!("{} {}  {} {}", Symbol::Rx, stats.rx_errors, Symbol::Tx, stats.tx_errors)),
+                ]),
+                Line::from(vec![
+                    Span::styled("丢包: ", Style::default().fg(Color::Red)),
+                    Span::raw(format!("{} {}  {} {}", Symbol::Rx, stats.rx_dropped, Symbol::Tx, stats.tx_dropped)),
+                ]),
+                Line::from(vec![
+                    Span::styled("组播: ", Style::default().fg(Color::Yellow)),
+                    Span::raw(format!("{}  ", counter(stats.multicast))),
+                    Span::styled("冲突: ", Style::default().fg(Color::Yellow)),
+                    Span::raw(counter(stats.collisions)),
+                ]),
+            ]);
+        }
+
+        let block = Block::default()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded);
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        // 文字下方至少能放下两行趋势图时才绘制接收/发送速率趋势
+        let text_height = lines.len() as u16;
+        let graph_height = inner.height.saturating_sub(text_height) / 2;
+        f.render_widget(Paragraph::new(lines), inner);
+        if !self.stats_display.shows_rate() || graph_height < 2 {
+            return;
+        }
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(text_height), Constraint::Length(graph_height), Constraint::Length(graph_height)])
+            .split(inner);
+        let history = self.traffic_monitor.history(&iface.name);
+        for (rx, label, color, area) in [
+            (true, "接收趋势", Color::Green, chunks[1]),
+            (false, "发送趋势", Color::Blue, chunks[2]),
+        ] {
+            let data = rate_history_data(history, rx, area.width as usize);
+            let peak = data.iter().max().copied().unwrap_or(0);
+            let sparkline = Sparkline::default()
+                .block(Block::default().title(Span::styled(
+                    format!("{} (峰值 {})", label, self.format_rate(peak as f64)),
+                    Style::default().fg(Color::DarkGray),
+                )))
+                .data(&data)
+                .style(Style::default().fg(color));
+            f.render_widget(sparkline, area);
+        }
+    }
+
+    fn draw_help(&self, f: &mut Frame) {
+        let help_text = vec![
+            Line::from(Span::styled("网卡管理工具 - 帮助", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))),
+            Line::from(""),
+            Line::from(Span::styled("导航:", Style::default().fg(Color::Cyan))),
+            Line::from(format!("  {:<8} - 上移", format!("{}/k", Symbol::Up))),
+            Line::from(format!("  {:<8} - 下移", format!("{}/j", Symbol::Down))),
+            Line::from(""),
+            Line::from(Span::styled("物理接口操作:", Style::default().fg(Color::Cyan))),
+            Line::from("  Enter/e  - 编辑IP/掩码/网关/DNS"),
+            Line::from("  t        - 切换DHCP/静态模式"),
+            Line::from("  w        - 把当前运行时配置写入Netplan（静态）"),
+            Line::from("  E        - 在$EDITOR中编辑netplan文件"),
+            Line::from("  A        - 执行netplan apply并查看输出"),
+            Line::from("  T        - 测试配置（netplan try，30秒内未确认自动回滚）"),
+            Line::from("  u        - 启用接口 (Up)"),
+            Line::from("  d        - 禁用接口 (Down)"),
+            Line::from(""),
+            Line::from(Span::styled("虚拟接口操作:", Style::default().fg(Color::Cyan))),
+            Line::from("  x/Del    - 删除接口"),
+            Line::from("  u        - 启用接口 (Up)"),
+            Line::from("  d        - 禁用接口 (Down)"),
+            Line::from(""),
+            Line::from(Span::styled("创建者操作:", Style::default().fg(Color::Cyan))),
+            Line::from("  o        - 停止服务/容器/进程"),
+            Line::from("             (停止systemd服务)"),
+            Line::from("             (停止Docker容器)"),
+            Line::from("             (删除无容器连接的Docker网络)"),
+            Line::from("             (终止nspawn容器)"),
+            Line::from("             (终止进程)"),
+            Line::from("             (断开NetworkManager连接)"),
+            Line::from("             (卸载内核模块)"),
+            Line::from("  I        - 查看创建者进程/容器的详情（只读）"),
+            Line::from(""),
+            Line::from(Span::styled("通用操作:", Style::default().fg(Color::Cyan))),
+            Line::from("  r        - 刷新接口列表"),
+            Line::from("  l        - 查看调试/命令日志"),
+            Line::from("  D        - 生成诊断信息文件（用于提交bug）"),
+            Line::from("  O        - 所有接口流量总览（s切换排序）"),
+            Line::from("  R        - 查看路由表（高亮默认路由和当前接口的路由）"),
+            Line::from("  B        - 创建网桥并选择成员接口"),
+            Line::from("  V        - 在物理网卡上创建VLAN子接口"),
+            Line::from("  L        - 创建链路聚合(bond)并选择模式和成员"),
+            Line::from("  c        - 连通性检查（载波/地址/网关/DNS）"),
+            Line::from("  z        - 清零当前接口的抖动计数"),
+            Line::from("  *        - 置顶/取消置顶当前接口"),
+            Line::from("  m / C    - 标记对比接口 / 打开两个接口的对比视图"),
+            Line::from("  b        - 以当前流量为会话基线重新计数"),
+            Line::from("  a        - 切换会话/累计流量"),
+            Line::from("  v        - 流量面板显示：两者/仅速率/仅累计"),
+            Line::from("  U        - 速率单位切换：字节/秒 (MB/s) 或 比特/秒 (Mbit/s)"),
+            Line::from("  i        - 接口列表单行/两行显示（第二行为主IP和创建者）"),
+            Line::from("  S        - 展开/收起扩展统计（错误/丢包/组播/冲突）"),
+            Line::from("  </>      - 调整列表/详情宽度"),
+            Line::from("  !        - 开启/关闭安全模式（阻止所有修改操作，关闭需确认）"),
+            Line::from("  q        - 退出程序"),
+            Line::from("  ?        - 显示/隐藏帮助"),
+            Line::from(""),
+            Line::from(Span::styled("编辑表单:", Style::default().fg(Color::Cyan))),
+            Line::from("  Tab      - 下一个字段"),
+            Line::from("  Shift+Tab- 上一个字段"),
+            Line::from("  Enter    - 保存配置"),
+            Line::from(format!(
+                "  {:<8} - 编辑字段时调出本次会话保存过的值（也可用Ctrl+P/N）",
+                format!("{}/{}", Symbol::Up, Symbol::Down)
+            )),
+            Line::from("  Esc      - 取消编辑"),
+            Line::from(""),
+            Line::from(Span::styled("确认对话框:", Style::default().fg(Color::Cyan))),
+            Line::from("  Y        - 确认操作"),
+            Line::from("  N/Esc    - 取消操作"),
+            Line::from(""),
+            Line::from(Span::styled("按任意键返回", Style::default().fg(Color::Green))),
+        ];
+
+        let paragraph = Paragraph::new(help_text)
+            .block(
+                Block::default()
+                    .title("帮助")
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+            )
+            .alignment(Alignment::Left);
+
+        let area = centered_rect(60, 60, f.size());
+        f.render_widget(paragraph, area);
+    }
+
+    /// 并排显示两个对比接口的流量统计和速率趋势
+    fn draw_compare(&self, f: &mut Frame) {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(1)])
+            .split(f.size());
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(rows[0]);
+
+        let pair: Vec<Option<&NetInterface>> = self.compare_pair
+            .iter()
+            .map(|name| self.interfaces.iter().find(|iface| iface.name == *name))
+            .collect();
+        let total_rx: f64 = pair.iter().flatten().map(|iface| iface.traffic_stats.rx_speed).sum();
+        let total_tx: f64 = pair.iter().flatten().map(|iface| iface.traffic_stats.tx_speed).sum();
+        let share = |speed: f64, total: f64| if total > 0.0 { speed / total * 100.0 } else { 0.0 };
+
+        for ((name, iface), &area) in self.compare_pair.iter().zip(&pair).zip(columns.iter()) {
+            let Some(iface) = iface else {
+                let paragraph = Paragraph::new(format!("接口 {} 不存在", name))
+                    .block(Block::default().title(name.as_str()).borders(Borders::ALL).border_type(BorderType::Rounded));
+                f.render_widget(paragraph, area);
+                continue;
+            };
+
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Length(1),
+                    Constraint::Length(7),
+                    Constraint::Min(3),
+                    Constraint::Min(3),
+                ])
+                .split(area);
+
+            let stats = &iface.traffic_stats;
+            f.render_widget(
+                Paragraph::new(Line::from(vec![
+                    Span::styled(format!(" {} ", iface.name), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+                    Span::raw(format!(
+                        "接收占比 {:.0}%  发送占比 {:.0}%",
+                        share(stats.rx_speed, total_rx),
+                        share(stats.tx_speed, total_tx)
+                    )),
+                ])),
+                chunks[0],
+            );
+            self.draw_traffic_stats(f, chunks[1], iface);
+
+            let history = self.traffic_monitor.history(&iface.name);
+            for (rx, title, color, area) in [
+                (true, format!("接收 {}", self.format_rate(stats.rx_speed)), Color::Green, chunks[2]),
+                (false, format!("发送 {}", self.format_rate(stats.tx_speed)), Color::Blue, chunks[3]),
+            ] {
+                let data = rate_history_data(history, rx, area.width.saturating_sub(2) as usize);
+                let sparkline = Sparkline::default()
+                    .block(Block::default().title(title).borders(Borders::ALL).border_type(BorderType::Rounded))
+                    .data(&data)
+                    .style(Style::default().fg(color));
+                f.render_widget(sparkline, area);
+            }
+        }
+
+        f.render_widget(
+            Paragraph::new(Span::styled(" 接口对比 - Esc/q/C: 返回", Style::default().fg(Color::DarkGray))),
+            rows[1],
+        );
+    }
+
+    /// 重新读取路由表，失败时保留错误信息在界面中显示
+    fn load_routes(&mut self) {
+        match routes::list_routes() {
+            Ok(routes) => {
+                self.routes = routes;
+                self.routes_error = None;
+            }
+            Err(e) => {
+                self.routes.clear();
+                self.routes_error = Some(format!("{:#}", e));
+            }
+        }
+        self.routes_scroll = self.routes_scroll.min(self.routes.len().saturating_sub(1));
+    }
+
+    fn draw_routes(&self, f: &mut Frame) {
+        let selected = self.selected_interface().map(|iface| iface.name.as_str());
+        let header = Row::new(vec!["协议", "目标", "网关", "接口", "度量", "来源"])
+            .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+
+        let rows: Vec<Row> = self
+            .routes
+            .iter()
+            .skip(self.routes_scroll)
+            .map(|route| {
+                // 默认路由最醒目，其次是当前选中接口的路由
+                let style = if route.is_default() {
+                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+                } else if route.dev.is_some() && route.dev.as_deref() == selected {
+                    Style::default().fg(Color::Cyan)
+                } else {
+                    Style::default().fg(Color::White)
+                };
+                let optional = |value: &Option<String>| value.clone().unwrap_or_else(|| "-".to_string());
+                Row::new(vec![
+                    Cell::from(if route.ipv6 { "IPv6" } else { "IPv4" }),
+                    Cell::from(route.destination()),
+                    Cell::from(optional(&route.via)),
+                    Cell::from(optional(&route.dev)),
+                    Cell::from(route.metric.map(|metric| metric.to_string()).unwrap_or_else(|| "-".to_string())),
+                    Cell::from(optional(&route.proto)),
+                ])
+                .style(style)
+            })
+            .collect();
+
+        let widths = [
+            Constraint::Length(6),
+            Constraint::Percentage(30),
+            Constraint::Percentage(25),
+            Constraint::Percentage(15),
+            Constraint::Length(8),
+            Constraint::Percentage(12),
+        ];
+
+        let title = match &self.routes_error {
+            Some(err) => format!("路由表 - {} {} (r 重试, Esc 返回)", Symbol::Error, err),
+            None => format!(
+                "路由表 - {} 条 ({}{}/PgUp/PgDn:滚动 r:刷新 Esc:返回)",
+                self.routes.len(), Symbol::Up, Symbol::Down
+            ),
+        };
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(
+                Block::default()
+                    .title(title)
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded),
+            );
+
+        f.render_widget(table, f.size());
+    }
+
+    fn draw_overview(&self, f: &mut Frame) {
+        let header = Row::new(vec!["接口", "类型", "累计接收", "累计发送", "接收速率", "发送速率"])
+            .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD));
+
+        let rows: Vec<Row> = sort_overview(&self.interfaces, self.overview_sort)
+            .into_iter()
+            .map(|iface| {
+                let stats = &self.displayed_traffic(iface);
+                Row::new(vec![
+                    Cell::from(iface.name.clone()).style(Style::default().fg(Color::Cyan)),
+                    Cell::from(iface.kind.display_name()),
+                    Cell::from(format_bytes(stats.rx_bytes)),
+                    Cell::from(format_bytes(stats.tx_bytes)),
+                    Cell::from(self.format_rate(stats.rx_speed)).style(Style::default().fg(Color::Green)),
+                    Cell::from(self.format_rate(stats.tx_speed)).style(Style::default().fg(Color::Blue)),
+                ])
+            })
+            .collect();
+
+        let widths = [
+            Constraint::Percentage(20),
+            Constraint::Percentage(12),
+            Constraint::Percentage(17),
+            Constraint::Percentage(17),
+            Constraint::Percentage(17),
+            Constraint::Percentage(17),
+        ];
+
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(
+                Block::default()
+                    .title(format!("流量总览 - 按{}排序 (s 切换排序, Esc 返回)", self.overview_sort.label()))
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded),
+            );
+
+        f.render_widget(table, f.size());
+    }
+
+    fn draw_command_log(&self, f: &mut Frame) {
+        use crate::utils::command::recent_commands;
+
+        let records = recent_commands();
+        let mut lines = Vec::new();
+
+        if records.is_empty() {
+            lines.push(Line::from("暂无命令记录"));
+        }
+
+        // 最新的命令显示在最上方
+        for record in records.iter().rev() {
+            let (status, status_color) = match record.exit_code {
+                Some(0) => ("OK".to_string(), Color::Green),
+                Some(code) => (format!("exit {}", code), Color::Red),
+                None => ("FAILED".to_string(), Color::Red),
+            };
+
+            lines.push(Line::from(vec![
+                Span::styled(format!("[{}] ", record.time), Style::default().fg(Color::DarkGray)),
+                Span::styled(format!("$ {}", record.command), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+                Span::raw("  "),
+                Span::styled(status, Style::default().fg(status_color)),
+            ]));
+            for line in record.stdout.lines() {
+                lines.push(Line::from(format!("  {}", line)));
+            }
+            for line in record.stderr.lines() {
+                lines.push(Line::from(Span::styled(
+                    format!("  {}", line),
+                    Style::default().fg(Color::Red),
+                )));
+            }
+            lines.push(Line::from(""));
+        }
+
+        let paragraph = Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .title(format!("调试/日志 - 最近 {} 条命令 ({}{}/PgUp/PgDn:滚动 Esc:返回)", records.len(), Symbol::Up, Symbol::Down))
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+            )
+            .scroll((self.log_scroll, 0));
+
+        f.render_widget(paragraph, f.size());
+    }
+
+    fn draw_confirm_delete(&self, f: &mut Frame) {
+        if let Some(i) = self.list_state.selected()
+            && let Some(iface) = self.interfaces.get(i) {
+            // 计算弹窗区域
+            let area = centered_rect(60, 60, f.size());
+
+            // 只清除弹窗区域
+            f.render_widget(Clear, area);
+            let default_strategy = RemovalManager::determine_strategy(iface);
+            let strategies = RemovalManager::applicable_strategies(iface);
+            let warnings = RemovalManager::check_safety(iface);
+
+            let mut text = vec![
+                Line::from(Span::styled(
+                    "确认删除接口",
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                )),
+                Line::from(""),
+                Line::from(vec![
+                    Span::raw("接口名称: "),
+                    Span::styled(&iface.name, Style::default().fg(Color::Yellow)),
+                ]),
+                Line::from(vec![
+                    Span::raw("接口类型: "),
+                    Span::raw(format!("{:?}", iface.kind)),
+                ]),
+            ];
+
+            // 备注、地址和创建者，帮助确认没有选错接口
+            if let Some(alias) = &iface.alias {
+                text.push(Line::from(vec![
+                    Span::raw("接口备注: "),
+                    Span::styled(alias, Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+                ]));
+            }
+            let addresses: Vec<&str> = iface.ipv4_addresses.iter()
+                .chain(iface.ipv6_details.iter().filter(|info| info.scope == "global").map(|info| &info.address))
+                .map(String::as_str)
+                .collect();
+            text.push(Line::from(vec![
+                Span::raw("IP地址:   "),
+                Span::raw(if addresses.is_empty() { "无".to_string() } else { addresses.join(", ") }),
+            ]));
+            text.push(Line::from(vec![
+                Span::raw("创建者:   "),
+                Span::raw(iface.owner.as_ref().map(|owner| owner.display_name()).unwrap_or_else(|| "未知".to_string())),
+            ]));
+            text.push(Line::from(""));
+            text.push(Line::from(Span::raw(format!("删除策略 ({}/{} 选择):", Symbol::Up, Symbol::Down))));
+
+            for (index, strategy) in strategies.iter().enumerate() {
+                let selected = index == self.delete_strategy_index;
+                let style = if selected {
+                    Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD).bg(Color::DarkGray)
+                } else {
+                    Style::default().fg(Color::White)
+                };
+                let cursor = if selected { format!("{} ", Symbol::Selected) } else { "  ".to_string() };
+                let default_mark = if *strategy == default_strategy { " [默认]" } else { "" };
+                text.push(Line::from(vec![
+                    Span::styled(cursor, Style::default().fg(Color::Green)),
+                    Span::styled(format!("{}{}", strategy.display_name(), default_mark), style),
+                ]));
+                if selected {
+                    text.push(Line::from(Span::styled(
+                        format!("    {}", strategy.description()),
+                        Style::default().fg(Color::DarkGray),
+                    )));
+                }
+            }
+            text.push(Line::from(""));
+
+            // 显示警告
+            if !warnings.is_empty() {
+                text.push(Line::from(Span::styled(
+                    format!("{}  警告:", Symbol::Warning),
+                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                )));
+                for warning in &warnings {
+                    text.push(Line::from(Span::styled(
+                        format!("  • {}", warning),
+                        Style::default().fg(Color::Yellow),
+                    )));
+                }
+                text.push(Line::from(""));
+            }
+
+            text.push(Line::from(Span::styled(
+                "确定要删除此接口吗？",
+                Style::default().fg(Color::Red),
+            )));
+            text.push(Line::from(""));
+            text.push(Line::from(vec![
+                Span::styled("Y", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
+                Span::raw(" - 确认删除  "),
+                Span::styled("N", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+                Span::raw(" - 取消"),
+            ]));
+
+            let paragraph = Paragraph::new(text)
+                .block(
+                    Block::default()
+                        .title("删除确认")
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .border_style(Style::default().fg(Color::Red))
+                        .style(Style::default().bg(Color::Black)),
+                )
+                .alignment(Alignment::Left);
+
+            // area已经在前面计算过了
+            f.render_widget(paragraph, area);
+        }
+    }
+
+    fn draw_edit_form(&self, f: &mut Frame) {
+        if let Some(form) = &self.edit_form {
+            // 计算弹窗区域
+            let area = centered_rect(70, 60, f.size());
+
+            // 只清除弹窗区域
+            f.render_widget(Clear, area);
+
+            let field_names = form.field_names();
+            let field_values = form.field_values();
+
+            let mut text = vec![
+                Line::from(Span::styled(
+                    format!("编辑接口配置 - {}", form.interface_name),
+                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                )),
+                Line::from(""),
+            ];
+
+            // 显示表单字段（纯IPv6模式下隐藏IPv4字段）
+            for &i in form.visible_fields() {
+                let (name, value) = (field_names[i], field_values[i]);
+                let is_current = i == form.current_field;
+                let is_editing_this = is_current && form.is_editing;
+
+                let style = if is_editing_this {
+                    // 正在编辑：青色背景，黑色文字
+                    Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)
+                } else if is_current {
+                    // 当前选中但未编辑：深灰背景，青色文字
+                    Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD).bg(Color::DarkGray)
+                } else {
+                    // 未选中：白色文字
+                    Style::default().fg(Color::White)
+                };
+
+                let cursor = if is_editing_this {
+                    format!("{} ", Symbol::Editing)  // 编辑图标
+                } else if is_current {
+                    format!("{} ", Symbol::Selected)  // 选中图标
+                } else {
+                    "  ".to_string()  // 空格
+                };
+
+                text.push(Line::from(vec![
+                    Span::styled(
+                        cursor,
+                        Style::default().fg(if is_editing_this { Color::Yellow } else { Color::Green }),
+                    ),
+                    Span::styled(format!("{:12}: ", name), style),
+                    Span::styled(value, style),
+                ]));
+            }
+
+            text.push(self.target_file_line());
+
+            if form.ipv6_only {
+                text.push(Line::from(Span::styled("  纯IPv6模式: 保存时将清除IPv4地址", Style::default().fg(Color::Yellow))));
+            }
+
+            text.push(Line::from(vec![
+                Span::raw("  保存方式: "),
+                if form.runtime_only {
+                    Span::styled("临时 (不持久化)", Style::default().fg(Color::Yellow))
+                } else {
+                    Span::styled("写入Netplan", Style::default().fg(Color::Green))
+                },
+            ]));
+
+            text.push(Line::from(vec![
+                Span::raw("  刷新DNS缓存: "),
+                Span::styled(
+                    if form.refresh_dns { "是" } else { "否" },
+                    Style::default().fg(if form.refresh_dns { Color::Green } else { Color::DarkGray }),
+                ),
+            ]));
+
+            text.push(Line::from(""));
+
+            // 显示错误信息
+            if let Some(err) = &form.error_message {
+                text.push(Line::from(Span::styled(
+                    format!("{} {}", Symbol::Error, err),
+                    Style::default().fg(Color::Red),
+                )));
+                text.push(Line::from(""));
+            }
+
+            text.push(Line::from(""));
+
+            // 根据模式显示不同的操作提示
+            if form.is_editing {
+                text.push(Line::from(Span::styled(
+                    "编辑模式:",
+                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                )));
+                text.push(Line::from("  输入字符 - 编辑内容"));
+                text.push(Line::from("  Backspace - 删除字符"));
+                text.push(Line::from(format!("  {}/{} 或 Ctrl+P/N - 调出本次会话保存过的值", Symbol::Up, Symbol::Down)));
+                text.push(Line::from("  IP地址可直接输入CIDR，如 192.168.1.10/24"));
+                text.push(Line::from("  IPv6可填多个，逗号分隔，如 2001:db8::10/64"));
+                text.push(Line::from("  Enter - 完成编辑"));
+                text.push(Line::from("  Esc - 取消编辑"));
+            } else {
+                text.push(Line::from(Span::styled(
+                    "导航模式:",
+                    Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+                )));
+                text.push(Line::from(format!("  {}/{} 或 k/j - 切换字段", Symbol::Up, Symbol::Down)));
+                text.push(Line::from("  Enter - 编辑当前字段"));
+                text.push(Line::from("  s - 保存配置"));
+                text.push(Line::from("  f - 切换保存后刷新DNS"));
+                text.push(Line::from("  p - 切换子网掩码/前缀长度格式"));
+                text.push(Line::from("  6 - 切换纯IPv6模式"));
+                text.push(Line::from("  t - 切换临时保存（不写入Netplan）"));
+                text.push(Line::from("  Esc - 取消"));
+            }
+
+            let paragraph = Paragraph::new(text)
+                .block(
+                    Block::default()
+                        .title("编辑配置")
+                        .style(Style::default().bg(Color::Black))
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .border_style(Style::default().fg(Color::Cyan)),
+                )
+                .alignment(Alignment::Left);
+
+            // area已经在前面计算过了
+            f.render_widget(paragraph, area);
+        }
+    }
+
+    /// 保存前显示修改前后的字段对比
+    fn draw_save_confirm(&self, f: &mut Frame) {
+        let Some(form) = self.edit_form.as_ref().filter(|form| form.confirming_save) else {
+            return;
+        };
+
+        let area = centered_rect(60, 45, f.size());
+        f.render_widget(Clear, area);
+
+        let changed = form.changed_fields();
+        let mut text = vec![
+            Line::from(Span::styled(
+                format!("确认保存 - {}", form.interface_name),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+            Line::from(Span::styled(
+                format!("  {:12}  {:18}  {}", "字段", "原值", "新值"),
+                Style::default().fg(Color::DarkGray),
+            )),
+        ];
+
+        let (field_names, field_values) = (form.field_names(), form.field_values());
+        for &i in form.visible_fields() {
+            let (name, new) = (field_names[i], field_values[i]);
+            let old = if form.original[i].is_empty() { "-" } else { form.original[i].as_str() };
+            let new = if new.is_empty() { "-" } else { new };
+            let (marker, style) = if changed.contains(&i) {
+                ("*", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+            } else {
+                (" ", Style::default().fg(Color::White))
+            };
+            text.push(Line::from(vec![
+                Span::styled(format!("{} {:12}  ", marker, name), style),
+                Span::styled(format!("{:18}  ", old), Style::default().fg(Color::DarkGray)),
+                Span::styled(new.to_string(), style),
+            ]));
+        }
+
+        text.push(Line::from(""));
+        if changed.is_empty() {
+            text.push(Line::from(Span::styled(
+                "没有字段被修改，保存将重写当前配置",
+                Style::default().fg(Color::DarkGray),
+            )));
+            text.push(Line::from(""));
+        }
+        if form.runtime_only {
+            text.push(Line::from(Span::styled(
+                "临时修改：只在运行时生效，不写入Netplan，重启后失效",
+                Style::default().fg(Color::Yellow),
+            )));
+            text.push(Line::from(""));
+        }
+        text.push(Line::from(vec![
+            Span::styled("Y", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
+            Span::raw(" - 确认保存  "),
+            Span::styled("N", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+            Span::raw(" - 返回修改"),
+        ]));
+
+        let paragraph = Paragraph::new(text)
+            .block(
+                Block::default()
+                    .title("保存确认")
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(Color::Yellow))
+                    .style(Style::default().bg(Color::Black)),
+            )
+            .alignment(Alignment::Left);
+        f.render_widget(paragraph, area);
+    }
+
+    /// 显示保存时将写入的netplan文件
+    fn target_file_line(&self) -> Line<'static> {
+        Line::from(vec![
+            Span::raw("  目标文件: "),
+            Span::styled(self.target_file.clone(), Style::default().fg(Color::DarkGray)),
+        ])
+    }
+
+    fn draw_freeze_preview(&self, f: &mut Frame) {
+        let Some(frozen) = &self.freeze_preview else {
+            return;
+        };
+
+        let area = centered_rect(60, 50, f.size());
+        f.render_widget(Clear, area);
+
+        let value = |text: String| Span::styled(text, Style::default().fg(Color::Cyan));
+        let mut text = vec![
+            Line::from(Span::styled(
+                "把当前运行时配置写入Netplan",
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+            Line::from(vec![Span::raw("接口名称: "), value(frozen.interface_name.clone())]),
+            self.target_file_line(),
+            Line::from(""),
+            Line::from(vec![Span::raw("地址:     "), value(frozen.address.clone())]),
+            Line::from(vec![Span::raw("网关:     "), value(frozen.gateway.clone().unwrap_or_else(|| "无".to_string()))]),
+            Line::from(vec![
+                Span::raw("DNS:      "),
+                value(if frozen.nameservers.is_empty() { "无".to_string() } else { frozen.nameservers.join(", ") }),
+            ]),
+        ];
+        if !frozen.search.is_empty() {
+            text.push(Line::from(vec![Span::raw("搜索域:   "), value(frozen.search.join(", "))]));
+        }
+
+        if !frozen.skipped.is_empty() {
+            text.push(Line::from(Span::styled(
+                format!("{} 以下地址不会写入: {}", Symbol::Warning, frozen.skipped.join(", ")),
+                Style::default().fg(Color::Yellow),
+            )));
+        }
+        if let Some(warning) = self.interfaces.iter().find(|iface| iface.name == frozen.interface_name).and_then(unmanaged_warning) {
+            text.push(Line::from(Span::styled(format!("{} {}", Symbol::Warning, warning), Style::default().fg(Color::Yellow))));
+        }
+
+        text.extend([
+            Line::from(""),
+            Line::from("  • 接口将改为静态配置（dhcp4: false）"),
+            Line::from("  • 原配置文件会先备份"),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled("Y", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
+                Span::raw(" - 写入  "),
+                Span::styled("N", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+                Span::raw(" - 取消"),
+            ]),
+        ]);
+
+        let paragraph = Paragraph::new(text)
+            .block(
+                Block::default()
+                    .title("固化当前配置")
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(Color::Yellow))
+                    .style(Style::default().bg(Color::Black)),
+            );
+
+        f.render_widget(paragraph, area);
+    }
+
+    fn draw_toggle_dhcp(&self, f: &mut Frame) {
+        if let Some(i) = self.list_state.selected()
+            && let Some(iface) = self.interfaces.get(i) {
+            // 计算弹窗区域
+            let area = centered_rect(60, 50, f.size());
+
+            // 只清除弹窗区域
+            f.render_widget(Clear, area);
+            let mut text = vec![
+                Line::from(Span::styled(
+                    "切换到DHCP模式",
+                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                )),
+                Line::from(""),
+                Line::from(vec![
+                    Span::raw("接口名称: "),
+                    Span::styled(&iface.name, Style::default().fg(Color::Cyan)),
+                ]),
+                self.target_file_line(),
+                Line::from(""),
+                Line::from(Span::styled(
+                    format!("{}  警告:", Symbol::Warning),
+                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                )),
+                Line::from("  • 当前静态IP配置将被清除"),
+                Line::from("  • 接口将自动从DHCP服务器获取IP"),
+                Line::from("  • 此操作将修改Netplan配置"),
+            ];
+            if let Some(warning) = unmanaged_warning(iface) {
+                text.push(Line::from(Span::styled(format!("  • {}", warning), Style::default().fg(Color::Yellow))));
+            }
+            text.extend([
+                Line::from(""),
+                Line::from(vec![
+                    Span::raw("保留现有DNS: "),
+                    Span::styled(
+                        if self.dhcp_keep_dns { "是（忽略DHCP下发的DNS）" } else { "否（使用DHCP下发的DNS）" },
+                        Style::default().fg(if self.dhcp_keep_dns { Color::Green } else { Color::DarkGray }),
+                    ),
+                    Span::styled("  k 切换", Style::default().fg(Color::DarkGray)),
+                ]),
+                Line::from(""),
+                Line::from(Span::styled(
+                    "确定要切换到DHCP模式吗？",
+                    Style::default().fg(Color::Yellow),
+                )),
+                Line::from(""),
+                Line::from(vec![
+                    Span::styled("Y", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
+                    Span::raw(" - 确认切换  "),
+                    Span::styled("N", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+                    Span::raw(" - 取消"),
+                ]),
+            ]);
+
+            let paragraph = Paragraph::new(text)
+                .block(
+                    Block::default()
+                        .title("切换DHCP")
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .border_style(Style::default().fg(Color::Yellow))
+                        .style(Style::default().bg(Color::Black)),
+                )
+                .alignment(Alignment::Left);
+
+            // area已经在前面计算过了
+            f.render_widget(paragraph, area);
+        }
+    }
+
+    fn draw_confirm_safe_mode_off(&self, f: &mut Frame) {
+        let area = centered_rect(50, 30, f.size());
+        f.render_widget(Clear, area);
+
+        let text = vec![
+            Line::from(Span::styled(
+                "关闭安全模式",
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+            Line::from("关闭后编辑、删除、启停接口、创建者操作等将真正执行。"),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled("Y", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
+                Span::raw(" - 关闭安全模式  "),
+                Span::styled("N", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+                Span::raw(" - 保持开启"),
+            ]),
+        ];
+
+        let paragraph = Paragraph::new(text)
+            .block(
+                Block::default()
+                    .title("安全模式")
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(Color::Yellow))
+                    .style(Style::default().bg(Color::Black)),
+            )
+            .wrap(Wrap { trim: true })
+            .alignment(Alignment::Left);
+
+        f.render_widget(paragraph, area);
+    }
+
+    fn draw_clear_config(&self, f: &mut Frame) {
+        let Some(iface) = self.selected_interface() else {
+            return;
+        };
+
+        let area = centered_rect(60, 50, f.size());
+        f.render_widget(Clear, area);
+
+        let defined_in = match self.netplan().find_interface_file(&iface.name) {
+            Some(path) => path.display().to_string(),
+            None => "未定义".to_string(),
+        };
+        let mut text = vec![
+            Line::from(Span::styled(
+                "清除接口配置",
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+            Line::from(vec![
+                Span::raw("接口名称: "),
+                Span::styled(iface.name.clone(), Style::default().fg(Color::Cyan)),
+            ]),
+            Line::from(vec![
+                Span::raw("  Netplan: "),
+                Span::styled(defined_in, Style::default().fg(Color::DarkGray)),
+            ]),
+            Line::from(""),
+            Line::from(Span::styled(
+                format!("{}  警告:", Symbol::Warning),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            )),
+            Line::from("  • 从所有Netplan文件中删除该接口的条目（修改前自动备份）"),
+            Line::from("  • 清除接口上的IPv4和全局IPv6地址"),
+            Line::from("  • 不会改为DHCP，接口将处于未配置状态"),
+        ];
+        if let Some(warning) = unmanaged_warning(iface) {
+            text.push(Line::from(Span::styled(format!("  • {}", warning), Style::default().fg(Color::Yellow))));
+        }
+        text.extend([
+            Line::from(""),
+            Line::from(vec![
+                Span::raw("清除后禁用接口: "),
+                Span::styled(
+                    if self.clear_link_down { "是（设置为DOWN）" } else { "否（保持当前状态）" },
+                    Style::default().fg(if self.clear_link_down { Color::Yellow } else { Color::DarkGray }),
+                ),
+                Span::styled("  d 切换", Style::default().fg(Color::DarkGray)),
+            ]),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled("Y", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
+                Span::raw(" - 确认清除  "),
+                Span::styled("N", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+                Span::raw(" - 取消"),
+            ]),
+        ]);
+
+        let paragraph = Paragraph::new(text)
+            .block(
+                Block::default()
+                    .title("清除配置")
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(Color::Red))
+                    .style(Style::default().bg(Color::Black)),
+            )
+            .alignment(Alignment::Left);
+
+        f.render_widget(paragraph, area);
+    }
+
+    fn draw_owner_actions(&self, f: &mut Frame) {
+        if let Some(i) = self.list_state.selected()
+            && let Some(iface) = self.interfaces.get(i)
+            && let Some(owner) = &iface.owner {
+            // 计算弹窗区域
+            let area = centered_rect(70, 60, f.size());
+
+            // 只清除弹窗区域
+            f.render_widget(Clear, area);
+
+            use crate::model::InterfaceOwner;
+            let (action_name, action_desc, warning) = match owner {
+                InterfaceOwner::SystemdService { name, .. } => (
+                    "停止systemd服务",
+                    format!("服务名: {}\n\n将执行: systemctl stop {}", name, name),
+                    format!("{} 警告：停止服务可能影响系统功能！", Symbol::Warning),
+                ),
+                InterfaceOwner::DockerContainer { id, name, .. } => (
+                    "停止Docker容器",
+                    format!("容器名: {}\n容器ID: {}\n\n将执行: docker stop {}", name, &id[..12.min(id.len())], &id[..12.min(id.len())]),
+                    format!("{} 警告：停止容器将中断容器内的所有服务！", Symbol::Warning),
+                ),
+                InterfaceOwner::DockerNetwork { id, name, containers } => (
+                    "删除Docker网络",
+                    format!("网络名: {}\n网络ID: {}\n\n将执行: docker network rm {}", name, &id[..12.min(id.len())], &id[..12.min(id.len())]),
+                    if *containers == 0 {
+                        format!("{} 警告：删除后使用该网络的compose项目需要重新创建网络！", Symbol::Warning)
+                    } else {
+                        format!("{} 仍有 {} 个容器连接到该网络，无法删除", Symbol::Warning, containers)
+                    },
+                ),
+                InterfaceOwner::Process { pid, name, .. } => (
+                    "终止进程",
+                    format!("进程名: {}\n进程ID: {}\n\n将执行: kill {}", name, pid, pid),
+                    format!("{} 警告：强制终止进程可能导致数据丢失！", Symbol::Warning),
+                ),
+                InterfaceOwner::NetworkManager { connection, .. } => (
+                    "断开NetworkManager连接",
+                    format!("连接名: {}\n\n将执行: nmcli connection down {}", connection, connection),
+                    format!("{} 警告：断开连接将中断网络服务！", Symbol::Warning),
+                ),
+                InterfaceOwner::NspawnMachine { machine } => (
+                    "终止nspawn容器",
+                    format!("机器名: {}\n\n将执行: machinectl terminate {}", machine, machine),
+                    format!("{} 警告：终止容器将中断容器内的所有服务！", Symbol::Warning),
+                ),
+                InterfaceOwner::Kernel { module } => (
+                    "卸载内核模块",
+                    format!("模块名: {}\n\n将执行: rmmod {}", module, module),
+                    format!("{} 警告：卸载内核模块可能导致系统不稳定！", Symbol::Warning),
+                ),
+                InterfaceOwner::Unknown => return,
+            };
+
+            let text = vec![
+                Line::from(Span::styled(
+                    action_name,
+                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                )),
+                Line::from(""),
+                Line::from(Span::styled(warning, Style::default().fg(Color::Red))),
+                Line::from(""),
+                Line::from(action_desc),
+                Line::from(""),
+                if command::use_sudo() {
+                    Line::from(Span::styled(
+                        format!("{} 将通过 sudo -n 执行（sudo凭据过期时会失败）", Symbol::Warning),
+                        Style::default().fg(Color::Yellow),
+                    ))
+                } else {
+                    Line::from("")
+                },
+                Line::from(vec![
+                    Span::styled("Y", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
+                    Span::raw(" - 确认执行  "),
+                    Span::styled("N", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+                    Span::raw(" - 取消"),
+                ]),
+            ];
+
+            let paragraph = Paragraph::new(text)
+                .block(
+                    Block::default()
+                        .title("创建者操作")
+                        .borders(Borders::ALL)
+                        .border_type(BorderType::Rounded)
+                        .border_style(Style::default().fg(Color::Yellow))
+                        .style(Style::default().bg(Color::Black)),
+                )
+                .alignment(Alignment::Left);
+
+            f.render_widget(paragraph, area);
+        }
+    }
+
+    fn execute_owner_action(&mut self) -> Result<()> {
+        if self.blocked_by_safe_mode() {
+            return Ok(());
+        }
+        if let Some(i) = self.list_state.selected()
+            && let Some(iface) = self.interfaces.get(i)
+            && let Some(owner) = &iface.owner {
+            use crate::model::InterfaceOwner;
+            use crate::utils::command::execute_privileged_stdout;
+
+            let result = match owner {
+                InterfaceOwner::SystemdService { name, .. } => {
+                    execute_privileged_stdout("systemctl", &["stop", name])
                 },
-                InterfaceOwner::Process { pid, name, cmdline } => {
-                    lines.push(Line::from(vec![
-                        Span::styled("  进程ID: ", Style::default().fg(Color::Cyan)),
-                        Span::raw(format!("{}", pid)),
-                    ]));
-                    lines.push(Line::from(vec![
-                        Span::styled("  进程名: ", Style::default().fg(Color::Cyan)),
-                        Span::raw(name),
-                    ]));
-                    if !cmdline.is_empty() {
-                        lines.push(Line::from(vec![
-                            Span::styled("  命令行: ", Style::default().fg(Color::Cyan)),
-                            Span::raw(cmdline),
-                        ]));
+                InterfaceOwner::DockerContainer { id, .. } => {
+                    // 检查是否是系统网桥（docker0等）
+                    if id == "system" {
+                        // docker0是系统网桥，不能通过docker stop停止
+                        // 返回一个友好的错误信息
+                        return Err(anyhow::anyhow!("Docker网桥是系统组件，无法停止。请使用 'systemctl stop docker' 停止Docker服务。"));
+                    }
+                    execute_privileged_stdout("docker", &["stop", id])
+                },
+                InterfaceOwner::DockerNetwork { id, name, .. } => {
+                    // 执行前重新确认没有容器连接，避免删除正在使用的网络
+                    use crate::backend::owner_detection::OwnerDetector;
+                    match OwnerDetector::docker_network_containers(id) {
+                        Ok(0) => execute_privileged_stdout("docker", &["network", "rm", id]),
+                        Ok(n) => Err(anyhow::anyhow!("Docker网络 {} 仍有 {} 个容器连接，未删除", name, n)),
+                        Err(e) => Err(e),
+                    }
+                },
+                InterfaceOwner::Process { pid, .. } => {
+                    execute_privileged_stdout("kill", &[&pid.to_string()])
+                },
+                InterfaceOwner::NetworkManager { connection, .. } => {
+                    execute_privileged_stdout("nmcli", &["connection", "down", connection])
+                },
+                InterfaceOwner::NspawnMachine { machine } => {
+                    execute_privileged_stdout("machinectl", &["terminate", machine])
+                },
+                InterfaceOwner::Kernel { module } => {
+                    execute_privileged_stdout("rmmod", &[module])
+                },
+                InterfaceOwner::Unknown => return Ok(()),
+            };
+
+            // 轮询等待接口消失，生效后立即刷新；nmcli返回时已完成停用，物理网卡也不会消失
+            if result.is_ok() && !matches!(owner, InterfaceOwner::NetworkManager { .. }) {
+                runtime::wait_for_interface_removed(&iface.name, self.options.owner_action_timeout);
+            }
+
+            // 刷新接口列表
+            self.refresh();
+
+            // 检查操作结果，如果失败则显示错误但不退出程序
+            if let Err(e) = result {
+                tracing::error!("操作失败: {:#}", e);
+                self.status_message = Some(format!("{} 操作失败: {}", Symbol::Error, e));
+                // 不传播错误，避免程序退出
+            }
+        }
+        Ok(())
+    }
+
+    fn start_path_mtu_test(&mut self, iface: &NetInterface) {
+        let gateway = iface.ipv4_config.as_ref().and_then(|cfg| cfg.gateway.clone());
+        if let Some(gateway) = gateway {
+            self.pmtu_progress = Some(pmtu::spawn_discovery(&iface.name, &gateway, iface.mtu));
+            self.screen = Screen::PathMtu;
+        } else {
+            self.status_message = Some(format!("{} 接口 {} 没有IPv4网关，无法探测路径MTU", Symbol::Warning, iface.name));
+            self.screen = Screen::Main;
+        }
+    }
+
+    fn start_health_check(&mut self, iface: &NetInterface) {
+        let gateway = iface.ipv4_config.as_ref().and_then(|cfg| cfg.gateway.clone());
+        self.health_report = Some(health::spawn_check(&iface.name, gateway));
+        self.screen = Screen::HealthCheck;
+    }
+
+    fn draw_health_check(&self, f: &mut Frame) {
+        let Some(report) = &self.health_report else {
+            return;
+        };
+        let report = match report.lock() {
+            Ok(r) => r.clone(),
+            Err(_) => return,
+        };
+
+        let area = centered_rect(60, 40, f.size());
+        f.render_widget(Clear, area);
+
+        let mut text = vec![
+            Line::from(Span::styled(
+                format!("连通性检查 - {}", report.iface_name),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+        ];
+
+        const SPINNER: [&str; 4] = ["|", "/", "-", "\\"];
+        let frame = (self.last_update.elapsed().as_millis() / 250) as usize % SPINNER.len();
+
+        for step in &report.steps {
+            let (mark, detail, color) = match &step.status {
+                health::CheckStatus::Pending => ("·".to_string(), String::new(), Color::DarkGray),
+                health::CheckStatus::Running => (SPINNER[frame].to_string(), "检查中...".to_string(), Color::Yellow),
+                health::CheckStatus::Passed(msg) => (Symbol::Ok.to_string(), msg.clone(), Color::Green),
+                health::CheckStatus::Failed(msg) => (Symbol::Error.to_string(), msg.clone(), Color::Red),
+                health::CheckStatus::Skipped(msg) => (Symbol::Warning.to_string(), msg.clone(), Color::Yellow),
+            };
+            text.push(Line::from(vec![
+                Span::styled(format!("{} ", mark), Style::default().fg(color)),
+                Span::styled(format!("{:8}", step.name), Style::default().fg(Color::Cyan)),
+                Span::styled(detail, Style::default().fg(color)),
+            ]));
+        }
+
+        text.push(Line::from(""));
+        if report.finished {
+            if report.all_passed() {
+                text.push(Line::from(Span::styled(
+                    "接口工作正常",
+                    Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
+                )));
+            } else {
+                text.push(Line::from(Span::styled(
+                    "部分检查未通过",
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                )));
+            }
+        }
+        text.push(Line::from(Span::styled("Esc - 关闭", Style::default().fg(Color::DarkGray))));
+
+        let paragraph = Paragraph::new(text)
+            .block(
+                Block::default()
+                    .title("连通性检查")
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(Color::Cyan))
+                    .style(Style::default().bg(Color::Black)),
+            )
+            .alignment(Alignment::Left);
+
+        f.render_widget(paragraph, area);
+    }
+
+    fn draw_path_mtu(&self, f: &mut Frame) {
+        let Some(progress) = &self.pmtu_progress else {
+            return;
+        };
+        let progress = match progress.lock() {
+            Ok(p) => p.clone(),
+            Err(_) => return,
+        };
+
+        let area = centered_rect(50, 30, f.size());
+        f.render_widget(Clear, area);
+
+        let mut text = vec![
+            Line::from(Span::styled(
+                format!("路径MTU测试 - 目标 {}", progress.target),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+        ];
+
+        match &progress.result {
+            None => {
+                const SPINNER: [&str; 4] = ["|", "/", "-", "\\"];
+                let frame = (self.last_update.elapsed().as_millis() / 250) as usize % SPINNER.len();
+                text.push(Line::from(format!(
+                    "{} 正在探测载荷 {} 字节 (范围 {}-{})",
+                    SPINNER[frame], progress.probing, progress.low, progress.high
+                )));
+            }
+            Some(Ok(mtu)) => {
+                text.push(Line::from(vec![
+                    Span::raw("路径MTU: "),
+                    Span::styled(format!("{} 字节", mtu), Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
+                ]));
+            }
+            Some(Err(e)) => {
+                text.push(Line::from(Span::styled(format!("{} 探测失败: {}", Symbol::Error, e), Style::default().fg(Color::Red))));
+            }
+        }
+
+        text.push(Line::from(""));
+        text.push(Line::from(Span::styled("Esc - 关闭", Style::default().fg(Color::DarkGray))));
+
+        let paragraph = Paragraph::new(text)
+            .block(
+                Block::default()
+                    .title("路径MTU")
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(Color::Cyan))
+                    .style(Style::default().bg(Color::Black)),
+            )
+            .alignment(Alignment::Left);
+
+        f.render_widget(paragraph, area);
+    }
+
+    fn draw_confirm_create_netplan(&self, f: &mut Frame) {
+        let Some(iface) = self.list_state.selected().and_then(|i| self.interfaces.get(i)) else {
+            return;
+        };
+
+        let area = centered_rect(60, 30, f.size());
+        f.render_widget(Clear, area);
+
+        let text = vec![
+            Line::from(Span::styled(
+                "未找到Netplan配置",
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+            Line::from(format!("没有任何netplan文件定义接口 {}", iface.name)),
+            Line::from(format!("是否创建 /etc/netplan/90-nicman-{}.yaml 并打开编辑？", iface.name)),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled("Y", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
+                Span::raw(" - 创建并编辑  "),
+                Span::styled("N", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+                Span::raw(" - 取消"),
+            ]),
+        ];
+
+        let paragraph = Paragraph::new(text)
+            .block(
+                Block::default()
+                    .title("编辑Netplan文件")
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(Color::Yellow))
+                    .style(Style::default().bg(Color::Black)),
+            )
+            .alignment(Alignment::Left);
+
+        f.render_widget(paragraph, area);
+    }
+
+    fn draw_owner_details(&self, f: &mut Frame) {
+        let Some(result) = &self.owner_details else {
+            return;
+        };
+
+        let area = centered_rect(70, 60, f.size());
+        f.render_widget(Clear, area);
+
+        let (title, text) = match result {
+            Ok(details) => {
+                let width = details.fields.iter().map(|(label, _)| label.chars().count()).max().unwrap_or(0);
+                let mut text: Vec<Line> = details
+                    .fields
+                    .iter()
+                    .map(|(label, value)| {
+                        // 中文标签按两个字符宽度对齐
+                        let pad = " ".repeat((width - label.chars().count()) * 2);
+                        Line::from(vec![
+                            Span::styled(format!("{}{}: ", label, pad), Style::default().fg(Color::Cyan)),
+                            Span::raw(value.clone()),
+                        ])
+                    })
+                    .collect();
+                text.push(Line::from(""));
+                text.push(Line::from(Span::styled("Esc - 关闭", Style::default().fg(Color::DarkGray))));
+                (details.title.clone(), text)
+            }
+            Err(e) => (
+                "创建者详情".to_string(),
+                vec![
+                    Line::from(Span::styled(format!("{} {}", Symbol::Error, e), Style::default().fg(Color::Red))),
+                    Line::from(""),
+                    Line::from(Span::styled("Esc - 关闭", Style::default().fg(Color::DarkGray))),
+                ],
+            ),
+        };
+
+        let paragraph = Paragraph::new(text)
+            .block(
+                Block::default()
+                    .title(title)
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(Color::Cyan))
+                    .style(Style::default().bg(Color::Black)),
+            )
+            .wrap(Wrap { trim: false })
+            .alignment(Alignment::Left);
+
+        f.render_widget(paragraph, area);
+    }
+
+    fn draw_neighbors(&self, f: &mut Frame) {
+        let (Some(result), Some(iface)) = (&self.neighbors, self.selected_interface()) else {
+            return;
+        };
+
+        let area = centered_rect(70, 60, f.size());
+        f.render_widget(Clear, area);
+
+        let mut text = Vec::new();
+        match result {
+            Ok(entries) if entries.is_empty() => {
+                text.push(Line::from(Span::styled("邻居表为空", Style::default().fg(Color::DarkGray))));
+            }
+            Ok(entries) => {
+                text.push(Line::from(Span::styled(
+                    // 中文标题每个字占两列，少补两个空格
+                    format!("{:<38} {:<16} {}", "IP地址", "MAC地址", "状态"),
+                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                )));
+                for entry in entries {
+                    let state_color = match entry.state.as_str() {
+                        "REACHABLE" | "PERMANENT" | "NOARP" => Color::Green,
+                        "STALE" | "DELAY" | "PROBE" => Color::Yellow,
+                        "FAILED" | "INCOMPLETE" => Color::Red,
+                        _ => Color::DarkGray,
+                    };
+                    let mut spans = vec![
+                        Span::styled(format!("{:<40} ", entry.ip), Style::default().fg(Color::Cyan)),
+                        Span::raw(format!("{:<18} ", entry.mac.as_deref().unwrap_or("-"))),
+                        Span::styled(entry.state.clone(), Style::default().fg(state_color)),
+                    ];
+                    if entry.router {
+                        spans.push(Span::styled(" (路由器)", Style::default().fg(Color::DarkGray)));
                     }
-                    lines.push(Line::from(vec![
-                        Span::styled("  操作: ", Style::default().fg(Color::Green)),
-                        Span::raw("按 'o' 键终止进程"),
-                    ]));
-                },
-                InterfaceOwner::NetworkManager { connection, .. } => {
-                    lines.push(Line::from(vec![
-                        Span::styled("  连接名: ", Style::default().fg(Color::Cyan)),
-                        Span::raw(connection),
-                    ]));
-                    lines.push(Line::from(vec![
-                        Span::styled("  操作: ", Style::default().fg(Color::Green)),
-                        Span::raw("按 'o' 键断开连接"),
-                    ]));
-                },
-                InterfaceOwner::Kernel { module } => {
-                    lines.push(Line::from(vec![
-                        Span::styled("  内核模块: ", Style::default().fg(Color::Cyan)),
-                        Span::raw(module),
-                    ]));
-                    lines.push(Line::from(vec![
-                        Span::styled("  操作: ", Style::default().fg(Color::Green)),
-                        Span::raw("按 'o' 键卸载模块"),
-                    ]));
-                },
-                InterfaceOwner::Unknown => {},
+                    text.push(Line::from(spans));
+                }
+            }
+            Err(e) => {
+                text.push(Line::from(Span::styled(format!("{} {}", Symbol::Error, e), Style::default().fg(Color::Red))));
+            }
+        }
+        text.push(Line::from(""));
+        text.push(Line::from(Span::styled("r - 刷新  Esc - 关闭", Style::default().fg(Color::DarkGray))));
+
+        let paragraph = Paragraph::new(text)
+            .block(
+                Block::default()
+                    .title(format!("邻居表 - {}", iface.name))
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(Color::Cyan))
+                    .style(Style::default().bg(Color::Black)),
+            )
+            .alignment(Alignment::Left);
+
+        f.render_widget(paragraph, area);
+    }
+
+    fn draw_diagnostics(&self, f: &mut Frame) {
+        let area = centered_rect(60, 40, f.size());
+        f.render_widget(Clear, area);
+
+        let mut text = vec![
+            Line::from(Span::styled(
+                "生成诊断信息",
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+            Line::from("包含 ip link/addr/route 输出、netplan配置、接口创建者和版本号"),
+            Line::from(vec![
+                Span::raw("隐藏IP/MAC地址: "),
+                Span::styled(
+                    if self.diag_redact { "是" } else { "否" },
+                    Style::default().fg(if self.diag_redact { Color::Green } else { Color::Red }),
+                ),
+            ]),
+            Line::from(""),
+        ];
+
+        match &self.diag_result {
+            None => {
+                text.push(Line::from(Span::styled(
+                    "Enter - 生成  r - 切换隐藏地址  Esc - 取消",
+                    Style::default().fg(Color::DarkGray),
+                )));
+            }
+            Some(Ok(path)) => {
+                text.push(Line::from(Span::styled(
+                    format!("{} 已写入 {}", Symbol::Ok, path.display()),
+                    Style::default().fg(Color::Green),
+                )));
+                text.push(Line::from(Span::styled("Esc - 关闭", Style::default().fg(Color::DarkGray))));
+            }
+            Some(Err(e)) => {
+                text.push(Line::from(Span::styled(
+                    format!("{} {}", Symbol::Error, e),
+                    Style::default().fg(Color::Red),
+                )));
+                text.push(Line::from(Span::styled("Esc - 关闭", Style::default().fg(Color::DarkGray))));
             }
         }
 
-        let paragraph = Paragraph::new(lines)
+        let paragraph = Paragraph::new(text)
+            .block(
+                Block::default()
+                    .title("诊断信息")
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(Color::Cyan))
+                    .style(Style::default().bg(Color::Black)),
+            )
+            .alignment(Alignment::Left)
+            .wrap(Wrap { trim: false });
+
+        f.render_widget(paragraph, area);
+    }
+
+    fn draw_try_netplan(&self, f: &mut Frame) {
+        let area = centered_rect(70, 50, f.size());
+        f.render_widget(Clear, area);
+
+        let mut text = vec![
+            Line::from(Span::styled(
+                "测试配置 (netplan try)",
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+        ];
+
+        if let Some(session) = &self.try_session {
+            text.push(Line::from("新配置已临时应用，请确认网络是否正常"));
+            text.push(Line::from(vec![
+                Span::raw("剩余 "),
+                Span::styled(
+                    format!("{}", session.remaining().as_secs()),
+                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(" 秒后自动回滚"),
+            ]));
+            text.push(Line::from(""));
+            text.push(Line::from(vec![
+                Span::styled("Y", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
+                Span::raw(" - 保留配置  "),
+                Span::styled("N", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+                Span::raw(" - 立即回滚"),
+            ]));
+        } else if let Some(result) = &self.try_result {
+            let (title, output, color) = match result {
+                Ok(output) => (format!("{} 测试结束", Symbol::Ok), output, Color::Green),
+                Err(output) => (format!("{} 配置已回滚或测试失败", Symbol::Warning), output, Color::Red),
+            };
+            text.push(Line::from(Span::styled(title, Style::default().fg(color).add_modifier(Modifier::BOLD))));
+            text.push(Line::from(""));
+            for line in output.lines() {
+                text.push(Line::from(line.to_string()));
+            }
+            text.push(Line::from(""));
+            text.push(Line::from(Span::styled("Esc - 关闭", Style::default().fg(Color::DarkGray))));
+        }
+
+        let paragraph = Paragraph::new(text)
+            .block(
+                Block::default()
+                    .title("测试配置")
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(Color::Yellow))
+                    .style(Style::default().bg(Color::Black)),
+            )
+            .alignment(Alignment::Left)
+            .wrap(Wrap { trim: false });
+
+        f.render_widget(paragraph, area);
+    }
+
+    fn draw_apply_netplan(&self, f: &mut Frame) {
+        let area = centered_rect(70, 50, f.size());
+        f.render_widget(Clear, area);
+
+        let mut text = Vec::new();
+        let border_color = match &self.apply_result {
+            None => {
+                text.push(Line::from(Span::styled(
+                    "应用Netplan配置",
+                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                )));
+                text.push(Line::from(""));
+                text.push(Line::from("将执行 netplan apply，使/etc/netplan下的所有配置生效"));
+                text.push(Line::from(Span::styled(
+                    format!("{}  远程连接的接口配置错误可能导致断连", Symbol::Warning),
+                    Style::default().fg(Color::Yellow),
+                )));
+                text.push(Line::from(""));
+                text.push(Line::from(vec![
+                    Span::styled("Y", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
+                    Span::raw(" - 应用  "),
+                    Span::styled("N", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+                    Span::raw(" - 取消"),
+                ]));
+                Color::Yellow
+            }
+            Some(Ok(output)) => {
+                text.push(Line::from(Span::styled(
+                    format!("{} Netplan配置已应用", Symbol::Ok),
+                    Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
+                )));
+                text.push(Line::from(""));
+                if output.is_empty() {
+                    text.push(Line::from(Span::styled("(无输出)", Style::default().fg(Color::DarkGray))));
+                }
+                for line in output.lines() {
+                    text.push(Line::from(line.to_string()));
+                }
+                Color::Green
+            }
+            Some(Err(e)) => {
+                text.push(Line::from(Span::styled(
+                    format!("{} 应用失败", Symbol::Error),
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                )));
+                text.push(Line::from(""));
+                for line in e.lines() {
+                    text.push(Line::from(Span::styled(line.to_string(), Style::default().fg(Color::Red))));
+                }
+                Color::Red
+            }
+        };
+
+        if self.apply_result.is_some() {
+            text.push(Line::from(""));
+            text.push(Line::from(Span::styled("Esc - 关闭", Style::default().fg(Color::DarkGray))));
+        }
+
+        let paragraph = Paragraph::new(text)
+            .block(
+                Block::default()
+                    .title("netplan apply")
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(border_color))
+                    .style(Style::default().bg(Color::Black)),
+            )
+            .alignment(Alignment::Left)
+            .wrap(Wrap { trim: false });
+
+        f.render_widget(paragraph, area);
+    }
+
+    fn draw_gateway_form(&self, f: &mut Frame) {
+        let Some(form) = &self.gateway_form else {
+            return;
+        };
+
+        let area = centered_rect(60, 40, f.size());
+        f.render_widget(Clear, area);
+
+        let mut text = vec![
+            Line::from(Span::styled(
+                format!("修改默认网关 - {}", form.interface_name),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled("接口地址: ", Style::default().fg(Color::Cyan)),
+                Span::raw(form.addresses.join(", ")),
+            ]),
+            self.target_file_line(),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled(format!("{} ", Symbol::Editing), Style::default().fg(Color::Yellow)),
+                Span::styled("网关: ", Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)),
+                Span::styled(form.gateway.as_str(), Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            ]),
+            Line::from(""),
+        ];
+
+        if let Some(err) = &form.error_message {
+            text.push(Line::from(Span::styled(
+                format!("{} {}", Symbol::Error, err),
+                Style::default().fg(Color::Red),
+            )));
+            text.push(Line::from(""));
+        }
+
+        text.push(Line::from("仅修改默认路由，不会清除接口地址"));
+        text.push(Line::from(Span::styled(
+            "Enter - 保存  Esc - 取消",
+            Style::default().fg(Color::DarkGray),
+        )));
+
+        let paragraph = Paragraph::new(text)
+            .block(
+                Block::default()
+                    .title("修改网关")
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(Color::Cyan))
+                    .style(Style::default().bg(Color::Black)),
+            )
+            .alignment(Alignment::Left);
+
+        f.render_widget(paragraph, area);
+    }
+
+    fn draw_mac_form(&self, f: &mut Frame) {
+        let Some(form) = &self.mac_form else {
+            return;
+        };
+
+        let area = centered_rect(60, 40, f.size());
+        f.render_widget(Clear, area);
+
+        let mut text = vec![
+            Line::from(Span::styled(
+                format!("修改MAC地址 - {}", form.interface_name),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled("当前MAC: ", Style::default().fg(Color::Cyan)),
+                Span::raw(form.current_mac.as_deref().unwrap_or("无")),
+            ]),
+            Line::from(vec![
+                Span::styled("出厂MAC: ", Style::default().fg(Color::Cyan)),
+                Span::raw(form.permanent_mac.as_deref().unwrap_or("未知")),
+            ]),
+            if form.persist {
+                self.target_file_line()
+            } else {
+                Line::from(Span::styled("  接口未在Netplan中定义，仅运行时生效", Style::default().fg(Color::DarkGray)))
+            },
+            Line::from(""),
+            Line::from(vec![
+                Span::styled(format!("{} ", Symbol::Editing), Style::default().fg(Color::Yellow)),
+                Span::styled("新MAC: ", Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)),
+                Span::styled(form.mac.as_str(), Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            ]),
+            Line::from(""),
+        ];
+
+        if let Some(err) = &form.error_message {
+            text.push(Line::from(Span::styled(
+                format!("{} {}", Symbol::Error, err),
+                Style::default().fg(Color::Red),
+            )));
+            text.push(Line::from(""));
+        }
+
+        text.push(Line::from("修改时接口会先禁用再启用，远程连接可能短暂中断"));
+        text.push(Line::from(Span::styled(
+            "Enter - 保存  Esc - 取消",
+            Style::default().fg(Color::DarkGray),
+        )));
+
+        let paragraph = Paragraph::new(text)
+            .block(
+                Block::default()
+                    .title("修改MAC")
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(Color::Cyan))
+                    .style(Style::default().bg(Color::Black)),
+            )
+            .alignment(Alignment::Left);
+
+        f.render_widget(paragraph, area);
+    }
+
+    fn draw_rename_form(&self, f: &mut Frame) {
+        let Some(form) = &self.rename_form else {
+            return;
+        };
+
+        let area = centered_rect(60, 40, f.size());
+        f.render_widget(Clear, area);
+
+        let persist_line = match &form.mac_address {
+            Some(mac) => Line::from(vec![
+                Span::raw("  写入Netplan: "),
+                Span::styled(
+                    if form.persist { "是" } else { "否" },
+                    Style::default().fg(if form.persist { Color::Green } else { Color::DarkGray }),
+                ),
+                Span::styled(format!("  (match macaddress: {})", mac), Style::default().fg(Color::DarkGray)),
+            ]),
+            None => Line::from(Span::styled("  没有MAC地址，无法写入Netplan set-name", Style::default().fg(Color::DarkGray))),
+        };
+
+        let mut text = vec![
+            Line::from(Span::styled(
+                format!("重命名接口 - {}", form.interface_name),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled(format!("{} ", Symbol::Editing), Style::default().fg(Color::Yellow)),
+                Span::styled("新名称: ", Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)),
+                Span::styled(form.name.as_str(), Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            ]),
+            persist_line,
+        ];
+        if form.persist {
+            text.push(self.target_file_line());
+        }
+        text.push(Line::from(""));
+
+        if let Some(err) = &form.error_message {
+            text.push(Line::from(Span::styled(
+                format!("{} {}", Symbol::Error, err),
+                Style::default().fg(Color::Red),
+            )));
+            text.push(Line::from(""));
+        }
+
+        text.push(Line::from("接口需处于DOWN状态，引用旧名称的其他配置不会自动修改"));
+        text.push(Line::from(Span::styled(
+            "Tab - 切换是否写入Netplan  Enter - 重命名  Esc - 取消",
+            Style::default().fg(Color::DarkGray),
+        )));
+
+        let paragraph = Paragraph::new(text)
             .block(
                 Block::default()
-                    .title("接口详情")
+                    .title("重命名")
                     .borders(Borders::ALL)
                     .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(Color::Cyan))
+                    .style(Style::default().bg(Color::Black)),
             )
-            .wrap(Wrap { trim: true });
+            .alignment(Alignment::Left);
 
         f.render_widget(paragraph, area);
     }
 
-    fn draw_traffic_stats(&self, f: &mut Frame, area: Rect, iface: &NetInterface) {
-        let stats = &iface.traffic_stats;
+    fn draw_bridge_form(&self, f: &mut Frame) {
+        let Some(form) = &self.bridge_form else {
+            return;
+        };
 
-        let lines = vec![
+        let area = centered_rect(60, 60, f.size());
+        f.render_widget(Clear, area);
+
+        let name_style = if form.editing_name {
+            Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        let mut text = vec![
+            Line::from(Span::styled("创建网桥", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))),
+            Line::from(""),
             Line::from(vec![
-                Span::styled("接收: ", Style::default().fg(Color::Green)),
-                Span::raw(format!("{} ({} 包)", format_bytes(stats.rx_bytes), stats.rx_packets)),
+                Span::styled(
+                    if form.editing_name { format!("{} ", Symbol::Editing) } else { "  ".to_string() },
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::styled("网桥名称: ", name_style),
+                Span::styled(form.name.as_str(), name_style),
             ]),
+            Line::from(""),
+            Line::from(Span::styled("成员接口 (空格选择):", Style::default().fg(Color::Cyan))),
+        ];
+
+        if form.candidates.is_empty() {
+            text.push(Line::from(Span::styled("  没有可加入网桥的接口", Style::default().fg(Color::DarkGray))));
+        }
+        text.extend(member_list_lines(&form.candidates, (!form.editing_name).then_some(form.cursor)));
+        text.push(Line::from(""));
+
+        if let Some(err) = &form.error_message {
+            text.push(Line::from(Span::styled(
+                format!("{} {}", if form.address_confirmed { Symbol::Warning } else { Symbol::Error }, err),
+                Style::default().fg(if form.address_confirmed { Color::Yellow } else { Color::Red }),
+            )));
+            text.push(Line::from(""));
+        }
+
+        text.push(Line::from("仅运行时生效，创建后网桥自动启用"));
+        text.push(Line::from(Span::styled(
+            "Tab - 切换名称/成员  空格 - 选择成员  Enter - 创建  Esc - 取消",
+            Style::default().fg(Color::DarkGray),
+        )));
+
+        let paragraph = Paragraph::new(text)
+            .block(
+                Block::default()
+                    .title("创建网桥")
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(Color::Cyan))
+                    .style(Style::default().bg(Color::Black)),
+            )
+            .alignment(Alignment::Left);
+
+        f.render_widget(paragraph, area);
+    }
+
+    fn draw_bond_form(&self, f: &mut Frame) {
+        let Some(form) = &self.bond_form else {
+            return;
+        };
+
+        let area = centered_rect(60, 60, f.size());
+        f.render_widget(Clear, area);
+
+        let field_style = |field: usize| {
+            if form.current_field == field {
+                Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            }
+        };
+        let cursor = |field: usize| {
+            if form.current_field == field { format!("{} ", Symbol::Editing) } else { "  ".to_string() }
+        };
+
+        let mut text = vec![
+            Line::from(Span::styled("创建链路聚合", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))),
+            Line::from(""),
             Line::from(vec![
-                Span::styled("发送: ", Style::default().fg(Color::Blue)),
-                Span::raw(format!("{} ({} 包)", format_bytes(stats.tx_bytes), stats.tx_packets)),
+                Span::styled(cursor(BondFormState::NAME_FIELD), Style::default().fg(Color::Yellow)),
+                Span::styled("名称: ", field_style(BondFormState::NAME_FIELD)),
+                Span::styled(form.name.as_str(), field_style(BondFormState::NAME_FIELD)),
             ]),
             Line::from(vec![
-                Span::styled("速率: ", Style::default().fg(Color::Magenta)),
-                Span::raw(format!("↓ {}  ↑ {}", format_speed(stats.rx_speed), format_speed(stats.tx_speed))),
+                Span::styled(cursor(BondFormState::MODE_FIELD), Style::default().fg(Color::Yellow)),
+                Span::styled("模式: ", field_style(BondFormState::MODE_FIELD)),
+                Span::styled(format!("< {} >", form.mode_name()), field_style(BondFormState::MODE_FIELD)),
             ]),
+            Line::from(""),
+            Line::from(Span::styled("成员接口 (空格选择):", Style::default().fg(Color::Cyan))),
         ];
 
-        let paragraph = Paragraph::new(lines)
+        if form.candidates.is_empty() {
+            text.push(Line::from(Span::styled("  没有可加入的物理网卡", Style::default().fg(Color::DarkGray))));
+        }
+        let focused = form.current_field == BondFormState::MEMBERS_FIELD;
+        text.extend(member_list_lines(&form.candidates, focused.then_some(form.cursor)));
+        text.push(Line::from(""));
+
+        if let Some(err) = &form.error_message {
+            text.push(Line::from(Span::styled(
+                format!("{} {}", if form.address_confirmed { Symbol::Warning } else { Symbol::Error }, err),
+                Style::default().fg(if form.address_confirmed { Color::Yellow } else { Color::Red }),
+            )));
+            text.push(Line::from(""));
+        }
+
+        if form.mode_name() == "802.3ad" {
+            text.push(Line::from(Span::styled("802.3ad需要交换机端口配置LACP", Style::default().fg(Color::Yellow))));
+        }
+        text.push(Line::from("仅运行时生效，成员会先被禁用再加入，创建后自动启用"));
+        text.push(Line::from(Span::styled(
+            "Tab - 切换字段  ←/→ - 选择模式  空格 - 选择成员  Enter - 创建  Esc - 取消",
+            Style::default().fg(Color::DarkGray),
+        )));
+
+        let paragraph = Paragraph::new(text)
             .block(
                 Block::default()
-                    .title("流量统计")
+                    .title("创建链路聚合")
                     .borders(Borders::ALL)
                     .border_type(BorderType::Rounded)
-            );
+                    .border_style(Style::default().fg(Color::Cyan))
+                    .style(Style::default().bg(Color::Black)),
+            )
+            .alignment(Alignment::Left);
 
         f.render_widget(paragraph, area);
     }
 
-    fn draw_help(&self, f: &mut Frame) {
-        let help_text = vec![
-            Line::from(Span::styled("网卡管理工具 - 帮助", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))),
-            Line::from(""),
-            Line::from(Span::styled("导航:", Style::default().fg(Color::Cyan))),
-            Line::from("  ↑/k      - 上移"),
-            Line::from("  ↓/j      - 下移"),
+    fn draw_vlan_form(&self, f: &mut Frame) {
+        let Some(form) = &self.vlan_form else {
+            return;
+        };
+
+        let area = centered_rect(60, 50, f.size());
+        f.render_widget(Clear, area);
+
+        let mut text = vec![
+            Line::from(Span::styled("创建VLAN子接口", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))),
             Line::from(""),
-            Line::from(Span::styled("物理接口操作:", Style::default().fg(Color::Cyan))),
-            Line::from("  Enter/e  - 编辑IP/掩码/网关/DNS"),
-            Line::from("  t        - 切换DHCP/静态模式"),
-            Line::from("  u        - 启用接口 (Up)"),
-            Line::from("  d        - 禁用接口 (Down)"),
+            Line::from(Span::styled("父接口:", Style::default().fg(Color::Cyan))),
+        ];
+
+        if form.parents.is_empty() {
+            text.push(Line::from(Span::styled("  没有物理网卡", Style::default().fg(Color::DarkGray))));
+        }
+        for (i, parent) in form.parents.iter().enumerate() {
+            let state = self
+                .interfaces
+                .iter()
+                .find(|iface| &iface.name == parent)
+                .map(|iface| iface.state.clone())
+                .unwrap_or(InterfaceState::Unknown);
+            let state_span = Span::styled(
+                format!("  {:?}", state),
+                Style::default().fg(if state == InterfaceState::Up { Color::Green } else { Color::Red }),
+            );
+            text.push(if i == form.parent {
+                Line::from(vec![
+                    Span::styled(
+                        format!("{} {}", Symbol::Selected, parent),
+                        Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD).bg(Color::DarkGray),
+                    ),
+                    state_span,
+                ])
+            } else {
+                Line::from(vec![Span::raw(format!("  {}", parent)), state_span])
+            });
+        }
+
+        let name = form
+            .parents
+            .get(form.parent)
+            .filter(|_| !form.vid.is_empty())
+            .map(|parent| format!("{}.{}", parent, form.vid))
+            .unwrap_or_default();
+        text.extend([
             Line::from(""),
-            Line::from(Span::styled("虚拟接口操作:", Style::default().fg(Color::Cyan))),
-            Line::from("  x/Del    - 删除接口"),
-            Line::from("  u        - 启用接口 (Up)"),
-            Line::from("  d        - 禁用接口 (Down)"),
+            Line::from(vec![
+                Span::styled(format!("{} ", Symbol::Editing), Style::default().fg(Color::Yellow)),
+                Span::styled("VLAN ID: ", Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)),
+                Span::styled(form.vid.as_str(), Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)),
+                Span::styled("  (1-4094)", Style::default().fg(Color::DarkGray)),
+            ]),
+            Line::from(vec![
+                Span::styled("  子接口名称: ", Style::default().fg(Color::Cyan)),
+                Span::raw(name),
+            ]),
+            Line::from(vec![
+                Span::raw("  创建后设置IP: "),
+                Span::styled(
+                    if form.edit_after { "是" } else { "否" },
+                    Style::default().fg(if form.edit_after { Color::Green } else { Color::DarkGray }),
+                ),
+            ]),
             Line::from(""),
-            Line::from(Span::styled("创建者操作:", Style::default().fg(Color::Cyan))),
-            Line::from("  o        - 停止服务/容器/进程"),
-            Line::from("             (停止systemd服务)"),
-            Line::from("             (停止Docker容器)"),
-            Line::from("             (终止进程)"),
-            Line::from("             (断开NetworkManager连接)"),
-            Line::from("             (卸载内核模块)"),
+        ]);
+
+        if let Some(err) = &form.error_message {
+            text.push(Line::from(Span::styled(
+                format!("{} {}", Symbol::Error, err),
+                Style::default().fg(Color::Red),
+            )));
+            text.push(Line::from(""));
+        }
+
+        text.push(Line::from("仅运行时生效，创建后子接口自动启用"));
+        text.push(Line::from(Span::styled(
+            format!("{}/{} - 选择父接口  e - 创建后设置IP  Enter - 创建  Esc - 取消", Symbol::Up, Symbol::Down),
+            Style::default().fg(Color::DarkGray),
+        )));
+
+        let paragraph = Paragraph::new(text)
+            .block(
+                Block::default()
+                    .title("创建VLAN")
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(Color::Cyan))
+                    .style(Style::default().bg(Color::Black)),
+            )
+            .alignment(Alignment::Left);
+
+        f.render_widget(paragraph, area);
+    }
+
+    fn draw_address_form(&self, f: &mut Frame) {
+        let Some(form) = &self.address_form else {
+            return;
+        };
+
+        let area = centered_rect(60, 50, f.size());
+        f.render_widget(Clear, area);
+
+        let mut text = vec![
+            Line::from(Span::styled(
+                format!("管理IP地址 - {}", form.interface_name),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            )),
             Line::from(""),
-            Line::from(Span::styled("通用操作:", Style::default().fg(Color::Cyan))),
-            Line::from("  r        - 刷新接口列表"),
-            Line::from("  q        - 退出程序"),
-            Line::from("  ?        - 显示/隐藏帮助"),
+        ];
+
+        if form.addresses.is_empty() {
+            text.push(Line::from(Span::styled("  没有IPv4地址", Style::default().fg(Color::DarkGray))));
+        }
+        for (i, address) in form.addresses.iter().enumerate() {
+            let selected = i == form.selected && !form.adding;
+            text.push(if selected {
+                Line::from(Span::styled(
+                    format!("{} {}", Symbol::Selected, address),
+                    Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD).bg(Color::DarkGray),
+                ))
+            } else {
+                Line::from(format!("  {}", address))
+            });
+        }
+        text.push(Line::from(""));
+
+        if form.adding {
+            text.push(Line::from(vec![
+                Span::styled(format!("{} ", Symbol::Editing), Style::default().fg(Color::Yellow)),
+                Span::styled("新地址: ", Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)),
+                Span::styled(form.input.as_str(), Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            ]));
+            text.push(Line::from(Span::styled("  CIDR格式，如 192.168.1.51/24", Style::default().fg(Color::DarkGray))));
+            text.push(Line::from(""));
+        }
+
+        if let Some(err) = &form.error_message {
+            text.push(Line::from(Span::styled(
+                format!("{} {}", if form.confirm_delete { Symbol::Warning } else { Symbol::Error }, err),
+                Style::default().fg(if form.confirm_delete { Color::Yellow } else { Color::Red }),
+            )));
+            text.push(Line::from(""));
+        }
+
+        text.push(Line::from(if form.persist {
+            "只增删单个地址，不影响其他地址；同时写入Netplan"
+        } else {
+            "只增删单个地址，不影响其他地址；接口未在Netplan中定义，仅运行时生效"
+        }));
+        text.push(Line::from(Span::styled(
+            if form.adding {
+                "Enter - 添加  Esc - 取消输入"
+            } else {
+                "a - 添加地址  d - 删除选中地址  Esc - 关闭"
+            },
+            Style::default().fg(Color::DarkGray),
+        )));
+
+        let paragraph = Paragraph::new(text)
+            .block(
+                Block::default()
+                    .title("添加/删除IP")
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(Color::Cyan))
+                    .style(Style::default().bg(Color::Black)),
+            )
+            .alignment(Alignment::Left);
+
+        f.render_widget(paragraph, area);
+    }
+
+    fn draw_rate_limit(&self, f: &mut Frame) {
+        let Some(form) = &self.rate_limit_form else {
+            return;
+        };
+
+        let area = centered_rect(70, 60, f.size());
+        f.render_widget(Clear, area);
+
+        let mut text = vec![
+            Line::from(Span::styled(
+                format!("出口限速 - {}", form.interface_name),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            )),
             Line::from(""),
-            Line::from(Span::styled("编辑表单:", Style::default().fg(Color::Cyan))),
-            Line::from("  Tab      - 下一个字段"),
-            Line::from("  Shift+Tab- 上一个字段"),
-            Line::from("  Enter    - 保存配置"),
-            Line::from("  Esc      - 取消编辑"),
+            Line::from(Span::styled("当前qdisc:", Style::default().fg(Color::Cyan))),
+        ];
+        for line in form.current_qdisc.lines() {
+            text.push(Line::from(format!("  {}", line)));
+        }
+        text.push(Line::from(""));
+
+        let fields = [("速率", &form.rate), ("突发", &form.burst), ("延迟", &form.latency)];
+        for (i, (name, value)) in fields.iter().enumerate() {
+            let is_current = i == form.current_field;
+            let style = if is_current && form.is_editing {
+                Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)
+            } else if is_current {
+                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD).bg(Color::DarkGray)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            let cursor = if is_current && form.is_editing {
+                format!("{} ", Symbol::Editing)
+            } else if is_current {
+                format!("{} ", Symbol::Selected)
+            } else {
+                "  ".to_string()
+            };
+
+            text.push(Line::from(vec![
+                Span::styled(cursor, Style::default().fg(Color::Green)),
+                Span::styled(format!("{:6}: ", name), style),
+                Span::styled(value.as_str(), style),
+            ]));
+        }
+
+        text.push(Line::from(""));
+        if let Some(err) = &form.error_message {
+            text.push(Line::from(Span::styled(
+                format!("{} {}", Symbol::Error, err),
+                Style::default().fg(Color::Red),
+            )));
+            text.push(Line::from(""));
+        }
+
+        text.push(Line::from(Span::styled(
+            "Enter - 编辑字段  s - 应用限速  c - 清除限速  Esc - 取消",
+            Style::default().fg(Color::DarkGray),
+        )));
+
+        let paragraph = Paragraph::new(text)
+            .block(
+                Block::default()
+                    .title("限速设置")
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(Color::Cyan))
+                    .style(Style::default().bg(Color::Black)),
+            )
+            .alignment(Alignment::Left);
+
+        f.render_widget(paragraph, area);
+    }
+
+    fn draw_policy_form(&self, f: &mut Frame) {
+        let Some(form) = &self.policy_form else {
+            return;
+        };
+
+        let area = centered_rect(70, 60, f.size());
+        f.render_widget(Clear, area);
+
+        let mut text = vec![
+            Line::from(Span::styled(
+                format!("策略路由 - {}", form.interface_name),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            )),
             Line::from(""),
-            Line::from(Span::styled("确认对话框:", Style::default().fg(Color::Cyan))),
-            Line::from("  Y        - 确认操作"),
-            Line::from("  N/Esc    - 取消操作"),
+            Line::from("来自源地址的流量将查找独立路由表，经指定网关出口"),
             Line::from(""),
-            Line::from(Span::styled("按任意键返回", Style::default().fg(Color::Green))),
         ];
 
-        let paragraph = Paragraph::new(help_text)
+        let fields = [("网关", &form.gateway), ("源地址", &form.source), ("路由表", &form.table)];
+        for (i, (name, value)) in fields.iter().enumerate() {
+            let is_current = i == form.current_field;
+            let style = if is_current && form.is_editing {
+                Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)
+            } else if is_current {
+                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD).bg(Color::DarkGray)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            let cursor = if is_current && form.is_editing {
+                format!("{} ", Symbol::Editing)
+            } else if is_current {
+                format!("{} ", Symbol::Selected)
+            } else {
+                "  ".to_string()
+            };
+
+            text.push(Line::from(vec![
+                Span::styled(cursor, Style::default().fg(Color::Green)),
+                Span::styled(format!("{:6}: ", name), style),
+                Span::styled(value.as_str(), style),
+            ]));
+        }
+
+        text.push(self.target_file_line());
+        text.push(Line::from(""));
+        if let Some(err) = &form.error_message {
+            text.push(Line::from(Span::styled(
+                format!("{} {}", Symbol::Error, err),
+                Style::default().fg(Color::Red),
+            )));
+            text.push(Line::from(""));
+        }
+
+        text.push(Line::from(Span::styled(
+            "Enter - 编辑字段  s - 应用并写入Netplan  Esc - 取消",
+            Style::default().fg(Color::DarkGray),
+        )));
+
+        let paragraph = Paragraph::new(text)
             .block(
                 Block::default()
-                    .title("帮助")
+                    .title("策略路由")
                     .borders(Borders::ALL)
                     .border_type(BorderType::Rounded)
+                    .border_style(Style::default().fg(Color::Cyan))
+                    .style(Style::default().bg(Color::Black)),
             )
             .alignment(Alignment::Left);
 
-        let area = centered_rect(60, 60, f.size());
         f.render_widget(paragraph, area);
     }
 
-    fn draw_confirm_delete(&self, f: &mut Frame) {
-        if let Some(i) = self.list_state.selected() {
-            if let Some(iface) = self.interfaces.get(i) {
-                // 计算弹窗区域
-                let area = centered_rect(60, 50, f.size());
-
-                // 只清除弹窗区域
-                f.render_widget(Clear, area);
-                use crate::backend::removal::RemovalManager;
-                let strategy = RemovalManager::determine_strategy(iface);
-                let warnings = RemovalManager::check_safety(iface);
-
-                let mut text = vec![
-                    Line::from(Span::styled(
-                        "确认删除接口",
-                        Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
-                    )),
-                    Line::from(""),
-                    Line::from(vec![
-                        Span::raw("接口名称: "),
-                        Span::styled(&iface.name, Style::default().fg(Color::Yellow)),
-                    ]),
-                    Line::from(vec![
-                        Span::raw("接口类型: "),
-                        Span::raw(format!("{:?}", iface.kind)),
-                    ]),
-                    Line::from(vec![
-                        Span::raw("删除策略: "),
-                        Span::styled(
-                            format!("{:?}", strategy),
-                            Style::default().fg(Color::Cyan),
-                        ),
-                    ]),
-                    Line::from(""),
-                ];
-
-                // 显示警告
-                if !warnings.is_empty() {
-                    text.push(Line::from(Span::styled(
-                        "⚠️  警告:",
-                        Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
-                    )));
-                    for warning in &warnings {
-                        text.push(Line::from(Span::styled(
-                            format!("  • {}", warning),
-                            Style::default().fg(Color::Yellow),
-                        )));
-                    }
-                    text.push(Line::from(""));
+    fn get_action_menu_items(&self) -> Vec<(&str, &str)> {
+        if let Some(i) = self.list_state.selected()
+            && let Some(iface) = self.interfaces.get(i) {
+            let mut items = Vec::new();
+
+            // 物理接口的操作
+            if matches!(iface.kind, InterfaceKind::Physical) {
+                items.push(("编辑配置", "修改IP/掩码/网关/DNS"));
+                items.push(("修改网关", "仅修改默认网关，不改动地址"));
+                items.push(("添加IP", "追加一个IPv4地址，保留已有地址"));
+                if !iface.ipv4_addresses.is_empty() {
+                    items.push(("删除IP", "删除单个IPv4地址，保留其他地址"));
+                }
+                items.push(("切换DHCP", "切换DHCP/静态模式"));
+                items.push(("固化当前配置", "把运行时地址/网关/DNS写入Netplan为静态配置"));
+                items.push(("清除配置", "从Netplan删除该接口并清除地址，不使用DHCP"));
+                items.push(("编辑Netplan文件", "在$EDITOR中直接编辑配置文件"));
+                items.push(("测试配置", "netplan try，未确认则30秒后回滚"));
+                items.push(("创建VLAN", "在该网卡上创建VLAN子接口"));
+                if iface.state != InterfaceState::Up {
+                    items.push(("重命名", "修改接口名称，可写入Netplan的set-name"));
+                }
+                items.push(("修改MAC", "设置自定义MAC地址（克隆MAC），接口会短暂断开"));
+                if iface.permanent_mac.is_some() && iface.permanent_mac != iface.mac_address {
+                    items.push(("恢复原始MAC", "恢复网卡的出厂MAC地址"));
                 }
+                items.push(("启用接口", "设置接口状态为UP"));
+                items.push(("禁用接口", "设置接口状态为DOWN"));
+            }
 
-                text.push(Line::from(Span::styled(
-                    "确定要删除此接口吗？",
-                    Style::default().fg(Color::Red),
-                )));
-                text.push(Line::from(""));
-                text.push(Line::from(vec![
-                    Span::styled("Y", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
-                    Span::raw(" - 确认删除  "),
-                    Span::styled("N", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
-                    Span::raw(" - 取消"),
-                ]));
+            // 虚拟接口的操作
+            if iface.kind != InterfaceKind::Physical && iface.kind != InterfaceKind::Loopback {
+                items.push(("删除接口", "删除虚拟网络接口"));
+                items.push(("启用接口", "设置接口状态为UP"));
+                items.push(("禁用接口", "设置接口状态为DOWN"));
+            }
 
-                let paragraph = Paragraph::new(text)
-                    .block(
-                        Block::default()
-                            .title("删除确认")
-                            .borders(Borders::ALL)
-                            .border_type(BorderType::Rounded)
-                            .border_style(Style::default().fg(Color::Red))
-                            .style(Style::default().bg(Color::Black)),
-                    )
-                    .alignment(Alignment::Left);
-
-                // area已经在前面计算过了
-                f.render_widget(paragraph, area);
+            // 网络唤醒（仅支持魔术包唤醒的物理网卡）
+            if let Some(wol) = iface.wol.as_ref().filter(|wol| wol.supports_magic_packet()) {
+                items.push(if wol.is_enabled() {
+                    ("关闭网络唤醒", "关闭Wake-on-LAN，已在Netplan中定义时同时写入")
+                } else {
+                    ("开启网络唤醒", "开启魔术包唤醒，已在Netplan中定义时同时写入")
+                });
             }
-        }
-    }
 
-    fn draw_edit_form(&self, f: &mut Frame) {
-        if let Some(form) = &self.edit_form {
-            // 计算弹窗区域
-            let area = centered_rect(70, 60, f.size());
+            // 网桥设置
+            if let Some(settings) = iface.bridge_settings {
+                items.push(if settings.stp {
+                    ("关闭STP", "关闭生成树协议，已在Netplan中定义时同时写入")
+                } else {
+                    ("开启STP", "开启生成树协议，已在Netplan中定义时同时写入")
+                });
+                items.push(if settings.vlan_filtering {
+                    ("关闭VLAN过滤", "关闭网桥VLAN过滤（仅运行时）")
+                } else {
+                    ("开启VLAN过滤", "开启网桥VLAN过滤（仅运行时）")
+                });
+            }
 
-            // 只清除弹窗区域
-            f.render_widget(Clear, area);
+            // 除回环接口外都可以设置限速和检查连通性
+            if iface.kind != InterfaceKind::Loopback {
+                items.push(("限速设置", "使用tc设置出口限速"));
+                items.push(("连通性检查", "检查载波、地址、网关和DNS"));
+            }
+
+            // 有IPv4地址的物理网卡可以配置策略路由（多出口）
+            if iface.kind == InterfaceKind::Physical && !iface.ipv4_addresses.is_empty() {
+                items.push(("策略路由", "按源地址使用独立路由表和网关（多出口）"));
+            }
+
+            // 有MAC地址的接口才有邻居表
+            if iface.mac_address.is_some() {
+                items.push(("邻居表", "查看ARP/NDP邻居缓存"));
+            }
+
+            // 有IPv4网关时可以探测路径MTU
+            if iface.ipv4_config.as_ref().is_some_and(|cfg| cfg.gateway.is_some()) {
+                items.push(("路径MTU测试", "探测到网关的最大不分片包长"));
+            }
+
+            // 如果有创建者，添加创建者操作
+            if let Some(owner) = &iface.owner {
+                use crate::model::InterfaceOwner;
+                match owner {
+                    InterfaceOwner::SystemdService { .. } => {
+                        items.push(("停止服务", "停止systemd服务"));
+                    },
+                    InterfaceOwner::DockerContainer { id, .. } => {
+                        // 只有真实的容器才显示"停止容器"选项
+                        // docker0等系统网桥的id是"system"，不显示停止选项
+                        if id != "system" {
+                            items.push(("查看创建者详情", "docker inspect 摘要：端口和挂载"));
+                            items.push(("停止容器", "停止Docker容器"));
+                        }
+                    },
+                    InterfaceOwner::DockerNetwork { containers, .. } => {
+                        // 只有没有容器连接的网络（如遗留的compose网络）才能删除
+                        if *containers == 0 {
+                            items.push(("删除Docker网络", "docker network rm，清理无容器连接的网络"));
+                        }
+                    },
+                    InterfaceOwner::Process { .. } => {
+                        items.push(("查看创建者详情", "进程状态、命令行、cgroup和打开的文件数"));
+                        items.push(("终止进程", "终止创建者进程"));
+                    },
+                    InterfaceOwner::NetworkManager { .. } => {
+                        items.push(("断开连接", "断开NetworkManager连接"));
+                    },
+                    InterfaceOwner::NspawnMachine { .. } => {
+                        items.push(("终止容器", "终止nspawn容器"));
+                    },
+                    InterfaceOwner::Kernel { .. } => {
+                        items.push(("卸载模块", "卸载内核模块"));
+                    },
+                    InterfaceOwner::Unknown => {},
+                }
+            }
+
+            return items;
+        }
+        Vec::new()
+    }
 
-            let field_names = ["IP地址", "子网掩码", "网关", "DNS"];
-            let field_values = [
-                &form.ip_address,
-                &form.netmask,
-                &form.gateway,
-                &form.dns,
-            ];
+    fn draw_interface_actions(&self, f: &mut Frame) {
+        if let Some(i) = self.list_state.selected()
+            && let Some(iface) = self.interfaces.get(i) {
+            let area = centered_rect(60, 70, f.size());
+            f.render_widget(Clear, area);
 
+            let items = self.get_action_menu_items();
             let mut text = vec![
                 Line::from(Span::styled(
-                    format!("编辑接口配置 - {}", form.interface_name),
+                    format!("接口操作 - {}", iface.name),
                     Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
                 )),
                 Line::from(""),
             ];
 
-            // 显示表单字段
-            for (i, (name, value)) in field_names.iter().zip(field_values.iter()).enumerate() {
-                let is_current = i == form.current_field;
-                let is_editing_this = is_current && form.is_editing;
+            // 显示接口基本信息
+            text.push(Line::from(vec![
+                Span::styled("接口类型: ", Style::default().fg(Color::Cyan)),
+                Span::raw(format!("{:?}", iface.kind)),
+            ]));
 
-                let style = if is_editing_this {
-                    // 正在编辑：青色背景，黑色文字
-                    Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)
-                } else if is_current {
-                    // 当前选中但未编辑：深灰背景，青色文字
-                    Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD).bg(Color::DarkGray)
+            // 显示创建者信息
+            if let Some(owner) = &iface.owner {
+                text.push(Line::from(vec![
+                    Span::styled("创建者: ", Style::default().fg(Color::Cyan)),
+                    Span::raw(owner.display_name()),
+                ]));
+            }
+
+            text.push(Line::from(""));
+            text.push(Line::from(Span::styled(
+                "可用操作:",
+                Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
+            )));
+            text.push(Line::from(""));
+
+            // 显示操作菜单
+            for (idx, (action, desc)) in items.iter().enumerate() {
+                let prefix = if idx == self.action_menu_state {
+                    format!("{} ", Symbol::Selected)
                 } else {
-                    // 未选中：白色文字
-                    Style::default().fg(Color::White)
+                    "  ".to_string()
                 };
 
-                let cursor = if is_editing_this {
-                    "✎ "  // 编辑图标
-                } else if is_current {
-                    "► "  // 选中图标
+                let style = if idx == self.action_menu_state {
+                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
                 } else {
-                    "  "  // 空格
+                    Style::default().fg(Color::White)
                 };
 
                 text.push(Line::from(vec![
-                    Span::styled(
-                        cursor,
-                        Style::default().fg(if is_editing_this { Color::Yellow } else { Color::Green }),
-                    ),
-                    Span::styled(format!("{:12}: ", name), style),
-                    Span::styled(*value, style),
+                    Span::styled(prefix, style),
+                    Span::styled(*action, style),
+                    Span::raw(" - "),
+                    Span::styled(*desc, Style::default().fg(Color::DarkGray)),
                 ]));
             }
 
             text.push(Line::from(""));
-
-            // 显示错误信息
-            if let Some(err) = &form.error_message {
-                text.push(Line::from(Span::styled(
-                    format!("❌ {}", err),
-                    Style::default().fg(Color::Red),
-                )));
-                text.push(Line::from(""));
-            }
-
             text.push(Line::from(""));
-
-            // 根据模式显示不同的操作提示
-            if form.is_editing {
-                text.push(Line::from(Span::styled(
-                    "编辑模式:",
-                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
-                )));
-                text.push(Line::from("  输入字符 - 编辑内容"));
-                text.push(Line::from("  Backspace - 删除字符"));
-                text.push(Line::from("  Enter - 完成编辑"));
-                text.push(Line::from("  Esc - 取消编辑"));
-            } else {
-                text.push(Line::from(Span::styled(
-                    "导航模式:",
-                    Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
-                )));
-                text.push(Line::from("  ↑/↓ 或 k/j - 切换字段"));
-                text.push(Line::from("  Enter - 编辑当前字段"));
-                text.push(Line::from("  s - 保存配置"));
-                text.push(Line::from("  Esc - 取消"));
-            }
+            text.push(Line::from(vec![
+                Span::styled(format!("{}{}", Symbol::Up, Symbol::Down), Style::default().fg(Color::Cyan)),
+                Span::raw(" - 选择  "),
+                Span::styled("Enter", Style::default().fg(Color::Green)),
+                Span::raw(" - 执行  "),
+                Span::styled("Esc", Style::default().fg(Color::Red)),
+                Span::raw(" - 取消"),
+            ]));
 
             let paragraph = Paragraph::new(text)
                 .block(
                     Block::default()
-                        .title("编辑配置")
-                        .style(Style::default().bg(Color::Black))
+                        .title("接口操作菜单")
                         .borders(Borders::ALL)
                         .border_type(BorderType::Rounded)
-                        .border_style(Style::default().fg(Color::Cyan)),
+                        .border_style(Style::default().fg(Color::Cyan))
+                        .style(Style::default().bg(Color::Black)),
                 )
                 .alignment(Alignment::Left);
 
-            // area已经在前面计算过了
             f.render_widget(paragraph, area);
         }
     }
 
-    fn draw_toggle_dhcp(&self, f: &mut Frame) {
-        if let Some(i) = self.list_state.selected() {
-            if let Some(iface) = self.interfaces.get(i) {
-                // 计算弹窗区域
-                let area = centered_rect(60, 50, f.size());
-
-                // 只清除弹窗区域
-                f.render_widget(Clear, area);
-                let text = vec![
-                    Line::from(Span::styled(
-                        "切换到DHCP模式",
-                        Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
-                    )),
-                    Line::from(""),
-                    Line::from(vec![
-                        Span::raw("接口名称: "),
-                        Span::styled(&iface.name, Style::default().fg(Color::Cyan)),
-                    ]),
-                    Line::from(""),
-                    Line::from(Span::styled(
-                        "⚠️  警告:",
-                        Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
-                    )),
-                    Line::from("  • 当前静态IP配置将被清除"),
-                    Line::from("  • 接口将自动从DHCP服务器获取IP"),
-                    Line::from("  • 此操作将修改Netplan配置"),
-                    Line::from(""),
-                    Line::from(Span::styled(
-                        "确定要切换到DHCP模式吗？",
-                        Style::default().fg(Color::Yellow),
-                    )),
-                    Line::from(""),
-                    Line::from(vec![
-                        Span::styled("Y", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
-                        Span::raw(" - 确认切换  "),
-                        Span::styled("N", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
-                        Span::raw(" - 取消"),
-                    ]),
-                ];
-
-                let paragraph = Paragraph::new(text)
-                    .block(
-                        Block::default()
-                            .title("切换DHCP")
-                            .borders(Borders::ALL)
-                            .border_type(BorderType::Rounded)
-                            .border_style(Style::default().fg(Color::Yellow))
-                            .style(Style::default().bg(Color::Black)),
-                    )
-                    .alignment(Alignment::Left);
-
-                // area已经在前面计算过了
-                f.render_widget(paragraph, area);
+    fn execute_action_menu_item(&mut self) -> Result<()> {
+        if let Some(i) = self.list_state.selected()
+            && let Some(iface) = self.interfaces.get(i).cloned() {
+            let items = self.get_action_menu_items();
+            if let Some((action, _)) = items.get(self.action_menu_state) {
+                match *action {
+                    "编辑配置" => {
+                        self.edit_form = Some(
+                            EditFormState::new(&iface)
+                                .with_netmask_format(self.netmask_format)
+                                .with_history(&self.edit_history),
+                        );
+                        self.open_form(Screen::EditIface, &iface.name);
+                    },
+                    "修改网关" => {
+                        self.gateway_form = Some(GatewayFormState {
+                            interface_name: iface.name.clone(),
+                            addresses: iface.ipv4_addresses.clone(),
+                            gateway: iface.ipv4_config.as_ref()
+                                .and_then(|cfg| cfg.gateway.clone())
+                                .unwrap_or_default(),
+                            error_message: None,
+                        });
+                        self.open_form(Screen::EditGateway, &iface.name);
+                    },
+                    "创建VLAN" => {
+                        self.vlan_form = Some(VlanFormState::new(&self.interfaces, Some(&iface.name)));
+                        self.screen = Screen::CreateVlan;
+                    },
+                    "修改MAC" => {
+                        self.mac_form = Some(MacFormState::new(&iface));
+                        self.open_form(Screen::EditMac, &iface.name);
+                    },
+                    "恢复原始MAC" => {
+                        self.screen = Screen::Main;
+                        self.restore_permanent_mac(&iface)?;
+                    },
+                    "重命名" => {
+                        self.rename_form = Some(RenameFormState::new(&iface));
+                        self.open_form(Screen::RenameIface, &iface.name);
+                    },
+                    "添加IP" | "删除IP" => {
+                        self.address_form = Some(AddressFormState::new(&iface, *action == "添加IP"));
+                        self.screen = Screen::ManageAddresses;
+                    },
+                    "切换DHCP" => {
+                        self.open_form(Screen::ToggleDhcp, &iface.name);
+                    },
+                    "固化当前配置" => {
+                        self.open_freeze_preview(&iface);
+                    },
+                    "清除配置" => {
+                        self.clear_link_down = false;
+                        self.screen = Screen::ClearConfig;
+                    },
+                    "编辑Netplan文件" => {
+                        self.open_netplan_in_editor();
+                    },
+                    "测试配置" => {
+                        self.start_netplan_try();
+                    },
+                    "启用接口" => {
+                        self.screen = Screen::Main;
+                        self.toggle_interface_up()?;
+                    },
+                    "禁用接口" => {
+                        self.screen = Screen::Main;
+                        self.toggle_interface_down()?;
+                    },
+                    "删除接口" => {
+                        self.open_confirm_delete();
+                    },
+                    "路径MTU测试" => {
+                        self.start_path_mtu_test(&iface);
+                    },
+                    "邻居表" => {
+                        self.open_neighbors();
+                    },
+                    "连通性检查" => {
+                        self.start_health_check(&iface);
+                    },
+                    "开启网络唤醒" | "关闭网络唤醒" => {
+                        let enabled = *action == "开启网络唤醒";
+                        self.screen = Screen::Main;
+                        self.set_wake_on_lan(&iface, enabled)?;
+                    },
+                    "开启STP" | "关闭STP" => {
+                        let enabled = *action == "开启STP";
+                        self.screen = Screen::Main;
+                        self.set_bridge_stp(&iface, enabled)?;
+                    },
+                    "开启VLAN过滤" | "关闭VLAN过滤" => {
+                        let enabled = *action == "开启VLAN过滤";
+                        self.screen = Screen::Main;
+                        self.set_bridge_vlan_filtering(&iface.name, enabled);
+                    },
+                    "限速设置" => {
+                        self.rate_limit_form = Some(RateLimitFormState::new(&iface.name));
+                        self.screen = Screen::RateLimit;
+                    },
+                    "策略路由" => {
+                        self.policy_form = Some(PolicyRouteFormState::new(&iface));
+                        self.open_form(Screen::PolicyRoute, &iface.name);
+                    },
+                    "查看创建者详情" => {
+                        self.open_owner_details();
+                    },
+                    "停止服务" | "停止容器" | "删除Docker网络" | "终止容器" | "终止进程" | "断开连接" | "卸载模块" => {
+                        self.screen = Screen::OwnerActions;
+                    },
+                    _ => {
+                        self.screen = Screen::Main;
+                    }
+                }
             }
         }
+        Ok(())
     }
+}
 
-    fn draw_owner_actions(&self, f: &mut Frame) {
-        if let Some(i) = self.list_state.selected() {
-            if let Some(iface) = self.interfaces.get(i) {
-                if let Some(owner) = &iface.owner {
-                    // 计算弹窗区域
-                    let area = centered_rect(70, 60, f.size());
-
-                    // 只清除弹窗区域
-                    f.render_widget(Clear, area);
-
-                    use crate::model::InterfaceOwner;
-                    let (action_name, action_desc, warning) = match owner {
-                        InterfaceOwner::SystemdService { name, .. } => (
-                            "停止systemd服务",
-                            format!("服务名: {}\n\n将执行: systemctl stop {}", name, name),
-                            "⚠️ 警告：停止服务可能影响系统功能！",
-                        ),
-                        InterfaceOwner::DockerContainer { id, name, .. } => (
-                            "停止Docker容器",
-                            format!("容器名: {}\n容器ID: {}\n\n将执行: docker stop {}", name, &id[..12.min(id.len())], &id[..12.min(id.len())]),
-                            "⚠️ 警告：停止容器将中断容器内的所有服务！",
-                        ),
-                        InterfaceOwner::Process { pid, name, .. } => (
-                            "终止进程",
-                            format!("进程名: {}\n进程ID: {}\n\n将执行: kill {}", name, pid, pid),
-                            "⚠️ 警告：强制终止进程可能导致数据丢失！",
-                        ),
-                        InterfaceOwner::NetworkManager { connection, .. } => (
-                            "断开NetworkManager连接",
-                            format!("连接名: {}\n\n将执行: nmcli connection down {}", connection, connection),
-                            "⚠️ 警告：断开连接将中断网络服务！",
-                        ),
-                        InterfaceOwner::Kernel { module } => (
-                            "卸载内核模块",
-                            format!("模块名: {}\n\n将执行: rmmod {}", module, module),
-                            "⚠️ 警告：卸载内核模块可能导致系统不稳定！",
-                        ),
-                        InterfaceOwner::Unknown => return,
-                    };
+/// 安装panic钩子：在打印panic信息前恢复终端，避免崩溃后终端停留在原始模式
+fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        restore_terminal();
+        default_hook(info);
+    }));
+}
 
-                    let text = vec![
-                        Line::from(Span::styled(
-                            action_name,
-                            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
-                        )),
-                        Line::from(""),
-                        Line::from(Span::styled(warning, Style::default().fg(Color::Red))),
-                        Line::from(""),
-                        Line::from(action_desc),
-                        Line::from(""),
-                        Line::from(""),
-                        Line::from(vec![
-                            Span::styled("Y", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
-                            Span::raw(" - 确认执行  "),
-                            Span::styled("N", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
-                            Span::raw(" - 取消"),
-                        ]),
-                    ];
+/// 终端恢复守卫，离开作用域时恢复终端
+struct TerminalGuard;
 
-                    let paragraph = Paragraph::new(text)
-                        .block(
-                            Block::default()
-                                .title("创建者操作")
-                                .borders(Borders::ALL)
-                                .border_type(BorderType::Rounded)
-                                .border_style(Style::default().fg(Color::Yellow))
-                                .style(Style::default().bg(Color::Black)),
-                        )
-                        .alignment(Alignment::Left);
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        restore_terminal();
+    }
+}
 
-                    f.render_widget(paragraph, area);
-                }
-            }
-        }
+/// 接口在Netplan中有定义但不由nicman管理时返回提醒（可能是手动维护的配置）
+fn unmanaged_warning(iface: &NetInterface) -> Option<String> {
+    if iface.nicman_managed {
+        return None;
     }
+    let file = iface.netplan_file.as_ref()?;
+    Some(format!("{} 的Netplan条目位于 {}，不由 nicman 管理（可能是手动维护的配置）", iface.name, file.display()))
+}
 
-    fn execute_owner_action(&mut self) -> Result<()> {
-        if let Some(i) = self.list_state.selected() {
-            if let Some(iface) = self.interfaces.get(i) {
-                if let Some(owner) = &iface.owner {
-                    use crate::model::InterfaceOwner;
-                    use crate::utils::command::execute_command_stdout;
-
-                    let result = match owner {
-                        InterfaceOwner::SystemdService { name, .. } => {
-                            execute_command_stdout("systemctl", &["stop", name])
-                        },
-                        InterfaceOwner::DockerContainer { id, .. } => {
-                            // 检查是否是系统网桥（docker0等）
-                            if id == "system" {
-                                // docker0是系统网桥，不能通过docker stop停止
-                                // 返回一个友好的错误信息
-                                return Err(anyhow::anyhow!("Docker网桥是系统组件，无法停止。请使用 'systemctl stop docker' 停止Docker服务。"));
-                            }
-                            execute_command_stdout("docker", &["stop", id])
-                        },
-                        InterfaceOwner::Process { pid, .. } => {
-                            execute_command_stdout("kill", &[&pid.to_string()])
-                        },
-                        InterfaceOwner::NetworkManager { connection, .. } => {
-                            execute_command_stdout("nmcli", &["connection", "down", connection])
-                        },
-                        InterfaceOwner::Kernel { module } => {
-                            execute_command_stdout("rmmod", &[module])
-                        },
-                        InterfaceOwner::Unknown => return Ok(()),
-                    };
+/// 所有Netplan写入操作共用的确认：第一次写入不由nicman管理的条目时返回提醒，
+/// 对同一接口再次执行写入视为已确认
+#[derive(Debug, Default)]
+struct UnmanagedGate {
+    confirmed: Option<String>,  // 已确认的接口
+}
+
+impl UnmanagedGate {
+    fn check(&mut self, interfaces: &[NetInterface], iface_name: &str) -> Option<String> {
+        if self.confirmed.as_deref() == Some(iface_name) {
+            return None;
+        }
+        let warning = interfaces.iter().find(|iface| iface.name == iface_name).and_then(unmanaged_warning)?;
+        self.confirmed = Some(iface_name.to_string());
+        Some(format!("{}，写入后将改由 nicman 管理。再次执行该操作以确认", warning))
+    }
+}
 
-                    // 等待一下让操作生效
-                    std::thread::sleep(std::time::Duration::from_millis(500));
+/// 两行列表模式的第二行：主IPv4地址和创建者摘要
+fn list_details_line(iface: &NetInterface) -> Line<'static> {
+    let address = iface.ipv4_addresses.first().cloned().unwrap_or_else(|| "无IPv4地址".to_string());
+    let mut text = format!("      {}", address);
+    if let Some(owner) = &iface.owner {
+        text.push_str(&format!(" · {}", owner.display_name()));
+    }
+    Line::from(Span::styled(text, Style::default().fg(Color::DarkGray)))
+}
 
-                    // 刷新接口列表
-                    self.refresh()?;
+/// 退出原始模式和备用屏幕（忽略错误，可在panic中调用）
+fn restore_terminal() {
+    let _ = disable_raw_mode();
+    let _ = execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture, DisableFocusChange, crossterm::cursor::Show);
+}
 
-                    // 检查操作结果，如果失败则显示错误但不退出程序
-                    if let Err(e) = result {
-                        eprintln!("操作失败: {}", e);
-                        // 不传播错误，避免程序退出
-                    }
-                }
-            }
+/// 标记或取消标记对比接口，超过两个时替换最早标记的接口
+fn toggle_compare(pair: &mut Vec<String>, name: &str) {
+    if let Some(pos) = pair.iter().position(|n| n == name) {
+        pair.remove(pos);
+    } else {
+        pair.push(name.to_string());
+        if pair.len() > 2 {
+            pair.remove(0);
         }
-        Ok(())
     }
+}
 
-    fn get_action_menu_items(&self) -> Vec<(&str, &str)> {
-        if let Some(i) = self.list_state.selected() {
-            if let Some(iface) = self.interfaces.get(i) {
-                let mut items = Vec::new();
-
-                // 物理接口的操作
-                if matches!(iface.kind, InterfaceKind::Physical) {
-                    items.push(("编辑配置", "修改IP/掩码/网关/DNS"));
-                    items.push(("切换DHCP", "切换DHCP/静态模式"));
-                    items.push(("启用接口", "设置接口状态为UP"));
-                    items.push(("禁用接口", "设置接口状态为DOWN"));
-                }
-
-                // 虚拟接口的操作
-                if iface.kind != InterfaceKind::Physical && iface.kind != InterfaceKind::Loopback {
-                    items.push(("删除接口", "删除虚拟网络接口"));
-                    items.push(("启用接口", "设置接口状态为UP"));
-                    items.push(("禁用接口", "设置接口状态为DOWN"));
-                }
-
-                // 如果有创建者，添加创建者操作
-                if let Some(owner) = &iface.owner {
-                    use crate::model::InterfaceOwner;
-                    match owner {
-                        InterfaceOwner::SystemdService { .. } => {
-                            items.push(("停止服务", "停止systemd服务"));
-                        },
-                        InterfaceOwner::DockerContainer { id, .. } => {
-                            // 只有真实的容器才显示"停止容器"选项
-                            // docker0等系统网桥的id是"system"，不显示停止选项
-                            if id != "system" {
-                                items.push(("停止容器", "停止Docker容器"));
-                            }
-                        },
-                        InterfaceOwner::Process { .. } => {
-                            items.push(("终止进程", "终止创建者进程"));
-                        },
-                        InterfaceOwner::NetworkManager { .. } => {
-                            items.push(("断开连接", "断开NetworkManager连接"));
-                        },
-                        InterfaceOwner::Kernel { .. } => {
-                            items.push(("卸载模块", "卸载内核模块"));
-                        },
-                        InterfaceOwner::Unknown => {},
-                    }
-                }
+/// 按输入格式显示前缀长度对应的子网掩码
+fn format_netmask(prefix: u8, format: NetmaskFormat) -> String {
+    match format {
+        NetmaskFormat::Dotted => runtime::prefix_to_netmask(prefix),
+        NetmaskFormat::Prefix => prefix.to_string(),
+    }
+}
 
-                return items;
-            }
+/// netplan修改生效方式的状态栏前缀：需要重启时显示警告
+fn advice_symbol(advice: &ApplyAdvice) -> Symbol {
+    match advice {
+        ApplyAdvice::Apply => Symbol::Ok,
+        ApplyAdvice::Reboot(_) => Symbol::Warning,
+    }
+}
+
+/// 将置顶接口按置顶顺序排在最前，其余接口保持原有顺序
+fn order_pinned(interfaces: &mut [NetInterface], pinned: &[String]) {
+    interfaces.sort_by_key(|iface| {
+        pinned.iter().position(|name| *name == iface.name).unwrap_or(usize::MAX)
+    });
+}
+
+/// 刷新后重新确定选中项：优先按名称匹配，接口已消失时选中原位置附近的接口
+fn reselect_index(
+    interfaces: &[NetInterface],
+    selected_name: Option<&str>,
+    selected_index: Option<usize>,
+) -> Option<usize> {
+    if interfaces.is_empty() {
+        return None;
+    }
+
+    if let Some(name) = selected_name
+        && let Some(i) = interfaces.iter().position(|iface| iface.name == name) {
+        return Some(i);
+    }
+
+    Some(selected_index.unwrap_or(0).min(interfaces.len() - 1))
+}
+
+/// 启动时的默认选中项：默认路由接口 > 第一个非回环接口 > 第一个接口
+fn initial_selection(interfaces: &[NetInterface], default_route: Option<&str>) -> Option<usize> {
+    default_route
+        .and_then(|name| interfaces.iter().position(|iface| iface.name == name))
+        .or_else(|| interfaces.iter().position(|iface| iface.kind != InterfaceKind::Loopback))
+        .or(if interfaces.is_empty() { None } else { Some(0) })
+}
+
+/// 终端太小时只显示提示信息
+fn draw_too_small(f: &mut Frame) {
+    let size = f.size();
+    let text = vec![
+        Line::from(Span::styled("终端太小", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))),
+        Line::from(format!("当前 {}x{}", size.width, size.height)),
+        Line::from(format!("至少需要 {}x{}", MIN_TERMINAL_WIDTH, MIN_TERMINAL_HEIGHT)),
+    ];
+
+    let paragraph = Paragraph::new(text)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+    f.render_widget(paragraph, size);
+}
+
+/// 计算居中弹窗区域：小终端上放大到最小尺寸，但不超出整个区域
+fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
+    let width = ((r.width as u32 * percent_x as u32 / 100) as u16)
+        .max(MIN_POPUP_WIDTH)
+        .min(r.width);
+    let height = ((r.height as u32 * percent_y as u32 / 100) as u16)
+        .max(MIN_POPUP_HEIGHT)
+        .min(r.height);
+
+    Rect {
+        x: r.x + (r.width - width) / 2,
+        y: r.y + (r.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ifaces(names: &[&str]) -> Vec<NetInterface> {
+        names
+            .iter()
+            .map(|name| NetInterface::new(name.to_string(), InterfaceKind::Unknown))
+            .collect()
+    }
+
+    #[test]
+    fn test_reselect_index_by_name() {
+        // 前面的接口被删除，选中项应跟随原接口移动
+        let after = ifaces(&["lo", "eth0", "wg0"]);
+        assert_eq!(reselect_index(&after, Some("wg0"), Some(3)), Some(2));
+    }
+
+    #[test]
+    fn test_reselect_index_when_selected_removed() {
+        // 选中的接口被删除，落在原位置的接口上
+        let after = ifaces(&["lo", "eth0", "wg0"]);
+        assert_eq!(reselect_index(&after, Some("veth1"), Some(1)), Some(1));
+
+        // 删除的是最后一个接口，选中新的最后一个
+        assert_eq!(reselect_index(&after, Some("veth1"), Some(3)), Some(2));
+
+        // 列表为空
+        assert_eq!(reselect_index(&[], Some("veth1"), Some(0)), None);
+    }
+
+    #[test]
+    fn test_sort_overview_by_rate() {
+        let mut list = ifaces(&["eth0", "lo", "wg0"]);
+        list[0].traffic_stats.rx_speed = 100.0;
+        list[1].traffic_stats.tx_speed = 500.0;
+        list[2].traffic_stats.rx_speed = 300.0;
+        list[2].traffic_stats.tx_speed = 300.0;
+        list[0].ifindex = 2;
+        list[1].ifindex = 1;
+        list[2].ifindex = 5;
+
+        let names = |sort| sort_overview(&list, sort).iter().map(|i| i.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(OverviewSort::TotalRate), ["wg0", "lo", "eth0"]);
+        assert_eq!(names(OverviewSort::RxRate), ["wg0", "eth0", "lo"]);
+        assert_eq!(names(OverviewSort::Name), ["eth0", "lo", "wg0"]);
+        assert_eq!(names(OverviewSort::Ifindex), ["lo", "eth0", "wg0"]);
+    }
+
+    #[test]
+    fn test_centered_rect_stays_inside_frame() {
+        // 大终端按百分比计算
+        let big = Rect::new(0, 0, 200, 60);
+        assert_eq!(centered_rect(50, 50, big), Rect::new(50, 15, 100, 30));
+
+        // 小终端放大到最小尺寸，但不超出边界
+        let small = Rect::new(0, 0, 60, 20);
+        let popup = centered_rect(50, 30, small);
+        assert_eq!(popup, Rect::new(2, 2, 56, 16));
+
+        let tiny = Rect::new(0, 0, 30, 10);
+        assert_eq!(centered_rect(50, 50, tiny), tiny);
+    }
+
+    #[test]
+    fn test_order_pinned() {
+        let mut list = ifaces(&["lo", "eth0", "eth1", "wg0", "veth1"]);
+        order_pinned(&mut list, &["wg0".to_string(), "eth0".to_string(), "gone0".to_string()]);
+
+        let names: Vec<&str> = list.iter().map(|iface| iface.name.as_str()).collect();
+        assert_eq!(names, ["wg0", "eth0", "lo", "eth1", "veth1"]);
+    }
+
+    #[test]
+    fn test_edit_form_changed_fields() {
+        let mut form = EditFormState::new(&ifaces(&["eth0"])[0]);
+        assert!(form.changed_fields().is_empty());
+
+        form.ip_address = "192.168.1.10".to_string();
+        form.dns = "223.5.5.5,114.114.114.114 ".to_string();
+        assert_eq!(form.changed_fields(), [0]);
+
+        form.gateway = "192.168.1.1".to_string();
+        assert_eq!(form.changed_fields(), [0, 2]);
+
+        form.ipv6_addresses = "2001:db8::10/64, fd00::1".to_string();
+        assert_eq!(form.changed_fields(), [0, 2, EditFormState::IPV6_FIELD]);
+        assert_eq!(form.ipv6_list(), ["2001:db8::10/64", "fd00::1"]);
+    }
+
+    #[test]
+    fn test_bridge_form_members() {
+        let mut interfaces = ifaces(&["lo", "eth0", "eth1", "br0"]);
+        interfaces[0].kind = InterfaceKind::Loopback;
+        interfaces[2].ipv4_addresses = vec!["192.168.1.10/24".to_string()];
+        interfaces[3].kind = InterfaceKind::Bridge;
+
+        // 回环接口和已有网桥不能作为成员
+        let mut form = BridgeFormState::new(&interfaces);
+        let names: Vec<&str> = form.candidates.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["eth0", "eth1"]);
+
+        form.candidates[0].selected = true;
+        assert!(member_address_warning(&form.candidates, "网桥").is_none());
+
+        // 有地址的成员需要确认
+        form.candidates[1].selected = true;
+        let warning = member_address_warning(&form.candidates, "网桥").unwrap();
+        assert!(warning.contains("eth1 (192.168.1.10/24)"));
+        assert_eq!(selected_members(&form.candidates).len(), 2);
+    }
+
+    #[test]
+    fn test_rate_history_data() {
+        let history: VecDeque<(f64, f64)> = [(100.0, 1.5), (200.0, 2.5), (300.0, 3.5)].into_iter().collect();
+        assert_eq!(rate_history_data(Some(&history), true, 10), [100, 200, 300]);
+        // 宽度不够时只保留最近的采样
+        assert_eq!(rate_history_data(Some(&history), false, 2), [2, 3]);
+        assert!(rate_history_data(None, true, 10).is_empty());
+    }
+
+    #[test]
+    fn test_bond_form_modes() {
+        let mut interfaces = ifaces(&["eth0", "eth1", "br0"]);
+        interfaces[0].kind = InterfaceKind::Physical;
+        interfaces[1].kind = InterfaceKind::Physical;
+        interfaces[2].kind = InterfaceKind::Bridge;
+
+        // 只有物理网卡可以加入链路聚合
+        let mut form = BondFormState::new(&interfaces);
+        let names: Vec<&str> = form.candidates.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["eth0", "eth1"]);
+        assert_eq!(form.mode_name(), "active-backup");
+
+        form.cycle_mode(true);
+        assert_eq!(form.mode_name(), "balance-xor");
+        form.cycle_mode(false);
+        form.cycle_mode(false);
+        assert_eq!(form.mode_name(), "balance-rr");
+        form.cycle_mode(false);
+        assert_eq!(form.mode_name(), "balance-alb");
+    }
+
+    #[test]
+    fn test_vlan_form_validation() {
+        let mut interfaces = ifaces(&["eth0", "eth1", "eth0.10", "wg0"]);
+        for iface in &mut interfaces[..2] {
+            iface.kind = InterfaceKind::Physical;
         }
-        Vec::new()
+        interfaces[0].state = InterfaceState::Up;
+        interfaces[1].state = InterfaceState::Down;
+
+        // 父接口只能是物理网卡，默认选中当前接口
+        let mut form = VlanFormState::new(&interfaces, Some("eth1"));
+        assert_eq!(form.parents, ["eth0", "eth1"]);
+        assert_eq!(form.parent, 1);
+
+        form.vid = "20".to_string();
+        assert!(form.validate(&interfaces).unwrap_err().to_string().contains("未启用"));
+
+        form.parent = 0;
+        assert_eq!(form.validate(&interfaces).unwrap(), ("eth0".to_string(), 20));
+
+        form.vid = "10".to_string();
+        assert!(form.validate(&interfaces).unwrap_err().to_string().contains("已存在"));
+
+        form.vid = "4095".to_string();
+        assert!(form.validate(&interfaces).is_err());
     }
 
-    fn draw_interface_actions(&self, f: &mut Frame) {
-        if let Some(i) = self.list_state.selected() {
-            if let Some(iface) = self.interfaces.get(i) {
-                let area = centered_rect(60, 70, f.size());
-                f.render_widget(Clear, area);
+    #[test]
+    fn test_edit_form_mtu() {
+        let mut form = EditFormState::new(&ifaces(&["eth0"])[0]);
+        assert_eq!(form.mtu, "1500");
+        assert_eq!(form.mtu_change().unwrap(), None);
 
-                let items = self.get_action_menu_items();
-                let mut text = vec![
-                    Line::from(Span::styled(
-                        format!("接口操作 - {}", iface.name),
-                        Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
-                    )),
-                    Line::from(""),
-                ];
+        form.mtu = "9000".to_string();
+        assert_eq!(form.changed_fields(), [EditFormState::MTU_FIELD]);
+        assert_eq!(form.mtu_change().unwrap(), Some(9000));
 
-                // 显示接口基本信息
-                text.push(Line::from(vec![
-                    Span::styled("接口类型: ", Style::default().fg(Color::Cyan)),
-                    Span::raw(format!("{:?}", iface.kind)),
-                ]));
+        form.mtu = "40".to_string();
+        assert!(form.mtu_change().is_err());
+    }
 
-                // 显示创建者信息
-                if let Some(owner) = &iface.owner {
-                    text.push(Line::from(vec![
-                        Span::styled("创建者: ", Style::default().fg(Color::Cyan)),
-                        Span::raw(owner.display_name()),
-                    ]));
-                }
+    #[test]
+    fn test_edit_form_ipv6_only_mode() {
+        let mut iface = ifaces(&["eth0"]).remove(0);
+        iface.ipv6_details = vec![crate::model::Ipv6AddressInfo {
+            address: "2001:db8::5/64".to_string(),
+            prefix: "2001:db8::/64".to_string(),
+            scope: "global".to_string(),
+            temporary: false,
+            deprecated: false,
+        }];
+        iface.ipv6_gateway = Some("2001:db8::1".to_string());
+
+        // 没有IPv4地址的接口自动进入纯IPv6模式，只显示IPv6相关字段
+        let mut form = EditFormState::new(&iface);
+        assert!(form.ipv6_only);
+        assert_eq!(form.current_field, EditFormState::IPV6_FIELD);
+        assert_eq!(form.gateway6, "2001:db8::1");
+        assert!(form.changed_fields().is_empty());
+        form.next_field();
+        assert_eq!(form.current_field, EditFormState::GATEWAY6_FIELD);
+
+        // 纯IPv6模式下不比较隐藏的IPv4字段
+        form.ip_address = "10.0.0.5".to_string();
+        assert!(form.changed_fields().is_empty());
+        form.toggle_ipv6_only();
+        assert!(!form.ipv6_only);
+        assert_eq!(form.changed_fields(), [0]);
+    }
 
-                text.push(Line::from(""));
-                text.push(Line::from(Span::styled(
-                    "可用操作:",
-                    Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
-                )));
-                text.push(Line::from(""));
+    #[test]
+    fn test_edit_form_ipv6_settings() {
+        let mut form = EditFormState::new(&ifaces(&["eth0"])[0]);
+        // IPv6部分为空时只配置IPv4
+        assert_eq!(form.ipv6_settings().unwrap(), Ipv6Settings::default());
 
-                // 显示操作菜单
-                for (idx, (action, desc)) in items.iter().enumerate() {
-                    let prefix = if idx == self.action_menu_state {
-                        "► "
-                    } else {
-                        "  "
-                    };
+        form.ipv6_addresses = "2001:db8::10/64".to_string();
+        form.gateway6 = "2001:db8::1".to_string();
+        assert_eq!(form.changed_fields(), [EditFormState::IPV6_FIELD, EditFormState::GATEWAY6_FIELD]);
+        assert_eq!(form.ipv6_settings().unwrap().gateway.as_deref(), Some("2001:db8::1"));
 
-                    let style = if idx == self.action_menu_state {
-                        Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
-                    } else {
-                        Style::default().fg(Color::White)
-                    };
+        form.gateway6 = "2001:db8::g".to_string();
+        assert!(form.ipv6_settings().is_err());
+    }
 
-                    text.push(Line::from(vec![
-                        Span::styled(prefix, style),
-                        Span::styled(*action, style),
-                        Span::raw(" - "),
-                        Span::styled(*desc, Style::default().fg(Color::DarkGray)),
-                    ]));
-                }
+    #[test]
+    fn test_toggle_compare() {
+        let mut pair = Vec::new();
+        toggle_compare(&mut pair, "eth0");
+        toggle_compare(&mut pair, "eth1");
+        assert_eq!(pair, ["eth0", "eth1"]);
 
-                text.push(Line::from(""));
-                text.push(Line::from(""));
-                text.push(Line::from(vec![
-                    Span::styled("↑↓", Style::default().fg(Color::Cyan)),
-                    Span::raw(" - 选择  "),
-                    Span::styled("Enter", Style::default().fg(Color::Green)),
-                    Span::raw(" - 执行  "),
-                    Span::styled("Esc", Style::default().fg(Color::Red)),
-                    Span::raw(" - 取消"),
-                ]));
+        // 第三个接口替换最早标记的接口
+        toggle_compare(&mut pair, "wg0");
+        assert_eq!(pair, ["eth1", "wg0"]);
 
-                let paragraph = Paragraph::new(text)
-                    .block(
-                        Block::default()
-                            .title("接口操作菜单")
-                            .borders(Borders::ALL)
-                            .border_type(BorderType::Rounded)
-                            .border_style(Style::default().fg(Color::Cyan))
-                            .style(Style::default().bg(Color::Black)),
-                    )
-                    .alignment(Alignment::Left);
+        // 再次标记即取消
+        toggle_compare(&mut pair, "eth1");
+        assert_eq!(pair, ["wg0"]);
+    }
 
-                f.render_widget(paragraph, area);
-            }
+    #[test]
+    fn test_initial_selection() {
+        let mut list = ifaces(&["lo", "eth0", "eth1"]);
+        list[0].kind = InterfaceKind::Loopback;
+
+        assert_eq!(initial_selection(&list, Some("eth1")), Some(2));
+        // 默认路由接口不在列表中（如被 --kind 过滤）时回退到第一个非回环接口
+        assert_eq!(initial_selection(&list, Some("wg0")), Some(1));
+        assert_eq!(initial_selection(&list, None), Some(1));
+
+        assert_eq!(initial_selection(&list[..1], None), Some(0));
+        assert_eq!(initial_selection(&[], Some("eth0")), None);
+    }
+
+    #[test]
+    fn test_edit_form_cidr_sync() {
+        let mut form = EditFormState::new(&ifaces(&["eth0"])[0]);
+
+        // IP字段输入CIDR时自动填写子网掩码
+        form.current_field = 0;
+        form.ip_address = "192.168.1.10/16".to_string();
+        form.sync_cidr();
+        assert_eq!(form.netmask, "255.255.0.0");
+        assert_eq!(form.ip_only(), "192.168.1.10");
+
+        // 修改子网掩码时同步IP字段中的前缀
+        form.current_field = 1;
+        form.netmask = "255.255.255.128".to_string();
+        form.sync_cidr();
+        assert_eq!(form.ip_address, "192.168.1.10/25");
+
+        // 无前缀时两个字段互不影响
+        form.ip_address = "10.0.0.5".to_string();
+        form.netmask = "255.0.0.0".to_string();
+        form.sync_cidr();
+        assert_eq!(form.ip_address, "10.0.0.5");
+    }
+
+    #[test]
+    fn test_empty_interface_list() {
+        use ratatui::backend::TestBackend;
+
+        let mut app = App::empty(AppOptions::default());
+        app.next();
+        app.previous();
+        assert_eq!(app.list_state.selected(), None);
+
+        // 各按键和界面在没有接口时都不应panic
+        for key in [KeyCode::Down, KeyCode::Up, KeyCode::Enter, KeyCode::Char('e'), KeyCode::Char('x'), KeyCode::Char('m')] {
+            app.handle_key(key, KeyModifiers::NONE).unwrap();
+        }
+
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        for screen in [Screen::Main, Screen::Overview, Screen::InterfaceActions, Screen::ConfirmDelete, Screen::Help] {
+            app.screen = screen;
+            terminal.draw(|f| app.ui(f)).unwrap();
         }
     }
 
-    fn execute_action_menu_item(&mut self) -> Result<()> {
-        if let Some(i) = self.list_state.selected() {
-            if let Some(iface) = self.interfaces.get(i).cloned() {
-                let items = self.get_action_menu_items();
-                if let Some((action, _)) = items.get(self.action_menu_state) {
-                    match *action {
-                        "编辑配置" => {
-                            self.edit_form = Some(EditFormState::new(&iface));
-                            self.screen = Screen::EditIface;
-                        },
-                        "切换DHCP" => {
-                            self.screen = Screen::ToggleDhcp;
-                        },
-                        "启用接口" => {
-                            self.screen = Screen::Main;
-                            self.toggle_interface_up()?;
-                        },
-                        "禁用接口" => {
-                            self.screen = Screen::Main;
-                            self.toggle_interface_down()?;
-                        },
-                        "删除接口" => {
-                            self.screen = Screen::ConfirmDelete;
-                        },
-                        "停止服务" | "停止容器" | "终止进程" | "断开连接" | "卸载模块" => {
-                            self.screen = Screen::OwnerActions;
-                        },
-                        _ => {
-                            self.screen = Screen::Main;
-                        }
-                    }
-                }
-            }
+    #[test]
+    fn test_frozen_config_from_interface() {
+        let mut iface = ifaces(&["eth0"]).remove(0);
+        let dns = || DnsConfig { nameservers: vec!["192.168.1.1".to_string()], search: Vec::new() };
+        assert!(FrozenConfig::from_interface(&iface, dns()).is_none());
+
+        iface.ipv4_addresses = vec!["192.168.1.10/24".to_string(), "10.0.0.2/8".to_string()];
+        iface.ipv4_config = Some(crate::model::Ipv4Config {
+            address: "10.0.0.2".to_string(),
+            netmask: "255.0.0.0".to_string(),
+            prefix: 8,
+            gateway: Some("192.168.1.1".to_string()),
+        });
+
+        let frozen = FrozenConfig::from_interface(&iface, dns()).unwrap();
+        assert_eq!(frozen.address, "192.168.1.10/24");
+        assert_eq!(frozen.gateway.as_deref(), Some("192.168.1.1"));
+        assert_eq!(frozen.nameservers, ["192.168.1.1"]);
+        assert_eq!(frozen.skipped, ["10.0.0.2/8"]);
+    }
+
+    #[test]
+    fn test_edit_form_netmask_format() {
+        let mut form = EditFormState::new(&ifaces(&["eth0"])[0]).with_netmask_format(NetmaskFormat::Prefix);
+        assert_eq!(form.netmask, "24");
+        assert_eq!(form.field_names()[EditFormState::NETMASK_FIELD], "前缀长度");
+        // 只切换格式不算修改
+        assert!(form.changed_fields().is_empty());
+
+        // 前缀模式下IP字段的CIDR同步为前缀长度
+        form.current_field = 0;
+        form.ip_address = "10.0.0.5/16".to_string();
+        form.sync_cidr();
+        assert_eq!(form.netmask, "16");
+
+        form.toggle_netmask_format();
+        assert_eq!(form.netmask, "255.255.0.0");
+        assert_eq!(form.original[EditFormState::NETMASK_FIELD], "255.255.255.0");
+
+        assert_eq!(App::netmask_to_prefix("/20").unwrap(), 20);
+        assert!(App::netmask_to_prefix("33").is_err());
+    }
+
+    #[test]
+    fn test_safe_mode_blocks_and_needs_confirm_to_disable() {
+        use ratatui::backend::TestBackend;
+
+        let mut app = App::empty(AppOptions { safe_mode: true, ..AppOptions::default() });
+        app.interfaces = ifaces(&["eth0"]);
+        app.list_state.select(Some(0));
+
+        // 禁用接口被阻止，不会执行ip命令
+        app.handle_key(KeyCode::Char('d'), KeyModifiers::NONE).unwrap();
+        assert!(app.status_message.as_deref().is_some_and(|msg| msg.contains(SAFE_MODE_BLOCKED)));
+        assert_eq!(app.screen, Screen::Main);
+
+        // 清除限速同样被阻止，不会执行tc命令
+        app.status_message = None;
+        app.rate_limit_form = Some(RateLimitFormState::new("eth0"));
+        app.screen = Screen::RateLimit;
+        app.handle_key(KeyCode::Char('c'), KeyModifiers::NONE).unwrap();
+        assert!(app.status_message.as_deref().is_some_and(|msg| msg.contains(SAFE_MODE_BLOCKED)));
+        assert_eq!(app.screen, Screen::Main);
+
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        terminal.draw(|f| app.ui(f)).unwrap();
+
+        // 关闭安全模式需要确认
+        app.handle_key(KeyCode::Char('!'), KeyModifiers::NONE).unwrap();
+        assert_eq!(app.screen, Screen::ConfirmSafeModeOff);
+        terminal.draw(|f| app.ui(f)).unwrap();
+        app.handle_key(KeyCode::Char('n'), KeyModifiers::NONE).unwrap();
+        assert!(app.safe_mode);
+
+        app.handle_key(KeyCode::Char('!'), KeyModifiers::NONE).unwrap();
+        app.handle_key(KeyCode::Char('y'), KeyModifiers::NONE).unwrap();
+        assert!(!app.safe_mode);
+
+        // 开启不需要确认
+        app.handle_key(KeyCode::Char('!'), KeyModifiers::NONE).unwrap();
+        assert!(app.safe_mode);
+    }
+
+    #[test]
+    fn test_edit_form_history_recall() {
+        let iface = &ifaces(&["eth0"])[0];
+        let mut history = EditHistory::default();
+        for gateway in ["10.0.0.1", "192.168.1.1", "10.0.0.1"] {
+            let mut form = EditFormState::new(iface);
+            form.gateway = gateway.to_string();
+            form.dns = "1.1.1.1".to_string();
+            history.record(&form);
         }
-        Ok(())
+        // 重复值移到最前，空值不记录
+        assert_eq!(history.values(2), ["10.0.0.1", "192.168.1.1"]);
+        assert!(history.values(4).is_empty());
+
+        // 没有DNS配置的接口预填最近保存的DNS
+        let mut form = EditFormState::new(iface).with_history(&history);
+        assert_eq!(form.dns, "1.1.1.1");
+
+        form.current_field = 2;
+        form.gateway = "172.16.".to_string();
+        form.recall_history(history.values(2), true);
+        assert_eq!(form.gateway, "10.0.0.1");
+        form.recall_history(history.values(2), true);
+        form.recall_history(history.values(2), true);
+        assert_eq!(form.gateway, "192.168.1.1");
+        form.recall_history(history.values(2), false);
+        form.recall_history(history.values(2), false);
+        // 翻回最新之后恢复原来输入的内容
+        assert_eq!(form.gateway, "172.16.");
+        assert_eq!(form.history_pos, None);
     }
-}
 
-fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
-    let popup_layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Percentage((100 - percent_y) / 2),
-            Constraint::Percentage(percent_y),
-            Constraint::Percentage((100 - percent_y) / 2),
-        ])
-        .split(r);
-
-    Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage((100 - percent_x) / 2),
-            Constraint::Percentage(percent_x),
-            Constraint::Percentage((100 - percent_x) / 2),
-        ])
-        .split(popup_layout[1])[1]
+    #[test]
+    fn test_duplicate_address_warning() {
+        let mut interfaces = ifaces(&["eth0", "eth1"]);
+        interfaces[0].ipv4_addresses = vec!["192.168.1.10/24".to_string()];
+        interfaces[1].ipv4_addresses = vec!["10.0.0.5/8".to_string()];
+
+        let mut form = EditFormState::new(&interfaces[0]);
+        // 本接口已有的地址不算重复
+        assert!(form.duplicate_address_warning(&interfaces).is_none());
+
+        form.ip_address = "10.0.0.5/24".to_string();
+        let warning = form.duplicate_address_warning(&interfaces).unwrap();
+        assert!(warning.contains("eth1"));
+
+        // 前缀相同但地址不同不算重复
+        form.ip_address = "10.0.0.50".to_string();
+        assert!(form.duplicate_address_warning(&interfaces).is_none());
+    }
+
+    #[test]
+    fn test_list_details_line() {
+        let mut iface = ifaces(&["veth1"]).remove(0);
+        let text = |line: Line| line.spans.iter().map(|span| span.content.to_string()).collect::<String>();
+        assert_eq!(text(list_details_line(&iface)).trim(), "无IPv4地址");
+
+        iface.ipv4_addresses = vec!["172.17.0.2/16".to_string(), "172.17.0.3/16".to_string()];
+        iface.owner = Some(crate::model::InterfaceOwner::Unknown);
+        let line = text(list_details_line(&iface));
+        assert!(line.trim().starts_with("172.17.0.2/16 · "));
+        assert!(!line.contains("172.17.0.3"));
+    }
+
+    #[test]
+    fn test_unmanaged_warning() {
+        let mut iface = ifaces(&["eth0"]).remove(0);
+        assert!(unmanaged_warning(&iface).is_none());
+
+        iface.netplan_file = Some(PathBuf::from("/etc/netplan/01-manual.yaml"));
+        assert!(unmanaged_warning(&iface).unwrap().contains("01-manual.yaml"));
+
+        iface.nicman_managed = true;
+        assert!(unmanaged_warning(&iface).is_none());
+    }
+
+    #[test]
+    fn test_unmanaged_gate() {
+        let mut interfaces = ifaces(&["eth0", "eth1"]);
+        for iface in &mut interfaces {
+            iface.netplan_file = Some(PathBuf::from("/etc/netplan/01-manual.yaml"));
+        }
+        let mut gate = UnmanagedGate::default();
+
+        // 第一次提醒，同一接口再次执行时放行
+        assert!(gate.check(&interfaces, "eth0").is_some());
+        assert!(gate.check(&interfaces, "eth0").is_none());
+
+        // 换了接口需要重新确认
+        assert!(gate.check(&interfaces, "eth1").is_some());
+        assert!(gate.check(&interfaces, "eth0").is_some());
+
+        // 由nicman管理的接口不需要确认
+        interfaces[1].nicman_managed = true;
+        assert!(gate.check(&interfaces, "eth1").is_none());
+    }
 }
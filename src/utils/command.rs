@@ -1,14 +1,103 @@
 // 命令执行工具
 
 use anyhow::{Context, Result};
-use std::process::{Command, Output};
+use std::collections::VecDeque;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+/// 命令日志保留的最大条数
+const COMMAND_LOG_CAPACITY: usize = 100;
+
+/// 最近执行的命令记录（供调试界面查看）
+static COMMAND_LOG: Mutex<VecDeque<CommandRecord>> = Mutex::new(VecDeque::new());
+
+/// 单条命令执行记录
+#[derive(Debug, Clone)]
+pub struct CommandRecord {
+    pub time: String,           // 执行时间
+    pub command: String,        // 完整命令行
+    pub exit_code: Option<i32>, // 退出码（启动失败或被信号终止时为None）
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// 非root运行时通过sudo执行修改类命令（--use-sudo）
+static USE_SUDO: AtomicBool = AtomicBool::new(false);
+
+/// 视为瞬时故障、值得重试的错误信息
+const TRANSIENT_ERRORS: &[&str] = &[
+    "Device or resource busy",
+    "Resource temporarily unavailable",
+    "Cannot connect to the Docker daemon",
+    "Transport endpoint is not connected",
+];
 
 /// 执行系统命令并返回输出
 pub fn execute_command(program: &str, args: &[&str]) -> Result<Output> {
-    Command::new(program)
-        .args(args)
+    let result = build_command(program, args)
         .output()
-        .with_context(|| format!("执行命令失败: {} {}", program, args.join(" ")))
+        .with_context(|| format!("执行命令失败: {} {}", program, args.join(" ")));
+
+    record_command(program, args, &result);
+    result
+}
+
+/// 组装命令，强制使用C locale
+///
+/// 各解析器依赖 `ip`、`ethtool` 等工具的英文输出（如 "state UP"、"Speed:"），
+/// 非英文locale下输出会被翻译导致解析失败。sudo默认保留LC_*变量，特权命令同样生效。
+fn build_command(program: &str, args: &[&str]) -> Command {
+    let mut command = Command::new(program);
+    command.args(args).env("LC_ALL", "C").env("LANG", "C");
+    command
+}
+
+/// 记录命令执行结果
+fn record_command(program: &str, args: &[&str], result: &Result<Output>) {
+    let (exit_code, stdout, stderr) = match result {
+        Ok(output) => (
+            output.status.code(),
+            String::from_utf8_lossy(&output.stdout).to_string(),
+            String::from_utf8_lossy(&output.stderr).to_string(),
+        ),
+        Err(e) => (None, String::new(), format!("{:#}", e)),
+    };
+
+    let record = CommandRecord {
+        time: chrono::Local::now().format("%H:%M:%S").to_string(),
+        command: std::iter::once(program).chain(args.iter().copied()).collect::<Vec<_>>().join(" "),
+        exit_code,
+        stdout,
+        stderr,
+    };
+
+    tracing::debug!(
+        command = %record.command,
+        exit_code = ?record.exit_code,
+        stderr = %record.stderr.trim(),
+        "执行命令"
+    );
+
+    if let Ok(mut log) = COMMAND_LOG.lock() {
+        if log.len() >= COMMAND_LOG_CAPACITY {
+            log.pop_front();
+        }
+        log.push_back(record);
+    }
+}
+
+/// 获取最近执行的命令记录（按时间先后排列）
+pub fn recent_commands() -> Vec<CommandRecord> {
+    COMMAND_LOG
+        .lock()
+        .map(|log| log.iter().cloned().collect())
+        .unwrap_or_default()
 }
 
 /// 执行命令并返回stdout字符串
@@ -23,12 +112,201 @@ pub fn execute_command_stdout(program: &str, args: &[&str]) -> Result<String> {
     Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
 
+/// 开启/关闭sudo模式
+pub fn set_use_sudo(enabled: bool) {
+    USE_SUDO.store(enabled, Ordering::Relaxed);
+}
+
+/// 修改类命令是否会通过sudo执行
+pub fn use_sudo() -> bool {
+    USE_SUDO.load(Ordering::Relaxed)
+}
+
+/// 执行修改系统状态的命令，sudo模式下以 `sudo -n` 执行
+///
+/// TUI运行时无法输入密码，凭据需要在启动时通过 `sudo -v` 缓存；
+/// 缓存过期时sudo直接失败而不是卡在密码提示上。
+pub fn execute_privileged_stdout(program: &str, args: &[&str]) -> Result<String> {
+    let (program, args) = privileged_command(program, args, use_sudo());
+    execute_command_stdout(program, &args)
+}
+
+/// 执行修改系统状态的命令并返回完整输出（需要自行检查退出状态时使用）
+pub fn execute_privileged(program: &str, args: &[&str]) -> Result<Output> {
+    let (program, args) = privileged_command(program, args, use_sudo());
+    execute_command(program, &args)
+}
+
+/// 检查修改类命令是否执行成功
+pub fn privileged_success(program: &str, args: &[&str]) -> bool {
+    execute_privileged(program, args)
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+/// 读取只有root可读的文件（如Netplan配置），sudo模式下权限不足时改用 `sudo -n cat`
+pub fn read_privileged(path: &Path) -> Result<String> {
+    match fs::read_to_string(path) {
+        Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied && use_sudo() => {
+            execute_privileged_stdout("cat", &[&path.to_string_lossy()])
+        }
+        result => Ok(result?),
+    }
+}
+
+/// 写入系统文件，sudo模式下通过 `sudo -n tee` 写入
+pub fn write_privileged(path: &Path, content: &str) -> Result<()> {
+    if !use_sudo() {
+        return Ok(fs::write(path, content)?);
+    }
+
+    let path_arg = path.to_string_lossy();
+    let args = ["-n", "tee", path_arg.as_ref()];
+    let result = build_command("sudo", &args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .and_then(|mut child| {
+            if let Some(mut stdin) = child.stdin.take() {
+                stdin.write_all(content.as_bytes())?;
+            }
+            child.wait_with_output()
+        })
+        .with_context(|| format!("执行命令失败: sudo -n tee {}", path_arg));
+    record_command("sudo", &args, &result);
+
+    let output = result?;
+    if !output.status.success() {
+        anyhow::bail!("命令执行失败: {}", String::from_utf8_lossy(&output.stderr));
+    }
+    Ok(())
+}
+
+/// 复制系统文件（保留权限），sudo模式下通过 `sudo -n cp -p` 复制
+pub fn copy_privileged(from: &Path, to: &Path) -> Result<()> {
+    if !use_sudo() {
+        fs::copy(from, to)?;
+        return Ok(());
+    }
+    execute_privileged_stdout("cp", &["-p", &from.to_string_lossy(), &to.to_string_lossy()])?;
+    Ok(())
+}
+
+/// 修改系统文件的权限，sudo模式下通过 `sudo -n chmod` 修改
+pub fn set_mode_privileged(path: &Path, mode: u32) -> Result<()> {
+    if !use_sudo() {
+        use std::os::unix::fs::PermissionsExt;
+        return Ok(fs::set_permissions(path, fs::Permissions::from_mode(mode))?);
+    }
+    execute_privileged_stdout("chmod", &[&format!("{:o}", mode), &path.to_string_lossy()])?;
+    Ok(())
+}
+
+/// 组装实际执行的命令行
+fn privileged_command<'a>(program: &'a str, args: &[&'a str], sudo: bool) -> (&'a str, Vec<&'a str>) {
+    if sudo {
+        ("sudo", ["-n", program].into_iter().chain(args.iter().copied()).collect())
+    } else {
+        (program, args.to_vec())
+    }
+}
+
 /// 检查命令是否执行成功
 pub fn command_success(program: &str, args: &[&str]) -> bool {
-    Command::new(program)
-        .args(args)
-        .output()
+    execute_command(program, args)
         .map(|output| output.status.success())
         .unwrap_or(false)
 }
 
+
+/// 执行命令并返回stdout字符串，遇到瞬时故障时按指数退避重试
+///
+/// 最多执行 `attempts` 次，首次重试等待 `initial_delay`，之后每次翻倍。
+/// 仅用于写操作（sudo模式下通过sudo执行）；读操作应直接使用 `execute_command_stdout`。
+pub fn execute_command_stdout_retry(
+    program: &str,
+    args: &[&str],
+    attempts: u32,
+    initial_delay: Duration,
+) -> Result<String> {
+    let mut delay = initial_delay;
+    let mut attempt = 1;
+
+    loop {
+        match execute_privileged_stdout(program, args) {
+            Ok(output) => return Ok(output),
+            Err(e) if attempt < attempts && is_transient_error(&e.to_string()) => {
+                thread::sleep(delay);
+                delay *= 2;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// 判断错误信息是否属于瞬时故障
+fn is_transient_error(message: &str) -> bool {
+    TRANSIENT_ERRORS.iter().any(|pattern| message.contains(pattern))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_transient_error() {
+        assert!(is_transient_error("命令执行失败: RTNETLINK answers: Device or resource busy"));
+        assert!(!is_transient_error("命令执行失败: Cannot find device \"eth9\""));
+    }
+
+    #[test]
+    fn test_privileged_command() {
+        assert_eq!(privileged_command("ip", &["link", "set", "eth0", "up"], false), ("ip", vec!["link", "set", "eth0", "up"]));
+        assert_eq!(
+            privileged_command("ip", &["link", "set", "eth0", "up"], true),
+            ("sudo", vec!["-n", "ip", "link", "set", "eth0", "up"])
+        );
+    }
+
+    #[test]
+    fn test_command_forces_c_locale() {
+        let command = build_command("ip", &["-o", "link", "show"]);
+        let envs: Vec<_> = command.get_envs().collect();
+        assert!(envs.contains(&("LC_ALL".as_ref(), Some("C".as_ref()))));
+        assert!(envs.contains(&("LANG".as_ref(), Some("C".as_ref()))));
+
+        let output = execute_command_stdout("sh", &["-c", "echo $LC_ALL"]).unwrap();
+        assert_eq!(output.trim(), "C");
+    }
+
+    #[test]
+    fn test_command_is_recorded() {
+        let _ = execute_command("true", &["nicman-record-test"]);
+        assert!(recent_commands()
+            .iter()
+            .any(|record| record.command == "true nicman-record-test" && record.exit_code == Some(0)));
+    }
+
+    #[test]
+    fn test_privileged_file_helpers_without_sudo() {
+        let path = std::env::temp_dir().join(format!("nicman-privileged-{}.yaml", std::process::id()));
+        let backup = path.with_extension("yaml.backup");
+        write_privileged(&path, "network:\n  version: 2\n").unwrap();
+        set_mode_privileged(&path, 0o600).unwrap();
+        copy_privileged(&path, &backup).unwrap();
+        assert_eq!(read_privileged(&backup).unwrap(), "network:\n  version: 2\n");
+
+        use std::os::unix::fs::PermissionsExt;
+        assert_eq!(fs::metadata(&backup).unwrap().permissions().mode() & 0o777, 0o600);
+        fs::remove_file(&path).ok();
+        fs::remove_file(&backup).ok();
+    }
+
+    #[test]
+    fn test_retry_non_transient_fails_fast() {
+        let result = execute_command_stdout_retry("false", &[], 3, Duration::from_secs(10));
+        assert!(result.is_err());
+    }
+}
@@ -1,7 +1,99 @@
 // 格式化工具函数
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// 流量显示单位。Auto按大小自动切换，其余固定单位便于横向比较多个接口；
+/// 比特单位只作用于速率（按1000进位），累计流量使用对应的字节单位
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrafficUnit {
+    #[default]
+    Auto,
+    Kb,
+    Mb,
+    Gb,
+    Kbit,
+    Mbit,
+    Gbit,
+}
+
+impl TrafficUnit {
+    pub const ALL: [TrafficUnit; 7] = [
+        TrafficUnit::Auto,
+        TrafficUnit::Kb,
+        TrafficUnit::Mb,
+        TrafficUnit::Gb,
+        TrafficUnit::Kbit,
+        TrafficUnit::Mbit,
+        TrafficUnit::Gbit,
+    ];
+
+    /// 字节单位的名称和除数；比特单位返回对应的字节单位
+    fn byte_unit(self) -> Option<(&'static str, f64)> {
+        match self {
+            TrafficUnit::Auto => None,
+            TrafficUnit::Kb | TrafficUnit::Kbit => Some(("KB", 1024.0)),
+            TrafficUnit::Mb | TrafficUnit::Mbit => Some(("MB", 1024.0 * 1024.0)),
+            TrafficUnit::Gb | TrafficUnit::Gbit => Some(("GB", 1024.0 * 1024.0 * 1024.0)),
+        }
+    }
+
+    /// 是否为比特单位
+    pub fn is_bits(self) -> bool {
+        self.bit_unit().is_some()
+    }
+
+    /// 比特单位的名称和除数
+    fn bit_unit(self) -> Option<(&'static str, f64)> {
+        match self {
+            TrafficUnit::Kbit => Some(("Kbit", 1e3)),
+            TrafficUnit::Mbit => Some(("Mbit", 1e6)),
+            TrafficUnit::Gbit => Some(("Gbit", 1e9)),
+            _ => None,
+        }
+    }
+}
+
+impl std::str::FromStr for TrafficUnit {
+    type Err = String;
+
+    /// 按单位名解析（不区分大小写），如 auto、MB、mbit
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|unit| format!("{:?}", unit).eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| {
+                let valid: Vec<String> = Self::ALL.iter().map(|unit| format!("{:?}", unit).to_lowercase()).collect();
+                format!("未知的流量单位: {}（可选: {}）", s, valid.join(", "))
+            })
+    }
+}
+
+/// 当前流量显示单位（TrafficUnit::ALL中的下标）
+static TRAFFIC_UNIT: AtomicU8 = AtomicU8::new(0);
+
+/// 设置format_bytes/format_speed使用的单位
+pub fn set_traffic_unit(unit: TrafficUnit) {
+    let index = TrafficUnit::ALL.iter().position(|u| *u == unit).unwrap_or(0);
+    TRAFFIC_UNIT.store(index as u8, Ordering::Relaxed);
+}
+
+/// 当前流量显示单位
+pub fn traffic_unit() -> TrafficUnit {
+    TrafficUnit::ALL[TRAFFIC_UNIT.load(Ordering::Relaxed) as usize]
+}
 
 /// 格式化字节数为人类可读格式
 pub fn format_bytes(bytes: u64) -> String {
+    format_bytes_in(bytes, traffic_unit())
+}
+
+/// 按指定单位格式化字节数
+pub fn format_bytes_in(bytes: u64, unit: TrafficUnit) -> String {
+    if let Some((name, divisor)) = unit.byte_unit() {
+        return format!("{:.1} {}", bytes as f64 / divisor, name);
+    }
+
     const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
     
     if bytes == 0 {
@@ -25,7 +117,49 @@ pub fn format_bytes(bytes: u64) -> String {
 
 /// 格式化速率（字节/秒）
 pub fn format_speed(bytes_per_sec: f64) -> String {
-    format!("{}/s", format_bytes(bytes_per_sec as u64))
+    format_speed_in(bytes_per_sec, traffic_unit())
+}
+
+/// 按指定单位格式化速率（字节/秒）
+pub fn format_speed_in(bytes_per_sec: f64, unit: TrafficUnit) -> String {
+    match unit.bit_unit() {
+        Some((name, divisor)) => format!("{:.1} {}/s", bytes_per_sec * 8.0 / divisor, name),
+        None => format!("{}/s", format_bytes_in(bytes_per_sec as u64, unit)),
+    }
+}
+
+/// 按比特格式化速率（字节/秒 × 8），按1000进位自动切换单位，如 1.0 Mbit/s
+pub fn format_speed_bits(bytes_per_sec: f64) -> String {
+    const UNITS: &[&str] = &["bit", "Kbit", "Mbit", "Gbit", "Tbit"];
+
+    let bits = bytes_per_sec * 8.0;
+    let mut size = bits;
+    let mut unit_index = 0;
+
+    while size >= 1000.0 && unit_index < UNITS.len() - 1 {
+        size /= 1000.0;
+        unit_index += 1;
+    }
+
+    if unit_index == 0 {
+        format!("{} {}/s", bits as u64, UNITS[unit_index])
+    } else {
+        format!("{:.1} {}/s", size, UNITS[unit_index])
+    }
+}
+
+/// 格式化时长，如 1h23m、2d3h、45s
+pub fn format_duration(secs: u64) -> String {
+    let (days, hours, minutes) = (secs / 86400, secs % 86400 / 3600, secs % 3600 / 60);
+    if days > 0 {
+        format!("{}d{}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h{}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m{}s", minutes, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
 }
 
 #[cfg(test)]
@@ -47,5 +181,45 @@ mod tests {
         assert_eq!(format_speed(1024.0), "1.0 KB/s");
         assert_eq!(format_speed(1048576.0), "1.0 MB/s");
     }
+
+    #[test]
+    fn test_format_speed_bits() {
+        assert_eq!(format_speed_bits(0.0), "0 bit/s");
+        assert_eq!(format_speed_bits(100.0), "800 bit/s");
+        assert_eq!(format_speed_bits(125.0), "1.0 Kbit/s");
+        assert_eq!(format_speed_bits(125_000.0), "1.0 Mbit/s");
+        assert_eq!(format_speed_bits(1_250_000_000.0), "10.0 Gbit/s");
+    }
+
+    #[test]
+    fn test_fixed_traffic_unit() {
+        // 固定字节单位：小值和大值都不再切换单位
+        assert_eq!(format_bytes_in(0, TrafficUnit::Mb), "0.0 MB");
+        assert_eq!(format_bytes_in(524288, TrafficUnit::Mb), "0.5 MB");
+        assert_eq!(format_bytes_in(1073741824, TrafficUnit::Mb), "1024.0 MB");
+        assert_eq!(format_bytes_in(1536, TrafficUnit::Kb), "1.5 KB");
+        assert_eq!(format_bytes_in(1073741824, TrafficUnit::Gb), "1.0 GB");
+        assert_eq!(format_speed_in(1048576.0, TrafficUnit::Kb), "1024.0 KB/s");
+        assert_eq!(format_speed_in(1024.0, TrafficUnit::Mb), "0.0 MB/s");
+
+        // 比特单位：速率按1000进位，累计值使用对应字节单位
+        assert_eq!(format_speed_in(125000.0, TrafficUnit::Mbit), "1.0 Mbit/s");
+        assert_eq!(format_speed_in(125_000_000.0, TrafficUnit::Mbit), "1000.0 Mbit/s");
+        assert_eq!(format_speed_in(125.0, TrafficUnit::Kbit), "1.0 Kbit/s");
+        assert_eq!(format_speed_in(0.0, TrafficUnit::Gbit), "0.0 Gbit/s");
+        assert_eq!(format_bytes_in(1048576, TrafficUnit::Mbit), "1.0 MB");
+
+        assert_eq!(format_bytes_in(1536, TrafficUnit::Auto), "1.5 KB");
+        assert_eq!("MBIT".parse::<TrafficUnit>(), Ok(TrafficUnit::Mbit));
+        assert!("mib".parse::<TrafficUnit>().is_err());
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(45), "45s");
+        assert_eq!(format_duration(125), "2m5s");
+        assert_eq!(format_duration(4980), "1h23m");
+        assert_eq!(format_duration(183600), "2d3h");
+    }
 }
 
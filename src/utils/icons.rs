@@ -0,0 +1,59 @@
+// 图标映射 - 统一管理emoji与ASCII两套显示符号
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// 是否使用ASCII模式（终端不支持emoji时启用）
+static ASCII_MODE: AtomicBool = AtomicBool::new(false);
+
+/// 设置ASCII模式
+pub fn set_ascii_mode(enabled: bool) {
+    ASCII_MODE.store(enabled, Ordering::Relaxed);
+}
+
+/// 当前是否为ASCII模式
+pub fn is_ascii_mode() -> bool {
+    ASCII_MODE.load(Ordering::Relaxed)
+}
+
+/// 根据当前模式选择emoji或ASCII表示
+pub fn pick(emoji: &'static str, ascii: &'static str) -> &'static str {
+    if is_ascii_mode() { ascii } else { emoji }
+}
+
+/// 界面中通用的提示符号
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Symbol {
+    Ok,        // 成功
+    Warning,   // 警告
+    Error,     // 错误
+    Selected,  // 选中项
+    Editing,   // 编辑中
+    Rx,        // 接收/下行
+    Tx,        // 发送/上行
+    Up,        // 上移
+    Down,      // 下移
+    Pinned,    // 置顶
+}
+
+impl Symbol {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Symbol::Ok => pick("✅", "[OK]"),
+            Symbol::Warning => pick("⚠️", "[!]"),
+            Symbol::Error => pick("❌", "[X]"),
+            Symbol::Selected => pick("►", ">"),
+            Symbol::Editing => pick("✎", "*"),
+            Symbol::Rx => pick("↓", "RX"),
+            Symbol::Tx => pick("↑", "TX"),
+            Symbol::Up => pick("↑", "Up"),
+            Symbol::Down => pick("↓", "Dn"),
+            Symbol::Pinned => pick("📌", "*"),
+        }
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
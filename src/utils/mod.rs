@@ -1,4 +1,5 @@
 // 工具模块
 pub mod format;
 pub mod command;
+pub mod icons;
 